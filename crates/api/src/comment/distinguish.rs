@@ -2,6 +2,7 @@ use actix_web::web::{Data, Json};
 use lemmy_api_common::{
   comment::{CommentResponse, DistinguishComment},
   context::LemmyContext,
+  image_proxy::ProxyImages,
   utils::{check_community_ban, is_mod_or_admin},
 };
 use lemmy_db_schema::{
@@ -43,13 +44,15 @@ pub async fn distinguish_comment(
     .await
     .with_lemmy_type(LemmyErrorType::CouldntUpdateComment)?;
 
-  let comment_view = CommentView::read(
+  let mut comment_view = CommentView::read(
     &mut context.pool(),
     data.comment_id,
     Some(local_user_view.person.id),
   )
   .await?;
 
+  comment_view.proxy_images(&context);
+
   Ok(Json(CommentResponse {
     comment_view,
     recipient_ids: Vec::new(),
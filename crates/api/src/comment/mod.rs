@@ -1,3 +1,4 @@
 pub mod distinguish;
 pub mod like;
+pub mod mute;
 pub mod save;
@@ -0,0 +1,35 @@
+use actix_web::web::{Data, Json};
+use lemmy_api_common::{
+  comment::{MuteComment, MuteCommentResponse},
+  context::LemmyContext,
+};
+use lemmy_db_schema::source::comment::{CommentMute, CommentMuteForm};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::{LemmyError, LemmyErrorExt, LemmyErrorType};
+
+#[tracing::instrument(skip(context))]
+pub async fn mute_comment(
+  data: Json<MuteComment>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> Result<Json<MuteCommentResponse>, LemmyError> {
+  let form = CommentMuteForm {
+    person_id: local_user_view.person.id,
+    comment_id: data.comment_id,
+  };
+
+  if data.mute {
+    CommentMute::mute(&mut context.pool(), &form)
+      .await
+      .with_lemmy_type(LemmyErrorType::CouldntUpdateNotificationSettings)?;
+  } else {
+    CommentMute::unmute(&mut context.pool(), &form)
+      .await
+      .with_lemmy_type(LemmyErrorType::CouldntUpdateNotificationSettings)?;
+  }
+
+  Ok(Json(MuteCommentResponse {
+    comment_id: data.comment_id,
+    muted: data.mute,
+  }))
+}
@@ -2,6 +2,7 @@ use actix_web::web::{Data, Json};
 use lemmy_api_common::{
   comment::{CommentResponse, SaveComment},
   context::LemmyContext,
+  image_proxy::ProxyImages,
 };
 use lemmy_db_schema::{
   source::comment::{CommentSaved, CommentSavedForm},
@@ -33,7 +34,10 @@ pub async fn save_comment(
 
   let comment_id = data.comment_id;
   let person_id = local_user_view.person.id;
-  let comment_view = CommentView::read(&mut context.pool(), comment_id, Some(person_id)).await?;
+  let mut comment_view =
+    CommentView::read(&mut context.pool(), comment_id, Some(person_id)).await?;
+
+  comment_view.proxy_images(&context);
 
   Ok(Json(CommentResponse {
     comment_view,
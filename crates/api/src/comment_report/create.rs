@@ -4,8 +4,15 @@ use actix_web::web::Json;
 use lemmy_api_common::{
   comment::{CommentReportResponse, CreateCommentReport},
   context::LemmyContext,
+  image_proxy::ProxyImages,
+  live_events::{LiveEventKind, LiveEvents},
   send_activity::{ActivityChannel, SendActivityData},
-  utils::{check_community_ban, sanitize_html_api, send_new_report_email_to_admins},
+  utils::{
+    check_community_ban,
+    sanitize_html_api,
+    send_new_report_email_to_admins,
+    send_new_report_push_notifications,
+  },
 };
 use lemmy_db_schema::{
   source::{
@@ -16,6 +23,7 @@ use lemmy_db_schema::{
 };
 use lemmy_db_views::structs::{CommentReportView, CommentView, LocalUserView};
 use lemmy_utils::error::{LemmyError, LemmyErrorExt, LemmyErrorType};
+use tracing::warn;
 
 /// Creates a comment report and notifies the moderators of the community
 #[tracing::instrument(skip(context))]
@@ -46,7 +54,7 @@ pub async fn create_comment_report(
     .await
     .with_lemmy_type(LemmyErrorType::CouldntCreateReport)?;
 
-  let comment_report_view =
+  let mut comment_report_view =
     CommentReportView::read(&mut context.pool(), report.id, person_id).await?;
 
   // Email the admins
@@ -60,6 +68,22 @@ pub async fn create_comment_report(
     .await?;
   }
 
+  // The report is already stored, so failing to notify shouldn't fail the request
+  if let Err(e) = send_new_report_push_notifications(
+    &comment_report_view.creator.name,
+    &comment_report_view.comment_creator.name,
+    &comment_report_view.comment_report.reason,
+    Some(comment_report_view.community.id),
+    &context,
+  )
+  .await
+  {
+    warn!("Failed to send push notifications for new report: {e}");
+  }
+  LiveEvents::publish(LiveEventKind::NewReport(Some(
+    comment_report_view.community.id,
+  )));
+
   ActivityChannel::submit_activity(
     SendActivityData::CreateReport(
       comment_view.comment.ap_id.inner().clone(),
@@ -71,6 +95,8 @@ pub async fn create_comment_report(
   )
   .await?;
 
+  comment_report_view.proxy_images(&context);
+
   Ok(Json(CommentReportResponse {
     comment_report_view,
   }))
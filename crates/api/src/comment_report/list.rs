@@ -2,6 +2,7 @@ use actix_web::web::{Data, Json, Query};
 use lemmy_api_common::{
   comment::{ListCommentReports, ListCommentReportsResponse},
   context::LemmyContext,
+  image_proxy::ProxyImages,
 };
 use lemmy_db_views::{comment_report_view::CommentReportQuery, structs::LocalUserView};
 use lemmy_utils::error::LemmyError;
@@ -19,7 +20,7 @@ pub async fn list_comment_reports(
 
   let page = data.page;
   let limit = data.limit;
-  let comment_reports = CommentReportQuery {
+  let mut comment_reports = CommentReportQuery {
     community_id,
     unresolved_only,
     page,
@@ -28,5 +29,7 @@ pub async fn list_comment_reports(
   .list(&mut context.pool(), &local_user_view)
   .await?;
 
+  comment_reports.proxy_images(&context);
+
   Ok(Json(ListCommentReportsResponse { comment_reports }))
 }
@@ -2,6 +2,7 @@ use actix_web::web::{Data, Json};
 use lemmy_api_common::{
   comment::{CommentReportResponse, ResolveCommentReport},
   context::LemmyContext,
+  image_proxy::ProxyImages,
   utils::is_mod_or_admin,
 };
 use lemmy_db_schema::{source::comment_report::CommentReport, traits::Reportable};
@@ -33,9 +34,11 @@ pub async fn resolve_comment_report(
   }
 
   let report_id = data.report_id;
-  let comment_report_view =
+  let mut comment_report_view =
     CommentReportView::read(&mut context.pool(), report_id, person_id).await?;
 
+  comment_report_view.proxy_images(&context);
+
   Ok(Json(CommentReportResponse {
     comment_report_view,
   }))
@@ -3,8 +3,14 @@ use actix_web::web::Json;
 use lemmy_api_common::{
   community::{BanFromCommunity, BanFromCommunityResponse},
   context::LemmyContext,
+  image_proxy::ProxyImages,
   send_activity::{ActivityChannel, SendActivityData},
-  utils::{is_mod_or_admin, remove_user_data_in_community, sanitize_html_api_opt},
+  utils::{
+    is_mod_or_admin,
+    remove_user_data_in_community,
+    sanitize_html_api_opt,
+    send_mod_action_notification,
+  },
 };
 use lemmy_db_schema::{
   source::{
@@ -14,9 +20,11 @@ use lemmy_db_schema::{
       CommunityPersonBan,
       CommunityPersonBanForm,
     },
+    mod_action_notification::ModActionNotificationInsertForm,
     moderator::{ModBanFromCommunity, ModBanFromCommunityForm},
   },
   traits::{Bannable, Crud, Followable},
+  ModActionType,
 };
 use lemmy_db_views::structs::LocalUserView;
 use lemmy_db_views_actor::structs::PersonView;
@@ -24,6 +32,7 @@ use lemmy_utils::{
   error::{LemmyError, LemmyErrorExt, LemmyErrorType},
   utils::{time::naive_from_unix, validation::is_valid_body_field},
 };
+use tracing::warn;
 
 #[tracing::instrument(skip(context))]
 pub async fn ban_from_community(
@@ -88,7 +97,27 @@ pub async fn ban_from_community(
 
   ModBanFromCommunity::create(&mut context.pool(), &form).await?;
 
-  let person_view = PersonView::read(&mut context.pool(), data.person_id).await?;
+  send_mod_action_notification(
+    ModActionNotificationInsertForm {
+      recipient_id: banned_person_id,
+      mod_person_id: local_user_view.person.id,
+      action: if data.ban {
+        ModActionType::BanFromCommunity
+      } else {
+        ModActionType::UnbanFromCommunity
+      },
+      community_id: data.community_id,
+      post_id: None,
+      comment_id: None,
+      reason: form.reason,
+    },
+    &context,
+  )
+  .await
+  .map_err(|e| warn!("Failed to send mod action notification: {e}"))
+  .ok();
+
+  let mut person_view = PersonView::read(&mut context.pool(), data.person_id).await?;
 
   ActivityChannel::submit_activity(
     SendActivityData::BanFromCommunity(
@@ -101,6 +130,8 @@ pub async fn ban_from_community(
   )
   .await?;
 
+  person_view.proxy_images(&context);
+
   Ok(Json(BanFromCommunityResponse {
     person_view,
     banned: data.ban,
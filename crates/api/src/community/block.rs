@@ -3,6 +3,7 @@ use actix_web::web::Json;
 use lemmy_api_common::{
   community::{BlockCommunity, BlockCommunityResponse},
   context::LemmyContext,
+  image_proxy::ProxyImages,
   send_activity::{ActivityChannel, SendActivityData},
 };
 use lemmy_db_schema::{
@@ -50,7 +51,7 @@ pub async fn block_community(
       .with_lemmy_type(LemmyErrorType::CommunityBlockAlreadyExists)?;
   }
 
-  let community_view =
+  let mut community_view =
     CommunityView::read(&mut context.pool(), community_id, Some(person_id), false).await?;
 
   ActivityChannel::submit_activity(
@@ -63,6 +64,8 @@ pub async fn block_community(
   )
   .await?;
 
+  community_view.proxy_images(&context);
+
   Ok(Json(BlockCommunityResponse {
     blocked: data.block,
     community_view,
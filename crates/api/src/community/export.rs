@@ -0,0 +1,57 @@
+use actix_web::web::{Data, Json, Query};
+use lemmy_api_common::{
+  community::{CommunityExport, ExportCommunity, ExportCommunityResponse},
+  context::LemmyContext,
+  utils::{is_admin, is_top_mod},
+};
+use lemmy_db_schema::{
+  source::{actor_language::CommunityLanguage, community::Community},
+  traits::Crud,
+};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_db_views_actor::structs::CommunityModeratorView;
+use lemmy_utils::error::{LemmyError, LemmyErrorType};
+
+/// Exports everything necessary to recreate the community on another instance, before moving it.
+#[tracing::instrument(skip(context))]
+pub async fn export_community(
+  data: Query<ExportCommunity>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> Result<Json<ExportCommunityResponse>, LemmyError> {
+  let community_id = data.community_id;
+  let community_mods =
+    CommunityModeratorView::for_community(&mut context.pool(), community_id).await?;
+
+  // Only the top mod or an admin can export a community
+  if !(is_top_mod(&local_user_view, &community_mods).is_ok() || is_admin(&local_user_view).is_ok())
+  {
+    Err(LemmyErrorType::NotTopMod)?
+  }
+
+  let community = Community::read(&mut context.pool(), community_id).await?;
+  if !community.local {
+    Err(LemmyErrorType::ObjectNotLocal)?
+  }
+
+  let discussion_languages = CommunityLanguage::read(&mut context.pool(), community_id).await?;
+
+  Ok(Json(ExportCommunityResponse {
+    export: CommunityExport {
+      actor_id: community.actor_id,
+      name: community.name,
+      title: community.title,
+      description: community.description,
+      icon: community.icon,
+      banner: community.banner,
+      nsfw: community.nsfw,
+      posting_restricted_to_mods: community.posting_restricted_to_mods,
+      require_alt_text: community.require_alt_text,
+      discussion_languages,
+      moderators: community_mods
+        .into_iter()
+        .map(|m| m.moderator.actor_id)
+        .collect(),
+    },
+  }))
+}
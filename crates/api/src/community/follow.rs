@@ -3,6 +3,7 @@ use actix_web::web::Json;
 use lemmy_api_common::{
   community::{CommunityResponse, FollowCommunity},
   context::LemmyContext,
+  image_proxy::ProxyImages,
   send_activity::{ActivityChannel, SendActivityData},
   utils::{check_community_ban, check_community_deleted_or_removed},
 };
@@ -31,6 +32,9 @@ pub async fn follow_community(
   };
 
   if data.follow {
+    if community.moved_to.is_some() {
+      Err(LemmyErrorType::CommunityWasMoved)?
+    }
     if community.local {
       check_community_ban(local_user_view.person.id, community.id, &mut context.pool()).await?;
       check_community_deleted_or_removed(community.id, &mut context.pool()).await?;
@@ -60,10 +64,12 @@ pub async fn follow_community(
 
   let community_id = data.community_id;
   let person_id = local_user_view.person.id;
-  let community_view =
+  let mut community_view =
     CommunityView::read(&mut context.pool(), community_id, Some(person_id), false).await?;
   let discussion_languages = CommunityLanguage::read(&mut context.pool(), community_id).await?;
 
+  community_view.proxy_images(&context);
+
   Ok(Json(CommunityResponse {
     community_view,
     discussion_languages,
@@ -1,6 +1,7 @@
 pub mod add_mod;
 pub mod ban;
 pub mod block;
+pub mod export;
 pub mod follow;
 pub mod hide;
 pub mod transfer;
@@ -3,6 +3,7 @@ use anyhow::Context;
 use lemmy_api_common::{
   community::{GetCommunityResponse, TransferCommunity},
   context::LemmyContext,
+  image_proxy::ProxyImages,
   utils::{is_admin, is_top_mod},
 };
 use lemmy_db_schema::{
@@ -76,16 +77,19 @@ pub async fn transfer_community(
 
   let community_id = data.community_id;
   let person_id = local_user_view.person.id;
-  let community_view =
+  let mut community_view =
     CommunityView::read(&mut context.pool(), community_id, Some(person_id), false)
       .await
       .with_lemmy_type(LemmyErrorType::CouldntFindCommunity)?;
 
   let community_id = data.community_id;
-  let moderators = CommunityModeratorView::for_community(&mut context.pool(), community_id)
+  let mut moderators = CommunityModeratorView::for_community(&mut context.pool(), community_id)
     .await
     .with_lemmy_type(LemmyErrorType::CouldntFindCommunity)?;
 
+  community_view.proxy_images(&context);
+  moderators.proxy_images(&context);
+
   // Return the jwt
   Ok(Json(GetCommunityResponse {
     community_view,
@@ -1,6 +1,7 @@
 use actix_web::web::{Data, Json};
 use lemmy_api_common::{
   context::LemmyContext,
+  image_proxy::ProxyImages,
   person::{AddAdmin, AddAdminResponse},
   utils::is_admin,
 };
@@ -49,7 +50,9 @@ pub async fn add_admin(
 
   ModAdd::create(&mut context.pool(), &form).await?;
 
-  let admins = PersonView::admins(&mut context.pool()).await?;
+  let mut admins = PersonView::admins(&mut context.pool()).await?;
+
+  admins.proxy_images(&context);
 
   Ok(Json(AddAdminResponse { admins }))
 }
@@ -2,6 +2,7 @@ use activitypub_federation::config::Data;
 use actix_web::web::Json;
 use lemmy_api_common::{
   context::LemmyContext,
+  image_proxy::ProxyImages,
   person::{BanPerson, BanPersonResponse},
   send_activity::{ActivityChannel, SendActivityData},
   utils::{is_admin, remove_user_data, sanitize_html_api_opt},
@@ -61,7 +62,7 @@ pub async fn ban_from_site(
 
   ModBan::create(&mut context.pool(), &form).await?;
 
-  let person_view = PersonView::read(&mut context.pool(), data.person_id).await?;
+  let mut person_view = PersonView::read(&mut context.pool(), data.person_id).await?;
 
   ActivityChannel::submit_activity(
     SendActivityData::BanFromSite(
@@ -73,6 +74,8 @@ pub async fn ban_from_site(
   )
   .await?;
 
+  person_view.proxy_images(&context);
+
   Ok(Json(BanPersonResponse {
     person_view,
     banned: data.ban,
@@ -1,7 +1,10 @@
-use actix_web::web::{Data, Json};
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
 use lemmy_api_common::{
   context::LemmyContext,
+  image_proxy::ProxyImages,
   person::{BlockPerson, BlockPersonResponse},
+  send_activity::{ActivityChannel, SendActivityData},
 };
 use lemmy_db_schema::{
   source::person_block::{PersonBlock, PersonBlockForm},
@@ -45,7 +48,20 @@ pub async fn block_person(
       .with_lemmy_type(LemmyErrorType::PersonBlockAlreadyExists)?;
   }
 
-  let person_view = PersonView::read(&mut context.pool(), target_id).await?;
+  let mut person_view = PersonView::read(&mut context.pool(), target_id).await?;
+
+  ActivityChannel::submit_activity(
+    SendActivityData::BlockPerson(
+      local_user_view.person.clone(),
+      person_view.person.clone(),
+      data.block,
+    ),
+    &context,
+  )
+  .await?;
+
+  person_view.proxy_images(&context);
+
   Ok(Json(BlockPersonResponse {
     person_view,
     blocked: data.block,
@@ -0,0 +1,36 @@
+use actix_web::web::{Data, Json};
+use lemmy_api_common::{
+  context::LemmyContext,
+  person::{AddDeviceKey, ListDeviceKeysResponse},
+};
+use lemmy_db_schema::source::person_device_key::{PersonDeviceKey, PersonDeviceKeyForm};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::{
+  error::LemmyError,
+  utils::validation::{check_device_key_count, is_valid_device_key},
+};
+
+/// Publishes the key of a device. Other instances see it the next time they fetch the person.
+#[tracing::instrument(skip(context))]
+pub async fn add_device_key(
+  data: Json<AddDeviceKey>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> Result<Json<ListDeviceKeysResponse>, LemmyError> {
+  is_valid_device_key(&data.device_id, &data.public_key)?;
+  let person_id = local_user_view.person.id;
+  let existing = PersonDeviceKey::list_for_person(&mut context.pool(), person_id).await?;
+  if !existing.iter().any(|k| k.device_id == data.device_id) {
+    check_device_key_count(existing.len() + 1)?;
+  }
+
+  let form = PersonDeviceKeyForm {
+    person_id,
+    device_id: data.device_id.clone(),
+    public_key: data.public_key.clone(),
+  };
+  PersonDeviceKey::upsert(&mut context.pool(), &form).await?;
+
+  let device_keys = PersonDeviceKey::list_for_person(&mut context.pool(), person_id).await?;
+  Ok(Json(ListDeviceKeysResponse { device_keys }))
+}
@@ -0,0 +1,22 @@
+use actix_web::web::{Data, Json};
+use lemmy_api_common::{
+  context::LemmyContext,
+  person::{DeleteDeviceKey, ListDeviceKeysResponse},
+};
+use lemmy_db_schema::source::person_device_key::PersonDeviceKey;
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::LemmyError;
+
+/// Removes the key of a device, and returns the remaining keys.
+#[tracing::instrument(skip(context))]
+pub async fn delete_device_key(
+  data: Json<DeleteDeviceKey>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> Result<Json<ListDeviceKeysResponse>, LemmyError> {
+  let person_id = local_user_view.person.id;
+  PersonDeviceKey::delete(&mut context.pool(), person_id, &data.device_id).await?;
+
+  let device_keys = PersonDeviceKey::list_for_person(&mut context.pool(), person_id).await?;
+  Ok(Json(ListDeviceKeysResponse { device_keys }))
+}
@@ -0,0 +1,17 @@
+use actix_web::web::{Data, Json, Query};
+use lemmy_api_common::{
+  context::LemmyContext,
+  person::{ListDeviceKeys, ListDeviceKeysResponse},
+};
+use lemmy_db_schema::source::person_device_key::PersonDeviceKey;
+use lemmy_utils::error::LemmyError;
+
+/// Lists the device keys of a person. These are public, like on the person actor.
+#[tracing::instrument(skip(context))]
+pub async fn list_device_keys(
+  data: Query<ListDeviceKeys>,
+  context: Data<LemmyContext>,
+) -> Result<Json<ListDeviceKeysResponse>, LemmyError> {
+  let device_keys = PersonDeviceKey::list_for_person(&mut context.pool(), data.person_id).await?;
+  Ok(Json(ListDeviceKeysResponse { device_keys }))
+}
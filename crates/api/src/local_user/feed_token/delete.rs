@@ -0,0 +1,16 @@
+use actix_web::web::{Data, Json};
+use lemmy_api_common::{context::LemmyContext, person::FeedTokenResponse};
+use lemmy_db_schema::source::feed_token::FeedToken;
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::LemmyError;
+
+/// Revokes the token for private feeds.
+#[tracing::instrument(skip(context))]
+pub async fn delete_feed_token(
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> Result<Json<FeedTokenResponse>, LemmyError> {
+  FeedToken::delete(&mut context.pool(), local_user_view.local_user.id).await?;
+
+  Ok(Json(FeedTokenResponse { feed_token: None }))
+}
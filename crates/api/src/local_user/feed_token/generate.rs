@@ -0,0 +1,26 @@
+use actix_web::web::{Data, Json};
+use lemmy_api_common::{context::LemmyContext, person::FeedTokenResponse, sensitive::Sensitive};
+use lemmy_db_schema::source::feed_token::{FeedToken, FeedTokenForm};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::{LemmyError, LemmyErrorExt, LemmyErrorType};
+use uuid::Uuid;
+
+/// Generates a new token for private feeds like the front page and inbox. Feed links with the
+/// previous token stop working.
+#[tracing::instrument(skip(context))]
+pub async fn generate_feed_token(
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> Result<Json<FeedTokenResponse>, LemmyError> {
+  let form = FeedTokenForm {
+    local_user_id: local_user_view.local_user.id,
+    token: Uuid::new_v4().simple().to_string(),
+  };
+  let feed_token = FeedToken::regenerate(&mut context.pool(), &form)
+    .await
+    .with_lemmy_type(LemmyErrorType::CouldntUpdateUser)?;
+
+  Ok(Json(FeedTokenResponse {
+    feed_token: Some(Sensitive::new(feed_token.token)),
+  }))
+}
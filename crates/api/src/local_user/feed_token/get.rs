@@ -0,0 +1,19 @@
+use actix_web::web::{Data, Json};
+use lemmy_api_common::{context::LemmyContext, person::FeedTokenResponse, sensitive::Sensitive};
+use lemmy_db_schema::source::feed_token::FeedToken;
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::LemmyError;
+
+/// Returns the current token for private feeds, if one was generated.
+#[tracing::instrument(skip(context))]
+pub async fn get_feed_token(
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> Result<Json<FeedTokenResponse>, LemmyError> {
+  let feed_token = FeedToken::read(&mut context.pool(), local_user_view.local_user.id)
+    .await
+    .ok()
+    .map(|t| Sensitive::new(t.token));
+
+  Ok(Json(FeedTokenResponse { feed_token }))
+}
@@ -1,5 +1,10 @@
 use actix_web::web::{Data, Json};
-use lemmy_api_common::{context::LemmyContext, person::BannedPersonsResponse, utils::is_admin};
+use lemmy_api_common::{
+  context::LemmyContext,
+  image_proxy::ProxyImages,
+  person::BannedPersonsResponse,
+  utils::is_admin,
+};
 use lemmy_db_views::structs::LocalUserView;
 use lemmy_db_views_actor::structs::PersonView;
 use lemmy_utils::error::LemmyError;
@@ -11,7 +16,8 @@ pub async fn list_banned_users(
   // Make sure user is an admin
   is_admin(&local_user_view)?;
 
-  let banned = PersonView::banned(&mut context.pool()).await?;
+  let mut banned = PersonView::banned(&mut context.pool()).await?;
+  banned.proxy_images(&context);
 
   Ok(Json(BannedPersonsResponse { banned }))
 }
@@ -0,0 +1,32 @@
+use actix_web::web::{Data, Json};
+use lemmy_api_common::{
+  context::LemmyContext,
+  person::{DeleteMedia, DeleteMediaResponse},
+  request::delete_image_from_pictrs,
+  utils::is_admin,
+};
+use lemmy_db_schema::source::image_upload::ImageUpload;
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::{LemmyError, LemmyErrorExt, LemmyErrorType};
+
+/// Deletes an upload using the stored delete token, so that users don't have to keep it.
+#[tracing::instrument(skip(context))]
+pub async fn delete_media(
+  data: Json<DeleteMedia>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> Result<Json<DeleteMediaResponse>, LemmyError> {
+  let upload = ImageUpload::read_by_alias(&mut context.pool(), &data.alias)
+    .await
+    .with_lemmy_type(LemmyErrorType::CouldntFindImage)?;
+
+  // Only the uploader or an admin can delete an image
+  if upload.local_user_id != local_user_view.local_user.id {
+    is_admin(&local_user_view)?;
+  }
+
+  delete_image_from_pictrs(&upload.pictrs_alias, &upload.pictrs_delete_token, &context).await?;
+  ImageUpload::delete(&mut context.pool(), upload.id).await?;
+
+  Ok(Json(DeleteMediaResponse {}))
+}
@@ -0,0 +1,39 @@
+use actix_web::web::{Data, Json};
+use lemmy_api_common::{
+  context::LemmyContext,
+  person::{EditMedia, EditMediaResponse},
+  utils::sanitize_html_api,
+};
+use lemmy_db_schema::source::image_upload::ImageUpload;
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::{
+  error::{LemmyError, LemmyErrorExt, LemmyErrorType},
+  utils::validation::is_valid_alt_text_field,
+};
+
+#[tracing::instrument(skip(context))]
+pub async fn edit_media(
+  data: Json<EditMedia>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> Result<Json<EditMediaResponse>, LemmyError> {
+  let upload = ImageUpload::read_by_alias(&mut context.pool(), &data.alias)
+    .await
+    .with_lemmy_type(LemmyErrorType::CouldntFindImage)?;
+
+  // Only the uploader can describe an image
+  if upload.local_user_id != local_user_view.local_user.id {
+    Err(LemmyErrorType::CouldntFindImage)?
+  }
+
+  let alt_text = data
+    .alt_text
+    .as_deref()
+    .map(|a| sanitize_html_api(a.trim()))
+    .filter(|a| !a.is_empty());
+  is_valid_alt_text_field(&alt_text)?;
+
+  let image = ImageUpload::update_alt_text(&mut context.pool(), upload.id, alt_text).await?;
+
+  Ok(Json(EditMediaResponse { image }))
+}
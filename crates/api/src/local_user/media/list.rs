@@ -0,0 +1,23 @@
+use actix_web::web::{Data, Json, Query};
+use lemmy_api_common::{
+  context::LemmyContext,
+  person::{ListMedia, ListMediaResponse},
+};
+use lemmy_db_views::structs::{ImageUploadView, LocalUserView};
+use lemmy_utils::error::LemmyError;
+
+#[tracing::instrument(skip(context))]
+pub async fn list_media(
+  data: Query<ListMedia>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> Result<Json<ListMediaResponse>, LemmyError> {
+  let images = ImageUploadView::list(
+    &mut context.pool(),
+    Some(local_user_view.local_user.id),
+    data.page,
+    data.limit,
+  )
+  .await?;
+  Ok(Json(ListMediaResponse { images }))
+}
@@ -0,0 +1,6 @@
+pub mod delete;
+pub mod edit;
+pub mod list;
+pub mod list_all;
+pub mod quota;
+pub mod set_quota;
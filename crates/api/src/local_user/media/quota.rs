@@ -0,0 +1,17 @@
+use actix_web::web::{Data, Json};
+use lemmy_api_common::{
+  context::LemmyContext,
+  person::UploadQuotaResponse,
+  utils::read_upload_quota,
+};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::LemmyError;
+
+#[tracing::instrument(skip(context))]
+pub async fn get_upload_quota(
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> Result<Json<UploadQuotaResponse>, LemmyError> {
+  let quota = read_upload_quota(&local_user_view.local_user, &context).await?;
+  Ok(Json(quota))
+}
@@ -0,0 +1,33 @@
+use actix_web::web::{Data, Json};
+use lemmy_api_common::{
+  context::LemmyContext,
+  person::{SetUploadQuota, UploadQuotaResponse},
+  utils::{is_admin, read_upload_quota},
+};
+use lemmy_db_schema::{
+  source::local_user::{LocalUser, LocalUserUpdateForm},
+  traits::Crud,
+};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::LemmyError;
+
+#[tracing::instrument(skip(context))]
+pub async fn set_upload_quota(
+  data: Json<SetUploadQuota>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> Result<Json<UploadQuotaResponse>, LemmyError> {
+  // Only let admins change quotas
+  is_admin(&local_user_view)?;
+
+  let target = LocalUserView::read_person(&mut context.pool(), data.person_id).await?;
+  let form = LocalUserUpdateForm {
+    upload_quota_max_total_size: Some(data.max_total_size),
+    upload_quota_max_files: Some(data.max_files),
+    ..Default::default()
+  };
+  let local_user = LocalUser::update(&mut context.pool(), target.local_user.id, &form).await?;
+
+  let quota = read_upload_quota(&local_user, &context).await?;
+  Ok(Json(quota))
+}
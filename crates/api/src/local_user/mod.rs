@@ -3,11 +3,16 @@ pub mod ban_person;
 pub mod block;
 pub mod change_password;
 pub mod change_password_after_reset;
+pub mod device_key;
+pub mod feed_token;
 pub mod generate_totp_secret;
 pub mod get_captcha;
 pub mod list_banned;
 pub mod login;
+pub mod media;
+pub mod notification_preference;
 pub mod notifications;
+pub mod push_subscription;
 pub mod report_count;
 pub mod reset_password;
 pub mod save_settings;
@@ -0,0 +1,34 @@
+use actix_web::web::{Data, Json};
+use lemmy_api_common::{
+  context::LemmyContext,
+  person::{EditNotificationPreference, NotificationPreferenceResponse},
+};
+use lemmy_db_schema::source::notification_preference::{
+  NotificationPreference,
+  NotificationPreferenceForm,
+};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::{LemmyError, LemmyErrorExt, LemmyErrorType};
+
+#[tracing::instrument(skip(context))]
+pub async fn edit_notification_preference(
+  data: Json<EditNotificationPreference>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> Result<Json<NotificationPreferenceResponse>, LemmyError> {
+  let form = NotificationPreferenceForm {
+    local_user_id: local_user_view.local_user.id,
+    notification_type: data.notification_type,
+    push: data.push,
+    in_app: data.in_app,
+    email: data.email,
+    only_from_followed: data.only_from_followed,
+  };
+  let notification_preference = NotificationPreference::upsert(&mut context.pool(), &form)
+    .await
+    .with_lemmy_type(LemmyErrorType::CouldntUpdateUser)?;
+
+  Ok(Json(NotificationPreferenceResponse {
+    notification_preference,
+  }))
+}
@@ -0,0 +1,18 @@
+use actix_web::web::{Data, Json};
+use lemmy_api_common::{context::LemmyContext, person::ListNotificationPreferencesResponse};
+use lemmy_db_schema::source::notification_preference::NotificationPreference;
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::LemmyError;
+
+#[tracing::instrument(skip(context))]
+pub async fn list_notification_preferences(
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> Result<Json<ListNotificationPreferencesResponse>, LemmyError> {
+  let notification_preferences =
+    NotificationPreference::list(&mut context.pool(), local_user_view.local_user.id).await?;
+
+  Ok(Json(ListNotificationPreferencesResponse {
+    notification_preferences,
+  }))
+}
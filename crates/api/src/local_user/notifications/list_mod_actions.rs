@@ -0,0 +1,32 @@
+use actix_web::web::{Data, Json, Query};
+use lemmy_api_common::{
+  context::LemmyContext,
+  image_proxy::ProxyImages,
+  person::{ListModActionNotifications, ListModActionNotificationsResponse},
+};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_db_views_actor::structs::ModActionNotificationView;
+use lemmy_utils::error::LemmyError;
+
+#[tracing::instrument(skip(context))]
+pub async fn list_mod_action_notifications(
+  data: Query<ListModActionNotifications>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> Result<Json<ListModActionNotificationsResponse>, LemmyError> {
+  let unread_only = data.unread_only.unwrap_or_default();
+  let person_id = local_user_view.person.id;
+
+  let mut mod_actions = ModActionNotificationView::list(
+    &mut context.pool(),
+    person_id,
+    unread_only,
+    data.page,
+    data.limit,
+  )
+  .await?;
+
+  mod_actions.proxy_images(&context);
+
+  Ok(Json(ListModActionNotificationsResponse { mod_actions }))
+}
@@ -1,6 +1,7 @@
 use actix_web::web::{Data, Json, Query};
 use lemmy_api_common::{
   context::LemmyContext,
+  image_proxy::ProxyImages,
   person::{GetReplies, GetRepliesResponse},
 };
 use lemmy_db_views::structs::LocalUserView;
@@ -20,7 +21,7 @@ pub async fn list_replies(
   let person_id = Some(local_user_view.person.id);
   let show_bot_accounts = local_user_view.local_user.show_bot_accounts;
 
-  let replies = CommentReplyQuery {
+  let mut replies = CommentReplyQuery {
     recipient_id: person_id,
     my_person_id: person_id,
     sort,
@@ -32,5 +33,7 @@ pub async fn list_replies(
   .list(&mut context.pool())
   .await?;
 
+  replies.proxy_images(&context);
+
   Ok(Json(GetRepliesResponse { replies }))
 }
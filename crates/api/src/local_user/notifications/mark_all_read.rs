@@ -2,6 +2,7 @@ use actix_web::web::{Data, Json};
 use lemmy_api_common::{context::LemmyContext, person::GetRepliesResponse};
 use lemmy_db_schema::source::{
   comment_reply::CommentReply,
+  mod_action_notification::ModActionNotification,
   person_mention::PersonMention,
   private_message::PrivateMessage,
 };
@@ -25,6 +26,11 @@ pub async fn mark_all_notifications_read(
     .await
     .with_lemmy_type(LemmyErrorType::CouldntUpdateComment)?;
 
+  // Mark all moderator action notifications as read
+  ModActionNotification::mark_all_as_read(&mut context.pool(), person_id)
+    .await
+    .with_lemmy_type(LemmyErrorType::CouldntUpdateNotificationSettings)?;
+
   // Mark all private_messages as read
   PrivateMessage::mark_all_as_read(&mut context.pool(), person_id)
     .await
@@ -1,6 +1,7 @@
 use actix_web::web::{Data, Json};
 use lemmy_api_common::{
   context::LemmyContext,
+  image_proxy::ProxyImages,
   person::{MarkPersonMentionAsRead, PersonMentionResponse},
 };
 use lemmy_db_schema::{
@@ -36,9 +37,11 @@ pub async fn mark_person_mention_as_read(
 
   let person_mention_id = read_person_mention.id;
   let person_id = local_user_view.person.id;
-  let person_mention_view =
+  let mut person_mention_view =
     PersonMentionView::read(&mut context.pool(), person_mention_id, Some(person_id)).await?;
 
+  person_mention_view.proxy_images(&context);
+
   Ok(Json(PersonMentionResponse {
     person_mention_view,
   }))
@@ -0,0 +1,45 @@
+use actix_web::web::{Data, Json};
+use lemmy_api_common::{
+  context::LemmyContext,
+  image_proxy::ProxyImages,
+  person::{MarkModActionNotificationAsRead, ModActionNotificationResponse},
+};
+use lemmy_db_schema::{
+  source::mod_action_notification::{ModActionNotification, ModActionNotificationUpdateForm},
+  traits::Crud,
+};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_db_views_actor::structs::ModActionNotificationView;
+use lemmy_utils::error::{LemmyError, LemmyErrorExt, LemmyErrorType};
+
+#[tracing::instrument(skip(context))]
+pub async fn mark_mod_action_notification_as_read(
+  data: Json<MarkModActionNotificationAsRead>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> Result<Json<ModActionNotificationResponse>, LemmyError> {
+  let notification_id = data.mod_action_notification_id;
+  let notification = ModActionNotification::read(&mut context.pool(), notification_id).await?;
+
+  if local_user_view.person.id != notification.recipient_id {
+    Err(LemmyErrorType::CouldntUpdateNotificationSettings)?
+  }
+
+  let read = Some(data.read);
+  ModActionNotification::update(
+    &mut context.pool(),
+    notification_id,
+    &ModActionNotificationUpdateForm { read },
+  )
+  .await
+  .with_lemmy_type(LemmyErrorType::CouldntUpdateNotificationSettings)?;
+
+  let mut mod_action_notification_view =
+    ModActionNotificationView::read(&mut context.pool(), notification_id).await?;
+
+  mod_action_notification_view.proxy_images(&context);
+
+  Ok(Json(ModActionNotificationResponse {
+    mod_action_notification_view,
+  }))
+}
@@ -1,6 +1,7 @@
 use actix_web::web::{Data, Json};
 use lemmy_api_common::{
   context::LemmyContext,
+  image_proxy::ProxyImages,
   person::{CommentReplyResponse, MarkCommentReplyAsRead},
 };
 use lemmy_db_schema::{
@@ -37,8 +38,10 @@ pub async fn mark_reply_as_read(
 
   let comment_reply_id = read_comment_reply.id;
   let person_id = local_user_view.person.id;
-  let comment_reply_view =
+  let mut comment_reply_view =
     CommentReplyView::read(&mut context.pool(), comment_reply_id, Some(person_id)).await?;
 
+  comment_reply_view.proxy_images(&context);
+
   Ok(Json(CommentReplyResponse { comment_reply_view }))
 }
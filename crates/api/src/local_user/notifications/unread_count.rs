@@ -1,7 +1,11 @@
 use actix_web::web::{Data, Json};
 use lemmy_api_common::{context::LemmyContext, person::GetUnreadCountResponse};
-use lemmy_db_views::structs::{LocalUserView, PrivateMessageView};
-use lemmy_db_views_actor::structs::{CommentReplyView, PersonMentionView};
+use lemmy_db_views::structs::{ConversationView, LocalUserView, PrivateMessageView};
+use lemmy_db_views_actor::structs::{
+  CommentReplyView,
+  ModActionNotificationView,
+  PersonMentionView,
+};
 use lemmy_utils::error::LemmyError;
 
 #[tracing::instrument(skip(context))]
@@ -18,9 +22,17 @@ pub async fn unread_count(
   let private_messages =
     PrivateMessageView::get_unread_messages(&mut context.pool(), person_id).await?;
 
+  let mod_actions =
+    ModActionNotificationView::get_unread_count(&mut context.pool(), person_id).await?;
+
+  let message_requests =
+    ConversationView::get_message_request_count(&mut context.pool(), person_id).await?;
+
   Ok(Json(GetUnreadCountResponse {
     replies,
     mentions,
     private_messages,
+    mod_actions,
+    message_requests,
   }))
 }
@@ -0,0 +1,88 @@
+use actix_web::web::{Data, Json};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use lemmy_api_common::{
+  context::LemmyContext,
+  person::{CreatePushSubscription, PushSubscriptionResponse},
+  request::check_url_is_public,
+};
+use lemmy_db_schema::source::push_subscription::{PushSubscription, PushSubscriptionForm};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::{LemmyError, LemmyErrorType};
+use url::Url;
+
+/// Registers a device for push notifications. Subscribing again from the same browser updates the
+/// existing subscription.
+#[tracing::instrument(skip(context))]
+pub async fn create_push_subscription(
+  data: Json<CreatePushSubscription>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> Result<Json<PushSubscriptionResponse>, LemmyError> {
+  let endpoint = check_push_subscription(&data)?;
+  // Push messages are sent by the server, so they must not go into its local network
+  check_url_is_public(&endpoint).await?;
+
+  let form = PushSubscriptionForm {
+    local_user_id: local_user_view.local_user.id,
+    endpoint: data.endpoint.clone(),
+    p256dh: data.p256dh.clone(),
+    auth: data.auth.clone(),
+  };
+  let push_subscription = PushSubscription::upsert(&mut context.pool(), &form).await?;
+
+  Ok(Json(PushSubscriptionResponse { push_subscription }))
+}
+
+/// The endpoint must be a https url with the domain of the push service, and the keys must have
+/// the sizes from the Web Push spec, otherwise encrypting messages fails later.
+fn check_push_subscription(data: &CreatePushSubscription) -> Result<Url, LemmyError> {
+  let endpoint = Url::parse(&data.endpoint).map_err(|_| LemmyErrorType::InvalidPushSubscription)?;
+  let p256dh = URL_SAFE_NO_PAD.decode(data.p256dh.trim_end_matches('='));
+  let auth = URL_SAFE_NO_PAD.decode(data.auth.trim_end_matches('='));
+  match (endpoint.scheme(), endpoint.domain(), p256dh, auth) {
+    ("https", Some(_), Ok(p256dh), Ok(auth)) if p256dh.len() == 65 && auth.len() == 16 => {
+      Ok(endpoint)
+    }
+    _ => Err(LemmyErrorType::InvalidPushSubscription)?,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use super::check_push_subscription;
+  use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+  use lemmy_api_common::person::CreatePushSubscription;
+
+  #[test]
+  fn test_check_push_subscription() {
+    let valid = CreatePushSubscription {
+      endpoint: "https://push.example.com/send/abc".to_string(),
+      p256dh: URL_SAFE_NO_PAD.encode([4; 65]),
+      auth: URL_SAFE_NO_PAD.encode([1; 16]),
+    };
+    assert!(check_push_subscription(&valid).is_ok());
+
+    let http = CreatePushSubscription {
+      endpoint: "http://push.example.com/send/abc".to_string(),
+      ..valid.clone()
+    };
+    assert!(check_push_subscription(&http).is_err());
+
+    for endpoint in ["https://127.0.0.1/send/abc", "https://[fd00::1]/send/abc"] {
+      let ip_literal = CreatePushSubscription {
+        endpoint: endpoint.to_string(),
+        ..valid.clone()
+      };
+      assert!(check_push_subscription(&ip_literal).is_err());
+    }
+
+    let short_key = CreatePushSubscription {
+      p256dh: URL_SAFE_NO_PAD.encode([4; 33]),
+      ..valid
+    };
+    assert!(check_push_subscription(&short_key).is_err());
+  }
+}
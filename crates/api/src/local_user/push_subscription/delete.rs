@@ -0,0 +1,27 @@
+use actix_web::web::{Data, Json};
+use lemmy_api_common::{
+  context::LemmyContext,
+  person::{DeletePushSubscription, ListPushSubscriptionsResponse},
+};
+use lemmy_db_schema::source::push_subscription::PushSubscription;
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::LemmyError;
+
+/// Stops push notifications for a device, and returns the remaining subscriptions.
+#[tracing::instrument(skip(context))]
+pub async fn delete_push_subscription(
+  data: Json<DeletePushSubscription>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> Result<Json<ListPushSubscriptionsResponse>, LemmyError> {
+  let local_user_id = local_user_view.local_user.id;
+  PushSubscription::delete(&mut context.pool(), local_user_id, &data.endpoint).await?;
+
+  let push_subscriptions =
+    PushSubscription::list_for_local_user(&mut context.pool(), local_user_id).await?;
+
+  Ok(Json(ListPushSubscriptionsResponse {
+    vapid_public_key: context.secret().vapid_public_key.clone(),
+    push_subscriptions,
+  }))
+}
@@ -0,0 +1,21 @@
+use actix_web::web::{Data, Json};
+use lemmy_api_common::{context::LemmyContext, person::ListPushSubscriptionsResponse};
+use lemmy_db_schema::source::push_subscription::PushSubscription;
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::LemmyError;
+
+/// Lists the devices which receive push notifications, together with the key to subscribe more.
+#[tracing::instrument(skip(context))]
+pub async fn list_push_subscriptions(
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> Result<Json<ListPushSubscriptionsResponse>, LemmyError> {
+  let push_subscriptions =
+    PushSubscription::list_for_local_user(&mut context.pool(), local_user_view.local_user.id)
+      .await?;
+
+  Ok(Json(ListPushSubscriptionsResponse {
+    vapid_public_key: context.secret().vapid_public_key.clone(),
+    push_subscriptions,
+  }))
+}
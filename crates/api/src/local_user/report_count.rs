@@ -2,13 +2,9 @@ use actix_web::web::{Data, Json};
 use lemmy_api_common::{
   context::LemmyContext,
   person::{GetReportCount, GetReportCountResponse},
+  utils::get_report_count,
 };
-use lemmy_db_views::structs::{
-  CommentReportView,
-  LocalUserView,
-  PostReportView,
-  PrivateMessageReportView,
-};
+use lemmy_db_views::structs::LocalUserView;
 use lemmy_utils::error::LemmyError;
 
 #[tracing::instrument(skip(context))]
@@ -17,27 +13,13 @@ pub async fn report_count(
   context: Data<LemmyContext>,
   local_user_view: LocalUserView,
 ) -> Result<Json<GetReportCountResponse>, LemmyError> {
-  let person_id = local_user_view.person.id;
-  let admin = local_user_view.local_user.admin;
-  let community_id = data.community_id;
-
-  let comment_reports =
-    CommentReportView::get_report_count(&mut context.pool(), person_id, admin, community_id)
-      .await?;
-
-  let post_reports =
-    PostReportView::get_report_count(&mut context.pool(), person_id, admin, community_id).await?;
-
-  let private_message_reports = if admin && community_id.is_none() {
-    Some(PrivateMessageReportView::get_report_count(&mut context.pool()).await?)
-  } else {
-    None
-  };
-
-  Ok(Json(GetReportCountResponse {
-    community_id,
-    comment_reports,
-    post_reports,
-    private_message_reports,
-  }))
+  Ok(Json(
+    get_report_count(
+      local_user_view.person.id,
+      local_user_view.local_user.admin,
+      data.community_id,
+      &mut context.pool(),
+    )
+    .await?,
+  ))
 }
@@ -105,6 +105,8 @@ pub async fn save_user_settings(
     show_read_posts: data.show_read_posts,
     show_new_post_notifs: data.show_new_post_notifs,
     send_notifications_to_email: data.send_notifications_to_email,
+    email_digest: data.email_digest,
+    private_message_policy: data.private_message_policy,
     show_nsfw: data.show_nsfw,
     blur_nsfw: data.blur_nsfw,
     auto_expand: data.auto_expand,
@@ -5,17 +5,25 @@ use lemmy_api_common::{
   context::LemmyContext,
   post::{LockPost, PostResponse},
   send_activity::{ActivityChannel, SendActivityData},
-  utils::{check_community_ban, check_community_deleted_or_removed, is_mod_or_admin},
+  utils::{
+    check_community_ban,
+    check_community_deleted_or_removed,
+    is_mod_or_admin,
+    send_mod_action_notification,
+  },
 };
 use lemmy_db_schema::{
   source::{
+    mod_action_notification::ModActionNotificationInsertForm,
     moderator::{ModLockPost, ModLockPostForm},
     post::{Post, PostUpdateForm},
   },
   traits::Crud,
+  ModActionType,
 };
 use lemmy_db_views::structs::LocalUserView;
 use lemmy_utils::error::LemmyError;
+use tracing::warn;
 
 #[tracing::instrument(skip(context))]
 pub async fn lock_post(
@@ -63,6 +71,26 @@ pub async fn lock_post(
   };
   ModLockPost::create(&mut context.pool(), &form).await?;
 
+  send_mod_action_notification(
+    ModActionNotificationInsertForm {
+      recipient_id: orig_post.creator_id,
+      mod_person_id: local_user_view.person.id,
+      action: if locked {
+        ModActionType::LockPost
+      } else {
+        ModActionType::UnlockPost
+      },
+      community_id: orig_post.community_id,
+      post_id: Some(post_id),
+      comment_id: None,
+      reason: None,
+    },
+    &context,
+  )
+  .await
+  .map_err(|e| warn!("Failed to send mod action notification: {e}"))
+  .ok();
+
   let person_id = local_user_view.person.id;
   ActivityChannel::submit_activity(
     SendActivityData::LockPost(post, local_user_view.person, data.locked),
@@ -1,6 +1,7 @@
 use actix_web::web::{Data, Json};
 use lemmy_api_common::{
   context::LemmyContext,
+  image_proxy::ProxyImages,
   post::{MarkPostAsRead, PostResponse},
   utils,
 };
@@ -24,7 +25,9 @@ pub async fn mark_post_as_read(
   }
 
   // Fetch it
-  let post_view = PostView::read(&mut context.pool(), post_id, Some(person_id), false).await?;
+  let mut post_view = PostView::read(&mut context.pool(), post_id, Some(person_id), false).await?;
+
+  post_view.proxy_images(&context);
 
   Ok(Json(PostResponse { post_view }))
 }
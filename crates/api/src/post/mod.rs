@@ -3,4 +3,8 @@ pub mod get_link_metadata;
 pub mod like;
 pub mod lock;
 pub mod mark_read;
+pub mod mute;
+pub mod notification_settings;
+pub mod refresh_metadata;
 pub mod save;
+pub mod subscribe;
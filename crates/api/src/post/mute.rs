@@ -0,0 +1,36 @@
+use crate::post::notification_settings::build_post_notification_settings;
+use actix_web::web::{Data, Json};
+use lemmy_api_common::{
+  context::LemmyContext,
+  post::{MutePost, PostNotificationSettingsResponse},
+};
+use lemmy_db_schema::source::post::{PostMute, PostMuteForm};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::{LemmyError, LemmyErrorExt, LemmyErrorType};
+
+#[tracing::instrument(skip(context))]
+pub async fn mute_post(
+  data: Json<MutePost>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> Result<Json<PostNotificationSettingsResponse>, LemmyError> {
+  let person_id = local_user_view.person.id;
+  let form = PostMuteForm {
+    person_id,
+    post_id: data.post_id,
+  };
+
+  if data.mute {
+    PostMute::mute(&mut context.pool(), &form)
+      .await
+      .with_lemmy_type(LemmyErrorType::CouldntUpdateNotificationSettings)?;
+  } else {
+    PostMute::unmute(&mut context.pool(), &form)
+      .await
+      .with_lemmy_type(LemmyErrorType::CouldntUpdateNotificationSettings)?;
+  }
+
+  Ok(Json(
+    build_post_notification_settings(person_id, data.post_id, &mut context.pool()).await?,
+  ))
+}
@@ -0,0 +1,36 @@
+use actix_web::web::{Data, Json, Query};
+use lemmy_api_common::{
+  context::LemmyContext,
+  post::{GetPostNotificationSettings, PostNotificationSettingsResponse},
+};
+use lemmy_db_schema::{
+  newtypes::{PersonId, PostId},
+  source::post::{PostMute, PostSubscription},
+  utils::DbPool,
+};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::LemmyError;
+
+#[tracing::instrument(skip(context))]
+pub async fn get_post_notification_settings(
+  data: Query<GetPostNotificationSettings>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> Result<Json<PostNotificationSettingsResponse>, LemmyError> {
+  Ok(Json(
+    build_post_notification_settings(local_user_view.person.id, data.post_id, &mut context.pool())
+      .await?,
+  ))
+}
+
+pub(crate) async fn build_post_notification_settings(
+  person_id: PersonId,
+  post_id: PostId,
+  pool: &mut DbPool<'_>,
+) -> Result<PostNotificationSettingsResponse, LemmyError> {
+  Ok(PostNotificationSettingsResponse {
+    post_id,
+    subscribed: PostSubscription::is_subscribed(pool, person_id, post_id).await?,
+    muted: PostMute::is_muted(pool, person_id, post_id).await?,
+  })
+}
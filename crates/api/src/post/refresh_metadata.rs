@@ -0,0 +1,48 @@
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use lemmy_api_common::{
+  build_response::build_post_response,
+  context::LemmyContext,
+  post::{PostResponse, RefreshPostMetadata},
+  request::{fetch_site_data, site_metadata_post_form},
+  utils::is_mod_or_admin,
+};
+use lemmy_db_schema::{source::post::Post, traits::Crud};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::{LemmyError, LemmyErrorExt, LemmyErrorType};
+
+/// Fetches the link metadata and thumbnail of a post again. The result is only stored locally,
+/// other instances fetch metadata on their own.
+#[tracing::instrument(skip(context))]
+pub async fn refresh_post_metadata(
+  data: Json<RefreshPostMetadata>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> Result<Json<PostResponse>, LemmyError> {
+  let post = Post::read(&mut context.pool(), data.post_id).await?;
+
+  is_mod_or_admin(
+    &mut context.pool(),
+    local_user_view.person.id,
+    post.community_id,
+  )
+  .await?;
+
+  let url = post.url.map(Into::into);
+  let (metadata_res, thumbnail_url) = fetch_site_data(&context, url.as_ref(), true).await;
+  Post::update(
+    &mut context.pool(),
+    post.id,
+    &site_metadata_post_form(metadata_res, thumbnail_url, false),
+  )
+  .await
+  .with_lemmy_type(LemmyErrorType::CouldntUpdatePost)?;
+
+  build_post_response(
+    &context,
+    post.community_id,
+    local_user_view.person.id,
+    post.id,
+  )
+  .await
+}
@@ -1,6 +1,7 @@
 use actix_web::web::{Data, Json};
 use lemmy_api_common::{
   context::LemmyContext,
+  image_proxy::ProxyImages,
   post::{PostResponse, SavePost},
   utils::mark_post_as_read,
 };
@@ -34,10 +35,12 @@ pub async fn save_post(
 
   let post_id = data.post_id;
   let person_id = local_user_view.person.id;
-  let post_view = PostView::read(&mut context.pool(), post_id, Some(person_id), false).await?;
+  let mut post_view = PostView::read(&mut context.pool(), post_id, Some(person_id), false).await?;
 
   // Mark the post as read
   mark_post_as_read(person_id, post_id, &mut context.pool()).await?;
 
+  post_view.proxy_images(&context);
+
   Ok(Json(PostResponse { post_view }))
 }
@@ -0,0 +1,36 @@
+use crate::post::notification_settings::build_post_notification_settings;
+use actix_web::web::{Data, Json};
+use lemmy_api_common::{
+  context::LemmyContext,
+  post::{PostNotificationSettingsResponse, SubscribeToPost},
+};
+use lemmy_db_schema::source::post::{PostSubscription, PostSubscriptionForm};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::{LemmyError, LemmyErrorExt, LemmyErrorType};
+
+#[tracing::instrument(skip(context))]
+pub async fn subscribe_to_post(
+  data: Json<SubscribeToPost>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> Result<Json<PostNotificationSettingsResponse>, LemmyError> {
+  let person_id = local_user_view.person.id;
+  let form = PostSubscriptionForm {
+    person_id,
+    post_id: data.post_id,
+  };
+
+  if data.subscribe {
+    PostSubscription::subscribe(&mut context.pool(), &form)
+      .await
+      .with_lemmy_type(LemmyErrorType::CouldntUpdateNotificationSettings)?;
+  } else {
+    PostSubscription::unsubscribe(&mut context.pool(), &form)
+      .await
+      .with_lemmy_type(LemmyErrorType::CouldntUpdateNotificationSettings)?;
+  }
+
+  Ok(Json(
+    build_post_notification_settings(person_id, data.post_id, &mut context.pool()).await?,
+  ))
+}
@@ -3,9 +3,16 @@ use activitypub_federation::config::Data;
 use actix_web::web::Json;
 use lemmy_api_common::{
   context::LemmyContext,
+  image_proxy::ProxyImages,
+  live_events::{LiveEventKind, LiveEvents},
   post::{CreatePostReport, PostReportResponse},
   send_activity::{ActivityChannel, SendActivityData},
-  utils::{check_community_ban, sanitize_html_api, send_new_report_email_to_admins},
+  utils::{
+    check_community_ban,
+    sanitize_html_api,
+    send_new_report_email_to_admins,
+    send_new_report_push_notifications,
+  },
 };
 use lemmy_db_schema::{
   source::{
@@ -16,6 +23,7 @@ use lemmy_db_schema::{
 };
 use lemmy_db_views::structs::{LocalUserView, PostReportView, PostView};
 use lemmy_utils::error::{LemmyError, LemmyErrorExt, LemmyErrorType};
+use tracing::warn;
 
 /// Creates a post report and notifies the moderators of the community
 #[tracing::instrument(skip(context))]
@@ -48,7 +56,8 @@ pub async fn create_post_report(
     .await
     .with_lemmy_type(LemmyErrorType::CouldntCreateReport)?;
 
-  let post_report_view = PostReportView::read(&mut context.pool(), report.id, person_id).await?;
+  let mut post_report_view =
+    PostReportView::read(&mut context.pool(), report.id, person_id).await?;
 
   // Email the admins
   if local_site.reports_email_admins {
@@ -61,6 +70,22 @@ pub async fn create_post_report(
     .await?;
   }
 
+  // The report is already stored, so failing to notify shouldn't fail the request
+  if let Err(e) = send_new_report_push_notifications(
+    &post_report_view.creator.name,
+    &post_report_view.post_creator.name,
+    &post_report_view.post_report.reason,
+    Some(post_report_view.community.id),
+    &context,
+  )
+  .await
+  {
+    warn!("Failed to send push notifications for new report: {e}");
+  }
+  LiveEvents::publish(LiveEventKind::NewReport(Some(
+    post_report_view.community.id,
+  )));
+
   ActivityChannel::submit_activity(
     SendActivityData::CreateReport(
       post_view.post.ap_id.inner().clone(),
@@ -72,5 +97,7 @@ pub async fn create_post_report(
   )
   .await?;
 
+  post_report_view.proxy_images(&context);
+
   Ok(Json(PostReportResponse { post_report_view }))
 }
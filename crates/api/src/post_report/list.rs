@@ -1,6 +1,7 @@
 use actix_web::web::{Data, Json, Query};
 use lemmy_api_common::{
   context::LemmyContext,
+  image_proxy::ProxyImages,
   post::{ListPostReports, ListPostReportsResponse},
 };
 use lemmy_db_views::{post_report_view::PostReportQuery, structs::LocalUserView};
@@ -19,7 +20,7 @@ pub async fn list_post_reports(
 
   let page = data.page;
   let limit = data.limit;
-  let post_reports = PostReportQuery {
+  let mut post_reports = PostReportQuery {
     community_id,
     unresolved_only,
     page,
@@ -28,5 +29,7 @@ pub async fn list_post_reports(
   .list(&mut context.pool(), &local_user_view)
   .await?;
 
+  post_reports.proxy_images(&context);
+
   Ok(Json(ListPostReportsResponse { post_reports }))
 }
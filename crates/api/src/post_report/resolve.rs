@@ -1,6 +1,7 @@
 use actix_web::web::{Data, Json};
 use lemmy_api_common::{
   context::LemmyContext,
+  image_proxy::ProxyImages,
   post::{PostReportResponse, ResolvePostReport},
   utils::is_mod_or_admin,
 };
@@ -32,7 +33,10 @@ pub async fn resolve_post_report(
       .with_lemmy_type(LemmyErrorType::CouldntResolveReport)?;
   }
 
-  let post_report_view = PostReportView::read(&mut context.pool(), report_id, person_id).await?;
+  let mut post_report_view =
+    PostReportView::read(&mut context.pool(), report_id, person_id).await?;
+
+  post_report_view.proxy_images(&context);
 
   Ok(Json(PostReportResponse { post_report_view }))
 }
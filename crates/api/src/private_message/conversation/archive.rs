@@ -0,0 +1,38 @@
+use actix_web::web::{Data, Json};
+use lemmy_api_common::{
+  context::LemmyContext,
+  image_proxy::ProxyImages,
+  private_message::{ArchiveConversation, ConversationResponse},
+};
+use lemmy_db_schema::source::conversation::{
+  ConversationParticipant,
+  ConversationParticipantUpdateForm,
+};
+use lemmy_db_views::structs::{ConversationView, LocalUserView};
+use lemmy_utils::error::{LemmyError, LemmyErrorExt, LemmyErrorType};
+
+#[tracing::instrument(skip(context))]
+pub async fn archive_conversation(
+  data: Json<ArchiveConversation>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> Result<Json<ConversationResponse>, LemmyError> {
+  let conversation_id = data.conversation_id;
+  let person_id = local_user_view.person.id;
+  // Also checks that the user takes part in the conversation
+  ConversationParticipant::read(&mut context.pool(), conversation_id, person_id).await?;
+
+  let form = ConversationParticipantUpdateForm {
+    archived: Some(data.archived),
+    ..Default::default()
+  };
+  ConversationParticipant::update(&mut context.pool(), conversation_id, person_id, &form)
+    .await
+    .with_lemmy_type(LemmyErrorType::CouldntUpdateConversation)?;
+
+  let mut conversation_view =
+    ConversationView::read(&mut context.pool(), conversation_id, person_id).await?;
+  conversation_view.proxy_images(&context);
+
+  Ok(Json(ConversationResponse { conversation_view }))
+}
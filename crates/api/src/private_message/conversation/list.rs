@@ -0,0 +1,28 @@
+use actix_web::web::{Data, Json, Query};
+use lemmy_api_common::{
+  context::LemmyContext,
+  image_proxy::ProxyImages,
+  private_message::{ListConversations, ListConversationsResponse},
+};
+use lemmy_db_views::{conversation_view::ConversationQuery, structs::LocalUserView};
+use lemmy_utils::error::LemmyError;
+
+#[tracing::instrument(skip(context))]
+pub async fn list_conversations(
+  data: Query<ListConversations>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> Result<Json<ListConversationsResponse>, LemmyError> {
+  let mut conversations = ConversationQuery {
+    archived: data.archived.unwrap_or_default(),
+    message_requests: data.message_requests.unwrap_or_default(),
+    page: data.page,
+    limit: data.limit,
+  }
+  .list(&mut context.pool(), local_user_view.person.id)
+  .await?;
+
+  conversations.proxy_images(&context);
+
+  Ok(Json(ListConversationsResponse { conversations }))
+}
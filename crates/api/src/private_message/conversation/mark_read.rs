@@ -0,0 +1,31 @@
+use actix_web::web::{Data, Json};
+use lemmy_api_common::{
+  context::LemmyContext,
+  image_proxy::ProxyImages,
+  private_message::{ConversationResponse, MarkConversationAsRead},
+};
+use lemmy_db_schema::source::conversation::ConversationParticipant;
+use lemmy_db_views::structs::{ConversationView, LocalUserView};
+use lemmy_utils::error::{LemmyError, LemmyErrorExt, LemmyErrorType};
+
+#[tracing::instrument(skip(context))]
+pub async fn mark_conversation_as_read(
+  data: Json<MarkConversationAsRead>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> Result<Json<ConversationResponse>, LemmyError> {
+  let conversation_id = data.conversation_id;
+  let person_id = local_user_view.person.id;
+  // Also checks that the user takes part in the conversation
+  ConversationParticipant::read(&mut context.pool(), conversation_id, person_id).await?;
+
+  ConversationParticipant::mark_as_read(&mut context.pool(), conversation_id, person_id)
+    .await
+    .with_lemmy_type(LemmyErrorType::CouldntUpdatePrivateMessage)?;
+
+  let mut conversation_view =
+    ConversationView::read(&mut context.pool(), conversation_id, person_id).await?;
+  conversation_view.proxy_images(&context);
+
+  Ok(Json(ConversationResponse { conversation_view }))
+}
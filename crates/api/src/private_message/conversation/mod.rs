@@ -0,0 +1,6 @@
+pub mod accept;
+pub mod archive;
+pub mod list;
+pub mod mark_read;
+pub mod mute;
+pub mod read;
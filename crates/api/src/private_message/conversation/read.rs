@@ -0,0 +1,26 @@
+use actix_web::web::{Data, Json, Query};
+use lemmy_api_common::{
+  context::LemmyContext,
+  image_proxy::ProxyImages,
+  private_message::{ConversationResponse, GetConversation},
+};
+use lemmy_db_views::structs::{ConversationView, LocalUserView};
+use lemmy_utils::error::LemmyError;
+
+#[tracing::instrument(skip(context))]
+pub async fn get_conversation(
+  data: Query<GetConversation>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> Result<Json<ConversationResponse>, LemmyError> {
+  let mut conversation_view = ConversationView::read(
+    &mut context.pool(),
+    data.conversation_id,
+    local_user_view.person.id,
+  )
+  .await?;
+
+  conversation_view.proxy_images(&context);
+
+  Ok(Json(ConversationResponse { conversation_view }))
+}
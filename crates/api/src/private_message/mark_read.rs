@@ -1,10 +1,14 @@
 use actix_web::web::{Data, Json};
 use lemmy_api_common::{
   context::LemmyContext,
+  image_proxy::ProxyImages,
   private_message::{MarkPrivateMessageAsRead, PrivateMessageResponse},
 };
 use lemmy_db_schema::{
-  source::private_message::{PrivateMessage, PrivateMessageUpdateForm},
+  source::{
+    conversation::ConversationParticipant,
+    private_message::{PrivateMessage, PrivateMessageUpdateForm},
+  },
   traits::Crud,
 };
 use lemmy_db_views::structs::{LocalUserView, PrivateMessageView};
@@ -36,8 +40,20 @@ pub async fn mark_pm_as_read(
   )
   .await
   .with_lemmy_type(LemmyErrorType::CouldntUpdatePrivateMessage)?;
+  // Unread counts of conversations are based on the read marker of the participant
+  ConversationParticipant::mark_message_as_read(
+    &mut context.pool(),
+    orig_private_message.conversation_id,
+    local_user_view.person.id,
+    orig_private_message.published,
+    read,
+  )
+  .await
+  .with_lemmy_type(LemmyErrorType::CouldntUpdatePrivateMessage)?;
+
+  let mut view = PrivateMessageView::read(&mut context.pool(), private_message_id).await?;
+  view.proxy_images(&context);
 
-  let view = PrivateMessageView::read(&mut context.pool(), private_message_id).await?;
   Ok(Json(PrivateMessageResponse {
     private_message_view: view,
   }))
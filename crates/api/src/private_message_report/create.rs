@@ -2,8 +2,10 @@ use crate::check_report_reason;
 use actix_web::web::{Data, Json};
 use lemmy_api_common::{
   context::LemmyContext,
+  image_proxy::ProxyImages,
+  live_events::{LiveEventKind, LiveEvents},
   private_message::{CreatePrivateMessageReport, PrivateMessageReportResponse},
-  utils::{sanitize_html_api, send_new_report_email_to_admins},
+  utils::{sanitize_html_api, send_new_report_email_to_admins, send_new_report_push_notifications},
 };
 use lemmy_db_schema::{
   source::{
@@ -15,6 +17,7 @@ use lemmy_db_schema::{
 };
 use lemmy_db_views::structs::{LocalUserView, PrivateMessageReportView};
 use lemmy_utils::error::{LemmyError, LemmyErrorExt, LemmyErrorType};
+use tracing::warn;
 
 #[tracing::instrument(skip(context))]
 pub async fn create_pm_report(
@@ -42,7 +45,7 @@ pub async fn create_pm_report(
     .await
     .with_lemmy_type(LemmyErrorType::CouldntCreateReport)?;
 
-  let private_message_report_view =
+  let mut private_message_report_view =
     PrivateMessageReportView::read(&mut context.pool(), report.id).await?;
 
   // Email the admins
@@ -56,8 +59,24 @@ pub async fn create_pm_report(
     .await?;
   }
 
+  // The report is already stored, so failing to notify shouldn't fail the request
+  if let Err(e) = send_new_report_push_notifications(
+    &private_message_report_view.creator.name,
+    &private_message_report_view.private_message_creator.name,
+    &private_message_report_view.private_message_report.reason,
+    None,
+    &context,
+  )
+  .await
+  {
+    warn!("Failed to send push notifications for new report: {e}");
+  }
+  LiveEvents::publish(LiveEventKind::NewReport(None));
+
   // TODO: consider federating this
 
+  private_message_report_view.proxy_images(&context);
+
   Ok(Json(PrivateMessageReportResponse {
     private_message_report_view,
   }))
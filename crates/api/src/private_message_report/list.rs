@@ -1,6 +1,7 @@
 use actix_web::web::{Data, Json, Query};
 use lemmy_api_common::{
   context::LemmyContext,
+  image_proxy::ProxyImages,
   private_message::{ListPrivateMessageReports, ListPrivateMessageReportsResponse},
   utils::is_admin,
 };
@@ -21,7 +22,7 @@ pub async fn list_pm_reports(
   let unresolved_only = data.unresolved_only.unwrap_or_default();
   let page = data.page;
   let limit = data.limit;
-  let private_message_reports = PrivateMessageReportQuery {
+  let mut private_message_reports = PrivateMessageReportQuery {
     unresolved_only,
     page,
     limit,
@@ -29,6 +30,8 @@ pub async fn list_pm_reports(
   .list(&mut context.pool())
   .await?;
 
+  private_message_reports.proxy_images(&context);
+
   Ok(Json(ListPrivateMessageReportsResponse {
     private_message_reports,
   }))
@@ -1,6 +1,7 @@
 use actix_web::web::{Data, Json};
 use lemmy_api_common::{
   context::LemmyContext,
+  image_proxy::ProxyImages,
   private_message::{PrivateMessageReportResponse, ResolvePrivateMessageReport},
   utils::is_admin,
 };
@@ -28,9 +29,11 @@ pub async fn resolve_pm_report(
       .with_lemmy_type(LemmyErrorType::CouldntResolveReport)?;
   }
 
-  let private_message_report_view =
+  let mut private_message_report_view =
     PrivateMessageReportView::read(&mut context.pool(), report_id).await?;
 
+  private_message_report_view.proxy_images(&context);
+
   Ok(Json(PrivateMessageReportResponse {
     private_message_report_view,
   }))
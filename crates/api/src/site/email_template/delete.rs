@@ -0,0 +1,29 @@
+use actix_web::web::{Data, Json};
+use lemmy_api_common::{
+  context::LemmyContext,
+  email_template::{DeleteEmailTemplate, ListEmailTemplatesResponse},
+  utils::is_admin,
+};
+use lemmy_db_schema::{
+  impls::actor_language::UNDETERMINED_ID,
+  source::email_template::EmailTemplate,
+};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::LemmyError;
+
+/// Removes an email template, so that the built-in text is used again. Returns the remaining
+/// templates.
+#[tracing::instrument(skip(context))]
+pub async fn delete_email_template(
+  data: Json<DeleteEmailTemplate>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> Result<Json<ListEmailTemplatesResponse>, LemmyError> {
+  is_admin(&local_user_view)?;
+
+  let language_id = data.language_id.unwrap_or(UNDETERMINED_ID);
+  EmailTemplate::delete(&mut context.pool(), data.template_type, language_id).await?;
+
+  let email_templates = EmailTemplate::list(&mut context.pool()).await?;
+  Ok(Json(ListEmailTemplatesResponse { email_templates }))
+}
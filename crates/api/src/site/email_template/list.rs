@@ -0,0 +1,20 @@
+use actix_web::web::{Data, Json};
+use lemmy_api_common::{
+  context::LemmyContext,
+  email_template::ListEmailTemplatesResponse,
+  utils::is_admin,
+};
+use lemmy_db_schema::source::email_template::EmailTemplate;
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::LemmyError;
+
+#[tracing::instrument(skip(context))]
+pub async fn list_email_templates(
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> Result<Json<ListEmailTemplatesResponse>, LemmyError> {
+  is_admin(&local_user_view)?;
+
+  let email_templates = EmailTemplate::list(&mut context.pool()).await?;
+  Ok(Json(ListEmailTemplatesResponse { email_templates }))
+}
@@ -0,0 +1,81 @@
+use actix_web::web::{Data, Json};
+use lemmy_api_common::{
+  context::LemmyContext,
+  email_template::{PreviewEmailTemplate, PreviewEmailTemplateResponse},
+  utils::{email_template_variables, is_admin, lang_str_to_lang, render_email},
+};
+use lemmy_db_schema::{
+  impls::actor_language::UNDETERMINED_ID,
+  source::{email_template::EmailTemplate, language::Language},
+  utils::naive_now,
+};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::{error::LemmyError, utils::validation::is_valid_email_template};
+
+/// Renders an email with example values, either from the given template or as it would be sent
+/// to users of the language.
+#[tracing::instrument(skip(context))]
+pub async fn preview_email_template(
+  data: Json<PreviewEmailTemplate>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> Result<Json<PreviewEmailTemplateResponse>, LemmyError> {
+  is_admin(&local_user_view)?;
+
+  let template_type = data.template_type;
+  let variables = email_template_variables(template_type);
+  let template = match (&data.subject, &data.body) {
+    (Some(subject), Some(body)) => {
+      is_valid_email_template(subject, body, variables)?;
+      Some(EmailTemplate {
+        template_type,
+        language_id: data.language_id.unwrap_or(UNDETERMINED_ID),
+        subject: subject.clone(),
+        body: body.clone(),
+        published: naive_now(),
+        updated: None,
+      })
+    }
+    _ => {
+      EmailTemplate::read_for_language(&mut context.pool(), template_type, data.language_id).await?
+    }
+  };
+  let language_code = match data.language_id {
+    Some(language_id) => {
+      Language::read_from_id(&mut context.pool(), language_id)
+        .await?
+        .code
+    }
+    None => local_user_view.local_user.interface_language.clone(),
+  };
+
+  let settings = context.settings();
+  let link = format!("{}/inbox", settings.get_protocol_and_hostname());
+  let example_values = [
+    ("hostname", settings.hostname.as_str()),
+    ("recipient", local_user_view.person.name.as_str()),
+    ("sender", "example_user"),
+    ("content", "<p>Example content</p>"),
+    ("link", &link),
+    ("reason", "Example reason"),
+    ("action", "Your post was removed from Example community"),
+    ("community", "Example community"),
+    ("count", "3"),
+  ];
+  let example_values: Vec<_> = example_values
+    .into_iter()
+    .filter(|(name, _)| variables.contains(name))
+    .collect();
+  let (subject, body) = render_email(
+    template_type,
+    template.as_ref(),
+    &lang_str_to_lang(&language_code),
+    &example_values,
+  );
+
+  Ok(Json(PreviewEmailTemplateResponse {
+    subject,
+    body,
+    variables: variables.iter().map(ToString::to_string).collect(),
+  }))
+}
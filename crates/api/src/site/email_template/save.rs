@@ -0,0 +1,42 @@
+use actix_web::web::{Data, Json};
+use lemmy_api_common::{
+  context::LemmyContext,
+  email_template::{EmailTemplateResponse, SaveEmailTemplate},
+  utils::{email_template_variables, is_admin},
+};
+use lemmy_db_schema::{
+  impls::actor_language::UNDETERMINED_ID,
+  source::email_template::{EmailTemplate, EmailTemplateForm},
+};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::{
+  error::{LemmyError, LemmyErrorExt, LemmyErrorType},
+  utils::validation::is_valid_email_template,
+};
+
+/// Sets the email template for a type and language, replacing the existing one.
+#[tracing::instrument(skip(context))]
+pub async fn save_email_template(
+  data: Json<SaveEmailTemplate>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> Result<Json<EmailTemplateResponse>, LemmyError> {
+  is_admin(&local_user_view)?;
+  is_valid_email_template(
+    &data.subject,
+    &data.body,
+    email_template_variables(data.template_type),
+  )?;
+
+  let form = EmailTemplateForm {
+    template_type: data.template_type,
+    language_id: data.language_id.unwrap_or(UNDETERMINED_ID),
+    subject: data.subject.trim().to_string(),
+    body: data.body.clone(),
+  };
+  let email_template = EmailTemplate::upsert(&mut context.pool(), &form)
+    .await
+    .with_lemmy_type(LemmyErrorType::InvalidEmailTemplate)?;
+
+  Ok(Json(EmailTemplateResponse { email_template }))
+}
@@ -0,0 +1,45 @@
+use crate::site::federation_blocklist::{apply_diff, read_blocklist};
+use actix_web::web::{Data, Json};
+use lemmy_api_common::{
+  context::LemmyContext,
+  site::{
+    DeleteFederationBlocklistSubscription,
+    FederationBlocklistDiff,
+    FederationBlocklistResponse,
+  },
+  utils::is_admin,
+};
+use lemmy_db_schema::{
+  source::federation_blocklist::FederationBlocklistSubscription,
+  traits::Crud,
+};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::LemmyError;
+
+/// Unsubscribes from a shared blocklist. The instances which were imported from it are unblocked.
+#[tracing::instrument(skip(context))]
+pub async fn delete_federation_blocklist_subscription(
+  data: Json<DeleteFederationBlocklistSubscription>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> Result<Json<FederationBlocklistResponse>, LemmyError> {
+  is_admin(&local_user_view)?;
+
+  let subscription =
+    FederationBlocklistSubscription::read(&mut context.pool(), data.subscription_id).await?;
+  let diff = FederationBlocklistDiff {
+    removed: read_blocklist(&mut context.pool())
+      .await?
+      .into_iter()
+      .filter(|e| e.subscription_id == Some(subscription.id))
+      .collect(),
+    ..Default::default()
+  };
+  apply_diff(&diff, local_user_view.person.id, &mut context.pool()).await?;
+  FederationBlocklistSubscription::delete(&mut context.pool(), subscription.id).await?;
+
+  Ok(Json(FederationBlocklistResponse {
+    diff,
+    subscription: Some(subscription),
+  }))
+}
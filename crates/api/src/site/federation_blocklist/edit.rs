@@ -0,0 +1,59 @@
+use crate::site::federation_blocklist::{
+  apply_diff,
+  diff_blocklist,
+  read_blocklist,
+  BlocklistSource,
+};
+use actix_web::web::{Data, Json};
+use lemmy_api_common::{
+  context::LemmyContext,
+  site::{
+    EditFederationBlock,
+    FederationBlocklistDiff,
+    FederationBlocklistEntry,
+    FederationBlocklistResponse,
+  },
+  utils::{is_admin, sanitize_html_api_opt},
+};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::{LemmyError, LemmyErrorType};
+
+#[tracing::instrument(skip(context))]
+pub async fn edit_federation_block(
+  data: Json<EditFederationBlock>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> Result<Json<FederationBlocklistResponse>, LemmyError> {
+  is_admin(&local_user_view)?;
+
+  let domain = data.domain.trim().to_lowercase();
+  if domain.is_empty() || domain == context.settings().hostname {
+    Err(LemmyErrorType::InvalidFederationBlocklist)?
+  }
+
+  let existing = read_blocklist(&mut context.pool()).await?;
+  let diff = if data.block {
+    let entry = FederationBlocklistEntry {
+      domain,
+      severity: data.severity.unwrap_or_default(),
+      public_comment: sanitize_html_api_opt(&data.public_comment),
+      private_comment: sanitize_html_api_opt(&data.private_comment),
+      subscription_id: None,
+    };
+    diff_blocklist(&existing, vec![entry], BlocklistSource::Manual)
+  } else {
+    FederationBlocklistDiff {
+      removed: existing
+        .into_iter()
+        .filter(|e| e.domain == domain)
+        .collect(),
+      ..Default::default()
+    }
+  };
+  apply_diff(&diff, local_user_view.person.id, &mut context.pool()).await?;
+
+  Ok(Json(FederationBlocklistResponse {
+    diff,
+    subscription: None,
+  }))
+}
@@ -0,0 +1,68 @@
+use crate::site::federation_blocklist::{fetch_blocklist, import_blocklist, BlocklistSource};
+use actix_web::web::{Data, Json};
+use lemmy_api_common::{
+  context::LemmyContext,
+  site::{FederationBlocklistResponse, ImportFederationBlocklist},
+  utils::is_admin,
+};
+use lemmy_db_schema::{
+  source::federation_blocklist::{
+    FederationBlocklistSubscription,
+    FederationBlocklistSubscriptionInsertForm,
+    FederationBlocklistSubscriptionUpdateForm,
+  },
+  traits::Crud,
+  utils::naive_now,
+};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::{LemmyError, LemmyErrorType};
+
+/// Imports a shared blocklist once, or subscribes to it so that it is refreshed every day. With
+/// `preview` the changes are only returned, so that admins can review them first.
+#[tracing::instrument(skip(context))]
+pub async fn import_federation_blocklist(
+  data: Json<ImportFederationBlocklist>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> Result<Json<FederationBlocklistResponse>, LemmyError> {
+  is_admin(&local_user_view)?;
+  let subscribe = data.subscribe.unwrap_or(false);
+  let preview = data.preview.unwrap_or(false);
+
+  // Subscriptions always use the list at the given url
+  let csv = match (&data.csv, &data.url) {
+    (Some(csv), _) if !subscribe => csv.clone(),
+    (_, Some(url)) => fetch_blocklist(url, &context).await?,
+    _ => Err(LemmyErrorType::InvalidFederationBlocklist)?,
+  };
+
+  let mut subscription = None;
+  let source = if let (true, Some(url)) = (subscribe, &data.url) {
+    let url = url.clone().into();
+    subscription =
+      FederationBlocklistSubscription::read_from_url(&mut context.pool(), &url).await?;
+    if subscription.is_none() && !preview {
+      let form = FederationBlocklistSubscriptionInsertForm {
+        url,
+        admin_person_id: local_user_view.person.id,
+      };
+      subscription =
+        Some(FederationBlocklistSubscription::create(&mut context.pool(), &form).await?);
+    }
+    BlocklistSource::Subscription(subscription.as_ref().map(|s| s.id))
+  } else {
+    BlocklistSource::Manual
+  };
+
+  let diff = import_blocklist(&csv, source, local_user_view.person.id, preview, &context).await?;
+
+  if let (Some(s), false) = (&subscription, preview) {
+    let form = FederationBlocklistSubscriptionUpdateForm {
+      updated: Some(Some(naive_now())),
+    };
+    subscription =
+      Some(FederationBlocklistSubscription::update(&mut context.pool(), s.id, &form).await?);
+  }
+
+  Ok(Json(FederationBlocklistResponse { diff, subscription }))
+}
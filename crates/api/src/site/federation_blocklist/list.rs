@@ -0,0 +1,41 @@
+use crate::site::federation_blocklist::read_blocklist;
+use actix_web::web::{Data, Json};
+use lemmy_api_common::{
+  context::LemmyContext,
+  site::ListFederationBlocklistResponse,
+  utils::{check_private_instance, is_admin},
+};
+use lemmy_db_schema::source::{
+  federation_blocklist::FederationBlocklistSubscription,
+  local_site::LocalSite,
+};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::LemmyError;
+
+#[tracing::instrument(skip(context))]
+pub async fn list_federation_blocklist(
+  context: Data<LemmyContext>,
+  local_user_view: Option<LocalUserView>,
+) -> Result<Json<ListFederationBlocklistResponse>, LemmyError> {
+  let local_site = LocalSite::read(&mut context.pool()).await?;
+  check_private_instance(&local_user_view, &local_site)?;
+
+  let mut blocklist = read_blocklist(&mut context.pool()).await?;
+  let is_admin = local_user_view
+    .as_ref()
+    .map(|l| is_admin(l).is_ok())
+    .unwrap_or(false);
+  let subscriptions = if is_admin {
+    FederationBlocklistSubscription::list(&mut context.pool()).await?
+  } else {
+    for entry in &mut blocklist {
+      entry.private_comment = None;
+    }
+    vec![]
+  };
+
+  Ok(Json(ListFederationBlocklistResponse {
+    blocklist,
+    subscriptions,
+  }))
+}
@@ -0,0 +1,332 @@
+use lemmy_api_common::{
+  context::LemmyContext,
+  site::{FederationBlocklistDiff, FederationBlocklistEntry},
+  utils::sanitize_html_api,
+};
+use lemmy_db_schema::{
+  newtypes::PersonId,
+  source::{
+    federation_blocklist::{
+      FederationBlockList,
+      FederationBlockListForm,
+      FederationBlocklistSubscription,
+      FederationBlocklistSubscriptionUpdateForm,
+    },
+    instance::Instance,
+    moderator::{AdminBlockInstance, AdminBlockInstanceForm},
+  },
+  traits::Crud,
+  utils::{naive_now, DbPool},
+  FederationBlockSeverity,
+};
+use lemmy_utils::error::{LemmyErrorExt, LemmyErrorType, LemmyResult};
+use tracing::warn;
+use url::Url;
+
+pub mod delete_subscription;
+pub mod edit;
+pub mod import;
+pub mod list;
+
+/// Where imported entries of the federation blocklist come from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum BlocklistSource {
+  /// Imported once by an admin. These take over existing entries from subscriptions.
+  Manual,
+  /// Fetched from a shared list. The id is missing when previewing a new subscription.
+  Subscription(Option<i32>),
+}
+
+/// Fetches the shared blocklists which admins subscribed to again, and applies the changes.
+pub async fn refresh_blocklist_subscriptions(context: &LemmyContext) -> LemmyResult<()> {
+  let subscriptions = FederationBlocklistSubscription::list(&mut context.pool()).await?;
+  for subscription in subscriptions {
+    let csv = match fetch_blocklist(subscription.url.inner(), context).await {
+      Ok(csv) => csv,
+      Err(e) => {
+        warn!(
+          "Failed to fetch federation blocklist {}: {e}",
+          subscription.url
+        );
+        continue;
+      }
+    };
+    let source = BlocklistSource::Subscription(Some(subscription.id));
+    if let Err(e) =
+      import_blocklist(&csv, source, subscription.admin_person_id, false, context).await
+    {
+      warn!(
+        "Failed to import federation blocklist {}: {e}",
+        subscription.url
+      );
+      continue;
+    }
+
+    let form = FederationBlocklistSubscriptionUpdateForm {
+      updated: Some(Some(naive_now())),
+    };
+    FederationBlocklistSubscription::update(&mut context.pool(), subscription.id, &form).await?;
+  }
+  Ok(())
+}
+
+async fn fetch_blocklist(url: &Url, context: &LemmyContext) -> LemmyResult<String> {
+  let res = context.client().get(url.as_str()).send().await?;
+  if !res.status().is_success() {
+    Err(LemmyErrorType::InvalidFederationBlocklist)?
+  }
+  Ok(res.text().await?)
+}
+
+/// Compares the given list with the current blocklist, and applies the changes unless `preview`
+/// is set.
+async fn import_blocklist(
+  csv: &str,
+  source: BlocklistSource,
+  admin_person_id: PersonId,
+  preview: bool,
+  context: &LemmyContext,
+) -> LemmyResult<FederationBlocklistDiff> {
+  let entries = parse_blocklist(csv, &context.settings().hostname)?;
+  let existing = read_blocklist(&mut context.pool()).await?;
+  let diff = diff_blocklist(&existing, entries, source);
+  if !preview {
+    apply_diff(&diff, admin_person_id, &mut context.pool()).await?;
+  }
+  Ok(diff)
+}
+
+async fn read_blocklist(pool: &mut DbPool<'_>) -> LemmyResult<Vec<FederationBlocklistEntry>> {
+  Ok(
+    FederationBlockList::list(pool)
+      .await?
+      .into_iter()
+      .map(|(entry, instance)| FederationBlocklistEntry {
+        domain: instance.domain,
+        severity: entry.severity,
+        public_comment: entry.public_comment,
+        private_comment: entry.private_comment,
+        subscription_id: entry.subscription_id,
+      })
+      .collect(),
+  )
+}
+
+/// Parses a list in Mastodon's domain_blocks CSV format. Columns are found by their header, only
+/// `#domain` is required. Entries with severity `noop` only reject media or reports, and are
+/// skipped like obfuscated domains and the local instance.
+fn parse_blocklist(csv: &str, local_domain: &str) -> LemmyResult<Vec<FederationBlocklistEntry>> {
+  let mut reader = csv::ReaderBuilder::new()
+    .flexible(true)
+    .trim(csv::Trim::All)
+    .from_reader(csv.as_bytes());
+  let headers: Vec<String> = reader
+    .headers()
+    .with_lemmy_type(LemmyErrorType::InvalidFederationBlocklist)?
+    .iter()
+    .map(|h| h.trim_start_matches('#').to_lowercase())
+    .collect();
+  let column = |name: &str| headers.iter().position(|h| h == name);
+  let domain_column = column("domain").ok_or(LemmyErrorType::InvalidFederationBlocklist)?;
+  let severity_column = column("severity");
+  let public_comment_column = column("public_comment");
+  let private_comment_column = column("private_comment");
+
+  let mut entries = vec![];
+  for record in reader.records() {
+    let record = record.with_lemmy_type(LemmyErrorType::InvalidFederationBlocklist)?;
+    let field =
+      |column: Option<usize>| column.and_then(|c| record.get(c)).filter(|f| !f.is_empty());
+    let Some(domain) = field(Some(domain_column)).map(str::to_lowercase) else {
+      continue;
+    };
+    if domain.contains('*') || domain == local_domain {
+      continue;
+    }
+    let severity = match field(severity_column) {
+      Some("noop") => continue,
+      Some("silence") => FederationBlockSeverity::Silence,
+      _ => FederationBlockSeverity::Suspend,
+    };
+    entries.push(FederationBlocklistEntry {
+      domain,
+      severity,
+      public_comment: field(public_comment_column).map(sanitize_html_api),
+      private_comment: field(private_comment_column).map(sanitize_html_api),
+      subscription_id: None,
+    });
+  }
+  entries.sort_by(|a, b| a.domain.cmp(&b.domain));
+  entries.dedup_by(|a, b| a.domain == b.domain);
+  Ok(entries)
+}
+
+/// Subscriptions only change their own entries, so that they don't override entries which were
+/// added by hand or come from another list. Only subscriptions remove entries which are missing
+/// from the new list.
+fn diff_blocklist(
+  existing: &[FederationBlocklistEntry],
+  new: Vec<FederationBlocklistEntry>,
+  source: BlocklistSource,
+) -> FederationBlocklistDiff {
+  let subscription_id = match source {
+    BlocklistSource::Manual => None,
+    BlocklistSource::Subscription(id) => id,
+  };
+  let owned = |entry: &FederationBlocklistEntry| match source {
+    BlocklistSource::Manual => true,
+    BlocklistSource::Subscription(id) => id.is_some() && entry.subscription_id == id,
+  };
+
+  let mut diff = FederationBlocklistDiff::default();
+  if matches!(source, BlocklistSource::Subscription(_)) {
+    diff.removed = existing
+      .iter()
+      .filter(|e| owned(e) && !new.iter().any(|n| n.domain == e.domain))
+      .cloned()
+      .collect();
+  }
+  for mut entry in new {
+    entry.subscription_id = subscription_id;
+    match existing.iter().find(|e| e.domain == entry.domain) {
+      None => diff.added.push(entry),
+      Some(e) if owned(e) && e != &entry => diff.changed.push(entry),
+      Some(_) => {}
+    }
+  }
+  diff
+}
+
+/// Writes the changes to the blocklist, and records each of them in the modlog.
+async fn apply_diff(
+  diff: &FederationBlocklistDiff,
+  admin_person_id: PersonId,
+  pool: &mut DbPool<'_>,
+) -> LemmyResult<()> {
+  let added = diff.added.iter().map(|e| (e, None));
+  let changed = diff.changed.iter().map(|e| (e, Some(naive_now())));
+  for (entry, updated) in added.chain(changed) {
+    let instance = Instance::read_or_create(pool, entry.domain.clone()).await?;
+    let form = FederationBlockListForm {
+      instance_id: instance.id,
+      updated,
+      severity: entry.severity,
+      public_comment: entry.public_comment.clone(),
+      private_comment: entry.private_comment.clone(),
+      subscription_id: entry.subscription_id,
+    };
+    FederationBlockList::upsert(pool, &form).await?;
+
+    let form = AdminBlockInstanceForm {
+      admin_person_id,
+      instance_id: instance.id,
+      severity: entry.severity,
+      blocked: true,
+      reason: entry.public_comment.clone(),
+    };
+    AdminBlockInstance::create(pool, &form).await?;
+  }
+
+  for entry in &diff.removed {
+    let instance = Instance::read_or_create(pool, entry.domain.clone()).await?;
+    FederationBlockList::delete(pool, instance.id).await?;
+
+    let form = AdminBlockInstanceForm {
+      admin_person_id,
+      instance_id: instance.id,
+      severity: entry.severity,
+      blocked: false,
+      reason: None,
+    };
+    AdminBlockInstance::create(pool, &form).await?;
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use super::{diff_blocklist, parse_blocklist, BlocklistSource};
+  use lemmy_api_common::site::FederationBlocklistEntry;
+  use lemmy_db_schema::FederationBlockSeverity;
+
+  fn entry(
+    domain: &str,
+    severity: FederationBlockSeverity,
+    subscription_id: Option<i32>,
+  ) -> FederationBlocklistEntry {
+    FederationBlocklistEntry {
+      domain: domain.to_string(),
+      severity,
+      public_comment: None,
+      private_comment: None,
+      subscription_id,
+    }
+  }
+
+  #[test]
+  fn test_parse_mastodon_blocklist() {
+    let csv = "#domain,#severity,#reject_media,#reject_reports,#public_comment,#obfuscate\n\
+      spam.tld,suspend,true,true,\"Spam, lots of it\",false\n\
+      Loud.tld,silence,false,false,,false\n\
+      media.tld,noop,true,false,,false\n\
+      hidden.*,suspend,true,true,,true\n\
+      lemmy.tld,suspend,false,false,,false\n";
+    let entries = parse_blocklist(csv, "lemmy.tld").unwrap();
+
+    assert_eq!(2, entries.len());
+    assert_eq!("loud.tld", entries[0].domain);
+    assert_eq!(FederationBlockSeverity::Silence, entries[0].severity);
+    assert_eq!(None, entries[0].public_comment);
+    assert_eq!("spam.tld", entries[1].domain);
+    assert_eq!(FederationBlockSeverity::Suspend, entries[1].severity);
+    assert_eq!(
+      Some("Spam, lots of it".to_string()),
+      entries[1].public_comment
+    );
+
+    assert!(parse_blocklist("severity\nsuspend\n", "lemmy.tld").is_err());
+  }
+
+  #[test]
+  fn test_diff_blocklist() {
+    use FederationBlockSeverity::*;
+    let existing = vec![
+      entry("manual.tld", Suspend, None),
+      entry("other-list.tld", Suspend, Some(2)),
+      entry("gone.tld", Suspend, Some(1)),
+      entry("same.tld", Silence, Some(1)),
+      entry("upgraded.tld", Silence, Some(1)),
+    ];
+    let new = vec![
+      entry("manual.tld", Silence, None),
+      entry("other-list.tld", Silence, None),
+      entry("same.tld", Silence, None),
+      entry("upgraded.tld", Suspend, None),
+      entry("new.tld", Suspend, None),
+    ];
+
+    let diff = diff_blocklist(
+      &existing,
+      new.clone(),
+      BlocklistSource::Subscription(Some(1)),
+    );
+    assert_eq!(vec![entry("new.tld", Suspend, Some(1))], diff.added);
+    assert_eq!(vec![entry("upgraded.tld", Suspend, Some(1))], diff.changed);
+    assert_eq!(vec![entry("gone.tld", Suspend, Some(1))], diff.removed);
+
+    // A new subscription doesn't touch any existing entries
+    let diff = diff_blocklist(&existing, new.clone(), BlocklistSource::Subscription(None));
+    assert_eq!(1, diff.added.len());
+    assert!(diff.changed.is_empty());
+    assert!(diff.removed.is_empty());
+
+    // Manual imports take over entries from subscriptions, but don't remove anything
+    let diff = diff_blocklist(&existing, new, BlocklistSource::Manual);
+    assert_eq!(1, diff.added.len());
+    assert_eq!(4, diff.changed.len());
+    assert!(diff.removed.is_empty());
+  }
+}
@@ -1,5 +1,10 @@
 use actix_web::web::{Data, Json};
-use lemmy_api_common::{context::LemmyContext, site::GetSiteResponse, utils::is_admin};
+use lemmy_api_common::{
+  context::LemmyContext,
+  image_proxy::ProxyImages,
+  site::GetSiteResponse,
+  utils::is_admin,
+};
 use lemmy_db_schema::{
   source::{
     actor_language::SiteLanguage,
@@ -51,8 +56,8 @@ pub async fn leave_admin(
   ModAdd::create(&mut context.pool(), &form).await?;
 
   // Reread site and admins
-  let site_view = SiteView::read_local(&mut context.pool()).await?;
-  let admins = PersonView::admins(&mut context.pool()).await?;
+  let mut site_view = SiteView::read_local(&mut context.pool()).await?;
+  let mut admins = PersonView::admins(&mut context.pool()).await?;
 
   let all_languages = Language::read_all(&mut context.pool()).await?;
   let discussion_languages = SiteLanguage::read_local_raw(&mut context.pool()).await?;
@@ -60,6 +65,9 @@ pub async fn leave_admin(
   let custom_emojis =
     CustomEmojiView::get_all(&mut context.pool(), site_view.local_site.id).await?;
 
+  site_view.proxy_images(&context);
+  admins.proxy_images(&context);
+
   Ok(Json(GetSiteResponse {
     site_view,
     admins,
@@ -0,0 +1,28 @@
+use actix_web::web::{Data, Json, Query};
+use lemmy_api_common::{
+  context::LemmyContext,
+  site::{ListMediaScans, ListMediaScansResponse},
+  utils::is_admin,
+};
+use lemmy_db_views::structs::{LocalUserView, MediaScanView};
+use lemmy_utils::error::LemmyError;
+
+/// Lists the results of the media scanner, for reviewing quarantined media.
+#[tracing::instrument(skip(context))]
+pub async fn list_media_scans(
+  data: Query<ListMediaScans>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> Result<Json<ListMediaScansResponse>, LemmyError> {
+  is_admin(&local_user_view)?;
+
+  let media_scans = MediaScanView::list(
+    &mut context.pool(),
+    data.unresolved_only.unwrap_or_default(),
+    data.page,
+    data.limit,
+  )
+  .await?;
+
+  Ok(Json(ListMediaScansResponse { media_scans }))
+}
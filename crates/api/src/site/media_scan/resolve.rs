@@ -0,0 +1,34 @@
+use actix_web::web::{Data, Json};
+use lemmy_api_common::{
+  context::LemmyContext,
+  request::purge_image_from_pictrs_by_alias,
+  site::{MediaScanResponse, ResolveMediaScan},
+  utils::is_admin,
+};
+use lemmy_db_schema::source::media_scan::MediaScan;
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::LemmyError;
+
+/// Marks a media scan as reviewed. Quarantined media is served again afterwards, unless it is
+/// purged.
+#[tracing::instrument(skip(context))]
+pub async fn resolve_media_scan(
+  data: Json<ResolveMediaScan>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> Result<Json<MediaScanResponse>, LemmyError> {
+  is_admin(&local_user_view)?;
+
+  let media_scan = MediaScan::read(&mut context.pool(), data.id).await?;
+  if data.purge {
+    purge_image_from_pictrs_by_alias(&media_scan.pictrs_alias, &context).await?;
+  }
+  let media_scan = MediaScan::resolve(
+    &mut context.pool(),
+    media_scan.id,
+    local_user_view.person.id,
+  )
+  .await?;
+
+  Ok(Json(MediaScanResponse { media_scan }))
+}
@@ -1,6 +1,9 @@
 pub mod block;
+pub mod email_template;
 pub mod federated_instances;
+pub mod federation_blocklist;
 pub mod leave_admin;
+pub mod media_scan;
 pub mod mod_log;
 pub mod purge;
 pub mod registration_applications;
@@ -1,6 +1,7 @@
 use actix_web::web::{Data, Json, Query};
 use lemmy_api_common::{
   context::LemmyContext,
+  image_proxy::ProxyImages,
   site::{GetModlog, GetModlogResponse},
   utils::{check_private_instance, is_admin, is_mod_or_admin},
 };
@@ -11,6 +12,7 @@ use lemmy_db_schema::{
 };
 use lemmy_db_views::structs::LocalUserView;
 use lemmy_db_views_moderator::structs::{
+  AdminBlockInstanceView,
   AdminPurgeCommentView,
   AdminPurgeCommunityView,
   AdminPurgePersonView,
@@ -125,6 +127,7 @@ pub async fn get_mod_log(
     admin_purged_communities,
     admin_purged_posts,
     admin_purged_comments,
+    admin_blocked_instances,
   ) = if data.community_id.is_none() {
     (
       match type_ {
@@ -165,13 +168,18 @@ pub async fn get_mod_log(
         }
         _ => Default::default(),
       },
+      match type_ {
+        All | AdminBlockInstance if other_person_id.is_none() => {
+          AdminBlockInstanceView::list(&mut context.pool(), params).await?
+        }
+        _ => Default::default(),
+      },
     )
   } else {
     Default::default()
   };
 
-  // Return the jwt
-  Ok(Json(GetModlogResponse {
+  let mut modlog = GetModlogResponse {
     removed_posts,
     locked_posts,
     featured_posts,
@@ -187,5 +195,9 @@ pub async fn get_mod_log(
     admin_purged_posts,
     admin_purged_comments,
     hidden_communities,
-  }))
+    admin_blocked_instances,
+  };
+  modlog.proxy_images(&context);
+
+  Ok(Json(modlog))
 }
@@ -1,13 +1,11 @@
 use actix_web::web::{Data, Json};
 use lemmy_api_common::{
   context::LemmyContext,
-  request::delete_image_from_pictrs,
   site::{PurgeItemResponse, PurgePerson},
-  utils::{is_admin, sanitize_html_api_opt},
+  utils::{delete_uploaded_images, is_admin, sanitize_html_api_opt},
 };
 use lemmy_db_schema::{
   source::{
-    image_upload::ImageUpload,
     moderator::{AdminPurgePerson, AdminPurgePersonForm},
     person::Person,
   },
@@ -28,14 +26,9 @@ pub async fn purge_person(
   // Read the person to get their images
   let person_id = data.person_id;
 
-  let local_user = LocalUserView::read_person(&mut context.pool(), person_id).await?;
-  let pictrs_uploads =
-    ImageUpload::get_all_by_local_user_id(&mut context.pool(), &local_user.local_user.id).await?;
-
-  for upload in pictrs_uploads {
-    delete_image_from_pictrs(&upload.pictrs_alias, &upload.pictrs_delete_token, &context)
-      .await
-      .ok();
+  // Only local users have uploads
+  if let Ok(local_user) = LocalUserView::read_person(&mut context.pool(), person_id).await {
+    delete_uploaded_images(local_user.local_user.id, &context).await?;
   }
 
   Person::delete(&mut context.pool(), person_id).await?;
@@ -1,8 +1,9 @@
 use actix_web::web::{Data, Json};
 use lemmy_api_common::{
   context::LemmyContext,
+  image_proxy::ProxyImages,
   site::{ApproveRegistrationApplication, RegistrationApplicationResponse},
-  utils::{is_admin, send_application_approved_email},
+  utils::{is_admin, send_application_approved_email, send_application_denied_email},
 };
 use lemmy_db_schema::{
   source::{
@@ -44,18 +45,27 @@ pub async fn approve_registration_application(
   let approved_user_id = registration_application.local_user_id;
   LocalUser::update(&mut context.pool(), approved_user_id, &local_user_form).await?;
 
-  if data.approve {
-    let approved_local_user_view =
-      LocalUserView::read(&mut context.pool(), approved_user_id).await?;
-
-    if approved_local_user_view.local_user.email.is_some() {
-      send_application_approved_email(&approved_local_user_view, context.settings()).await?;
+  let applicant_view = LocalUserView::read(&mut context.pool(), approved_user_id).await?;
+  if applicant_view.local_user.email.is_some() {
+    if data.approve {
+      send_application_approved_email(&applicant_view, &mut context.pool(), context.settings())
+        .await?;
+    } else {
+      let deny_reason = data.deny_reason.as_deref().unwrap_or_default();
+      send_application_denied_email(
+        &applicant_view,
+        deny_reason,
+        &mut context.pool(),
+        context.settings(),
+      )
+      .await?;
     }
   }
 
   // Read the view
-  let registration_application =
+  let mut registration_application =
     RegistrationApplicationView::read(&mut context.pool(), app_id).await?;
+  registration_application.proxy_images(&context);
 
   Ok(Json(RegistrationApplicationResponse {
     registration_application,
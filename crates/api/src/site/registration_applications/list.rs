@@ -1,6 +1,7 @@
 use actix_web::web::{Data, Json, Query};
 use lemmy_api_common::{
   context::LemmyContext,
+  image_proxy::ProxyImages,
   site::{ListRegistrationApplications, ListRegistrationApplicationsResponse},
   utils::is_admin,
 };
@@ -27,7 +28,7 @@ pub async fn list_registration_applications(
 
   let page = data.page;
   let limit = data.limit;
-  let registration_applications = RegistrationApplicationQuery {
+  let mut registration_applications = RegistrationApplicationQuery {
     unread_only,
     verified_email_only,
     page,
@@ -36,6 +37,8 @@ pub async fn list_registration_applications(
   .list(&mut context.pool())
   .await?;
 
+  registration_applications.proxy_images(&context);
+
   Ok(Json(ListRegistrationApplicationsResponse {
     registration_applications,
   }))
@@ -2,8 +2,20 @@ use crate::{
   comment::CommentResponse,
   community::CommunityResponse,
   context::LemmyContext,
+  image_proxy::ProxyImages,
   post::PostResponse,
-  utils::{check_person_block, get_interface_language, is_mod_or_admin, send_email_to_user},
+  reply_by_email::ReplyTarget,
+  utils::{
+    build_email,
+    check_limited_instance,
+    check_person_block,
+    check_person_instance_block,
+    get_notification_preference,
+    is_mod_or_admin,
+    is_reply_muted,
+    send_email_to_user,
+  },
+  web_push::{send_push_notification, PushNotification},
 };
 use actix_web::web::Json;
 use lemmy_db_schema::{
@@ -14,9 +26,11 @@ use lemmy_db_schema::{
     comment_reply::{CommentReply, CommentReplyInsertForm},
     person::Person,
     person_mention::{PersonMention, PersonMentionInsertForm},
-    post::Post,
+    post::{Post, PostSubscription},
   },
   traits::Crud,
+  EmailTemplateType,
+  NotificationType,
 };
 use lemmy_db_views::structs::{CommentView, LocalUserView, PostView};
 use lemmy_db_views_actor::structs::CommunityView;
@@ -29,7 +43,8 @@ pub async fn build_comment_response(
   recipient_ids: Vec<LocalUserId>,
 ) -> Result<CommentResponse, LemmyError> {
   let person_id = local_user_view.map(|l| l.person.id);
-  let comment_view = CommentView::read(&mut context.pool(), comment_id, person_id).await?;
+  let mut comment_view = CommentView::read(&mut context.pool(), comment_id, person_id).await?;
+  comment_view.proxy_images(context);
   Ok(CommentResponse {
     comment_view,
     recipient_ids,
@@ -46,13 +61,14 @@ pub async fn build_community_response(
       .await
       .is_ok();
   let person_id = local_user_view.person.id;
-  let community_view = CommunityView::read(
+  let mut community_view = CommunityView::read(
     &mut context.pool(),
     community_id,
     Some(person_id),
     is_mod_or_admin,
   )
   .await?;
+  community_view.proxy_images(context);
   let discussion_languages = CommunityLanguage::read(&mut context.pool(), community_id).await?;
 
   Ok(Json(CommunityResponse {
@@ -70,13 +86,14 @@ pub async fn build_post_response(
   let is_mod_or_admin = is_mod_or_admin(&mut context.pool(), person_id, community_id)
     .await
     .is_ok();
-  let post_view = PostView::read(
+  let mut post_view = PostView::read(
     &mut context.pool(),
     post_id,
     Some(person_id),
     is_mod_or_admin,
   )
   .await?;
+  post_view.proxy_images(context);
   Ok(Json(PostResponse { post_view }))
 }
 
@@ -92,6 +109,11 @@ pub async fn send_local_notifs(
 ) -> Result<Vec<LocalUserId>, LemmyError> {
   let mut recipient_ids = Vec::new();
   let inbox_link = format!("{}/inbox", context.settings().get_protocol_and_hostname());
+  let comment_link = format!(
+    "{}/comment/{}",
+    context.settings().get_protocol_and_hostname(),
+    comment.id
+  );
 
   // Send the local mentions
   for mention in mentions
@@ -101,117 +123,253 @@ pub async fn send_local_notifs(
     let mention_name = mention.name.clone();
     let user_view = LocalUserView::read_from_name(&mut context.pool(), &mention_name).await;
     if let Ok(mention_user_view) = user_view {
+      // Don't notify if the mentioned person has blocked the creator or their instance, or if
+      // the creator is from a limited instance and not followed by them
+      if is_blocked_by(person, mention_user_view.person.id, context).await
+        || check_limited_instance(person, mention_user_view.person.id, &mut context.pool())
+          .await
+          .is_err()
+      {
+        continue;
+      }
+      let Some(preference) = get_notification_preference(
+        &mention_user_view,
+        person.id,
+        NotificationType::Mention,
+        &mut context.pool(),
+      )
+      .await?
+      else {
+        continue;
+      };
+
       // TODO
       // At some point, make it so you can't tag the parent creator either
       // This can cause two notifications, one for reply and the other for mention
       recipient_ids.push(mention_user_view.local_user.id);
 
-      let user_mention_form = PersonMentionInsertForm {
-        recipient_id: mention_user_view.person.id,
-        comment_id: comment.id,
-        read: None,
-      };
+      if preference.in_app {
+        let user_mention_form = PersonMentionInsertForm {
+          recipient_id: mention_user_view.person.id,
+          comment_id: comment.id,
+          read: None,
+        };
 
-      // Allow this to fail softly, since comment edits might re-update or replace it
-      // Let the uniqueness handle this fail
-      PersonMention::create(&mut context.pool(), &user_mention_form)
-        .await
-        .ok();
+        // Allow this to fail softly, since comment edits might re-update or replace it
+        // Let the uniqueness handle this fail
+        PersonMention::create(&mut context.pool(), &user_mention_form)
+          .await
+          .ok();
+      }
 
       // Send an email to those local users that have notifications on
       if do_send_email {
-        let lang = get_interface_language(&mention_user_view);
-        send_email_to_user(
+        let (subject, body) = build_email(
+          EmailTemplateType::Mention,
           &mention_user_view,
-          &lang.notification_mentioned_by_subject(&person.name),
-          &lang.notification_mentioned_by_body(&comment.content, &inbox_link, &person.name),
+          &[
+            ("sender", &person.name),
+            ("content", &comment.content),
+            ("link", &inbox_link),
+          ],
+          &mut context.pool(),
           context.settings(),
         )
-        .await
+        .await;
+        if preference.email {
+          send_email_to_user(
+            &mention_user_view,
+            &subject,
+            &body,
+            Some(ReplyTarget::Comment(comment.id)),
+            context,
+          )
+          .await;
+        }
+        send_push_notification(
+          mention_user_view.local_user.id,
+          PushNotification::new(
+            NotificationType::Mention,
+            &subject,
+            &comment.content,
+            &comment_link,
+          ),
+          context,
+        );
       }
     }
   }
 
   // Send comment_reply to the parent commenter / poster
-  if let Some(parent_comment_id) = comment.parent_comment_id() {
-    let parent_comment = Comment::read(&mut context.pool(), parent_comment_id).await?;
-
-    // Get the parent commenter local_user
-    let parent_creator_id = parent_comment.creator_id;
-
-    // Only add to recipients if that person isn't blocked
-    let creator_blocked = check_person_block(person.id, parent_creator_id, &mut context.pool())
-      .await
-      .is_err();
+  let parent_comment = match comment.parent_comment_id() {
+    Some(parent_comment_id) => Some(Comment::read(&mut context.pool(), parent_comment_id).await?),
+    None => None,
+  };
+  // If there's no parent, its the post creator
+  let parent_creator_id = parent_comment
+    .as_ref()
+    .map(|c| c.creator_id)
+    .unwrap_or(post.creator_id);
 
-    // Don't send a notif to yourself
-    if parent_comment.creator_id != person.id && !creator_blocked {
-      let user_view = LocalUserView::read_person(&mut context.pool(), parent_creator_id).await;
-      if let Ok(parent_user_view) = user_view {
+  // Don't send a notif to yourself, or if that person blocked you or muted the thread
+  if parent_creator_id != person.id
+    && !is_blocked_by(person, parent_creator_id, context).await
+    && !is_reply_muted(parent_creator_id, comment, &mut context.pool()).await?
+  {
+    let user_view = LocalUserView::read_person(&mut context.pool(), parent_creator_id).await;
+    if let Ok(parent_user_view) = user_view {
+      let preference = get_notification_preference(
+        &parent_user_view,
+        person.id,
+        NotificationType::Reply,
+        &mut context.pool(),
+      )
+      .await?;
+      if let Some(preference) = preference {
         recipient_ids.push(parent_user_view.local_user.id);
 
-        let comment_reply_form = CommentReplyInsertForm {
-          recipient_id: parent_user_view.person.id,
-          comment_id: comment.id,
-          read: None,
-        };
-
-        // Allow this to fail softly, since comment edits might re-update or replace it
-        // Let the uniqueness handle this fail
-        CommentReply::create(&mut context.pool(), &comment_reply_form)
-          .await
-          .ok();
+        if preference.in_app {
+          create_comment_reply(&parent_user_view, comment, context).await;
+        }
 
         if do_send_email {
-          let lang = get_interface_language(&parent_user_view);
-          send_email_to_user(
+          let template_type = if parent_comment.is_some() {
+            EmailTemplateType::CommentReply
+          } else {
+            EmailTemplateType::PostReply
+          };
+          let (subject, body) = build_email(
+            template_type,
             &parent_user_view,
-            &lang.notification_comment_reply_subject(&person.name),
-            &lang.notification_comment_reply_body(&comment.content, &inbox_link, &person.name),
+            &[
+              ("sender", &person.name),
+              ("content", &comment.content),
+              ("link", &inbox_link),
+            ],
+            &mut context.pool(),
             context.settings(),
           )
-          .await
+          .await;
+          if preference.email {
+            send_email_to_user(
+              &parent_user_view,
+              &subject,
+              &body,
+              Some(ReplyTarget::Comment(comment.id)),
+              context,
+            )
+            .await;
+          }
+          send_push_notification(
+            parent_user_view.local_user.id,
+            PushNotification::new(
+              NotificationType::Reply,
+              &subject,
+              &comment.content,
+              &comment_link,
+            ),
+            context,
+          );
         }
       }
     }
-  } else {
-    // If there's no parent, its the post creator
-    // Only add to recipients if that person isn't blocked
-    let creator_blocked = check_person_block(person.id, post.creator_id, &mut context.pool())
-      .await
-      .is_err();
-
-    if post.creator_id != person.id && !creator_blocked {
-      let creator_id = post.creator_id;
-      let parent_user = LocalUserView::read_person(&mut context.pool(), creator_id).await;
-      if let Ok(parent_user_view) = parent_user {
-        recipient_ids.push(parent_user_view.local_user.id);
+  }
 
-        let comment_reply_form = CommentReplyInsertForm {
-          recipient_id: parent_user_view.person.id,
-          comment_id: comment.id,
-          read: None,
-        };
+  // Notify the local users who subscribed to the post, unless they were notified already
+  for subscriber_id in PostSubscription::list_subscribers(&mut context.pool(), post.id).await? {
+    if subscriber_id == person.id
+      || is_blocked_by(person, subscriber_id, context).await
+      || is_reply_muted(subscriber_id, comment, &mut context.pool()).await?
+    {
+      continue;
+    }
+    let Ok(subscriber_view) = LocalUserView::read_person(&mut context.pool(), subscriber_id).await
+    else {
+      continue;
+    };
+    if recipient_ids.contains(&subscriber_view.local_user.id) {
+      continue;
+    }
+    let Some(preference) = get_notification_preference(
+      &subscriber_view,
+      person.id,
+      NotificationType::PostSubscription,
+      &mut context.pool(),
+    )
+    .await?
+    else {
+      continue;
+    };
+    recipient_ids.push(subscriber_view.local_user.id);
 
-        // Allow this to fail softly, since comment edits might re-update or replace it
-        // Let the uniqueness handle this fail
-        CommentReply::create(&mut context.pool(), &comment_reply_form)
-          .await
-          .ok();
+    if preference.in_app {
+      create_comment_reply(&subscriber_view, comment, context).await;
+    }
 
-        if do_send_email {
-          let lang = get_interface_language(&parent_user_view);
-          send_email_to_user(
-            &parent_user_view,
-            &lang.notification_post_reply_subject(&person.name),
-            &lang.notification_post_reply_body(&comment.content, &inbox_link, &person.name),
-            context.settings(),
-          )
-          .await
-        }
+    if do_send_email {
+      let (subject, body) = build_email(
+        EmailTemplateType::PostReply,
+        &subscriber_view,
+        &[
+          ("sender", &person.name),
+          ("content", &comment.content),
+          ("link", &inbox_link),
+        ],
+        &mut context.pool(),
+        context.settings(),
+      )
+      .await;
+      if preference.email {
+        send_email_to_user(
+          &subscriber_view,
+          &subject,
+          &body,
+          Some(ReplyTarget::Comment(comment.id)),
+          context,
+        )
+        .await;
       }
+      send_push_notification(
+        subscriber_view.local_user.id,
+        PushNotification::new(
+          NotificationType::PostSubscription,
+          &subject,
+          &comment.content,
+          &comment_link,
+        ),
+        context,
+      );
     }
   }
 
   Ok(recipient_ids)
 }
+
+/// Adds the comment to the inbox of the recipient.
+async fn create_comment_reply(
+  recipient: &LocalUserView,
+  comment: &Comment,
+  context: &LemmyContext,
+) {
+  let comment_reply_form = CommentReplyInsertForm {
+    recipient_id: recipient.person.id,
+    comment_id: comment.id,
+    read: None,
+  };
+
+  // Allow this to fail softly, since comment edits might re-update or replace it
+  // Let the uniqueness handle this fail
+  CommentReply::create(&mut context.pool(), &comment_reply_form)
+    .await
+    .ok();
+}
+
+/// Checks if the recipient has blocked the person, or the instance of the person.
+async fn is_blocked_by(person: &Person, recipient_id: PersonId, context: &LemmyContext) -> bool {
+  check_person_block(person.id, recipient_id, &mut context.pool())
+    .await
+    .is_err()
+    || check_person_instance_block(person.instance_id, recipient_id, &mut context.pool())
+      .await
+      .is_err()
+}
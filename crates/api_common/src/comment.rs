@@ -81,6 +81,23 @@ pub struct SaveComment {
   pub save: bool,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Mute a comment, to not get notified about replies to it or anywhere below it.
+pub struct MuteComment {
+  pub comment_id: CommentId,
+  pub mute: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+pub struct MuteCommentResponse {
+  pub comment_id: CommentId,
+  pub muted: bool,
+}
+
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[cfg_attr(feature = "full", derive(TS))]
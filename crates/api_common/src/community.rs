@@ -1,5 +1,5 @@
 use lemmy_db_schema::{
-  newtypes::{CommunityId, LanguageId, PersonId},
+  newtypes::{CommunityId, DbUrl, LanguageId, PersonId},
   source::site::Site,
   ListingType,
   SortType,
@@ -54,6 +54,8 @@ pub struct CreateCommunity {
   /// Whether to restrict posting only to moderators.
   pub posting_restricted_to_mods: Option<bool>,
   pub discussion_languages: Option<Vec<LanguageId>>,
+  /// Whether posts linking to an image or video need alt text.
+  pub require_alt_text: Option<bool>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -147,6 +149,8 @@ pub struct EditCommunity {
   /// Whether to restrict posting only to moderators.
   pub posting_restricted_to_mods: Option<bool>,
   pub discussion_languages: Option<Vec<LanguageId>>,
+  /// Whether posts linking to an image or video need alt text.
+  pub require_alt_text: Option<bool>,
 }
 
 #[skip_serializing_none]
@@ -219,3 +223,59 @@ pub struct TransferCommunity {
   pub community_id: CommunityId,
   pub person_id: PersonId,
 }
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Export a local community, so that it can be recreated on another instance.
+pub struct ExportCommunity {
+  pub community_id: CommunityId,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The settings and moderators of a community. Followers are moved over by the `Move` activity.
+pub struct CommunityExport {
+  /// The actor id of the exported community.
+  pub actor_id: DbUrl,
+  pub name: String,
+  pub title: String,
+  pub description: Option<String>,
+  pub icon: Option<DbUrl>,
+  pub banner: Option<DbUrl>,
+  pub nsfw: bool,
+  pub posting_restricted_to_mods: bool,
+  #[serde(default)]
+  pub require_alt_text: bool,
+  pub discussion_languages: Vec<LanguageId>,
+  /// Actor ids of the moderators, with the top mod first.
+  pub moderators: Vec<DbUrl>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The community export response.
+pub struct ExportCommunityResponse {
+  pub export: CommunityExport,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Recreate a community from an export of another instance. The current user becomes top mod.
+pub struct ImportCommunity {
+  pub export: CommunityExport,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Move a local community to another instance. Followers are switched over to the new community.
+pub struct MoveCommunity {
+  pub community_id: CommunityId,
+  /// The new community. Example: star_trek@xyz.tld
+  pub target: String,
+}
@@ -0,0 +1,82 @@
+use lemmy_db_schema::newtypes::{CommunityId, CustomFeedId, PersonId};
+use lemmy_db_views_actor::structs::CustomFeedView;
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+#[cfg(feature = "full")]
+use ts_rs::TS;
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Create a custom feed from local or remote communities.
+pub struct CreateCustomFeed {
+  pub name: String,
+  pub description: Option<String>,
+  /// Public feeds can be viewed by anyone who has the link.
+  pub public: Option<bool>,
+  pub community_ids: Vec<CommunityId>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Edit a custom feed. The communities are replaced if they are given.
+pub struct EditCustomFeed {
+  pub id: CustomFeedId,
+  pub name: Option<String>,
+  pub description: Option<String>,
+  pub public: Option<bool>,
+  pub community_ids: Option<Vec<CommunityId>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Delete a custom feed.
+pub struct DeleteCustomFeed {
+  pub id: CustomFeedId,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The response for deleting a custom feed.
+pub struct DeleteCustomFeedResponse {
+  pub id: CustomFeedId,
+  pub success: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Get a custom feed. Feeds of other users are only visible if they are public.
+pub struct GetCustomFeed {
+  pub id: CustomFeedId,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// List the custom feeds of a user, by default your own.
+pub struct ListCustomFeeds {
+  pub person_id: Option<PersonId>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A response for a custom feed.
+pub struct CustomFeedResponse {
+  pub custom_feed_view: CustomFeedView,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The custom feeds of a user.
+pub struct ListCustomFeedsResponse {
+  pub custom_feeds: Vec<CustomFeedView>,
+}
@@ -0,0 +1,75 @@
+use lemmy_db_schema::{
+  newtypes::LanguageId,
+  source::email_template::EmailTemplate,
+  EmailTemplateType,
+};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+#[cfg(feature = "full")]
+use ts_rs::TS;
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Create or replace the email template of a type and language. Only for admins.
+pub struct SaveEmailTemplate {
+  pub template_type: EmailTemplateType,
+  /// Leave out to set the template for all languages without a template of their own.
+  pub language_id: Option<LanguageId>,
+  /// Plain text subject. Both subject and body can contain `{{ variable }}` placeholders, the
+  /// available variables are returned by `PreviewEmailTemplate`.
+  pub subject: String,
+  /// Html body.
+  pub body: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// An email template response.
+pub struct EmailTemplateResponse {
+  pub email_template: EmailTemplate,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Delete an email template, so that the built-in text is used again. Only for admins.
+pub struct DeleteEmailTemplate {
+  pub template_type: EmailTemplateType,
+  pub language_id: Option<LanguageId>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// All email templates set by the admins.
+pub struct ListEmailTemplatesResponse {
+  pub email_templates: Vec<EmailTemplate>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Render an email with example values. Only for admins.
+pub struct PreviewEmailTemplate {
+  pub template_type: EmailTemplateType,
+  pub language_id: Option<LanguageId>,
+  /// Preview an unsaved template. If subject and body are left out, the email is rendered as it
+  /// would be sent to users of the language, from the saved template or the built-in text.
+  pub subject: Option<String>,
+  pub body: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The rendered email, and the variables which are available for templates of its type.
+pub struct PreviewEmailTemplateResponse {
+  pub subject: String,
+  pub body: String,
+  pub variables: Vec<String>,
+}
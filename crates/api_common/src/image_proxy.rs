@@ -0,0 +1,486 @@
+use crate::{
+  context::LemmyContext,
+  site::{GetModlogResponse, MyUserInfo},
+};
+use hmac::{Hmac, Mac};
+use lemmy_db_schema::{
+  newtypes::DbUrl,
+  source::{
+    comment::Comment,
+    community::Community,
+    person::Person,
+    post::Post,
+    private_message::PrivateMessage,
+    site::Site,
+  },
+};
+use lemmy_db_views::structs::{
+  CommentReportView,
+  CommentView,
+  ConversationView,
+  PostReportView,
+  PostView,
+  PrivateMessageReportView,
+  PrivateMessageView,
+  RegistrationApplicationView,
+  SiteView,
+};
+use lemmy_db_views_actor::structs::{
+  CommentReplyView,
+  CommunityBlockView,
+  CommunityFollowerView,
+  CommunityModeratorView,
+  CommunityView,
+  CustomFeedView,
+  ModActionNotificationView,
+  PersonBlockView,
+  PersonMentionView,
+  PersonView,
+};
+use lemmy_db_views_moderator::structs::{
+  AdminPurgeCommentView,
+  AdminPurgeCommunityView,
+  AdminPurgePersonView,
+  AdminPurgePostView,
+  ModAddCommunityView,
+  ModAddView,
+  ModBanFromCommunityView,
+  ModBanView,
+  ModFeaturePostView,
+  ModHideCommunityView,
+  ModLockPostView,
+  ModRemoveCommentView,
+  ModRemoveCommunityView,
+  ModRemovePostView,
+  ModTransferCommunityView,
+};
+use lemmy_utils::{
+  error::{LemmyErrorExt, LemmyErrorType, LemmyResult},
+  settings::structs::ImageProxyConfig,
+  utils::markdown::markdown_rewrite_image_links,
+};
+use sha2::Sha256;
+use url::Url;
+
+/// Returns the link under which a remote image is loaded through this instance. Local images and
+/// all images when the proxy is disabled are returned unchanged.
+pub fn proxy_image_link(link: &Url, context: &LemmyContext) -> Url {
+  let settings = context.settings();
+  if !settings.media.image_proxy.enabled || !matches!(link.scheme(), "http" | "https") {
+    return link.clone();
+  }
+  let Ok(local_url) = Url::parse(&settings.get_protocol_and_hostname()) else {
+    return link.clone();
+  };
+  if link.origin() == local_url.origin() {
+    return link.clone();
+  }
+  let signature = image_proxy_signature(link.as_str(), context);
+  Url::parse_with_params(
+    &format!(
+      "{}/api/v3/image_proxy",
+      settings.get_protocol_and_hostname()
+    ),
+    &[("url", link.as_str()), ("sig", &signature)],
+  )
+  .unwrap_or_else(|_| link.clone())
+}
+
+/// Signs proxied links, so that the proxy only fetches images which were included in content
+/// from this instance, and can't be used to load arbitrary urls.
+pub fn image_proxy_signature(link: &str, context: &LemmyContext) -> String {
+  hex::encode(image_proxy_mac(link, context).finalize().into_bytes())
+}
+
+pub fn check_image_proxy_signature(
+  link: &str,
+  signature: &str,
+  context: &LemmyContext,
+) -> LemmyResult<()> {
+  let signature =
+    hex::decode(signature).with_lemmy_type(LemmyErrorType::InvalidImageProxySignature)?;
+  image_proxy_mac(link, context)
+    .verify_slice(&signature)
+    .with_lemmy_type(LemmyErrorType::InvalidImageProxySignature)
+}
+
+fn image_proxy_mac(link: &str, context: &LemmyContext) -> Hmac<Sha256> {
+  // Hmac accepts keys of any length
+  #[allow(clippy::expect_used)]
+  let mut mac =
+    Hmac::<Sha256>::new_from_slice(context.secret().jwt_secret.as_bytes()).expect("hmac key");
+  // Prefix so that the signature can't be reused for other purposes of the same key
+  mac.update(b"image_proxy:");
+  mac.update(link.as_bytes());
+  mac
+}
+
+/// Checks the domain of a proxied image against the allow and block lists. Links with an IP
+/// address instead of a domain are never proxied.
+pub fn check_image_proxy_domain(link: &Url, config: &ImageProxyConfig) -> LemmyResult<()> {
+  let domain = link.domain().ok_or(LemmyErrorType::UrlNotPublic)?;
+  let matches = |d: &String| domain == d || domain.ends_with(&format!(".{d}"));
+  if config.blocked_domains.iter().any(matches) {
+    Err(LemmyErrorType::DomainBlocked(domain.to_string()))?
+  }
+  if !config.allowed_domains.is_empty() && !config.allowed_domains.iter().any(matches) {
+    Err(LemmyErrorType::DomainNotInAllowList(domain.to_string()))?
+  }
+  Ok(())
+}
+
+/// Rewrites links to remote images in API responses, see [proxy_image_link].
+pub trait ProxyImages {
+  fn proxy_images(&mut self, context: &LemmyContext);
+}
+
+fn proxy_db_url(link: &mut Option<DbUrl>, context: &LemmyContext) {
+  if let Some(l) = link {
+    *l = proxy_image_link(l, context).into();
+  }
+}
+
+fn proxy_markdown(text: &mut Option<String>, context: &LemmyContext) {
+  if let Some(t) = text {
+    *t = proxy_markdown_text(t, context);
+  }
+}
+
+fn proxy_markdown_text(text: &str, context: &LemmyContext) -> String {
+  if !context.settings().media.image_proxy.enabled {
+    return text.to_string();
+  }
+  markdown_rewrite_image_links(text, |link| {
+    let link = Url::parse(link).ok()?;
+    Some(proxy_image_link(&link, context).to_string())
+  })
+}
+
+/// Guesses from the file extension whether a link points directly to an image.
+fn is_image_url(link: &Url) -> bool {
+  let path = link.path().to_lowercase();
+  [".png", ".jpg", ".jpeg", ".gif", ".webp", ".avif", ".svg"]
+    .iter()
+    .any(|e| path.ends_with(e))
+}
+
+impl ProxyImages for Post {
+  fn proxy_images(&mut self, context: &LemmyContext) {
+    // Only direct image links, other links are opened by the user and not loaded by the client
+    if self.url.as_ref().is_some_and(|u| is_image_url(u)) {
+      proxy_db_url(&mut self.url, context);
+    }
+    proxy_db_url(&mut self.thumbnail_url, context);
+    proxy_markdown(&mut self.body, context);
+  }
+}
+
+impl ProxyImages for Comment {
+  fn proxy_images(&mut self, context: &LemmyContext) {
+    self.content = proxy_markdown_text(&self.content, context);
+  }
+}
+
+impl ProxyImages for Person {
+  fn proxy_images(&mut self, context: &LemmyContext) {
+    proxy_db_url(&mut self.avatar, context);
+    proxy_db_url(&mut self.banner, context);
+    proxy_markdown(&mut self.bio, context);
+  }
+}
+
+impl ProxyImages for Community {
+  fn proxy_images(&mut self, context: &LemmyContext) {
+    proxy_db_url(&mut self.icon, context);
+    proxy_db_url(&mut self.banner, context);
+    proxy_markdown(&mut self.description, context);
+  }
+}
+
+impl ProxyImages for PrivateMessage {
+  fn proxy_images(&mut self, context: &LemmyContext) {
+    // Encrypted messages can only be read by the participants
+    if !self.encrypted {
+      self.content = proxy_markdown_text(&self.content, context);
+    }
+  }
+}
+
+impl ProxyImages for Site {
+  fn proxy_images(&mut self, context: &LemmyContext) {
+    proxy_db_url(&mut self.icon, context);
+    proxy_db_url(&mut self.banner, context);
+    proxy_markdown(&mut self.sidebar, context);
+  }
+}
+
+impl<T: ProxyImages> ProxyImages for Option<T> {
+  fn proxy_images(&mut self, context: &LemmyContext) {
+    if let Some(t) = self {
+      t.proxy_images(context);
+    }
+  }
+}
+
+impl ProxyImages for PostView {
+  fn proxy_images(&mut self, context: &LemmyContext) {
+    self.post.proxy_images(context);
+    self.creator.proxy_images(context);
+    self.community.proxy_images(context);
+    for image in &mut self.gallery {
+      image.url = proxy_image_link(&image.url, context).into();
+    }
+  }
+}
+
+impl ProxyImages for CommentView {
+  fn proxy_images(&mut self, context: &LemmyContext) {
+    self.comment.proxy_images(context);
+    self.creator.proxy_images(context);
+    self.post.proxy_images(context);
+    self.community.proxy_images(context);
+  }
+}
+
+impl ProxyImages for CommunityView {
+  fn proxy_images(&mut self, context: &LemmyContext) {
+    self.community.proxy_images(context);
+  }
+}
+
+impl ProxyImages for CommunityModeratorView {
+  fn proxy_images(&mut self, context: &LemmyContext) {
+    self.community.proxy_images(context);
+    self.moderator.proxy_images(context);
+  }
+}
+
+impl ProxyImages for PersonView {
+  fn proxy_images(&mut self, context: &LemmyContext) {
+    self.person.proxy_images(context);
+  }
+}
+
+impl ProxyImages for CommunityFollowerView {
+  fn proxy_images(&mut self, context: &LemmyContext) {
+    self.community.proxy_images(context);
+    self.follower.proxy_images(context);
+  }
+}
+
+impl ProxyImages for CommunityBlockView {
+  fn proxy_images(&mut self, context: &LemmyContext) {
+    self.person.proxy_images(context);
+    self.community.proxy_images(context);
+  }
+}
+
+impl ProxyImages for PersonBlockView {
+  fn proxy_images(&mut self, context: &LemmyContext) {
+    self.person.proxy_images(context);
+    self.target.proxy_images(context);
+  }
+}
+
+impl ProxyImages for CustomFeedView {
+  fn proxy_images(&mut self, context: &LemmyContext) {
+    self.creator.proxy_images(context);
+    self.communities.proxy_images(context);
+  }
+}
+
+impl ProxyImages for CommentReplyView {
+  fn proxy_images(&mut self, context: &LemmyContext) {
+    self.comment.proxy_images(context);
+    self.creator.proxy_images(context);
+    self.post.proxy_images(context);
+    self.community.proxy_images(context);
+    self.recipient.proxy_images(context);
+  }
+}
+
+impl ProxyImages for PersonMentionView {
+  fn proxy_images(&mut self, context: &LemmyContext) {
+    self.comment.proxy_images(context);
+    self.creator.proxy_images(context);
+    self.post.proxy_images(context);
+    self.community.proxy_images(context);
+    self.recipient.proxy_images(context);
+  }
+}
+
+impl ProxyImages for ModActionNotificationView {
+  fn proxy_images(&mut self, context: &LemmyContext) {
+    self.moderator.proxy_images(context);
+    self.community.proxy_images(context);
+    self.post.proxy_images(context);
+    self.comment.proxy_images(context);
+  }
+}
+
+impl ProxyImages for PrivateMessageView {
+  fn proxy_images(&mut self, context: &LemmyContext) {
+    self.private_message.proxy_images(context);
+    self.creator.proxy_images(context);
+    self.recipient.proxy_images(context);
+  }
+}
+
+impl ProxyImages for ConversationView {
+  fn proxy_images(&mut self, context: &LemmyContext) {
+    self.participants.proxy_images(context);
+    self.last_message.proxy_images(context);
+  }
+}
+
+impl ProxyImages for CommentReportView {
+  fn proxy_images(&mut self, context: &LemmyContext) {
+    self.comment.proxy_images(context);
+    self.post.proxy_images(context);
+    self.community.proxy_images(context);
+    self.creator.proxy_images(context);
+    self.comment_creator.proxy_images(context);
+    self.resolver.proxy_images(context);
+  }
+}
+
+impl ProxyImages for PostReportView {
+  fn proxy_images(&mut self, context: &LemmyContext) {
+    self.post.proxy_images(context);
+    self.community.proxy_images(context);
+    self.creator.proxy_images(context);
+    self.post_creator.proxy_images(context);
+    self.resolver.proxy_images(context);
+  }
+}
+
+impl ProxyImages for PrivateMessageReportView {
+  fn proxy_images(&mut self, context: &LemmyContext) {
+    self.private_message.proxy_images(context);
+    self.private_message_creator.proxy_images(context);
+    self.creator.proxy_images(context);
+    self.resolver.proxy_images(context);
+  }
+}
+
+impl ProxyImages for RegistrationApplicationView {
+  fn proxy_images(&mut self, context: &LemmyContext) {
+    self.creator.proxy_images(context);
+    self.admin.proxy_images(context);
+  }
+}
+
+impl ProxyImages for SiteView {
+  fn proxy_images(&mut self, context: &LemmyContext) {
+    self.site.proxy_images(context);
+  }
+}
+
+impl ProxyImages for MyUserInfo {
+  fn proxy_images(&mut self, context: &LemmyContext) {
+    self.local_user_view.person.proxy_images(context);
+    self.follows.proxy_images(context);
+    self.moderates.proxy_images(context);
+    self.community_blocks.proxy_images(context);
+    self.person_blocks.proxy_images(context);
+  }
+}
+
+/// Implements [ProxyImages] for a modlog view by proxying the images of the given fields.
+macro_rules! proxy_modlog_view {
+  ($view:ty: $($field:ident),+) => {
+    impl ProxyImages for $view {
+      fn proxy_images(&mut self, context: &LemmyContext) {
+        $(self.$field.proxy_images(context);)+
+      }
+    }
+  };
+}
+
+proxy_modlog_view!(ModRemovePostView: moderator, post, community);
+proxy_modlog_view!(ModLockPostView: moderator, post, community);
+proxy_modlog_view!(ModFeaturePostView: moderator, post, community);
+proxy_modlog_view!(ModRemoveCommentView: moderator, comment, commenter, post, community);
+proxy_modlog_view!(ModRemoveCommunityView: moderator, community);
+proxy_modlog_view!(ModBanFromCommunityView: moderator, community, banned_person);
+proxy_modlog_view!(ModBanView: moderator, banned_person);
+proxy_modlog_view!(ModAddCommunityView: moderator, community, modded_person);
+proxy_modlog_view!(ModTransferCommunityView: moderator, community, modded_person);
+proxy_modlog_view!(ModAddView: moderator, modded_person);
+proxy_modlog_view!(ModHideCommunityView: admin, community);
+proxy_modlog_view!(AdminPurgePersonView: admin);
+proxy_modlog_view!(AdminPurgeCommunityView: admin);
+proxy_modlog_view!(AdminPurgePostView: admin, community);
+proxy_modlog_view!(AdminPurgeCommentView: admin, post);
+
+impl ProxyImages for GetModlogResponse {
+  fn proxy_images(&mut self, context: &LemmyContext) {
+    self.removed_posts.proxy_images(context);
+    self.locked_posts.proxy_images(context);
+    self.featured_posts.proxy_images(context);
+    self.removed_comments.proxy_images(context);
+    self.removed_communities.proxy_images(context);
+    self.banned_from_community.proxy_images(context);
+    self.banned.proxy_images(context);
+    self.added_to_community.proxy_images(context);
+    self.added.proxy_images(context);
+    self.transferred_to_community.proxy_images(context);
+    self.admin_purged_persons.proxy_images(context);
+    self.admin_purged_communities.proxy_images(context);
+    self.admin_purged_posts.proxy_images(context);
+    self.admin_purged_comments.proxy_images(context);
+    self.hidden_communities.proxy_images(context);
+  }
+}
+
+impl<T: ProxyImages> ProxyImages for Vec<T> {
+  fn proxy_images(&mut self, context: &LemmyContext) {
+    if context.settings().media.image_proxy.enabled {
+      self.iter_mut().for_each(|i| i.proxy_images(context));
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use crate::image_proxy::{check_image_proxy_domain, is_image_url};
+  use lemmy_utils::settings::structs::ImageProxyConfig;
+  use url::Url;
+
+  #[test]
+  fn test_check_image_proxy_domain() {
+    let link = |l: &str| Url::parse(l).unwrap();
+    let config = ImageProxyConfig {
+      enabled: true,
+      allowed_domains: vec![],
+      blocked_domains: vec!["tracker.com".to_string()],
+    };
+    assert!(check_image_proxy_domain(&link("https://example.com/a.png"), &config).is_ok());
+    assert!(check_image_proxy_domain(&link("https://tracker.com/a.png"), &config).is_err());
+    assert!(check_image_proxy_domain(&link("https://cdn.tracker.com/a.png"), &config).is_err());
+    assert!(check_image_proxy_domain(&link("https://nottracker.com/a.png"), &config).is_ok());
+    assert!(check_image_proxy_domain(&link("http://127.0.0.1/a.png"), &config).is_err());
+    assert!(check_image_proxy_domain(&link("http://[fd00::1]/a.png"), &config).is_err());
+
+    let config = ImageProxyConfig {
+      allowed_domains: vec!["example.com".to_string()],
+      ..config
+    };
+    assert!(check_image_proxy_domain(&link("https://i.example.com/a.png"), &config).is_ok());
+    assert!(check_image_proxy_domain(&link("https://other.com/a.png"), &config).is_err());
+  }
+
+  #[test]
+  fn test_is_image_url() {
+    let link = |l: &str| Url::parse(l).unwrap();
+    assert!(is_image_url(&link("https://example.com/cat.PNG")));
+    assert!(is_image_url(&link(
+      "https://example.com/cat.jpg?size=large"
+    )));
+    assert!(!is_image_url(&link("https://example.com/article")));
+    assert!(!is_image_url(&link("https://example.com/video.mp4")));
+  }
+}
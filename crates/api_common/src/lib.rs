@@ -5,10 +5,20 @@ pub mod community;
 #[cfg(feature = "full")]
 pub mod context;
 pub mod custom_emoji;
+pub mod custom_feed;
+pub mod email_template;
+#[cfg(feature = "full")]
+pub mod image_proxy;
+#[cfg(feature = "full")]
+pub mod live_events;
+#[cfg(feature = "full")]
+pub mod media;
 pub mod person;
 pub mod post;
 pub mod private_message;
 #[cfg(feature = "full")]
+pub mod reply_by_email;
+#[cfg(feature = "full")]
 pub mod request;
 #[cfg(feature = "full")]
 pub mod send_activity;
@@ -16,6 +26,8 @@ pub mod sensitive;
 pub mod site;
 #[cfg(feature = "full")]
 pub mod utils;
+#[cfg(feature = "full")]
+pub mod web_push;
 
 pub extern crate lemmy_db_schema;
 pub extern crate lemmy_db_views;
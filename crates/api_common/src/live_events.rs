@@ -0,0 +1,220 @@
+use crate::{person::GetReportCountResponse, web_push::PushNotification};
+use chrono::Utc;
+use lemmy_db_schema::newtypes::{CommentId, CommunityId, LocalUserId, PostId};
+use lemmy_utils::error::{LemmyError, LemmyErrorType};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::{
+  collections::{HashMap, VecDeque},
+  sync::{Arc, Mutex},
+};
+use tokio::sync::broadcast;
+use ts_rs::TS;
+
+/// Number of past events which are kept, so that clients can resume a live event stream with
+/// `Last-Event-ID` after reconnecting.
+const EVENT_HISTORY_SIZE: usize = 1000;
+
+/// Maximum number of open live event streams per user.
+pub const MAX_LIVE_EVENT_CONNECTIONS: usize = 5;
+
+/// Broker for live events. Events are only delivered to streams which are connected to the same
+/// process which handled the action.
+static LIVE_EVENTS: Lazy<LiveEvents> = Lazy::new(|| {
+  let (sender, _) = broadcast::channel(EVENT_HISTORY_SIZE);
+  LiveEvents {
+    sender,
+    history: Mutex::new(LiveEventHistory {
+      // Start at the current time so that ids keep increasing across restarts, and clients
+      // resuming with an id from before the restart don't miss any events.
+      next_id: Utc::now().timestamp_micros().unsigned_abs(),
+      events: VecDeque::with_capacity(EVENT_HISTORY_SIZE),
+    }),
+    connections: Mutex::new(HashMap::new()),
+  }
+});
+
+/// Something that happened on the instance, which may be of interest to connected clients.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LiveEventKind {
+  Notification(LocalUserId, PushNotification),
+  NewComment(PostId, CommentId),
+  NewPost(CommunityId, PostId),
+  /// A new report, with the community of the reported content. None for private message reports.
+  NewReport(Option<CommunityId>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LiveEvent {
+  pub id: u64,
+  pub kind: LiveEventKind,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[ts(export)]
+#[serde(tag = "type")]
+/// The data of a single event in the live event stream.
+pub enum LiveEventData {
+  Notification(PushNotification),
+  NewComment {
+    post_id: PostId,
+    comment_id: CommentId,
+  },
+  NewPost {
+    community_id: CommunityId,
+    post_id: PostId,
+  },
+  /// The current report counts, sent to admins and moderators when a new report comes in.
+  ReportCount(GetReportCountResponse),
+}
+
+pub struct LiveEvents {
+  sender: broadcast::Sender<Arc<LiveEvent>>,
+  history: Mutex<LiveEventHistory>,
+  connections: Mutex<HashMap<LocalUserId, usize>>,
+}
+
+struct LiveEventHistory {
+  next_id: u64,
+  events: VecDeque<Arc<LiveEvent>>,
+}
+
+impl LiveEvents {
+  /// Sends the event to all connected live event streams.
+  pub fn publish(kind: LiveEventKind) {
+    LIVE_EVENTS.publish_inner(kind);
+  }
+
+  /// Returns the events after `last_event_id` and a receiver for all following events. Without
+  /// `last_event_id`, or if it is too old to resume from, only new events are received.
+  pub fn subscribe(
+    last_event_id: Option<u64>,
+  ) -> (Vec<Arc<LiveEvent>>, broadcast::Receiver<Arc<LiveEvent>>) {
+    LIVE_EVENTS.subscribe_inner(last_event_id)
+  }
+
+  /// Registers a new live event stream for the user, which is unregistered when the returned
+  /// guard is dropped.
+  pub fn connect(local_user_id: LocalUserId) -> Result<LiveEventConnection, LemmyError> {
+    LIVE_EVENTS.connect_inner(local_user_id)
+  }
+
+  fn publish_inner(&self, kind: LiveEventKind) {
+    let mut history = self.history.lock().expect("lock live event history");
+    let event = Arc::new(LiveEvent {
+      id: history.next_id,
+      kind,
+    });
+    history.next_id += 1;
+    if history.events.len() == EVENT_HISTORY_SIZE {
+      history.events.pop_front();
+    }
+    history.events.push_back(event.clone());
+    // Sending under the lock keeps events ordered, and fails only if there are no receivers.
+    self.sender.send(event).ok();
+  }
+
+  fn subscribe_inner(
+    &self,
+    last_event_id: Option<u64>,
+  ) -> (Vec<Arc<LiveEvent>>, broadcast::Receiver<Arc<LiveEvent>>) {
+    let history = self.history.lock().expect("lock live event history");
+    // Subscribe while holding the lock, so that no event is missed or received twice.
+    let receiver = self.sender.subscribe();
+    let backlog = match (last_event_id, history.events.front()) {
+      (Some(last_event_id), Some(oldest)) if oldest.id <= last_event_id.saturating_add(1) => {
+        history
+          .events
+          .iter()
+          .filter(|e| e.id > last_event_id)
+          .cloned()
+          .collect()
+      }
+      _ => vec![],
+    };
+    (backlog, receiver)
+  }
+
+  fn connect_inner(&self, local_user_id: LocalUserId) -> Result<LiveEventConnection, LemmyError> {
+    let mut connections = self
+      .connections
+      .lock()
+      .expect("lock live event connections");
+    let count = connections.entry(local_user_id).or_default();
+    if *count >= MAX_LIVE_EVENT_CONNECTIONS {
+      Err(LemmyErrorType::TooManyLiveEventConnections)?
+    }
+    *count += 1;
+    Ok(LiveEventConnection { local_user_id })
+  }
+
+  fn disconnect(&self, local_user_id: LocalUserId) {
+    let mut connections = self
+      .connections
+      .lock()
+      .expect("lock live event connections");
+    if let Some(count) = connections.get_mut(&local_user_id) {
+      *count -= 1;
+      if *count == 0 {
+        connections.remove(&local_user_id);
+      }
+    }
+  }
+}
+
+/// An open live event stream, which counts towards the connection limit of the user.
+pub struct LiveEventConnection {
+  local_user_id: LocalUserId,
+}
+
+impl Drop for LiveEventConnection {
+  fn drop(&mut self) {
+    LIVE_EVENTS.disconnect(self.local_user_id);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+
+  use crate::live_events::{LiveEventKind, LiveEvents, MAX_LIVE_EVENT_CONNECTIONS};
+  use lemmy_db_schema::newtypes::{CommentId, LocalUserId, PostId};
+
+  #[test]
+  fn test_resume_from_last_event_id() {
+    let (_, mut receiver) = LiveEvents::subscribe(None);
+    let first_kind = LiveEventKind::NewComment(PostId(i32::MAX), CommentId(1));
+    let second_kind = LiveEventKind::NewComment(PostId(i32::MAX), CommentId(2));
+    LiveEvents::publish(first_kind.clone());
+    LiveEvents::publish(second_kind.clone());
+    let first = receiver.try_recv().unwrap();
+    let second = receiver.try_recv().unwrap();
+    assert_eq!(first_kind, first.kind);
+    assert_eq!(second_kind, second.kind);
+    assert!(first.id < second.id);
+
+    // resuming after the first event replays the second one
+    let (backlog, _) = LiveEvents::subscribe(Some(first.id));
+    assert!(!backlog.contains(&first));
+    assert!(backlog.contains(&second));
+
+    // a client without last event id only gets new events
+    assert!(LiveEvents::subscribe(None).0.is_empty());
+
+    // an invalid last event id from the client must not overflow
+    assert!(LiveEvents::subscribe(Some(u64::MAX)).0.is_empty());
+  }
+
+  #[test]
+  fn test_connection_limit() {
+    let local_user_id = LocalUserId(i32::MAX);
+    let mut connections = (0..MAX_LIVE_EVENT_CONNECTIONS)
+      .map(|_| LiveEvents::connect(local_user_id).unwrap())
+      .collect::<Vec<_>>();
+    assert!(LiveEvents::connect(local_user_id).is_err());
+
+    // closing a stream allows opening a new one
+    connections.pop();
+    assert!(LiveEvents::connect(local_user_id).is_ok());
+  }
+}
@@ -0,0 +1,368 @@
+use crate::media::{MediaBackend, MediaOptions, MediaResponse, StoredMedia, UploadStream};
+use async_trait::async_trait;
+use bytes::{Bytes, BytesMut};
+use futures::{stream, StreamExt, TryStreamExt};
+use image::{imageops::FilterType, DynamicImage, ImageFormat, ImageOutputFormat};
+use lemmy_utils::error::{LemmyErrorExt, LemmyErrorType, LemmyResult};
+use reqwest::{
+  header::{HeaderMap, HeaderValue, CACHE_CONTROL, CONTENT_TYPE},
+  StatusCode,
+};
+use std::io::Cursor;
+use uuid::Uuid;
+
+const MAX_THUMBNAIL_SIZE: u32 = 2048;
+/// Thumbnails are only generated in these sizes, so that only a few files are stored per image.
+/// Other sizes are rounded up.
+const THUMBNAIL_SIZES: [u32; 6] = [64, 128, 256, 512, 1024, MAX_THUMBNAIL_SIZE];
+
+/// Minimal storage of files by key, which the built-in backends are based on. Keys only contain
+/// ascii letters, digits, dots, dashes and slashes.
+#[async_trait]
+pub(super) trait FileStore: Send + Sync {
+  async fn put(&self, key: &str, data: Bytes, content_type: &str) -> LemmyResult<()>;
+
+  /// Returns `None` if there is no file with this key.
+  async fn get(&self, key: &str) -> LemmyResult<Option<Bytes>>;
+
+  /// Deletes the file with this key, and all files whose key starts with `{key}/`.
+  async fn delete(&self, key: &str) -> LemmyResult<()>;
+}
+
+/// Handles images inside Lemmy, without pictrs. Still images are decoded and encoded again, which
+/// strips metadata like the location, thumbnails are generated when they are first requested.
+pub(super) struct BuiltinBackend<S> {
+  store: S,
+}
+
+impl<S: FileStore> BuiltinBackend<S> {
+  pub(super) fn new(store: S) -> Self {
+    BuiltinBackend { store }
+  }
+
+  /// Reads the original file, or a converted version which is created and stored on first use.
+  async fn read_converted(
+    &self,
+    alias: &str,
+    options: &MediaOptions,
+  ) -> LemmyResult<(Bytes, ImageFormat)> {
+    let original_format = format_from_alias(alias)?;
+    let format = options
+      .format
+      .as_deref()
+      .and_then(ImageFormat::from_extension)
+      .filter(|f| can_encode(*f))
+      .unwrap_or(if can_encode(original_format) {
+        original_format
+      } else {
+        ImageFormat::Png
+      });
+    if options.thumbnail.is_none() && format == original_format {
+      let data = self
+        .store
+        .get(&original_key(alias))
+        .await?
+        .ok_or(LemmyErrorType::CouldntFindImage)?;
+      return Ok((data, original_format));
+    }
+
+    let thumbnail = options.thumbnail.map(thumbnail_size);
+    let key = format!(
+      "{}/{}.{}",
+      processed_prefix(alias),
+      thumbnail.unwrap_or_default(),
+      extension(format)
+    );
+    if let Some(data) = self.store.get(&key).await? {
+      return Ok((data, format));
+    }
+    let original = self
+      .store
+      .get(&original_key(alias))
+      .await?
+      .ok_or(LemmyErrorType::CouldntFindImage)?;
+    let data = tokio::task::spawn_blocking(move || {
+      let mut image = image::load_from_memory_with_format(&original, original_format)?;
+      if let Some(size) = thumbnail {
+        if image.width() > size || image.height() > size {
+          image = image.resize(size, size, FilterType::Triangle);
+        }
+      }
+      encode(&image, format)
+    })
+    .await??;
+    self
+      .store
+      .put(&key, data.clone(), mime_type(format))
+      .await?;
+    Ok((data, format))
+  }
+}
+
+#[async_trait]
+impl<S: FileStore> MediaBackend for BuiltinBackend<S> {
+  async fn upload(
+    &self,
+    data: UploadStream,
+    _content_type: Option<&str>,
+    _client_address: Option<&str>,
+  ) -> LemmyResult<StoredMedia> {
+    // Images need to be decoded as a whole
+    let data = data
+      .try_fold(BytesMut::new(), |mut data, chunk| async move {
+        data.extend_from_slice(&chunk);
+        Ok(data)
+      })
+      .await?
+      .freeze();
+    // The declared content type can't be trusted, so the format is detected from the data
+    let format = image::guess_format(&data)
+      .ok()
+      .filter(|f| is_supported(*f))
+      .ok_or(LemmyErrorType::UploadTypeNotAllowed)?;
+    let data = if format == ImageFormat::Gif || format == ImageFormat::WebP {
+      // Keep animations, and webp can't be encoded. Files which can't be decoded are still
+      // rejected.
+      image::load_from_memory_with_format(&data, format)
+        .with_lemmy_type(LemmyErrorType::UploadTypeNotAllowed)?;
+      data
+    } else {
+      tokio::task::spawn_blocking(move || {
+        let image = image::load_from_memory_with_format(&data, format)
+          .with_lemmy_type(LemmyErrorType::UploadTypeNotAllowed)?;
+        encode(&image, format)
+      })
+      .await??
+    };
+
+    let alias = format!("{}.{}", Uuid::new_v4().simple(), extension(format));
+    let delete_token = Uuid::new_v4().simple().to_string();
+    let size = data.len() as i64;
+    self
+      .store
+      .put(&original_key(&alias), data, mime_type(format))
+      .await?;
+    self
+      .store
+      .put(
+        &delete_token_key(&alias),
+        Bytes::from(delete_token.clone()),
+        "text/plain",
+      )
+      .await?;
+    Ok(StoredMedia {
+      alias,
+      delete_token,
+      content_type: Some(mime_type(format).to_string()),
+      size: Some(size),
+    })
+  }
+
+  async fn read(&self, alias: &str, options: &MediaOptions) -> LemmyResult<MediaResponse> {
+    let (data, format) = self.read_converted(alias, options).await?;
+    let mut headers = HeaderMap::new();
+    headers.insert(CONTENT_TYPE, HeaderValue::from_static(mime_type(format)));
+    // Files never change under the same alias
+    headers.insert(
+      CACHE_CONTROL,
+      HeaderValue::from_static("public, max-age=604800, immutable"),
+    );
+    Ok(MediaResponse {
+      status: StatusCode::OK,
+      headers,
+      body: stream::once(async { Ok(data) }).boxed(),
+    })
+  }
+
+  async fn delete(&self, alias: &str, delete_token: &str) -> LemmyResult<()> {
+    format_from_alias(alias)?;
+    let stored_token = self
+      .store
+      .get(&delete_token_key(alias))
+      .await?
+      .ok_or(LemmyErrorType::CouldntFindImage)?;
+    if stored_token != delete_token.as_bytes() {
+      Err(LemmyErrorType::InvalidDeleteToken)?
+    }
+    self.purge(alias).await
+  }
+
+  async fn purge(&self, alias: &str) -> LemmyResult<()> {
+    format_from_alias(alias)?;
+    self.store.delete(&processed_prefix(alias)).await?;
+    self.store.delete(&delete_token_key(alias)).await?;
+    self.store.delete(&original_key(alias)).await
+  }
+}
+
+fn original_key(alias: &str) -> String {
+  format!("original/{alias}")
+}
+
+fn delete_token_key(alias: &str) -> String {
+  format!("delete_token/{alias}")
+}
+
+fn processed_prefix(alias: &str) -> String {
+  format!("processed/{alias}")
+}
+
+/// Checks that the alias was generated by us, so that it can be used safely as part of a key.
+fn format_from_alias(alias: &str) -> LemmyResult<ImageFormat> {
+  let (name, extension) = alias
+    .split_once('.')
+    .ok_or(LemmyErrorType::CouldntFindImage)?;
+  if name.is_empty() || !name.chars().all(|c| c.is_ascii_hexdigit()) {
+    Err(LemmyErrorType::CouldntFindImage)?
+  }
+  ImageFormat::from_extension(extension)
+    .filter(|f| is_supported(*f))
+    .ok_or(LemmyErrorType::CouldntFindImage.into())
+}
+
+fn thumbnail_size(size: u32) -> u32 {
+  THUMBNAIL_SIZES
+    .into_iter()
+    .find(|s| *s >= size)
+    .unwrap_or(MAX_THUMBNAIL_SIZE)
+}
+
+fn is_supported(format: ImageFormat) -> bool {
+  matches!(
+    format,
+    ImageFormat::Png | ImageFormat::Jpeg | ImageFormat::Gif | ImageFormat::WebP
+  )
+}
+
+/// Webp can only be decoded, requests for it get png instead.
+fn can_encode(format: ImageFormat) -> bool {
+  matches!(
+    format,
+    ImageFormat::Png | ImageFormat::Jpeg | ImageFormat::Gif
+  )
+}
+
+fn extension(format: ImageFormat) -> &'static str {
+  match format {
+    ImageFormat::Jpeg => "jpg",
+    ImageFormat::Gif => "gif",
+    ImageFormat::WebP => "webp",
+    _ => "png",
+  }
+}
+
+fn mime_type(format: ImageFormat) -> &'static str {
+  match format {
+    ImageFormat::Jpeg => "image/jpeg",
+    ImageFormat::Gif => "image/gif",
+    ImageFormat::WebP => "image/webp",
+    _ => "image/png",
+  }
+}
+
+fn encode(image: &DynamicImage, format: ImageFormat) -> LemmyResult<Bytes> {
+  let output_format = match format {
+    ImageFormat::Jpeg => ImageOutputFormat::Jpeg(85),
+    ImageFormat::Gif => ImageOutputFormat::Gif,
+    _ => ImageOutputFormat::Png,
+  };
+  // Jpeg has no alpha channel
+  let image = if format == ImageFormat::Jpeg {
+    DynamicImage::ImageRgb8(image.to_rgb8())
+  } else {
+    image.clone()
+  };
+  let mut data = Cursor::new(Vec::new());
+  image.write_to(&mut data, output_format)?;
+  Ok(data.into_inner().into())
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use super::{BuiltinBackend, FileStore};
+  use crate::media::{filesystem::FilesystemStore, MediaBackend, MediaOptions, UploadStream};
+  use bytes::Bytes;
+  use futures::{future, stream, StreamExt};
+  use image::{DynamicImage, ImageFormat, ImageOutputFormat};
+  use reqwest::header::CONTENT_TYPE;
+  use std::io::Cursor;
+  use uuid::Uuid;
+
+  fn upload_stream(data: Bytes) -> UploadStream {
+    Box::pin(stream::once(future::ok(data)))
+  }
+
+  fn test_png(width: u32, height: u32) -> Bytes {
+    let mut data = Cursor::new(Vec::new());
+    DynamicImage::new_rgba8(width, height)
+      .write_to(&mut data, ImageOutputFormat::Png)
+      .unwrap();
+    data.into_inner().into()
+  }
+
+  #[tokio::test]
+  async fn test_builtin_backend() {
+    let dir = std::env::temp_dir().join(format!("lemmy-media-{}", Uuid::new_v4()));
+    let store = FilesystemStore::new(dir.to_str().unwrap());
+    let backend = BuiltinBackend::new(store);
+
+    // Anything which isn't an image is rejected
+    let html = upload_stream(Bytes::from_static(b"<html></html>"));
+    assert!(backend.upload(html, Some("image/png"), None).await.is_err());
+
+    let stored = backend
+      .upload(upload_stream(test_png(400, 200)), None, None)
+      .await
+      .unwrap();
+    assert!(stored.alias.ends_with(".png"));
+    assert_eq!(Some("image/png".to_string()), stored.content_type);
+
+    let options = MediaOptions {
+      format: Some("jpg".to_string()),
+      thumbnail: Some(100),
+    };
+    let response = backend.read(&stored.alias, &options).await.unwrap();
+    assert_eq!("image/jpeg", response.headers[CONTENT_TYPE]);
+    let data: Vec<u8> = response
+      .body
+      .map(|chunk| chunk.unwrap().to_vec())
+      .concat()
+      .await;
+    // The size is rounded up to the next fixed thumbnail size
+    let thumbnail = image::load_from_memory_with_format(&data, ImageFormat::Jpeg).unwrap();
+    assert_eq!((128, 64), (thumbnail.width(), thumbnail.height()));
+    let thumbnail_key = format!("processed/{}/128.jpg", stored.alias);
+    assert!(backend.store.get(&thumbnail_key).await.unwrap().is_some());
+
+    // Other sizes which round to the same thumbnail don't store another file
+    let options = MediaOptions {
+      thumbnail: Some(120),
+      ..options
+    };
+    backend.read(&stored.alias, &options).await.unwrap();
+    let processed_dir = dir.join(format!("processed/{}", stored.alias));
+    assert_eq!(1, std::fs::read_dir(processed_dir).unwrap().count());
+
+    // Paths outside of the store can't be read
+    assert!(backend
+      .read("../original/x.png", &MediaOptions::default())
+      .await
+      .is_err());
+
+    assert!(backend.delete(&stored.alias, "wrong").await.is_err());
+    backend
+      .delete(&stored.alias, &stored.delete_token)
+      .await
+      .unwrap();
+    assert!(backend
+      .read(&stored.alias, &MediaOptions::default())
+      .await
+      .is_err());
+    assert_eq!(None, backend.store.get(&thumbnail_key).await.unwrap());
+
+    std::fs::remove_dir_all(dir).unwrap();
+  }
+}
@@ -0,0 +1,56 @@
+use crate::media::builtin::FileStore;
+use async_trait::async_trait;
+use bytes::Bytes;
+use lemmy_utils::error::LemmyResult;
+use std::{io::ErrorKind, path::PathBuf};
+use tokio::fs;
+use uuid::Uuid;
+
+/// Stores files in a local directory, with the key as relative path.
+pub(super) struct FilesystemStore {
+  path: PathBuf,
+}
+
+impl FilesystemStore {
+  pub(super) fn new(path: &str) -> Self {
+    FilesystemStore { path: path.into() }
+  }
+}
+
+#[async_trait]
+impl FileStore for FilesystemStore {
+  async fn put(&self, key: &str, data: Bytes, _content_type: &str) -> LemmyResult<()> {
+    let path = self.path.join(key);
+    if let Some(parent) = path.parent() {
+      fs::create_dir_all(parent).await?;
+    }
+    // Write to a temporary file first, so that readers never see a partial file
+    let tmp_path = self
+      .path
+      .join(format!("{key}.{}.tmp", Uuid::new_v4().simple()));
+    fs::write(&tmp_path, data).await?;
+    fs::rename(tmp_path, path).await?;
+    Ok(())
+  }
+
+  async fn get(&self, key: &str) -> LemmyResult<Option<Bytes>> {
+    match fs::read(self.path.join(key)).await {
+      Ok(data) => Ok(Some(data.into())),
+      Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+      Err(e) => Err(e.into()),
+    }
+  }
+
+  async fn delete(&self, key: &str) -> LemmyResult<()> {
+    let path = self.path.join(key);
+    let res = if fs::metadata(&path).await.is_ok_and(|m| m.is_dir()) {
+      fs::remove_dir_all(path).await
+    } else {
+      fs::remove_file(path).await
+    };
+    match res {
+      Err(e) if e.kind() != ErrorKind::NotFound => Err(e.into()),
+      _ => Ok(()),
+    }
+  }
+}
@@ -0,0 +1,179 @@
+use crate::{
+  context::LemmyContext,
+  media::{
+    builtin::BuiltinBackend,
+    filesystem::FilesystemStore,
+    pictrs::PictrsBackend,
+    s3::S3Store,
+  },
+  request::get_public_url,
+};
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::{stream::BoxStream, Stream, StreamExt, TryStreamExt};
+use lemmy_utils::{
+  error::{LemmyError, LemmyErrorType, LemmyResult},
+  settings::structs::{MediaBackendType, Settings},
+};
+use reqwest::{
+  header::{HeaderMap, CONTENT_TYPE},
+  StatusCode,
+};
+use reqwest_middleware::ClientWithMiddleware;
+use serde::Deserialize;
+use std::pin::Pin;
+use url::Url;
+
+mod builtin;
+mod filesystem;
+mod pictrs;
+mod s3;
+pub mod scanner;
+
+/// A file as it was saved by the media backend.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredMedia {
+  /// The name under which the file is served at `/pictrs/image/{alias}`.
+  pub alias: String,
+  /// Needed to delete the file again without admin rights.
+  pub delete_token: String,
+  pub content_type: Option<String>,
+  /// The file size in bytes.
+  pub size: Option<i64>,
+}
+
+/// Conversion of an image before it is served. Without any options the original file is returned.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct MediaOptions {
+  /// The file extension of the wanted image format, like `jpg` or `webp`.
+  pub format: Option<String>,
+  /// The maximum width and height in pixels.
+  pub thumbnail: Option<u32>,
+}
+
+/// A stored file which is sent to clients.
+pub struct MediaResponse {
+  pub status: StatusCode,
+  pub headers: HeaderMap,
+  pub body: BoxStream<'static, Result<Bytes, reqwest::Error>>,
+}
+
+/// Content of a file which is stored, passed on while it is received.
+pub type UploadStream = Pin<Box<dyn Stream<Item = LemmyResult<Bytes>> + Send + Sync>>;
+
+/// Storage for uploaded images and cached thumbnails of remote images. Images are either handled
+/// by an external pictrs service, or by Lemmy itself in a directory or an S3 bucket.
+#[async_trait]
+pub trait MediaBackend: Send + Sync {
+  /// Stores a file which a local user uploaded, or which was downloaded. The size is already
+  /// limited by the caller. The client address is passed on to pictrs.
+  async fn upload(
+    &self,
+    data: UploadStream,
+    content_type: Option<&str>,
+    client_address: Option<&str>,
+  ) -> LemmyResult<StoredMedia>;
+
+  /// Returns a stored file, converted according to the options.
+  async fn read(&self, alias: &str, options: &MediaOptions) -> LemmyResult<MediaResponse>;
+
+  /// Deletes a file, if the token is the one which was returned when storing it.
+  async fn delete(&self, alias: &str, delete_token: &str) -> LemmyResult<()>;
+
+  /// Deletes a file without any token, which is used by admins to remove content.
+  async fn purge(&self, alias: &str) -> LemmyResult<()>;
+}
+
+/// Returns the media backend which is selected in the config.
+pub fn media_backend(
+  client: &ClientWithMiddleware,
+  settings: &Settings,
+) -> LemmyResult<Box<dyn MediaBackend>> {
+  let config = &settings.media;
+  Ok(match config.backend {
+    MediaBackendType::Pictrs => Box::new(PictrsBackend::new(client, settings.pictrs_config()?)),
+    MediaBackendType::Filesystem => {
+      Box::new(BuiltinBackend::new(FilesystemStore::new(&config.path)))
+    }
+    MediaBackendType::S3 => {
+      let s3_config = config
+        .s3
+        .clone()
+        .ok_or(LemmyErrorType::MediaBackendNotConfigured)?;
+      Box::new(BuiltinBackend::new(S3Store::new(s3_config, client)))
+    }
+  })
+}
+
+/// Downloads a remote image and stores it, so that it can be used as thumbnail. Images larger
+/// than the maximum upload size are rejected.
+pub async fn cache_remote_image(
+  backend: &dyn MediaBackend,
+  url: &Url,
+  context: &LemmyContext,
+) -> LemmyResult<StoredMedia> {
+  let max_size = context.settings().media.upload_quota.max_file_size;
+  let response = get_public_url(url, context.settings())
+    .await?
+    .error_for_status()?;
+  if response
+    .content_length()
+    .is_some_and(|length| i64::try_from(length).unwrap_or(i64::MAX) > max_size)
+  {
+    Err(LemmyErrorType::UploadTooLarge)?
+  }
+  let content_type = response
+    .headers()
+    .get(CONTENT_TYPE)
+    .and_then(|c| c.to_str().ok())
+    .map(ToString::to_string);
+  let data = limit_size(response.bytes_stream().map_err(LemmyError::from), max_size);
+  backend
+    .upload(Box::pin(data), content_type.as_deref(), None)
+    .await
+}
+
+/// Ends the stream with an error once it returned more than `max_size` bytes. The content length
+/// can be missing or wrong, so the size is counted while reading.
+pub fn limit_size<S>(stream: S, max_size: i64) -> impl Stream<Item = LemmyResult<Bytes>>
+where
+  S: Stream<Item = LemmyResult<Bytes>>,
+{
+  let mut size: i64 = 0;
+  stream.map(move |chunk| {
+    let chunk = chunk?;
+    size = size.saturating_add(i64::try_from(chunk.len()).unwrap_or(i64::MAX));
+    if size > max_size {
+      Err(LemmyErrorType::UploadTooLarge)?
+    }
+    Ok(chunk)
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use crate::media::limit_size;
+  use bytes::Bytes;
+  use futures::{stream, StreamExt};
+  use lemmy_utils::error::LemmyErrorType;
+
+  #[tokio::test]
+  async fn test_limit_size() {
+    let chunks = || {
+      stream::iter([
+        Ok(Bytes::from_static(b"12345")),
+        Ok(Bytes::from_static(b"678")),
+      ])
+    };
+    let all: Vec<_> = limit_size(chunks(), 8).collect().await;
+    assert!(all.iter().all(Result::is_ok));
+
+    let mut limited = Box::pin(limit_size(chunks(), 7));
+    assert!(limited.next().await.unwrap().is_ok());
+    let err = limited.next().await.unwrap().unwrap_err();
+    assert_eq!(LemmyErrorType::UploadTooLarge, err.error_type);
+  }
+}
@@ -0,0 +1,193 @@
+use crate::media::{MediaBackend, MediaOptions, MediaResponse, StoredMedia, UploadStream};
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::{future, stream, StreamExt, TryStreamExt};
+use lemmy_utils::{
+  error::{LemmyError, LemmyErrorType, LemmyResult},
+  settings::structs::PictrsConfig,
+  REQWEST_TIMEOUT,
+};
+use reqwest::{
+  header::{CONNECTION, CONTENT_LENGTH, CONTENT_TYPE},
+  Body,
+};
+use reqwest_middleware::ClientWithMiddleware;
+use serde::Deserialize;
+use std::io;
+use uuid::Uuid;
+
+#[derive(Deserialize, Debug, Clone)]
+struct PictrsResponse {
+  files: Option<Vec<PictrsFile>>,
+  msg: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct PictrsFile {
+  file: String,
+  delete_token: String,
+  details: Option<PictrsFileDetails>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct PictrsFileDetails {
+  content_type: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct PictrsPurgeResponse {
+  msg: String,
+}
+
+/// Forwards all media requests to an external pictrs service.
+pub(super) struct PictrsBackend {
+  client: ClientWithMiddleware,
+  config: PictrsConfig,
+}
+
+impl PictrsBackend {
+  pub(super) fn new(client: &ClientWithMiddleware, config: PictrsConfig) -> Self {
+    PictrsBackend {
+      client: client.clone(),
+      config,
+    }
+  }
+
+  /// Reads the first file from the response of an upload or download.
+  async fn stored_file(&self, response: reqwest::Response) -> LemmyResult<StoredMedia> {
+    let response: PictrsResponse = response.json().await.map_err(LemmyError::from)?;
+    let file = response
+      .files
+      .and_then(|f| f.into_iter().next())
+      .ok_or(LemmyErrorType::PictrsResponseError(response.msg))?;
+    let size = self.file_size(&file.file).await;
+    Ok(StoredMedia {
+      alias: file.file,
+      delete_token: file.delete_token,
+      content_type: file.details.map(|d| d.content_type),
+      size,
+    })
+  }
+
+  /// pictrs doesn't return the file size after storing, so it is read from the original.
+  async fn file_size(&self, alias: &str) -> Option<i64> {
+    let url = format!("{}image/original/{}", self.config.url, alias);
+    let res = self
+      .client
+      .head(url)
+      .timeout(REQWEST_TIMEOUT)
+      .send()
+      .await
+      .ok()?;
+    res
+      .headers()
+      .get(CONTENT_LENGTH)?
+      .to_str()
+      .ok()?
+      .parse()
+      .ok()
+  }
+}
+
+#[async_trait]
+impl MediaBackend for PictrsBackend {
+  async fn upload(
+    &self,
+    data: UploadStream,
+    content_type: Option<&str>,
+    client_address: Option<&str>,
+  ) -> LemmyResult<StoredMedia> {
+    // pictrs only accepts multipart uploads
+    let boundary = format!("lemmy-{}", Uuid::new_v4().simple());
+    let head = format!(
+      "--{boundary}\r\nContent-Disposition: form-data; name=\"images[]\"; filename=\"upload\"\r\n\
+      Content-Type: {}\r\n\r\n",
+      content_type.unwrap_or("application/octet-stream")
+    );
+    let tail = format!("\r\n--{boundary}--\r\n");
+    let body = stream::once(future::ok(Bytes::from(head)))
+      .chain(data)
+      .chain(stream::once(future::ok(Bytes::from(tail))))
+      .map_err(|e| io::Error::other(e.to_string()));
+
+    let mut request = self
+      .client
+      .post(format!("{}image", self.config.url))
+      .header(
+        CONTENT_TYPE,
+        format!("multipart/form-data; boundary={boundary}"),
+      )
+      .body(Body::wrap_stream(body));
+    if let Some(client_address) = client_address {
+      request = request.header("X-Forwarded-For", client_address);
+    }
+    let response = request.send().await?;
+    self.stored_file(response).await
+  }
+
+  async fn read(&self, alias: &str, options: &MediaOptions) -> LemmyResult<MediaResponse> {
+    // If there are no options, the URL is original
+    let url = if options.format.is_none() && options.thumbnail.is_none() {
+      format!("{}image/original/{}", self.config.url, alias)
+    } else {
+      // Take file type from name, or jpg if nothing is given
+      let format = options
+        .format
+        .clone()
+        .unwrap_or_else(|| alias.rsplit('.').next().unwrap_or("jpg").to_string());
+
+      let mut url = format!("{}image/process.{}?src={}", self.config.url, format, alias);
+
+      if let Some(size) = options.thumbnail {
+        url = format!("{url}&thumbnail={size}");
+      }
+      url
+    };
+
+    let response = self.client.get(url).send().await?;
+    let mut headers = response.headers().clone();
+    headers.remove(CONNECTION);
+    Ok(MediaResponse {
+      status: response.status(),
+      headers,
+      body: response.bytes_stream().boxed(),
+    })
+  }
+
+  async fn delete(&self, alias: &str, delete_token: &str) -> LemmyResult<()> {
+    let url = format!("{}image/delete/{}/{}", self.config.url, delete_token, alias);
+    self
+      .client
+      .delete(&url)
+      .timeout(REQWEST_TIMEOUT)
+      .send()
+      .await
+      .map_err(LemmyError::from)?;
+    Ok(())
+  }
+
+  async fn purge(&self, alias: &str) -> LemmyResult<()> {
+    let purge_url = format!("{}internal/purge?alias={}", self.config.url, alias);
+
+    let pictrs_api_key = self
+      .config
+      .api_key
+      .as_ref()
+      .ok_or(LemmyErrorType::PictrsApiKeyNotProvided)?;
+    let response = self
+      .client
+      .post(&purge_url)
+      .timeout(REQWEST_TIMEOUT)
+      .header("x-api-token", pictrs_api_key)
+      .send()
+      .await?;
+
+    let response: PictrsPurgeResponse = response.json().await.map_err(LemmyError::from)?;
+
+    if response.msg == "ok" {
+      Ok(())
+    } else {
+      Err(LemmyErrorType::PictrsPurgeResponseError(response.msg))?
+    }
+  }
+}
@@ -0,0 +1,247 @@
+use crate::{
+  context::LemmyContext,
+  media::{MediaBackend, MediaOptions, StoredMedia},
+  request::purge_image_from_pictrs_by_alias,
+};
+use bytes::{Bytes, BytesMut};
+use futures::TryStreamExt;
+use lemmy_db_schema::{
+  newtypes::PersonId,
+  source::media_scan::{MediaScan, MediaScanForm},
+  MediaScanResult,
+};
+use lemmy_utils::{
+  error::{LemmyError, LemmyErrorExt, LemmyErrorType, LemmyResult},
+  settings::structs::MediaScannerConfig,
+};
+use reqwest::header::CONTENT_TYPE;
+use serde::Deserialize;
+use std::{process::Stdio, time::Duration};
+use tokio::{io::AsyncWriteExt, process::Command};
+use tracing::warn;
+use url::Url;
+
+/// Response of the scanner service.
+#[derive(Deserialize)]
+struct ScanResponse {
+  result: ScanVerdict,
+  reason: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum ScanVerdict {
+  Allow,
+  Deny,
+  Quarantine,
+}
+
+impl From<ScanVerdict> for MediaScanResult {
+  fn from(verdict: ScanVerdict) -> Self {
+    match verdict {
+      ScanVerdict::Allow => MediaScanResult::Allow,
+      ScanVerdict::Deny => MediaScanResult::Deny,
+      ScanVerdict::Quarantine => MediaScanResult::Quarantine,
+    }
+  }
+}
+
+/// Scans a file which was just stored by the media backend, and records the result. Denied files
+/// are purged right away and return an error. Quarantined files stay stored, but are not served
+/// until an admin resolved the scan.
+///
+/// The data is read back from the backend if it isn't given, which is the case for remote images.
+pub async fn scan_stored_media(
+  context: &LemmyContext,
+  backend: &dyn MediaBackend,
+  media: &StoredMedia,
+  data: Option<Bytes>,
+  person_id: Option<PersonId>,
+  remote_url: Option<&Url>,
+) -> LemmyResult<MediaScanResult> {
+  let Some(config) = &context.settings().media.scanner else {
+    return Ok(MediaScanResult::Allow);
+  };
+  let data = match data {
+    Some(data) => Ok(data),
+    None => read_stored_media(backend, &media.alias).await,
+  };
+  let scan = match data {
+    Ok(data) => scan_media(context, config, data, media.content_type.as_deref()).await,
+    Err(e) => Err(e),
+  };
+  let (result, reason) = match scan {
+    Ok(scan) => scan,
+    Err(e) if config.allow_on_error => {
+      warn!("Failed to scan media {}: {e}", media.alias);
+      (MediaScanResult::Allow, Some(format!("Scan failed: {e}")))
+    }
+    Err(e) => {
+      warn!("Failed to scan media {}: {e}", media.alias);
+      purge_image_from_pictrs_by_alias(&media.alias, context)
+        .await
+        .ok();
+      Err(LemmyErrorType::MediaScanFailed)?
+    }
+  };
+
+  let form = MediaScanForm {
+    pictrs_alias: media.alias.clone(),
+    person_id,
+    remote_url: remote_url.cloned().map(Into::into),
+    result,
+    reason,
+    resolved: result == MediaScanResult::Allow,
+  };
+  MediaScan::create(&mut context.pool(), &form).await?;
+
+  if result == MediaScanResult::Deny {
+    purge_image_from_pictrs_by_alias(&media.alias, context).await?;
+    Err(LemmyErrorType::MediaDenied)?
+  }
+  Ok(result)
+}
+
+async fn read_stored_media(backend: &dyn MediaBackend, alias: &str) -> LemmyResult<Bytes> {
+  let res = backend.read(alias, &MediaOptions::default()).await?;
+  if !res.status.is_success() {
+    Err(LemmyErrorType::CouldntFindImage)?
+  }
+  let data = res
+    .body
+    .try_fold(BytesMut::new(), |mut data, chunk| async move {
+      data.extend_from_slice(&chunk);
+      Ok(data)
+    })
+    .await
+    .map_err(LemmyError::from)?;
+  Ok(data.freeze())
+}
+
+async fn scan_media(
+  context: &LemmyContext,
+  config: &MediaScannerConfig,
+  data: Bytes,
+  content_type: Option<&str>,
+) -> LemmyResult<(MediaScanResult, Option<String>)> {
+  let timeout = Duration::from_secs(config.timeout_seconds);
+  if let Some(url) = &config.url {
+    let mut req = context
+      .client()
+      .post(url.as_str())
+      .timeout(timeout)
+      .body(data);
+    if let Some(content_type) = content_type {
+      req = req.header(CONTENT_TYPE, content_type);
+    }
+    let res: ScanResponse = req.send().await?.error_for_status()?.json().await?;
+    Ok((res.result.into(), res.reason))
+  } else if let Some(command) = &config.command {
+    scan_media_with_command(command, data, timeout).await
+  } else {
+    Err(LemmyErrorType::MediaScanFailed)?
+  }
+}
+
+/// Runs the scanner command with the file on stdin. The first word of the output is the verdict,
+/// the rest of the line is the reason. The command is killed if it doesn't finish in time.
+async fn scan_media_with_command(
+  command: &str,
+  data: Bytes,
+  timeout: Duration,
+) -> LemmyResult<(MediaScanResult, Option<String>)> {
+  let mut args = command.split_whitespace();
+  let program = args.next().ok_or(LemmyErrorType::MediaScanFailed)?;
+  let mut child = Command::new(program)
+    .args(args)
+    .stdin(Stdio::piped())
+    .stdout(Stdio::piped())
+    .kill_on_drop(true)
+    .spawn()
+    .with_lemmy_type(LemmyErrorType::MediaScanFailed)?;
+  // Write in the background, otherwise a command which prints a lot before reading all of stdin
+  // blocks forever
+  if let Some(mut stdin) = child.stdin.take() {
+    tokio::spawn(async move {
+      // Fails if the command exits without reading the whole file, which is fine
+      stdin.write_all(&data).await.ok();
+    });
+  }
+  let output = tokio::time::timeout(timeout, child.wait_with_output())
+    .await
+    .with_lemmy_type(LemmyErrorType::MediaScanFailed)?
+    .with_lemmy_type(LemmyErrorType::MediaScanFailed)?;
+  if !output.status.success() {
+    Err(LemmyErrorType::MediaScanFailed)?
+  }
+  parse_scanner_output(&String::from_utf8_lossy(&output.stdout))
+}
+
+fn parse_scanner_output(output: &str) -> LemmyResult<(MediaScanResult, Option<String>)> {
+  let line = output.lines().next().unwrap_or_default().trim();
+  let (verdict, reason) = line.split_once(' ').unwrap_or((line, ""));
+  let result = match verdict.to_lowercase().as_str() {
+    "allow" => MediaScanResult::Allow,
+    "deny" => MediaScanResult::Deny,
+    "quarantine" => MediaScanResult::Quarantine,
+    _ => Err(LemmyErrorType::MediaScanFailed)?,
+  };
+  let reason = Some(reason.trim().to_string()).filter(|r| !r.is_empty());
+  Ok((result, reason))
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use crate::media::scanner::{parse_scanner_output, scan_media_with_command};
+  use bytes::Bytes;
+  use lemmy_db_schema::MediaScanResult;
+  use std::time::Duration;
+
+  #[test]
+  fn test_parse_scanner_output() {
+    assert_eq!(
+      (MediaScanResult::Allow, None),
+      parse_scanner_output("allow\n").unwrap()
+    );
+    assert_eq!(
+      (MediaScanResult::Deny, Some("hash match".to_string())),
+      parse_scanner_output("DENY hash match\nmore output").unwrap()
+    );
+    assert!(parse_scanner_output("").is_err());
+    assert!(parse_scanner_output("maybe").is_err());
+  }
+
+  #[tokio::test]
+  async fn test_scan_media_with_command() {
+    let timeout = Duration::from_secs(10);
+    // The file content is passed on stdin, so cat prints it back as verdict
+    let data = Bytes::from_static(b"quarantine needs review");
+    let res = scan_media_with_command("cat", data, timeout).await.unwrap();
+    assert_eq!(
+      (
+        MediaScanResult::Quarantine,
+        Some("needs review".to_string())
+      ),
+      res
+    );
+    assert!(scan_media_with_command("false", Bytes::new(), timeout)
+      .await
+      .is_err());
+
+    // Files larger than the pipe buffer don't block if the command doesn't read them
+    let data = Bytes::from(vec![b'a'; 1024 * 1024]);
+    let res = scan_media_with_command("echo allow", data, timeout).await;
+    assert_eq!((MediaScanResult::Allow, None), res.unwrap());
+  }
+
+  #[tokio::test]
+  async fn test_scan_media_with_command_timeout() {
+    let timeout = Duration::from_millis(100);
+    assert!(scan_media_with_command("sleep 10", Bytes::new(), timeout)
+      .await
+      .is_err());
+  }
+}
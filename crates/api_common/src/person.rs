@@ -1,14 +1,31 @@
 use crate::sensitive::Sensitive;
 use lemmy_db_schema::{
-  newtypes::{CommentReplyId, CommunityId, LanguageId, PersonId, PersonMentionId},
+  newtypes::{
+    CommentReplyId,
+    CommunityId,
+    LanguageId,
+    ModActionNotificationId,
+    PersonId,
+    PersonMentionId,
+  },
+  source::{
+    image_upload::ImageUpload,
+    notification_preference::NotificationPreference,
+    person_device_key::PersonDeviceKey,
+    push_subscription::PushSubscription,
+  },
   CommentSortType,
+  EmailDigestMode,
   ListingType,
+  NotificationType,
+  PrivateMessagePolicy,
   SortType,
 };
-use lemmy_db_views::structs::{CommentView, PostView};
+use lemmy_db_views::structs::{CommentView, ImageUploadView, PostView};
 use lemmy_db_views_actor::structs::{
   CommentReplyView,
   CommunityModeratorView,
+  ModActionNotificationView,
   PersonMentionView,
   PersonView,
 };
@@ -108,6 +125,10 @@ pub struct SaveUserSettings {
   pub show_avatars: Option<bool>,
   /// Sends notifications to your email.
   pub send_notifications_to_email: Option<bool>,
+  /// Bundle notification emails into a daily or weekly digest.
+  pub email_digest: Option<EmailDigestMode>,
+  /// Who can start a private message conversation with you.
+  pub private_message_policy: Option<PrivateMessagePolicy>,
   /// Whether this account is a bot account. Users can hide these accounts easily if they wish.
   pub bot_account: Option<bool>,
   /// Whether to show bot accounts.
@@ -303,6 +324,42 @@ pub struct PersonMentionResponse {
   pub person_mention_view: PersonMentionView,
 }
 
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Get notifications about moderator actions on your content.
+pub struct ListModActionNotifications {
+  pub page: Option<i64>,
+  pub limit: Option<i64>,
+  pub unread_only: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The response of moderator action notifications for your user.
+pub struct ListModActionNotificationsResponse {
+  pub mod_actions: Vec<ModActionNotificationView>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Mark a moderator action notification as read.
+pub struct MarkModActionNotificationAsRead {
+  pub mod_action_notification_id: ModActionNotificationId,
+  pub read: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The response for a moderator action notification action.
+pub struct ModActionNotificationResponse {
+  pub mod_action_notification_view: ModActionNotificationView,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
@@ -369,7 +426,7 @@ pub struct GetReportCount {
 }
 
 #[skip_serializing_none]
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
 /// A response for the number of reports.
@@ -388,6 +445,9 @@ pub struct GetUnreadCountResponse {
   pub replies: i64,
   pub mentions: i64,
   pub private_messages: i64,
+  pub mod_actions: i64,
+  /// Conversations which wait for you to accept them.
+  pub message_requests: i64,
 }
 
 #[derive(Serialize, Deserialize, Clone, Default, Debug)]
@@ -411,6 +471,115 @@ pub struct GenerateTotpSecretResponse {
   pub totp_secret_url: Sensitive<String>,
 }
 
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The token for private feeds, which is used instead of the login token in feed links.
+pub struct FeedTokenResponse {
+  pub feed_token: Option<Sensitive<String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Registers a device for push notifications, with the values of the browser `PushSubscription`.
+pub struct CreatePushSubscription {
+  pub endpoint: String,
+  /// The public key of the subscription, base64url encoded.
+  pub p256dh: String,
+  /// The authentication secret of the subscription, base64url encoded.
+  pub auth: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Stops push notifications for a device.
+pub struct DeletePushSubscription {
+  pub endpoint: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+pub struct PushSubscriptionResponse {
+  pub push_subscription: PushSubscription,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The devices of the user which receive push notifications.
+pub struct ListPushSubscriptionsResponse {
+  /// The key which browsers need as `applicationServerKey` to subscribe.
+  pub vapid_public_key: Option<String>,
+  pub push_subscriptions: Vec<PushSubscription>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Publishes the public key of a device, so that others can send it encrypted private messages.
+pub struct AddDeviceKey {
+  /// Chosen by the client. Adding a key for an existing device replaces it.
+  pub device_id: String,
+  pub public_key: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Removes the key of a device, for example when logging out.
+pub struct DeleteDeviceKey {
+  pub device_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Gets the device keys of a person, which are needed to encrypt private messages for them.
+pub struct ListDeviceKeys {
+  pub person_id: PersonId,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// If the list is empty, the person can't receive encrypted private messages.
+pub struct ListDeviceKeysResponse {
+  pub device_keys: Vec<PersonDeviceKey>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Changes how you are notified about one type of notification.
+pub struct EditNotificationPreference {
+  pub notification_type: NotificationType,
+  pub push: Option<bool>,
+  pub in_app: Option<bool>,
+  pub email: Option<bool>,
+  pub only_from_followed: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+pub struct NotificationPreferenceResponse {
+  pub notification_preference: NotificationPreference,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Your notification preferences for all notification types.
+pub struct ListNotificationPreferencesResponse {
+  pub notification_preferences: Vec<NotificationPreference>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
@@ -425,3 +594,78 @@ pub struct UpdateTotp {
 pub struct UpdateTotpResponse {
   pub enabled: bool,
 }
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Lists the images you uploaded, or the uploads of all users for admins.
+pub struct ListMedia {
+  pub page: Option<i64>,
+  pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The list of uploaded images.
+pub struct ListMediaResponse {
+  pub images: Vec<ImageUploadView>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Deletes an uploaded image. Admins can delete the uploads of any user.
+pub struct DeleteMedia {
+  pub alias: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The response of deleting an uploaded image.
+pub struct DeleteMediaResponse {}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Changes the description of an uploaded image, which is used as alt text for posts linking to it.
+pub struct EditMedia {
+  pub alias: String,
+  pub alt_text: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The edited image.
+pub struct EditMediaResponse {
+  pub image: ImageUpload,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// How much of their upload quota a user has used. Empty limits are unlimited.
+pub struct UploadQuotaResponse {
+  pub upload_count: i64,
+  /// The total size of all uploads in bytes.
+  pub upload_size: i64,
+  pub max_files: Option<i32>,
+  pub max_total_size: Option<i64>,
+  pub max_file_size: i64,
+  pub allowed_mime_types: Vec<String>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Changes the upload quota of a user. Empty limits fall back to the site default.
+pub struct SetUploadQuota {
+  pub person_id: PersonId,
+  pub max_total_size: Option<i64>,
+  pub max_files: Option<i32>,
+}
@@ -1,5 +1,5 @@
 use lemmy_db_schema::{
-  newtypes::{CommentId, CommunityId, DbUrl, LanguageId, PostId, PostReportId},
+  newtypes::{CommentId, CommunityId, CustomFeedId, DbUrl, LanguageId, PostId, PostReportId},
   ListingType,
   PostFeatureType,
   SortType,
@@ -28,6 +28,22 @@ pub struct CreatePost {
   pub honeypot: Option<String>,
   pub nsfw: Option<bool>,
   pub language_id: Option<LanguageId>,
+  /// A description of the image at the url, for screen readers.
+  pub alt_text: Option<String>,
+  /// Images for a gallery post. If set, the url and alt text are taken from the first image.
+  pub gallery: Option<Vec<GalleryImage>>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// An image of a gallery post.
+pub struct GalleryImage {
+  #[cfg_attr(feature = "full", ts(type = "string"))]
+  pub url: Url,
+  /// A description of the image, for screen readers.
+  pub alt_text: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -72,6 +88,8 @@ pub struct GetPosts {
   pub limit: Option<i64>,
   pub community_id: Option<CommunityId>,
   pub community_name: Option<String>,
+  /// Only posts from the communities of this custom feed
+  pub custom_feed_id: Option<CustomFeedId>,
   pub saved_only: Option<bool>,
   pub liked_only: Option<bool>,
   pub disliked_only: Option<bool>,
@@ -113,6 +131,11 @@ pub struct EditPost {
   pub body: Option<String>,
   pub nsfw: Option<bool>,
   pub language_id: Option<LanguageId>,
+  /// A description of the image at the url, for screen readers.
+  pub alt_text: Option<String>,
+  /// Replaces the images of a gallery post, an empty list removes the gallery. If set, the url and
+  /// alt text are taken from the first image.
+  pub gallery: Option<Vec<GalleryImage>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
@@ -172,6 +195,42 @@ pub struct SavePost {
   pub save: bool,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Subscribe to a post, to get notified about all new comments in it.
+pub struct SubscribeToPost {
+  pub post_id: PostId,
+  pub subscribe: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Mute a post, to not get notified about replies in it anymore.
+pub struct MutePost {
+  pub post_id: PostId,
+  pub mute: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Get your notification settings for a post.
+pub struct GetPostNotificationSettings {
+  pub post_id: PostId,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Whether you are subscribed to a post, or muted it.
+pub struct PostNotificationSettingsResponse {
+  pub post_id: PostId,
+  pub subscribed: bool,
+  pub muted: bool,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
@@ -229,6 +288,14 @@ pub struct GetSiteMetadata {
   pub url: Url,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Fetch the link metadata of a post again, for example if it was wrong (mods only).
+pub struct RefreshPostMetadata {
+  pub post_id: PostId,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
@@ -238,13 +305,19 @@ pub struct GetSiteMetadataResponse {
 }
 
 #[skip_serializing_none]
-#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Default)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
-/// Site metadata, from its opengraph tags.
+/// Site metadata, from its opengraph tags and oEmbed.
 pub struct SiteMetadata {
   pub title: Option<String>,
   pub description: Option<String>,
   pub(crate) image: Option<DbUrl>,
   pub embed_video_url: Option<DbUrl>,
+  /// The content type of the link, for example `text/html` or `video/mp4`.
+  pub content_type: Option<String>,
+  pub embed_width: Option<i32>,
+  pub embed_height: Option<i32>,
+  /// The duration of a video or audio, in seconds.
+  pub embed_duration: Option<i32>,
 }
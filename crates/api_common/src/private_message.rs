@@ -1,10 +1,16 @@
-use lemmy_db_schema::newtypes::{PersonId, PrivateMessageId, PrivateMessageReportId};
-use lemmy_db_views::structs::{PrivateMessageReportView, PrivateMessageView};
+use lemmy_db_schema::newtypes::{
+  ConversationId,
+  PersonId,
+  PrivateMessageId,
+  PrivateMessageReportId,
+};
+use lemmy_db_views::structs::{ConversationView, PrivateMessageReportView, PrivateMessageView};
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
 #[cfg(feature = "full")]
 use ts_rs::TS;
 
+#[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
@@ -12,6 +18,12 @@ use ts_rs::TS;
 pub struct CreatePrivateMessage {
   pub content: String,
   pub recipient_id: PersonId,
+  /// Further recipients, for a group conversation.
+  pub additional_recipient_ids: Option<Vec<PersonId>>,
+  /// The content was encrypted by the client for the device keys of all participants. Fails with
+  /// `recipient_does_not_support_encryption` if a recipient has no device keys, in which case the
+  /// client can send the message unencrypted instead.
+  pub encrypted: Option<bool>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
@@ -51,6 +63,10 @@ pub struct GetPrivateMessages {
   pub page: Option<i64>,
   pub limit: Option<i64>,
   pub creator_id: Option<PersonId>,
+  /// Only get the messages of this conversation.
+  pub conversation_id: Option<ConversationId>,
+  /// Get the messages from message requests instead of the inbox.
+  pub message_requests: Option<bool>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -69,6 +85,78 @@ pub struct PrivateMessageResponse {
   pub private_message_view: PrivateMessageView,
 }
 
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// List your private message conversations.
+pub struct ListConversations {
+  /// Only list archived conversations.
+  pub archived: Option<bool>,
+  /// Only list message requests, which are conversations started by persons you don't follow.
+  pub message_requests: Option<bool>,
+  pub page: Option<i64>,
+  pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The conversations response.
+pub struct ListConversationsResponse {
+  pub conversations: Vec<ConversationView>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Get a single conversation.
+pub struct GetConversation {
+  pub conversation_id: ConversationId,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Mute a conversation, so that new messages don't send notifications.
+pub struct MuteConversation {
+  pub conversation_id: ConversationId,
+  pub muted: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Archive a conversation, which hides it until a new message arrives.
+pub struct ArchiveConversation {
+  pub conversation_id: ConversationId,
+  pub archived: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Mark all messages of a conversation as read.
+pub struct MarkConversationAsRead {
+  pub conversation_id: ConversationId,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Move a conversation from the message requests to the inbox.
+pub struct AcceptMessageRequest {
+  pub conversation_id: ConversationId,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A single conversation response.
+pub struct ConversationResponse {
+  pub conversation_view: ConversationView,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
@@ -0,0 +1,238 @@
+use crate::context::LemmyContext;
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use lemmy_db_schema::newtypes::{CommentId, LocalUserId, PrivateMessageId};
+use lemmy_utils::error::{LemmyErrorExt, LemmyErrorType, LemmyResult};
+use sha2::Sha256;
+
+/// Number of signature bytes included in reply addresses, to keep them reasonably short
+const SIGNATURE_LENGTH: usize = 16;
+/// Reply addresses are rejected after this many seconds, so that a leaked address can't be used
+/// to post as the user forever.
+const REPLY_ADDRESS_MAX_AGE: i64 = 30 * 24 * 60 * 60;
+
+/// The content which is answered by replying to a notification email.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplyTarget {
+  Comment(CommentId),
+  PrivateMessage(PrivateMessageId),
+}
+
+/// Returns the signed reply-to address for a notification email, or `None` if reply by email is
+/// not configured. The address has the form `reply+c123-45-<issued>-<signature>@domain`.
+pub fn reply_address(
+  target: ReplyTarget,
+  local_user_id: LocalUserId,
+  context: &LemmyContext,
+) -> Option<String> {
+  let email = context.settings().email.as_ref()?;
+  let domain = email.reply_by_email.domain.as_ref()?;
+  let local_part = reply_local_part(
+    target,
+    local_user_id,
+    Utc::now().timestamp(),
+    &context.secret().jwt_secret,
+  );
+  Some(format!("{local_part}@{domain}"))
+}
+
+/// Verifies the signature and age of a reply address and returns the target and user it was issued
+/// for.
+pub fn parse_reply_address(
+  address: &str,
+  context: &LemmyContext,
+) -> LemmyResult<(ReplyTarget, LocalUserId)> {
+  let domain = context
+    .settings()
+    .email
+    .as_ref()
+    .and_then(|e| e.reply_by_email.domain.as_ref())
+    .ok_or(LemmyErrorType::ReplyByEmailDisabled)?;
+  let address = address.trim().to_lowercase();
+  let local_part = address
+    .strip_suffix(&format!("@{}", domain.to_lowercase()))
+    .ok_or(LemmyErrorType::InvalidReplyAddress)?;
+  parse_reply_local_part(
+    local_part,
+    Utc::now().timestamp(),
+    &context.secret().jwt_secret,
+  )
+}
+
+fn reply_local_part(
+  target: ReplyTarget,
+  local_user_id: LocalUserId,
+  issued: i64,
+  secret: &str,
+) -> String {
+  let payload = reply_payload(target, local_user_id, issued);
+  let signature = reply_mac(&payload, secret).finalize().into_bytes();
+  format!(
+    "reply+{payload}-{}",
+    hex::encode(&signature[..SIGNATURE_LENGTH])
+  )
+}
+
+fn parse_reply_local_part(
+  local_part: &str,
+  now: i64,
+  secret: &str,
+) -> LemmyResult<(ReplyTarget, LocalUserId)> {
+  let invalid = || LemmyErrorType::InvalidReplyAddress;
+  let rest = local_part.strip_prefix("reply+").ok_or_else(invalid)?;
+  let mut parts = rest.split('-');
+  let (Some(target), Some(user), Some(issued), Some(signature), None) = (
+    parts.next(),
+    parts.next(),
+    parts.next(),
+    parts.next(),
+    parts.next(),
+  ) else {
+    Err(invalid())?
+  };
+  let parse_id = |id: &str| id.parse::<i32>().with_lemmy_type(invalid());
+  let target = if let Some(id) = target.strip_prefix('c') {
+    ReplyTarget::Comment(CommentId(parse_id(id)?))
+  } else if let Some(id) = target.strip_prefix('m') {
+    ReplyTarget::PrivateMessage(PrivateMessageId(parse_id(id)?))
+  } else {
+    Err(invalid())?
+  };
+  let local_user_id = LocalUserId(user.parse::<i32>().with_lemmy_type(invalid())?);
+  let issued = issued.parse::<i64>().with_lemmy_type(invalid())?;
+
+  let signature = hex::decode(signature).with_lemmy_type(invalid())?;
+  if signature.len() != SIGNATURE_LENGTH {
+    Err(invalid())?
+  }
+  reply_mac(&reply_payload(target, local_user_id, issued), secret)
+    .verify_truncated_left(&signature)
+    .with_lemmy_type(invalid())?;
+  if now.saturating_sub(issued) > REPLY_ADDRESS_MAX_AGE {
+    Err(invalid())?
+  }
+  Ok((target, local_user_id))
+}
+
+fn reply_payload(target: ReplyTarget, local_user_id: LocalUserId, issued: i64) -> String {
+  let target = match target {
+    ReplyTarget::Comment(id) => format!("c{}", id.0),
+    ReplyTarget::PrivateMessage(id) => format!("m{}", id.0),
+  };
+  format!("{target}-{}-{issued}", local_user_id.0)
+}
+
+fn reply_mac(payload: &str, secret: &str) -> Hmac<Sha256> {
+  // Hmac accepts keys of any length
+  #[allow(clippy::expect_used)]
+  let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("hmac key");
+  // Prefix so that the signature can't be reused for other purposes of the same key
+  mac.update(b"reply-by-email:");
+  mac.update(payload.as_bytes());
+  mac
+}
+
+/// Removes the quoted original message and signature which mail clients add below (or above) the
+/// actual reply text.
+pub fn strip_quoted_reply(text: &str) -> String {
+  let lines: Vec<&str> = text.lines().collect();
+  let mut end = lines.len();
+  for (i, line) in lines.iter().enumerate() {
+    let trimmed = line.trim();
+    let next = lines.get(i + 1).map(|l| l.trim()).unwrap_or_default();
+    let is_attribution = trimmed.starts_with("On ")
+      && (trimmed.ends_with("wrote:") || (!trimmed.contains("wrote:") && next.ends_with("wrote:")));
+    if trimmed.starts_with('>')
+      || is_attribution
+      || trimmed.starts_with("-----Original Message-----")
+      || trimmed.starts_with("________________")
+      || line.trim_end_matches(['\r', '\n']) == "-- "
+      || trimmed.starts_with("From: ")
+    {
+      end = i;
+      break;
+    }
+  }
+  lines[..end].join("\n").trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use super::*;
+
+  const NOW: i64 = 1_700_000_000;
+
+  #[test]
+  fn test_reply_address_roundtrip() {
+    let secret = "secret";
+    let target = ReplyTarget::Comment(CommentId(123));
+    let local_part = reply_local_part(target, LocalUserId(45), NOW, secret);
+    assert!(local_part.starts_with("reply+c123-45-"));
+    let parsed = parse_reply_local_part(&local_part, NOW, secret).unwrap();
+    assert_eq!((target, LocalUserId(45)), parsed);
+
+    let target = ReplyTarget::PrivateMessage(PrivateMessageId(7));
+    let local_part = reply_local_part(target, LocalUserId(1), NOW, secret);
+    let parsed = parse_reply_local_part(&local_part, NOW + 60, secret).unwrap();
+    assert_eq!((target, LocalUserId(1)), parsed);
+  }
+
+  #[test]
+  fn test_reply_address_tampered() {
+    let secret = "secret";
+    let local_part = reply_local_part(
+      ReplyTarget::Comment(CommentId(123)),
+      LocalUserId(45),
+      NOW,
+      secret,
+    );
+    assert!(parse_reply_local_part(&local_part, NOW, "other secret").is_err());
+    let tampered = local_part.replace("reply+c123-45-", "reply+c124-45-");
+    assert!(parse_reply_local_part(&tampered, NOW, secret).is_err());
+    let tampered = local_part.replace("reply+c123-45-", "reply+c123-46-");
+    assert!(parse_reply_local_part(&tampered, NOW, secret).is_err());
+    let tampered = local_part.replace("reply+c", "reply+m");
+    assert!(parse_reply_local_part(&tampered, NOW, secret).is_err());
+    let tampered = local_part.replace(&NOW.to_string(), &(NOW + 1).to_string());
+    assert!(parse_reply_local_part(&tampered, NOW, secret).is_err());
+    assert!(parse_reply_local_part("reply+c123-45", NOW, secret).is_err());
+    assert!(parse_reply_local_part("reply+c123-45-00", NOW, secret).is_err());
+    // The address comes from inbound mail, so it may contain anything
+    assert!(parse_reply_local_part("reply+é1-2-3-sig", NOW, secret).is_err());
+    assert!(parse_reply_local_part("reply+cé-2-3-sig", NOW, secret).is_err());
+    assert!(parse_reply_local_part("reply+-2-3-sig", NOW, secret).is_err());
+  }
+
+  #[test]
+  fn test_reply_address_expired() {
+    let secret = "secret";
+    let target = ReplyTarget::Comment(CommentId(123));
+    let local_part = reply_local_part(target, LocalUserId(45), NOW, secret);
+    let last_valid = NOW + REPLY_ADDRESS_MAX_AGE;
+    assert!(parse_reply_local_part(&local_part, last_valid, secret).is_ok());
+    assert!(parse_reply_local_part(&local_part, last_valid + 1, secret).is_err());
+  }
+
+  #[test]
+  fn test_strip_quoted_reply() {
+    let text = "Thanks, sounds good!\n\nOn Mon, Oct 9, 2023 at 10:00 AM Lemmy <noreply@example.com> wrote:\n> Original comment";
+    assert_eq!("Thanks, sounds good!", strip_quoted_reply(text));
+
+    let text = "Split attribution\n\nOn Mon, Oct 9, 2023 at 10:00 AM Lemmy\n<noreply@example.com> wrote:\n> quoted";
+    assert_eq!("Split attribution", strip_quoted_reply(text));
+
+    let text = "Reply\r\n\r\n-----Original Message-----\r\nFrom: Lemmy";
+    assert_eq!("Reply", strip_quoted_reply(text));
+
+    let text = "Reply with signature\n-- \nJohn";
+    assert_eq!("Reply with signature", strip_quoted_reply(text));
+
+    let text = "Multiple\nlines\n\n________________________________\nFrom: Lemmy";
+    assert_eq!("Multiple\nlines", strip_quoted_reply(text));
+
+    assert_eq!("", strip_quoted_reply("> only quote"));
+  }
+}
@@ -1,35 +1,186 @@
-use crate::{context::LemmyContext, post::SiteMetadata};
+use crate::{
+  context::LemmyContext,
+  media::{cache_remote_image, media_backend, scanner::scan_stored_media, StoredMedia},
+  post::SiteMetadata,
+  utils::sanitize_html_api_opt,
+};
 use encoding::{all::encodings, DecoderTrap};
-use lemmy_db_schema::newtypes::DbUrl;
+use futures::StreamExt;
+use lemmy_db_schema::{newtypes::DbUrl, source::post::PostUpdateForm};
 use lemmy_utils::{
-  error::{LemmyError, LemmyErrorType},
+  error::{LemmyError, LemmyErrorExt, LemmyErrorType},
   settings::structs::Settings,
   version::VERSION,
   REQWEST_TIMEOUT,
 };
-use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use reqwest::{
+  header::{CONTENT_TYPE, LOCATION},
+  redirect::Policy,
+  Client,
+  Response,
+};
 use reqwest_middleware::ClientWithMiddleware;
 use serde::Deserialize;
+use serde_with::{serde_as, DisplayFromStr, PickFirst};
+use std::net::{IpAddr, SocketAddr};
 use tracing::info;
-use url::Url;
+use url::{Host, Url};
 use webpage::HTML;
 
-/// Fetches the post link html tags (like title, description, image, etc)
+/// Maximum size of a page which is read for its metadata
+const MAX_HTML_SIZE: usize = 1024 * 1024;
+/// Maximum size of an oEmbed response
+const MAX_OEMBED_SIZE: usize = 64 * 1024;
+/// Same limit as the default redirect policy of reqwest
+const MAX_REDIRECTS: usize = 10;
+
+static OEMBED_LINK_REGEX: Lazy<Regex> = Lazy::new(|| {
+  Regex::new(r#"(?i)<link\s[^>]*type=["']application/json\+oembed["'][^>]*>"#)
+    .expect("compile regex")
+});
+static HREF_REGEX: Lazy<Regex> =
+  Lazy::new(|| Regex::new(r#"(?i)href=["']([^"']+)["']"#).expect("compile regex"));
+static IFRAME_SRC_REGEX: Lazy<Regex> =
+  Lazy::new(|| Regex::new(r#"(?i)<iframe\s[^>]*src=["']([^"']+)["']"#).expect("compile regex"));
+
+/// Fetches the post link html tags (like title, description, image, etc), and the oEmbed data if
+/// the page links to it. Direct links to media files are only checked for their content type.
 #[tracing::instrument(skip_all)]
 pub async fn fetch_site_metadata(
   client: &ClientWithMiddleware,
   url: &Url,
 ) -> Result<SiteMetadata, LemmyError> {
   info!("Fetching site metadata for url: {}", url);
-  let response = client.get(url.as_str()).send().await?;
+  // A HEAD request avoids downloading large media files. Some servers don't support it, so the
+  // content type of the GET request below is used as fallback.
+  let content_type = match client
+    .head(url.as_str())
+    .timeout(REQWEST_TIMEOUT)
+    .send()
+    .await
+  {
+    Ok(response) if response.status().is_success() => response_content_type(&response),
+    _ => None,
+  };
+  if content_type.as_deref().is_some_and(is_media_content_type) {
+    return Ok(SiteMetadata {
+      content_type,
+      ..Default::default()
+    });
+  }
+
+  let response = client
+    .get(url.as_str())
+    .timeout(REQWEST_TIMEOUT)
+    .send()
+    .await?;
+  let content_type = content_type.or_else(|| response_content_type(&response));
 
   // Can't use .text() here, because it only checks the content header, not the actual bytes
   // https://github.com/LemmyNet/lemmy/issues/1964
-  let html_bytes = response.bytes().await.map_err(LemmyError::from)?.to_vec();
+  let html_bytes = read_limited(response, MAX_HTML_SIZE).await?;
+
+  let mut metadata = html_to_site_metadata(&html_bytes, url)?;
+  metadata.content_type = content_type;
+
+  if let Some(oembed_url) = oembed_link(&String::from_utf8_lossy(&html_bytes), url) {
+    // The oEmbed data is optional, so errors are ignored
+    if let Ok(oembed) = fetch_oembed(client, &oembed_url).await {
+      merge_oembed(&mut metadata, oembed, url);
+    }
+  }
+
+  Ok(metadata)
+}
+
+/// Reads at most `max_size` bytes of the response body, the rest is cut off. That is enough for
+/// the tags in the html head, and avoids reading huge or endless responses into memory.
+async fn read_limited(response: Response, max_size: usize) -> Result<Vec<u8>, LemmyError> {
+  let mut bytes = Vec::new();
+  let mut stream = response.bytes_stream();
+  while let Some(chunk) = stream.next().await {
+    bytes.extend_from_slice(&chunk?);
+    if bytes.len() >= max_size {
+      bytes.truncate(max_size);
+      break;
+    }
+  }
+  Ok(bytes)
+}
+
+/// Returns the content type without parameters like charset.
+fn response_content_type(response: &Response) -> Option<String> {
+  let content_type = response.headers().get(CONTENT_TYPE)?.to_str().ok()?;
+  let content_type = content_type.split(';').next()?.trim().to_lowercase();
+  Some(content_type).filter(|c| !c.is_empty())
+}
+
+fn is_media_content_type(content_type: &str) -> bool {
+  ["image/", "video/", "audio/"]
+    .iter()
+    .any(|m| content_type.starts_with(m))
+}
 
-  let tags = html_to_site_metadata(&html_bytes, url)?;
+/// oEmbed response, see https://oembed.com/#section2.3
+#[serde_as]
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+struct OEmbed {
+  title: Option<String>,
+  thumbnail_url: Option<Url>,
+  // Some providers send numbers as strings
+  #[serde_as(as = "Option<PickFirst<(_, DisplayFromStr)>>")]
+  width: Option<i32>,
+  #[serde_as(as = "Option<PickFirst<(_, DisplayFromStr)>>")]
+  height: Option<i32>,
+  // Not part of the spec, but sent by some video platforms
+  #[serde_as(as = "Option<PickFirst<(_, DisplayFromStr)>>")]
+  duration: Option<i32>,
+  html: Option<String>,
+}
 
-  Ok(tags)
+/// Finds the oEmbed discovery link in the page head.
+fn oembed_link(html: &str, url: &Url) -> Option<Url> {
+  let link = OEMBED_LINK_REGEX.find(html)?.as_str();
+  let href = HREF_REGEX.captures(link)?.get(1)?.as_str();
+  url.join(&href.replace("&amp;", "&")).ok()
+}
+
+#[tracing::instrument(skip_all)]
+async fn fetch_oembed(client: &ClientWithMiddleware, url: &Url) -> Result<OEmbed, LemmyError> {
+  let response = client
+    .get(url.as_str())
+    .timeout(REQWEST_TIMEOUT)
+    .send()
+    .await?;
+  // A truncated response fails to parse, which is fine as the oEmbed data is optional
+  let bytes = read_limited(response, MAX_OEMBED_SIZE).await?;
+  Ok(serde_json::from_slice(&bytes)?)
+}
+
+/// Fills in the fields which are missing from the opengraph tags.
+fn merge_oembed(metadata: &mut SiteMetadata, oembed: OEmbed, url: &Url) {
+  let embed_video_url = oembed
+    .html
+    .as_deref()
+    .and_then(|h| IFRAME_SRC_REGEX.captures(h))
+    .and_then(|c| c.get(1))
+    .and_then(|src| url.join(&src.as_str().replace("&amp;", "&")).ok());
+
+  metadata.title = metadata.title.take().or(oembed.title);
+  metadata.image = metadata
+    .image
+    .take()
+    .or(oembed.thumbnail_url.map(Into::into));
+  metadata.embed_video_url = metadata
+    .embed_video_url
+    .take()
+    .or(embed_video_url.map(Into::into));
+  metadata.embed_width = metadata.embed_width.or(oembed.width);
+  metadata.embed_height = metadata.embed_height.or(oembed.height);
+  metadata.embed_duration = metadata.embed_duration.or(oembed.duration);
 }
 
 fn html_to_site_metadata(html_bytes: &[u8], url: &Url) -> Result<SiteMetadata, LemmyError> {
@@ -81,79 +232,66 @@ fn html_to_site_metadata(html_bytes: &[u8], url: &Url) -> Result<SiteMetadata, L
     .first()
     // join also works if the target URL is absolute
     .and_then(|ogo| url.join(&ogo.url).ok());
-  let og_embed_url = page
-    .opengraph
-    .videos
-    .first()
+  let og_video = page.opengraph.videos.first();
+  let og_embed_url = og_video
     // join also works if the target URL is absolute
     .and_then(|v| url.join(&v.url).ok());
+  let og_video_property = |name: &str| {
+    og_video
+      .and_then(|v| v.properties.get(name))
+      .and_then(|p| p.trim().parse().ok())
+  };
+  let duration = ["og:video:duration", "video:duration", "music:duration"]
+    .iter()
+    .find_map(|d| page.meta.get(*d))
+    .and_then(|d| d.trim().parse().ok());
 
   Ok(SiteMetadata {
     title: og_title.or(page_title),
     description: og_description.or(page_description),
     image: og_image.map(Into::into),
     embed_video_url: og_embed_url.map(Into::into),
+    content_type: None,
+    embed_width: og_video_property("width"),
+    embed_height: og_video_property("height"),
+    embed_duration: duration,
   })
 }
 
-#[derive(Deserialize, Debug, Clone)]
-pub(crate) struct PictrsResponse {
-  files: Vec<PictrsFile>,
-  msg: String,
-}
-
-#[derive(Deserialize, Debug, Clone)]
-pub(crate) struct PictrsFile {
-  file: String,
-  #[allow(dead_code)]
-  delete_token: String,
-}
-
-#[derive(Deserialize, Debug, Clone)]
-pub(crate) struct PictrsPurgeResponse {
-  msg: String,
-}
-
+/// Stores a remote image with the media backend, for a persistent thumbnail link. The image is
+/// scanned before it is used.
 #[tracing::instrument(skip_all)]
 pub(crate) async fn fetch_pictrs(
-  client: &ClientWithMiddleware,
-  settings: &Settings,
+  context: &LemmyContext,
   image_url: &Url,
-) -> Result<PictrsResponse, LemmyError> {
-  let pictrs_config = settings.pictrs_config()?;
-  is_image_content_type(client, image_url).await?;
-
-  if pictrs_config.cache_remote_images {
-    // fetch remote non-pictrs images for persistent thumbnail link
-    let fetch_url = format!(
-      "{}image/download?url={}",
-      pictrs_config.url,
-      utf8_percent_encode(image_url.as_str(), NON_ALPHANUMERIC) // TODO this might not be needed
-    );
-
-    let response = client
-      .get(&fetch_url)
-      .timeout(REQWEST_TIMEOUT)
-      .send()
-      .await?;
-
-    let response: PictrsResponse = response.json().await.map_err(LemmyError::from)?;
-
-    if response.msg == "ok" {
-      Ok(response)
-    } else {
-      Err(LemmyErrorType::PictrsResponseError(response.msg))?
-    }
-  } else {
+) -> Result<StoredMedia, LemmyError> {
+  if context
+    .settings()
+    .pictrs_config()
+    .is_ok_and(|pictrs| !pictrs.cache_remote_images)
+  {
     Err(LemmyErrorType::PictrsCachingDisabled)?
   }
+  let backend = media_backend(context.client(), context.settings())?;
+  is_image_content_type(context.client(), image_url).await?;
+  let stored = cache_remote_image(backend.as_ref(), image_url, context).await?;
+  scan_stored_media(
+    context,
+    backend.as_ref(),
+    &stored,
+    None,
+    None,
+    Some(image_url),
+  )
+  .await?;
+  Ok(stored)
 }
 
-/// Purges an image from pictrs
+/// Purges an image from the media backend
 /// Note: This should often be coerced from a Result to .ok() in order to fail softly, because:
 /// - It might fail due to image being not local
 /// - It might not be an image
-/// - Pictrs might not be set up
+/// - The media backend might not be set up
 pub async fn purge_image_from_pictrs(
   image_url: &Url,
   context: &LemmyContext,
@@ -173,27 +311,9 @@ pub async fn purge_image_from_pictrs_by_alias(
   alias: &str,
   context: &LemmyContext,
 ) -> Result<(), LemmyError> {
-  let pictrs_config = context.settings().pictrs_config()?;
-  let purge_url = format!("{}internal/purge?alias={}", pictrs_config.url, alias);
-
-  let pictrs_api_key = pictrs_config
-    .api_key
-    .ok_or(LemmyErrorType::PictrsApiKeyNotProvided)?;
-  let response = context
-    .client()
-    .post(&purge_url)
-    .timeout(REQWEST_TIMEOUT)
-    .header("x-api-token", pictrs_api_key)
-    .send()
-    .await?;
-
-  let response: PictrsPurgeResponse = response.json().await.map_err(LemmyError::from)?;
-
-  if response.msg == "ok" {
-    Ok(())
-  } else {
-    Err(LemmyErrorType::PictrsPurgeResponseError(response.msg))?
-  }
+  media_backend(context.client(), context.settings())?
+    .purge(alias)
+    .await
 }
 
 pub async fn delete_image_from_pictrs(
@@ -201,27 +321,16 @@ pub async fn delete_image_from_pictrs(
   delete_token: &str,
   context: &LemmyContext,
 ) -> Result<(), LemmyError> {
-  let pictrs_config = context.settings().pictrs_config()?;
-  let url = format!(
-    "{}image/delete/{}/{}",
-    pictrs_config.url, &delete_token, &alias
-  );
-  context
-    .client()
-    .delete(&url)
-    .timeout(REQWEST_TIMEOUT)
-    .send()
+  media_backend(context.client(), context.settings())?
+    .delete(alias, delete_token)
     .await
-    .map_err(LemmyError::from)?;
-  Ok(())
 }
 
 /// Both are options, since the URL might be either an html page, or an image
 /// Returns the SiteMetadata, and an image URL, if there is a picture associated
 #[tracing::instrument(skip_all)]
 pub async fn fetch_site_data(
-  client: &ClientWithMiddleware,
-  settings: &Settings,
+  context: &LemmyContext,
   url: Option<&Url>,
   include_image: bool,
 ) -> (Option<SiteMetadata>, Option<DbUrl>) {
@@ -230,14 +339,13 @@ pub async fn fetch_site_data(
       // Fetch metadata
       // Ignore errors, since it may be an image, or not have the data.
       // Warning, this may ignore SSL errors
-      let metadata_option = fetch_site_metadata(client, url).await.ok();
+      let metadata_option = fetch_site_metadata(context.client(), url).await.ok();
       if !include_image {
         (metadata_option, None)
       } else {
-        let thumbnail_url =
-          fetch_pictrs_url_from_site_metadata(client, &metadata_option, settings, url)
-            .await
-            .ok();
+        let thumbnail_url = fetch_pictrs_url_from_site_metadata(context, &metadata_option, url)
+          .await
+          .ok();
         (metadata_option, thumbnail_url)
       }
     }
@@ -245,28 +353,58 @@ pub async fn fetch_site_data(
   }
 }
 
+/// Post fields for the fetched link metadata. These are only overwritten if fetching succeeded,
+/// unless the post url changed. Then the values of the previous link are cleared in any case.
+pub fn site_metadata_post_form(
+  metadata: Option<SiteMetadata>,
+  thumbnail_url: Option<DbUrl>,
+  url_changed: bool,
+) -> PostUpdateForm {
+  let (metadata, thumbnail_url) = if url_changed {
+    (Some(metadata.unwrap_or_default()), Some(thumbnail_url))
+  } else {
+    (metadata, thumbnail_url.map(Some))
+  };
+  let form = PostUpdateForm {
+    thumbnail_url,
+    ..Default::default()
+  };
+  match metadata {
+    Some(metadata) => PostUpdateForm {
+      embed_title: Some(sanitize_html_api_opt(&metadata.title)),
+      embed_description: Some(sanitize_html_api_opt(&metadata.description)),
+      embed_video_url: Some(metadata.embed_video_url),
+      url_content_type: Some(metadata.content_type),
+      embed_width: Some(metadata.embed_width),
+      embed_height: Some(metadata.embed_height),
+      embed_duration: Some(metadata.embed_duration),
+      ..form
+    },
+    None => form,
+  }
+}
+
 async fn fetch_pictrs_url_from_site_metadata(
-  client: &ClientWithMiddleware,
+  context: &LemmyContext,
   metadata_option: &Option<SiteMetadata>,
-  settings: &Settings,
   url: &Url,
 ) -> Result<DbUrl, LemmyError> {
-  let pictrs_res = match metadata_option {
+  let stored = match metadata_option {
     Some(metadata_res) => match &metadata_res.image {
       // Metadata, with image
       // Try to generate a small thumbnail if there's a full sized one from post-links
-      Some(metadata_image) => fetch_pictrs(client, settings, metadata_image).await,
+      Some(metadata_image) => fetch_pictrs(context, metadata_image).await,
       // Metadata, but no image
-      None => fetch_pictrs(client, settings, url).await,
+      None => fetch_pictrs(context, url).await,
     },
     // No metadata, try to fetch the URL as an image
-    None => fetch_pictrs(client, settings, url).await,
+    None => fetch_pictrs(context, url).await,
   }?;
 
   Url::parse(&format!(
     "{}/pictrs/image/{}",
-    settings.get_protocol_and_hostname(),
-    pictrs_res.files.first().expect("missing pictrs file").file
+    context.settings().get_protocol_and_hostname(),
+    stored.alias
   ))
   .map(Into::into)
   .map_err(Into::into)
@@ -288,6 +426,97 @@ async fn is_image_content_type(client: &ClientWithMiddleware, url: &Url) -> Resu
   }
 }
 
+/// Makes sure that a url which was chosen by a user doesn't point into the network of the server.
+/// The host needs to be a domain name, and all of its addresses need to be public.
+pub async fn check_url_is_public(url: &Url) -> Result<(), LemmyError> {
+  public_addresses(url).await.map(|_| ())
+}
+
+/// Resolves the domain of the url, and returns it together with its addresses if all of them are
+/// public.
+async fn public_addresses(url: &Url) -> Result<(&str, Vec<SocketAddr>), LemmyError> {
+  let Some(Host::Domain(domain)) = url.host() else {
+    Err(LemmyErrorType::UrlNotPublic)?
+  };
+  let port = url.port_or_known_default().unwrap_or(443);
+  let addresses: Vec<_> = tokio::net::lookup_host((domain, port))
+    .await
+    .map_err(|_| LemmyErrorType::UrlNotPublic)?
+    .collect();
+  if addresses.is_empty() || !addresses.iter().all(|a| is_public_ip(a.ip())) {
+    Err(LemmyErrorType::UrlNotPublic)?
+  }
+  Ok((domain, addresses))
+}
+
+/// Builds a client for a request to a url which was chosen by a user. It only connects to the
+/// addresses which were checked, so that another DNS lookup can't return a local one, and
+/// doesn't follow redirects.
+pub async fn public_url_client(url: &Url, settings: &Settings) -> Result<Client, LemmyError> {
+  let (domain, addresses) = public_addresses(url).await?;
+  Ok(
+    Client::builder()
+      .user_agent(build_user_agent(settings))
+      .timeout(REQWEST_TIMEOUT)
+      .redirect(Policy::none())
+      .resolve_to_addrs(domain, &addresses)
+      .build()?,
+  )
+}
+
+/// Sends a GET request to a url which was chosen by a user. Redirects are followed here instead
+/// of by reqwest, so that each target is checked in the same way as the url itself.
+pub async fn get_public_url(url: &Url, settings: &Settings) -> Result<Response, LemmyError> {
+  let mut url = url.clone();
+  for _ in 0..=MAX_REDIRECTS {
+    let response = public_url_client(&url, settings)
+      .await?
+      .get(url.as_str())
+      .send()
+      .await?;
+    if !response.status().is_redirection() {
+      return Ok(response);
+    }
+    let location = response
+      .headers()
+      .get(LOCATION)
+      .and_then(|location| location.to_str().ok())
+      .ok_or(LemmyErrorType::InvalidUrl)?;
+    url = url
+      .join(location)
+      .with_lemmy_type(LemmyErrorType::InvalidUrl)?;
+  }
+  Err(LemmyErrorType::TooManyRedirects)?
+}
+
+fn is_public_ip(ip: IpAddr) -> bool {
+  match ip {
+    IpAddr::V4(ip) => {
+      // 100.64.0.0/10 is used for carrier-grade NAT
+      let [first, second, ..] = ip.octets();
+      let shared = first == 100 && (second & 0xc0) == 64;
+      !(ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_multicast()
+        || shared)
+    }
+    IpAddr::V6(ip) => {
+      if let Some(ip) = ip.to_ipv4_mapped() {
+        return is_public_ip(IpAddr::V4(ip));
+      }
+      let [first, ..] = ip.segments();
+      // fc00::/7 are unique local and fe80::/10 link-local addresses
+      let unique_local = (first & 0xfe00) == 0xfc00;
+      let link_local = (first & 0xffc0) == 0xfe80;
+      !(ip.is_loopback() || ip.is_unspecified() || ip.is_multicast() || unique_local || link_local)
+    }
+  }
+}
+
 pub fn build_user_agent(settings: &Settings) -> String {
   format!(
     "Lemmy/{}; +{}",
@@ -303,10 +532,17 @@ mod tests {
 
   use crate::request::{
     build_user_agent,
+    check_url_is_public,
     fetch_site_metadata,
     html_to_site_metadata,
+    is_public_ip,
+    merge_oembed,
+    oembed_link,
+    site_metadata_post_form,
+    OEmbed,
     SiteMetadata,
   };
+  use lemmy_db_schema::newtypes::DbUrl;
   use lemmy_utils::settings::SETTINGS;
   use url::Url;
 
@@ -333,6 +569,10 @@ mod tests {
             .into()
         ),
         embed_video_url: None,
+        content_type: Some("text/html".to_string()),
+        embed_width: None,
+        embed_height: None,
+        embed_duration: None,
       },
       sample_res
     );
@@ -387,4 +627,113 @@ mod tests {
       Some(Url::parse("https://example.com/image.jpg").unwrap().into())
     );
   }
+
+  #[test]
+  fn test_video_metadata() {
+    let url = Url::parse("https://example.com/watch/1").unwrap();
+    let html_bytes = b"<!DOCTYPE html><html><head>\
+      <meta property='og:video' content='https://example.com/embed/1'>\
+      <meta property='og:video:width' content='1280'>\
+      <meta property='og:video:height' content='720'>\
+      <meta property='video:duration' content='95'>\
+      </head><body></body></html>";
+    let metadata = html_to_site_metadata(html_bytes, &url).unwrap();
+    assert_eq!(Some(1280), metadata.embed_width);
+    assert_eq!(Some(720), metadata.embed_height);
+    assert_eq!(Some(95), metadata.embed_duration);
+  }
+
+  #[test]
+  fn test_oembed() {
+    let url = Url::parse("https://example.com/watch/1").unwrap();
+    let html = "<!DOCTYPE html><html><head><link rel='alternate' \
+      type='application/json+oembed' href='/oembed?url=1&amp;format=json'></head></html>";
+    assert_eq!(
+      Some(Url::parse("https://example.com/oembed?url=1&format=json").unwrap()),
+      oembed_link(html, &url)
+    );
+
+    let oembed = OEmbed {
+      title: Some("A video".to_string()),
+      width: Some(640),
+      height: Some(360),
+      html: Some("<iframe src=\"https://example.com/embed/1\"></iframe>".to_string()),
+      ..Default::default()
+    };
+    let mut metadata = SiteMetadata {
+      title: Some("Opengraph title".to_string()),
+      ..Default::default()
+    };
+    merge_oembed(&mut metadata, oembed, &url);
+    assert_eq!(Some("Opengraph title".to_string()), metadata.title);
+    assert_eq!(None, metadata.description);
+    assert_eq!(Some(640), metadata.embed_width);
+    assert_eq!(Some(360), metadata.embed_height);
+    assert_eq!(
+      Some(Url::parse("https://example.com/embed/1").unwrap().into()),
+      metadata.embed_video_url
+    );
+  }
+
+  #[test]
+  fn test_site_metadata_post_form() {
+    let thumbnail: DbUrl = Url::parse("https://example.com/thumb.png").unwrap().into();
+
+    // A failed refresh keeps the previous values
+    let form = site_metadata_post_form(None, None, false);
+    assert_eq!(None, form.thumbnail_url);
+    assert_eq!(None, form.embed_title);
+
+    let metadata = SiteMetadata {
+      title: Some("Title".to_string()),
+      ..Default::default()
+    };
+    let form = site_metadata_post_form(Some(metadata), Some(thumbnail.clone()), false);
+    assert_eq!(Some(Some(thumbnail)), form.thumbnail_url);
+    assert_eq!(Some(Some("Title".to_string())), form.embed_title);
+
+    // The values of the previous link are removed when the url changed
+    let form = site_metadata_post_form(None, None, true);
+    assert_eq!(Some(None), form.thumbnail_url);
+    assert_eq!(Some(None), form.embed_title);
+  }
+
+  #[test]
+  fn test_is_public_ip() {
+    let public = |ip: &str| is_public_ip(ip.parse().unwrap());
+    assert!(public("93.184.216.34"));
+    assert!(public("2606:2800:220:1:248:1893:25c8:1946"));
+    // loopback
+    assert!(!public("127.0.0.1"));
+    assert!(!public("::1"));
+    // private
+    assert!(!public("10.1.2.3"));
+    assert!(!public("172.16.0.1"));
+    assert!(!public("192.168.1.1"));
+    assert!(!public("100.64.0.1"));
+    assert!(!public("::ffff:192.168.1.1"));
+    // link-local, which includes the cloud metadata service
+    assert!(!public("169.254.169.254"));
+    assert!(!public("fe80::1"));
+    // unique local
+    assert!(!public("fc00::1"));
+    assert!(!public("fd12:3456::1"));
+    assert!(!public("0.0.0.0"));
+    assert!(!public("::"));
+  }
+
+  #[tokio::test]
+  async fn test_check_url_is_public() {
+    let check = |url: &str| {
+      let url = Url::parse(url).unwrap();
+      async move { check_url_is_public(&url).await }
+    };
+    // IP literals are rejected even if they are public
+    assert!(check("https://93.184.216.34/a.png").await.is_err());
+    assert!(check("http://127.0.0.1:8536/a.png").await.is_err());
+    assert!(check("http://[::1]/a.png").await.is_err());
+    assert!(check("http://[fd00::1]/a.png").await.is_err());
+    // Resolved addresses are checked as well
+    assert!(check("http://localhost:8536/a.png").await.is_err());
+  }
 }
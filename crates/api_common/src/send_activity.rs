@@ -55,6 +55,7 @@ pub enum SendActivityData {
   AddModToCommunity(Person, CommunityId, PersonId, bool),
   BanFromCommunity(Person, CommunityId, Person, BanFromCommunity),
   BanFromSite(Person, Person, BanPerson),
+  BlockPerson(Person, Person, bool),
   CreatePrivateMessage(PrivateMessageView),
   UpdatePrivateMessage(PrivateMessageView),
   DeletePrivateMessage(Person, PrivateMessage, bool),
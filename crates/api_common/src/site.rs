@@ -1,6 +1,13 @@
 use lemmy_db_schema::{
-  newtypes::{CommentId, CommunityId, InstanceId, LanguageId, PersonId, PostId},
-  source::{instance::Instance, language::Language, tagline::Tagline},
+  newtypes::{CommentId, CommunityId, InstanceId, LanguageId, MediaScanId, PersonId, PostId},
+  source::{
+    federation_blocklist::FederationBlocklistSubscription,
+    instance::Instance,
+    language::Language,
+    media_scan::MediaScan,
+    tagline::Tagline,
+  },
+  FederationBlockSeverity,
   ListingType,
   ModlogActionType,
   RegistrationMode,
@@ -11,6 +18,7 @@ use lemmy_db_views::structs::{
   CommentView,
   CustomEmojiView,
   LocalUserView,
+  MediaScanView,
   PostView,
   RegistrationApplicationView,
   SiteView,
@@ -25,6 +33,7 @@ use lemmy_db_views_actor::structs::{
   PersonView,
 };
 use lemmy_db_views_moderator::structs::{
+  AdminBlockInstanceView,
   AdminPurgeCommentView,
   AdminPurgeCommunityView,
   AdminPurgePersonView,
@@ -45,6 +54,7 @@ use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
 #[cfg(feature = "full")]
 use ts_rs::TS;
+use url::Url;
 
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
@@ -133,6 +143,7 @@ pub struct GetModlogResponse {
   pub admin_purged_posts: Vec<AdminPurgePostView>,
   pub admin_purged_comments: Vec<AdminPurgeCommentView>,
   pub hidden_communities: Vec<ModHideCommunityView>,
+  pub admin_blocked_instances: Vec<AdminBlockInstanceView>,
 }
 
 #[skip_serializing_none]
@@ -258,6 +269,8 @@ pub struct EditSite {
   pub registration_mode: Option<RegistrationMode>,
   /// Whether to email admins for new reports.
   pub reports_email_admins: Option<bool>,
+  /// Whether to notify remote instances when a local user blocks one of their users.
+  pub federate_person_blocks: Option<bool>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -321,6 +334,88 @@ pub struct FederatedInstances {
   pub blocked: Vec<Instance>,
 }
 
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// An instance in the federation blocklist.
+pub struct FederationBlocklistEntry {
+  pub domain: String,
+  pub severity: FederationBlockSeverity,
+  pub public_comment: Option<String>,
+  /// Only visible to admins.
+  pub private_comment: Option<String>,
+  /// The subscription which this entry was imported from, if any.
+  pub subscription_id: Option<i32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The federation blocklist with severities and comments. Private comments and subscriptions are
+/// only included for admins.
+pub struct ListFederationBlocklistResponse {
+  pub blocklist: Vec<FederationBlocklistEntry>,
+  pub subscriptions: Vec<FederationBlocklistSubscription>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Imports instances into the federation blocklist, from a CSV list in Mastodon's domain_blocks
+/// export format. Either the list itself or a URL to fetch it from needs to be given.
+pub struct ImportFederationBlocklist {
+  pub csv: Option<String>,
+  #[cfg_attr(feature = "full", ts(type = "string"))]
+  pub url: Option<Url>,
+  /// Fetch the list from `url` again every day, and apply the changes.
+  pub subscribe: Option<bool>,
+  /// Only return the changes, without applying them.
+  pub preview: Option<bool>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Adds an instance to the federation blocklist, changes its entry, or removes it.
+pub struct EditFederationBlock {
+  pub domain: String,
+  pub block: bool,
+  pub severity: Option<FederationBlockSeverity>,
+  pub public_comment: Option<String>,
+  pub private_comment: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Stops refreshing a shared blocklist, and removes the instances which were imported from it.
+pub struct DeleteFederationBlocklistSubscription {
+  pub subscription_id: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Changes to the federation blocklist.
+pub struct FederationBlocklistDiff {
+  pub added: Vec<FederationBlocklistEntry>,
+  pub changed: Vec<FederationBlocklistEntry>,
+  pub removed: Vec<FederationBlocklistEntry>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The changes which were made to the federation blocklist, or which would be made for a preview.
+pub struct FederationBlocklistResponse {
+  pub diff: FederationBlocklistDiff,
+  pub subscription: Option<FederationBlocklistSubscription>,
+}
+
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[cfg_attr(feature = "full", derive(TS))]
@@ -416,6 +511,43 @@ pub struct GetUnreadRegistrationApplicationCountResponse {
   pub registration_applications: i64,
 }
 
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Fetches the results of the media scanner.
+pub struct ListMediaScans {
+  /// Only shows the denied and quarantined media which weren't reviewed yet
+  pub unresolved_only: Option<bool>,
+  pub page: Option<i64>,
+  pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The list of media scan results.
+pub struct ListMediaScansResponse {
+  pub media_scans: Vec<MediaScanView>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Reviews a media scan result. Quarantined media is either released, or purged.
+pub struct ResolveMediaScan {
+  pub id: MediaScanId,
+  pub purge: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The reviewed media scan result.
+pub struct MediaScanResponse {
+  pub media_scan: MediaScan,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
@@ -1,40 +1,70 @@
-use crate::{context::LemmyContext, request::purge_image_from_pictrs, site::FederatedInstances};
+use crate::{
+  context::LemmyContext,
+  person::{GetReportCountResponse, UploadQuotaResponse},
+  post::GalleryImage,
+  reply_by_email::{reply_address, ReplyTarget},
+  request::{delete_image_from_pictrs, purge_image_from_pictrs},
+  site::FederatedInstances,
+  web_push::{send_push_notification, PushNotification},
+};
 use anyhow::Context;
 use chrono::{DateTime, Utc};
 use lemmy_db_schema::{
   impls::person::is_banned,
-  newtypes::{CommunityId, DbUrl, PersonId, PostId},
+  newtypes::{CommunityId, CustomFeedId, DbUrl, InstanceId, LocalUserId, PersonId, PostId},
   source::{
-    comment::{Comment, CommentUpdateForm},
+    comment::{Comment, CommentMute, CommentUpdateForm},
     community::{Community, CommunityModerator, CommunityUpdateForm},
+    conversation::{Conversation, ConversationParticipant},
+    custom_feed::CustomFeed,
+    email_digest_item::{EmailDigestItem, EmailDigestItemForm},
+    email_outbox::{EmailOutbox, EmailOutboxForm},
+    email_template::EmailTemplate,
     email_verification::{EmailVerification, EmailVerificationForm},
+    image_upload::ImageUpload,
     instance::Instance,
+    instance_block::InstanceBlock,
     local_site::LocalSite,
     local_site_rate_limit::LocalSiteRateLimit,
+    local_user::LocalUser,
+    mod_action_notification::{ModActionNotification, ModActionNotificationInsertForm},
+    notification_preference::NotificationPreference,
     password_reset_request::PasswordResetRequest,
-    person::{Person, PersonUpdateForm},
+    person::{Person, PersonFollower, PersonUpdateForm},
     person_block::PersonBlock,
-    post::{Post, PostRead, PostReadForm},
+    post::{Post, PostMute, PostRead, PostReadForm},
   },
   traits::{Crud, Readable},
   utils::DbPool,
+  EmailDigestMode,
+  EmailTemplateType,
+  ModActionType,
+  NotificationType,
+  PrivateMessagePolicy,
+};
+use lemmy_db_views::{
+  comment_view::CommentQuery,
+  structs::{CommentReportView, LocalUserView, PostReportView, PrivateMessageReportView},
 };
-use lemmy_db_views::{comment_view::CommentQuery, structs::LocalUserView};
 use lemmy_db_views_actor::structs::{
   CommunityModeratorView,
   CommunityPersonBanView,
   CommunityView,
 };
 use lemmy_utils::{
-  email::{send_email, translations::Lang},
+  email::{render_email_template, send_email, translations::Lang},
   error::{LemmyError, LemmyErrorExt, LemmyErrorType},
   location_info,
   rate_limit::RateLimitConfig,
   settings::structs::Settings,
-  utils::slurs::build_slur_regex,
+  utils::{
+    slurs::build_slur_regex,
+    validation::{check_gallery_size, check_url_scheme, clean_url_params, is_valid_alt_text_field},
+  },
 };
 use regex::Regex;
 use rosetta_i18n::{Language, LanguageId};
+use std::collections::HashSet;
 use tracing::warn;
 use url::{ParseError, Url};
 
@@ -198,6 +228,39 @@ pub async fn check_person_block(
   }
 }
 
+/// Fails if the person has blocked the given instance.
+#[tracing::instrument(skip_all)]
+pub async fn check_person_instance_block(
+  my_instance_id: InstanceId,
+  potential_blocker_id: PersonId,
+  pool: &mut DbPool<'_>,
+) -> Result<(), LemmyError> {
+  let is_blocked = InstanceBlock::read(pool, potential_blocker_id, my_instance_id)
+    .await
+    .is_ok();
+  if is_blocked {
+    Err(LemmyErrorType::InstanceIsBlocked)?
+  } else {
+    Ok(())
+  }
+}
+
+/// Fails if the person is from a limited instance, and the recipient doesn't follow them.
+#[tracing::instrument(skip_all)]
+pub async fn check_limited_instance(
+  person: &Person,
+  recipient_id: PersonId,
+  pool: &mut DbPool<'_>,
+) -> Result<(), LemmyError> {
+  if Instance::is_limited(pool, person.instance_id).await?
+    && !PersonFollower::is_following(pool, person.id, recipient_id).await?
+  {
+    Err(LemmyErrorType::InstanceIsLimited)?
+  } else {
+    Ok(())
+  }
+}
+
 #[tracing::instrument(skip_all)]
 pub fn check_downvotes_enabled(score: i16, local_site: &LocalSite) -> Result<(), LemmyError> {
   if score == -1 && !local_site.enable_downvotes {
@@ -219,6 +282,22 @@ pub fn check_private_instance(
   }
 }
 
+/// Reads a custom feed. Feeds of other users are only visible if they are public, otherwise the
+/// feed is reported as missing.
+pub async fn read_custom_feed(
+  pool: &mut DbPool<'_>,
+  feed_id: CustomFeedId,
+  person_id: Option<PersonId>,
+) -> Result<CustomFeed, LemmyError> {
+  let feed = CustomFeed::read(pool, feed_id)
+    .await
+    .with_lemmy_type(LemmyErrorType::CustomFeedNotFound)?;
+  if !feed.public && Some(feed.creator_id) != person_id {
+    Err(LemmyErrorType::CustomFeedNotFound)?
+  }
+  Ok(feed)
+}
+
 #[tracing::instrument(skip_all)]
 pub async fn build_federated_instances(
   local_site: &LocalSite,
@@ -260,32 +339,77 @@ pub fn honeypot_check(honeypot: &Option<String>) -> Result<(), LemmyError> {
   }
 }
 
+/// Sends a notification email to the user, or adds it to their digest if they enabled one. If a
+/// reply target is given and reply by email is configured, the user can answer the email directly.
 pub async fn send_email_to_user(
   local_user_view: &LocalUserView,
   subject: &str,
   body: &str,
-  settings: &Settings,
+  reply_target: Option<ReplyTarget>,
+  context: &LemmyContext,
 ) {
   if local_user_view.person.banned || !local_user_view.local_user.send_notifications_to_email {
     return;
   }
 
-  if let Some(user_email) = &local_user_view.local_user.email {
-    match send_email(
+  let res = if local_user_view.local_user.email_digest == EmailDigestMode::Off {
+    let reply_to =
+      reply_target.and_then(|target| reply_address(target, local_user_view.local_user.id, context));
+    queue_email(
+      local_user_view,
       subject,
-      user_email,
-      &local_user_view.person.name,
       body,
-      settings,
+      reply_to.as_deref(),
+      &mut context.pool(),
+      context.settings(),
     )
     .await
-    {
-      Ok(_o) => _o,
-      Err(e) => warn!("{}", e),
+  } else {
+    let form = EmailDigestItemForm {
+      local_user_id: local_user_view.local_user.id,
+      subject: subject.to_string(),
+      html: body.to_string(),
     };
+    EmailDigestItem::create(&mut context.pool(), &form)
+      .await
+      .map(|_| ())
+      .map_err(LemmyError::from)
+  };
+  if let Err(e) = res {
+    warn!("{}", e);
   }
 }
 
+/// Adds an email to the outbox, from where it is sent by a background worker. Failed attempts are
+/// retried, so this should be used for all emails which don't have to arrive immediately.
+pub async fn queue_email(
+  user: &LocalUserView,
+  subject: &str,
+  html: &str,
+  reply_to: Option<&str>,
+  pool: &mut DbPool<'_>,
+  settings: &Settings,
+) -> Result<(), LemmyError> {
+  if settings.email.is_none() {
+    Err(LemmyErrorType::NoEmailSetup)?
+  }
+  let recipient_email = user
+    .local_user
+    .email
+    .clone()
+    .ok_or(LemmyErrorType::EmailRequired)?;
+  let form = EmailOutboxForm {
+    local_user_id: Some(user.local_user.id),
+    recipient_email,
+    recipient_name: user.person.name.clone(),
+    subject: subject.to_string(),
+    html: html.to_string(),
+    reply_to: reply_to.map(ToString::to_string),
+  };
+  EmailOutbox::create(pool, &form).await?;
+  Ok(())
+}
+
 pub async fn send_password_reset_email(
   user: &LocalUserView,
   pool: &mut DbPool<'_>,
@@ -299,12 +423,17 @@ pub async fn send_password_reset_email(
   PasswordResetRequest::create_token(pool, local_user_id, token.clone()).await?;
 
   let email = &user.local_user.email.clone().expect("email");
-  let lang = get_interface_language(user);
-  let subject = &lang.password_reset_subject(&user.person.name);
   let protocol_and_hostname = settings.get_protocol_and_hostname();
   let reset_link = format!("{}/password_change/{}", protocol_and_hostname, &token);
-  let body = &lang.password_reset_body(reset_link, &user.person.name);
-  send_email(subject, email, &user.person.name, body, settings).await
+  let (subject, body) = build_email(
+    EmailTemplateType::PasswordReset,
+    user,
+    &[("link", &reset_link)],
+    pool,
+    settings,
+  )
+  .await;
+  send_email(&subject, email, &user.person.name, &body, None, settings).await
 }
 
 /// Send a verification email
@@ -326,10 +455,23 @@ pub async fn send_verification_email(
   );
   EmailVerification::create(pool, &form).await?;
 
-  let lang = get_interface_language(user);
-  let subject = lang.verify_email_subject(&settings.hostname);
-  let body = lang.verify_email_body(&settings.hostname, &user.person.name, verify_link);
-  send_email(&subject, new_email, &user.person.name, &body, settings).await?;
+  let (subject, body) = build_email(
+    EmailTemplateType::VerifyEmail,
+    user,
+    &[("link", &verify_link)],
+    pool,
+    settings,
+  )
+  .await;
+  send_email(
+    &subject,
+    new_email,
+    &user.person.name,
+    &body,
+    None,
+    settings,
+  )
+  .await?;
 
   Ok(())
 }
@@ -342,7 +484,7 @@ pub fn get_interface_language_from_settings(user: &LocalUserView) -> Lang {
   lang_str_to_lang(&user.local_user.interface_language)
 }
 
-fn lang_str_to_lang(lang: &str) -> Lang {
+pub fn lang_str_to_lang(lang: &str) -> Lang {
   let lang_id = LanguageId::new(lang);
   Lang::from_language_id(&lang_id).unwrap_or_else(|| {
     let en = LanguageId::new("en");
@@ -390,13 +532,164 @@ pub fn local_site_opt_to_sensitive(local_site: &Option<LocalSite>) -> bool {
 
 pub async fn send_application_approved_email(
   user: &LocalUserView,
+  pool: &mut DbPool<'_>,
   settings: &Settings,
 ) -> Result<(), LemmyError> {
   let email = &user.local_user.email.clone().expect("email");
-  let lang = get_interface_language(user);
-  let subject = lang.registration_approved_subject(&user.person.actor_id);
-  let body = lang.registration_approved_body(&settings.hostname);
-  send_email(&subject, email, &user.person.name, &body, settings).await
+  let (subject, body) = build_email(
+    EmailTemplateType::ApplicationApproved,
+    user,
+    &[("link", user.person.actor_id.as_str())],
+    pool,
+    settings,
+  )
+  .await;
+  send_email(&subject, email, &user.person.name, &body, None, settings).await
+}
+
+pub async fn send_application_denied_email(
+  user: &LocalUserView,
+  deny_reason: &str,
+  pool: &mut DbPool<'_>,
+  settings: &Settings,
+) -> Result<(), LemmyError> {
+  let email = &user.local_user.email.clone().expect("email");
+  let (subject, body) = build_email(
+    EmailTemplateType::ApplicationDenied,
+    user,
+    &[("reason", deny_reason)],
+    pool,
+    settings,
+  )
+  .await;
+  send_email(&subject, email, &user.person.name, &body, None, settings).await
+}
+
+/// Returns the variables which can be used in email templates of the given type.
+pub fn email_template_variables(template_type: EmailTemplateType) -> &'static [&'static str] {
+  match template_type {
+    EmailTemplateType::VerifyEmail
+    | EmailTemplateType::PasswordReset
+    | EmailTemplateType::ApplicationApproved => &["hostname", "recipient", "link"],
+    EmailTemplateType::ApplicationDenied => &["hostname", "recipient", "reason"],
+    EmailTemplateType::CommentReply
+    | EmailTemplateType::PostReply
+    | EmailTemplateType::Mention
+    | EmailTemplateType::PrivateMessage => &["hostname", "recipient", "sender", "content", "link"],
+    EmailTemplateType::ModAction => &[
+      "hostname",
+      "recipient",
+      "action",
+      "community",
+      "reason",
+      "link",
+    ],
+    EmailTemplateType::EmailDigest => &["hostname", "recipient", "count", "content"],
+  }
+}
+
+/// Builds subject and body of an email to the user. Uses the template which the admins set for
+/// the interface language of the user or for all languages, and otherwise the built-in
+/// translation. The `hostname` and `recipient` variables are added automatically.
+pub async fn build_email(
+  template_type: EmailTemplateType,
+  user: &LocalUserView,
+  variables: &[(&str, &str)],
+  pool: &mut DbPool<'_>,
+  settings: &Settings,
+) -> (String, String) {
+  let language_id = lemmy_db_schema::source::language::Language::read_id_from_code(
+    pool,
+    Some(&user.local_user.interface_language),
+  )
+  .await
+  .unwrap_or_default();
+  let template = EmailTemplate::read_for_language(pool, template_type, language_id)
+    .await
+    .map_err(|e| warn!("Failed to read email template: {e}"))
+    .unwrap_or_default();
+  let mut variables = variables.to_vec();
+  variables.push(("hostname", &settings.hostname));
+  variables.push(("recipient", &user.person.name));
+  render_email(
+    template_type,
+    template.as_ref(),
+    &get_interface_language(user),
+    &variables,
+  )
+}
+
+/// Renders subject and body of an email from the template, or from the built-in translation if
+/// there is none.
+pub fn render_email(
+  template_type: EmailTemplateType,
+  template: Option<&EmailTemplate>,
+  lang: &Lang,
+  variables: &[(&str, &str)],
+) -> (String, String) {
+  if let Some(template) = template {
+    return (
+      render_email_template(&template.subject, variables),
+      render_email_template(&template.body, variables),
+    );
+  }
+  let var = |name: &str| {
+    variables
+      .iter()
+      .find(|(n, _)| *n == name)
+      .map(|(_, value)| value.to_string())
+      .unwrap_or_default()
+  };
+  let (hostname, recipient, link) = (var("hostname"), var("recipient"), var("link"));
+  let (sender, content) = (var("sender"), var("content"));
+  match template_type {
+    EmailTemplateType::VerifyEmail => (
+      lang.verify_email_subject(&hostname),
+      lang.verify_email_body(&hostname, &recipient, link),
+    ),
+    EmailTemplateType::PasswordReset => (
+      lang.password_reset_subject(&recipient),
+      lang.password_reset_body(link, &recipient),
+    ),
+    EmailTemplateType::ApplicationApproved => (
+      lang.registration_approved_subject(&link),
+      lang.registration_approved_body(&hostname),
+    ),
+    EmailTemplateType::ApplicationDenied => (
+      lang.registration_denied().to_string(),
+      format!("{}: {}", lang.registration_denied(), var("reason")),
+    ),
+    EmailTemplateType::CommentReply => (
+      lang.notification_comment_reply_subject(&sender),
+      lang.notification_comment_reply_body(&content, &link, &sender),
+    ),
+    EmailTemplateType::PostReply => (
+      lang.notification_post_reply_subject(&sender),
+      lang.notification_post_reply_body(&content, &link, &sender),
+    ),
+    EmailTemplateType::Mention => (
+      lang.notification_mentioned_by_subject(&sender),
+      lang.notification_mentioned_by_body(&content, &link, &sender),
+    ),
+    EmailTemplateType::PrivateMessage => (
+      lang.notification_private_message_subject(&sender),
+      lang.notification_private_message_body(link, &content, &sender),
+    ),
+    EmailTemplateType::ModAction => {
+      let action = var("action");
+      let reason = var("reason");
+      let mut body = format!("<h1>{action}</h1><br>");
+      if !reason.is_empty() {
+        body.push_str(&format!(
+          "<div>{}</div><br>",
+          lang.mod_action_reason(reason)
+        ));
+      }
+      body.push_str(&format!("<a href=\"{link}\">{link}</a>"));
+      (action, body)
+    }
+    EmailTemplateType::EmailDigest => (lang.email_digest_subject(var("count"), &hostname), content),
+  }
 }
 
 /// Send a new applicant email notification to all admins
@@ -414,11 +707,10 @@ pub async fn send_new_applicant_email_to_admins(
   );
 
   for admin in &admins {
-    let email = &admin.local_user.email.clone().expect("email");
     let lang = get_interface_language_from_settings(admin);
     let subject = lang.new_application_subject(&settings.hostname, applicant_username);
     let body = lang.new_application_body(applications_link);
-    send_email(&subject, email, &admin.person.name, &body, settings).await?;
+    queue_email(admin, &subject, &body, None, pool, settings).await?;
   }
   Ok(())
 }
@@ -436,11 +728,284 @@ pub async fn send_new_report_email_to_admins(
   let reports_link = &format!("{}/reports", settings.get_protocol_and_hostname(),);
 
   for admin in &admins {
-    let email = &admin.local_user.email.clone().expect("email");
     let lang = get_interface_language_from_settings(admin);
     let subject = lang.new_report_subject(&settings.hostname, reported_username, reporter_username);
     let body = lang.new_report_body(reports_link);
-    send_email(&subject, email, &admin.person.name, &body, settings).await?;
+    queue_email(admin, &subject, &body, None, pool, settings).await?;
+  }
+  Ok(())
+}
+
+/// Sends a push notification about a new report to the admins, and to the local moderators of
+/// the community if the reported content belongs to one.
+pub async fn send_new_report_push_notifications(
+  reporter_username: &str,
+  reported_username: &str,
+  reason: &str,
+  community_id: Option<CommunityId>,
+  context: &LemmyContext,
+) -> Result<(), LemmyError> {
+  let mut recipients = LocalUserView::list_admins(&mut context.pool()).await?;
+  if let Some(community_id) = community_id {
+    recipients.append(
+      &mut LocalUserView::list_community_moderators(&mut context.pool(), community_id).await?,
+    );
+  }
+  let mut notified = HashSet::new();
+  recipients.retain(|r| notified.insert(r.local_user.id));
+
+  let hostname = &context.settings().hostname;
+  let reports_link = format!("{}/reports", context.settings().get_protocol_and_hostname());
+  for recipient in &recipients {
+    let lang = get_interface_language(recipient);
+    let subject = lang.new_report_subject(hostname, reported_username, reporter_username);
+    send_push_notification(
+      recipient.local_user.id,
+      PushNotification::new(
+        NotificationType::ModeratorEvent,
+        &subject,
+        reason,
+        &reports_link,
+      ),
+      context,
+    );
+  }
+  Ok(())
+}
+
+/// Returns how the recipient wants to be notified about this type of notification from the
+/// sender, or None if they don't want to be notified at all.
+pub async fn get_notification_preference(
+  recipient: &LocalUserView,
+  sender_id: PersonId,
+  notification_type: NotificationType,
+  pool: &mut DbPool<'_>,
+) -> Result<Option<NotificationPreference>, LemmyError> {
+  let preference =
+    NotificationPreference::read(pool, recipient.local_user.id, notification_type).await?;
+  if !preference.in_app && !preference.email && !preference.push {
+    return Ok(None);
+  }
+  if preference.only_from_followed
+    && !PersonFollower::is_following(pool, sender_id, recipient.person.id).await?
+  {
+    return Ok(None);
+  }
+  Ok(Some(preference))
+}
+
+/// Checks that the local recipients accept a new private message conversation from the sender,
+/// according to their private message policy. Returns the recipients for which the conversation is
+/// a message request, because they don't follow the sender. Local admins can always start
+/// conversations.
+pub async fn check_private_message_policy(
+  sender: &Person,
+  sender_is_admin: bool,
+  recipients: &[&LocalUserView],
+  pool: &mut DbPool<'_>,
+) -> Result<Vec<PersonId>, LemmyError> {
+  let mut message_request_ids = vec![];
+  if sender_is_admin {
+    return Ok(message_request_ids);
+  }
+  for recipient in recipients {
+    let followed = PersonFollower::is_following(pool, sender.id, recipient.person.id).await?;
+    let allowed = match recipient.local_user.private_message_policy {
+      PrivateMessagePolicy::Everyone => true,
+      PrivateMessagePolicy::Following => followed,
+      PrivateMessagePolicy::Local => sender.local,
+      PrivateMessagePolicy::Nobody => false,
+    };
+    if !allowed {
+      Err(LemmyErrorType::RecipientDoesNotAcceptPrivateMessages)?
+    }
+    if !followed {
+      message_request_ids.push(recipient.person.id);
+    }
+  }
+  Ok(message_request_ids)
+}
+
+/// Returns the conversation between the sender and the other participants. A new conversation has
+/// to be allowed by the private message policy of the local recipients, and is a message request for
+/// those who don't follow the sender. Sending a message into a message request accepts it.
+pub async fn read_or_create_conversation(
+  sender: &Person,
+  sender_is_admin: bool,
+  participant_ids: &[PersonId],
+  local_recipients: &[&LocalUserView],
+  pool: &mut DbPool<'_>,
+) -> Result<Conversation, LemmyError> {
+  // Recipients can limit who starts new conversations with them, but not who replies
+  let message_request_ids = if Conversation::read_for_participants(pool, participant_ids)
+    .await?
+    .is_none()
+  {
+    check_private_message_policy(sender, sender_is_admin, local_recipients, pool).await?
+  } else {
+    vec![]
+  };
+  let conversation = Conversation::read_or_create(pool, participant_ids, &message_request_ids)
+    .await
+    .with_lemmy_type(LemmyErrorType::CouldntCreatePrivateMessage)?;
+  ConversationParticipant::accept_message_request(pool, conversation.id, sender.id)
+    .await
+    .with_lemmy_type(LemmyErrorType::CouldntUpdateConversation)?;
+  Ok(conversation)
+}
+
+/// Checks if the person muted the post of the comment, or any comment above it.
+pub async fn is_reply_muted(
+  person_id: PersonId,
+  comment: &Comment,
+  pool: &mut DbPool<'_>,
+) -> Result<bool, LemmyError> {
+  Ok(
+    PostMute::is_muted(pool, person_id, comment.post_id).await?
+      || CommentMute::is_muted(pool, person_id, &comment.ancestor_ids()).await?,
+  )
+}
+
+/// Notifies the affected local user about a moderator action, in their inbox and by email and
+/// push notification as far as they enabled it.
+pub async fn send_mod_action_notification(
+  form: ModActionNotificationInsertForm,
+  context: &LemmyContext,
+) -> Result<(), LemmyError> {
+  // Moderators don't need to be told about their own actions
+  if form.recipient_id == form.mod_person_id {
+    return Ok(());
+  }
+  let Ok(recipient) = LocalUserView::read_person(&mut context.pool(), form.recipient_id).await
+  else {
+    return Ok(());
+  };
+  let Some(preference) = get_notification_preference(
+    &recipient,
+    form.mod_person_id,
+    NotificationType::ModeratorAction,
+    &mut context.pool(),
+  )
+  .await?
+  else {
+    return Ok(());
+  };
+  if preference.in_app {
+    ModActionNotification::create(&mut context.pool(), &form).await?;
+  }
+
+  let community = Community::read(&mut context.pool(), form.community_id).await?;
+  let (action, link) = mod_action_message(
+    &form,
+    &community,
+    &get_interface_language(&recipient),
+    context.settings(),
+  );
+  let reason = form.reason.unwrap_or_default();
+  if preference.email {
+    let (subject, body) = build_email(
+      EmailTemplateType::ModAction,
+      &recipient,
+      &[
+        ("action", &sanitize_html_federation(&action)),
+        ("community", &sanitize_html_federation(&community.title)),
+        ("reason", &sanitize_html_federation(&reason)),
+        ("link", &link),
+      ],
+      &mut context.pool(),
+      context.settings(),
+    )
+    .await;
+    send_email_to_user(&recipient, &subject, &body, None, context).await;
+  }
+  send_push_notification(
+    recipient.local_user.id,
+    PushNotification::new(NotificationType::ModeratorAction, &action, &reason, &link),
+    context,
+  );
+  Ok(())
+}
+
+/// Returns the description of a mod action for the notification, and the link to the affected
+/// content.
+fn mod_action_message(
+  form: &ModActionNotificationInsertForm,
+  community: &Community,
+  lang: &Lang,
+  settings: &Settings,
+) -> (String, String) {
+  let name = &community.title;
+  let subject = match form.action {
+    ModActionType::RemovePost => lang.mod_action_post_removed(name),
+    ModActionType::RestorePost => lang.mod_action_post_restored(name),
+    ModActionType::RemoveComment => lang.mod_action_comment_removed(name),
+    ModActionType::RestoreComment => lang.mod_action_comment_restored(name),
+    ModActionType::LockPost => lang.mod_action_post_locked(name),
+    ModActionType::UnlockPost => lang.mod_action_post_unlocked(name),
+    ModActionType::BanFromCommunity => lang.mod_action_banned_from_community(name),
+    ModActionType::UnbanFromCommunity => lang.mod_action_unbanned_from_community(name),
+  };
+  let protocol_and_hostname = settings.get_protocol_and_hostname();
+  let link = match (form.comment_id, form.post_id) {
+    (Some(comment_id), _) => format!("{protocol_and_hostname}/comment/{comment_id}"),
+    (None, Some(post_id)) => format!("{protocol_and_hostname}/post/{post_id}"),
+    (None, None) => format!("{protocol_and_hostname}/modlog/{}", community.id.0),
+  };
+  (subject, link)
+}
+
+/// Returns the number of unresolved reports which the user can resolve, optionally only for a
+/// single community.
+pub async fn get_report_count(
+  person_id: PersonId,
+  admin: bool,
+  community_id: Option<CommunityId>,
+  pool: &mut DbPool<'_>,
+) -> Result<GetReportCountResponse, LemmyError> {
+  let comment_reports =
+    CommentReportView::get_report_count(pool, person_id, admin, community_id).await?;
+
+  let post_reports = PostReportView::get_report_count(pool, person_id, admin, community_id).await?;
+
+  let private_message_reports = if admin && community_id.is_none() {
+    Some(PrivateMessageReportView::get_report_count(pool).await?)
+  } else {
+    None
+  };
+
+  Ok(GetReportCountResponse {
+    community_id,
+    comment_reports,
+    post_reports,
+    private_message_reports,
+  })
+}
+
+/// Sends a push notification about a new registration application to all admins
+pub async fn send_new_applicant_push_notifications(
+  applicant_username: &str,
+  answer: &str,
+  context: &LemmyContext,
+) -> Result<(), LemmyError> {
+  let admins = LocalUserView::list_admins(&mut context.pool()).await?;
+  let hostname = &context.settings().hostname;
+  let applications_link = format!(
+    "{}/registration_applications",
+    context.settings().get_protocol_and_hostname()
+  );
+  for admin in &admins {
+    let lang = get_interface_language(admin);
+    let subject = lang.new_application_subject(hostname, applicant_username);
+    send_push_notification(
+      admin.local_user.id,
+      PushNotification::new(
+        NotificationType::ModeratorEvent,
+        &subject,
+        answer,
+        &applications_link,
+      ),
+      context,
+    );
   }
   Ok(())
 }
@@ -624,6 +1189,10 @@ pub async fn purge_user_account(
     purge_image_from_pictrs(&banner, context).await.ok();
   }
   // No need to update avatar and banner, those are handled in Person::delete_account
+  if person.local {
+    let local_user = LocalUserView::read_person(pool, person_id).await?;
+    delete_uploaded_images(local_user.local_user.id, context).await?;
+  }
 
   // Comments
   Comment::permadelete_for_creator(pool, person_id)
@@ -646,6 +1215,113 @@ pub async fn purge_user_account(
   Ok(())
 }
 
+/// Returns the upload usage of the user, with the limits from the config unless an admin changed
+/// them for this user.
+pub async fn read_upload_quota(
+  local_user: &LocalUser,
+  context: &LemmyContext,
+) -> Result<UploadQuotaResponse, LemmyError> {
+  let config = &context.settings().media.upload_quota;
+  let (upload_count, upload_size) = ImageUpload::usage(&mut context.pool(), local_user.id).await?;
+  Ok(UploadQuotaResponse {
+    upload_count,
+    upload_size,
+    max_files: local_user.upload_quota_max_files.or(config.max_files),
+    max_total_size: local_user
+      .upload_quota_max_total_size
+      .or(config.max_total_size),
+    max_file_size: config.max_file_size,
+    allowed_mime_types: config.allowed_mime_types.clone(),
+  })
+}
+
+/// Deletes all images which the local user uploaded, both from pict-rs and from the list of
+/// uploads.
+pub async fn delete_uploaded_images(
+  local_user_id: LocalUserId,
+  context: &LemmyContext,
+) -> Result<(), LemmyError> {
+  let pool = &mut context.pool();
+  let uploads = ImageUpload::get_all_by_local_user_id(pool, &local_user_id).await?;
+  for upload in uploads {
+    delete_image_from_pictrs(&upload.pictrs_alias, &upload.pictrs_delete_token, context)
+      .await
+      .ok();
+    ImageUpload::delete(pool, upload.id).await?;
+  }
+  Ok(())
+}
+
+/// Returns the alt text of a post image. Without explicit alt text the description of a local
+/// upload is used, and communities can require it for all posts linking to images or videos.
+pub async fn post_alt_text(
+  alt_text: Option<String>,
+  url: Option<&Url>,
+  community: &Community,
+  context: &LemmyContext,
+) -> Result<Option<String>, LemmyError> {
+  let alt_text = alt_text
+    .map(|a| sanitize_html_api(a.trim()))
+    .filter(|a| !a.is_empty());
+  is_valid_alt_text_field(&alt_text)?;
+  // Alt text only describes the linked image
+  let Some(url) = url else {
+    return Ok(None);
+  };
+
+  let upload = local_upload_alias(url, context.settings());
+  let upload = match upload {
+    Some(alias) => ImageUpload::read_by_alias(&mut context.pool(), alias)
+      .await
+      .ok(),
+    None => None,
+  };
+  let is_media = upload.is_some() || is_media_url(url);
+  let alt_text = alt_text.or(upload.and_then(|u| u.alt_text));
+  if community.require_alt_text && is_media && alt_text.is_none() {
+    Err(LemmyErrorType::AltTextRequired)?
+  }
+  Ok(alt_text)
+}
+
+/// Validates the images of a gallery post, and returns their cleaned urls with alt text.
+pub async fn post_gallery(
+  gallery: &Option<Vec<GalleryImage>>,
+  community: &Community,
+  context: &LemmyContext,
+) -> Result<Option<Vec<(Url, Option<String>)>>, LemmyError> {
+  let Some(gallery) = gallery else {
+    return Ok(None);
+  };
+  check_gallery_size(gallery.len())?;
+  let mut images = vec![];
+  for image in gallery {
+    let url = clean_url_params(&image.url);
+    check_url_scheme(&Some(url.clone()))?;
+    let alt_text = post_alt_text(image.alt_text.clone(), Some(&url), community, context).await?;
+    images.push((url, alt_text));
+  }
+  Ok(Some(images))
+}
+
+/// Returns the alias if the url points to an image uploaded to this instance.
+fn local_upload_alias<'a>(url: &'a Url, settings: &Settings) -> Option<&'a str> {
+  if url.domain() != Some(settings.hostname.as_str()) {
+    return None;
+  }
+  url.path().strip_prefix("/pictrs/image/")
+}
+
+/// Guesses from the file extension whether a link points to an image or video.
+fn is_media_url(url: &Url) -> bool {
+  let path = url.path().to_lowercase();
+  [
+    ".png", ".jpg", ".jpeg", ".gif", ".webp", ".avif", ".svg", ".mp4", ".webm",
+  ]
+  .iter()
+  .any(|e| path.ends_with(e))
+}
+
 pub enum EndpointType {
   Community,
   Person,
@@ -708,10 +1384,18 @@ pub fn generate_featured_url(actor_id: &DbUrl) -> Result<DbUrl, ParseError> {
   Ok(Url::parse(&format!("{actor_id}/featured"))?.into())
 }
 
+pub fn generate_replies_url(post_ap_id: &DbUrl) -> Result<DbUrl, ParseError> {
+  Ok(Url::parse(&format!("{post_ap_id}/replies"))?.into())
+}
+
 pub fn generate_moderators_url(community_id: &DbUrl) -> Result<DbUrl, LemmyError> {
   Ok(Url::parse(&format!("{community_id}/moderators"))?.into())
 }
 
+/// Shown instead of the content of encrypted private messages, where it can't be decrypted.
+pub const ENCRYPTED_MESSAGE_NOTICE: &str =
+  "This message is end-to-end encrypted. Open it in a client which supports encryption to read it.";
+
 /// Replace special HTML characters in API parameters to prevent XSS attacks.
 ///
 /// Taken from https://github.com/OWASP/CheatSheetSeries/blob/master/cheatsheets/Cross_Site_Scripting_Prevention_Cheat_Sheet.md#output-encoding-for-html-contexts
@@ -748,7 +1432,44 @@ mod tests {
   #![allow(clippy::unwrap_used)]
   #![allow(clippy::indexing_slicing)]
 
-  use crate::utils::{honeypot_check, password_length_check};
+  use crate::{
+    context::LemmyContext,
+    utils::{
+      check_private_message_policy,
+      honeypot_check,
+      lang_str_to_lang,
+      password_length_check,
+      post_alt_text,
+      read_or_create_conversation,
+      render_email,
+    },
+  };
+  use lemmy_db_schema::{
+    newtypes::LanguageId,
+    source::{
+      community::{Community, CommunityInsertForm},
+      conversation::ConversationParticipant,
+      email_template::EmailTemplate,
+      image_upload::{ImageUpload, ImageUploadForm},
+      instance::Instance,
+      local_user::{LocalUser, LocalUserInsertForm},
+      person::{Person, PersonFollower, PersonFollowerForm, PersonInsertForm},
+      secret::Secret,
+    },
+    traits::{Crud, Followable},
+    utils::{build_db_pool_for_tests, naive_now, DbPool},
+    EmailTemplateType,
+    PrivateMessagePolicy,
+  };
+  use lemmy_db_views::structs::LocalUserView;
+  use lemmy_utils::{
+    error::LemmyErrorType,
+    rate_limit::{RateLimitCell, RateLimitConfig},
+  };
+  use reqwest::Client;
+  use reqwest_middleware::ClientBuilder;
+  use serial_test::serial;
+  use url::Url;
 
   #[test]
   #[rustfmt::skip]
@@ -766,4 +1487,283 @@ mod tests {
     assert!(honeypot_check(&Some("1".to_string())).is_err());
     assert!(honeypot_check(&Some("message".to_string())).is_err());
   }
+
+  #[test]
+  fn email_digest_email() {
+    let variables = [
+      ("hostname", "example.com"),
+      ("recipient", "user"),
+      ("count", "2"),
+      ("content", "<h3>Reply from alice</h3>"),
+    ];
+    let (subject, body) = render_email(
+      EmailTemplateType::EmailDigest,
+      None,
+      &lang_str_to_lang("en"),
+      &variables,
+    );
+    assert_eq!("Your digest from example.com: 2 notifications", subject);
+    assert_eq!("<h3>Reply from alice</h3>", body);
+  }
+
+  #[test]
+  fn mod_action_email() {
+    let variables = [
+      ("hostname", "example.com"),
+      ("recipient", "user"),
+      ("action", "Your post was removed from Cats"),
+      ("community", "Cats"),
+      ("reason", "Off topic"),
+      ("link", "https://example.com/post/1"),
+    ];
+    let (subject, body) = render_email(
+      EmailTemplateType::ModAction,
+      None,
+      &lang_str_to_lang("en"),
+      &variables,
+    );
+    assert_eq!("Your post was removed from Cats", subject);
+    assert!(body.contains("Reason: Off topic"));
+    assert!(body.contains("https://example.com/post/1"));
+
+    // Admins can replace the text, for example in the language of their users
+    let template = EmailTemplate {
+      template_type: EmailTemplateType::ModAction,
+      language_id: LanguageId(0),
+      subject: "Moderation in {{community}}".to_string(),
+      body: "{{action}}: {{reason}}".to_string(),
+      published: naive_now(),
+      updated: None,
+    };
+    let (subject, body) = render_email(
+      EmailTemplateType::ModAction,
+      Some(&template),
+      &lang_str_to_lang("en"),
+      &variables,
+    );
+    assert_eq!("Moderation in Cats", subject);
+    assert_eq!("Your post was removed from Cats: Off topic", body);
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_post_alt_text_required() {
+    let pool = build_db_pool_for_tests().await;
+    let client = ClientBuilder::new(Client::default()).build();
+    let secret = Secret {
+      id: 0,
+      jwt_secret: String::new(),
+      vapid_private_key: None,
+      vapid_public_key: None,
+    };
+    let rate_limit_cell = RateLimitCell::new(RateLimitConfig::builder().build()).await;
+    let context = LemmyContext::create(pool, client, secret, rate_limit_cell.clone());
+    let pool = &mut context.pool();
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+    let person_form = PersonInsertForm::builder()
+      .name("alt_text_person".into())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let person = Person::create(pool, &person_form).await.unwrap();
+    let local_user_form = LocalUserInsertForm::builder()
+      .person_id(person.id)
+      .password_encrypted("123456".to_string())
+      .build();
+    let local_user = LocalUser::create(pool, &local_user_form).await.unwrap();
+    let community_form = CommunityInsertForm::builder()
+      .name("alt_text_community".into())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .require_alt_text(Some(true))
+      .build();
+    let community = Community::create(pool, &community_form).await.unwrap();
+    let upload_form = ImageUploadForm {
+      local_user_id: local_user.id,
+      pictrs_alias: "alt_text_upload.png".to_string(),
+      pictrs_delete_token: "token".to_string(),
+      size: None,
+      alt_text: Some("Uploaded description".to_string()),
+    };
+    ImageUpload::create(pool, &upload_form).await.unwrap();
+
+    let image = Url::parse("https://example.com/image.png").unwrap();
+    let page = Url::parse("https://example.com/article").unwrap();
+    let upload = Url::parse(&format!(
+      "https://{}/pictrs/image/alt_text_upload.png",
+      context.settings().hostname
+    ))
+    .unwrap();
+
+    // Images need alt text in this community
+    let missing = post_alt_text(None, Some(&image), &community, &context).await;
+    assert_eq!(
+      Some(LemmyErrorType::AltTextRequired),
+      missing.err().map(|e| e.error_type)
+    );
+    let blank = post_alt_text(Some(" ".to_string()), Some(&image), &community, &context).await;
+    assert!(blank.is_err());
+    let given = post_alt_text(
+      Some("A cat".to_string()),
+      Some(&image),
+      &community,
+      &context,
+    )
+    .await
+    .unwrap();
+    assert_eq!(Some("A cat".to_string()), given);
+
+    // Other links and text posts don't, and uploads use their stored description
+    assert!(post_alt_text(None, Some(&page), &community, &context)
+      .await
+      .unwrap()
+      .is_none());
+    assert!(post_alt_text(None, None, &community, &context)
+      .await
+      .unwrap()
+      .is_none());
+    let from_upload = post_alt_text(None, Some(&upload), &community, &context)
+      .await
+      .unwrap();
+    assert_eq!(Some("Uploaded description".to_string()), from_upload);
+
+    // Without the community setting alt text stays optional
+    let optional = Community {
+      require_alt_text: false,
+      ..community.clone()
+    };
+    assert!(post_alt_text(None, Some(&image), &optional, &context)
+      .await
+      .unwrap()
+      .is_none());
+
+    Person::delete(pool, person.id).await.unwrap();
+    Community::delete(pool, community.id).await.unwrap();
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
+
+  async fn create_local_user(pool: &mut DbPool<'_>, person: &Person) -> LocalUserView {
+    let local_user_form = LocalUserInsertForm::builder()
+      .person_id(person.id)
+      .password_encrypted("123456".to_string())
+      .build();
+    let local_user = LocalUser::create(pool, &local_user_form).await.unwrap();
+    LocalUserView::read(pool, local_user.id).await.unwrap()
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_private_message_policy() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+    let mut persons = vec![];
+    for name in [
+      "pm_policy_sender",
+      "pm_policy_stranger",
+      "pm_policy_follower",
+    ] {
+      let person_form = PersonInsertForm::builder()
+        .name(name.into())
+        .public_key("pubkey".to_string())
+        .instance_id(inserted_instance.id)
+        .build();
+      persons.push(Person::create(pool, &person_form).await.unwrap());
+    }
+    let sender = persons[0].clone();
+    let remote_sender = Person {
+      local: false,
+      ..sender.clone()
+    };
+    let mut stranger = create_local_user(pool, &persons[1]).await;
+    let mut follower = create_local_user(pool, &persons[2]).await;
+    let follower_form = PersonFollowerForm {
+      person_id: sender.id,
+      follower_id: follower.person.id,
+      pending: false,
+    };
+    PersonFollower::follow(pool, &follower_form).await.unwrap();
+
+    // (policy, local sender allowed, remote sender allowed) for a recipient who doesn't follow
+    // the sender
+    for (policy, local_allowed, remote_allowed) in [
+      (PrivateMessagePolicy::Everyone, true, true),
+      (PrivateMessagePolicy::Following, false, false),
+      (PrivateMessagePolicy::Local, true, false),
+      (PrivateMessagePolicy::Nobody, false, false),
+    ] {
+      stranger.local_user.private_message_policy = policy;
+      follower.local_user.private_message_policy = policy;
+      for (sender, allowed) in [(&sender, local_allowed), (&remote_sender, remote_allowed)] {
+        let result = check_private_message_policy(sender, false, &[&stranger], pool).await;
+        if allowed {
+          // Strangers end up in the message requests
+          assert_eq!(vec![stranger.person.id], result.unwrap(), "{policy}");
+        } else {
+          assert_eq!(
+            Some(LemmyErrorType::RecipientDoesNotAcceptPrivateMessages),
+            result.err().map(|e| e.error_type),
+            "{policy}"
+          );
+        }
+      }
+
+      // Followers only refuse everyone, and never get message requests
+      let result = check_private_message_policy(&sender, false, &[&follower], pool).await;
+      if policy == PrivateMessagePolicy::Nobody {
+        assert!(result.is_err());
+      } else {
+        assert!(result.unwrap().is_empty(), "{policy}");
+      }
+
+      // Admins can always write
+      let recipients = [&stranger, &follower];
+      let result = check_private_message_policy(&remote_sender, true, &recipients, pool).await;
+      assert!(result.unwrap().is_empty(), "{policy}");
+    }
+
+    // A new conversation from a stranger is a message request, and replying accepts it
+    stranger.local_user.private_message_policy = PrivateMessagePolicy::Everyone;
+    let participant_ids = [sender.id, stranger.person.id];
+    let conversation =
+      read_or_create_conversation(&sender, false, &participant_ids, &[&stranger], pool)
+        .await
+        .unwrap();
+    let participant = ConversationParticipant::read(pool, conversation.id, stranger.person.id)
+      .await
+      .unwrap();
+    assert!(participant.message_request);
+
+    // The policy only applies to new conversations
+    stranger.local_user.private_message_policy = PrivateMessagePolicy::Nobody;
+    let again = read_or_create_conversation(&sender, false, &participant_ids, &[&stranger], pool)
+      .await
+      .unwrap();
+    assert_eq!(conversation.id, again.id);
+    assert!(
+      ConversationParticipant::read(pool, conversation.id, stranger.person.id)
+        .await
+        .unwrap()
+        .message_request
+    );
+    let reply = read_or_create_conversation(&stranger.person, false, &participant_ids, &[], pool)
+      .await
+      .unwrap();
+    assert_eq!(conversation.id, reply.id);
+    assert!(
+      !ConversationParticipant::read(pool, conversation.id, stranger.person.id)
+        .await
+        .unwrap()
+        .message_request
+    );
+
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
 }
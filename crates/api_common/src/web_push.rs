@@ -0,0 +1,227 @@
+use crate::{
+  context::LemmyContext,
+  live_events::{LiveEventKind, LiveEvents},
+  request::public_url_client,
+};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use lemmy_db_schema::{
+  newtypes::LocalUserId,
+  source::{
+    notification_preference::NotificationPreference,
+    push_subscription::PushSubscription,
+    secret::Secret,
+  },
+  utils::DbPool,
+  NotificationType,
+};
+use lemmy_utils::{
+  error::{LemmyError, LemmyErrorExt, LemmyErrorType},
+  spawn_try_task,
+};
+use openssl::{
+  bn::BigNumContext,
+  ec::{EcGroup, EcKey, PointConversionForm},
+  nid::Nid,
+};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+use ts_rs::TS;
+use url::Url;
+use web_push::{
+  request_builder,
+  ContentEncoding,
+  SubscriptionInfo,
+  VapidSignatureBuilder,
+  WebPushError,
+  WebPushMessageBuilder,
+};
+
+/// How long the push service keeps a message for devices which are offline, in seconds.
+const PUSH_MESSAGE_TTL: u32 = 60 * 60 * 24;
+
+/// Maximum length of the notification body, push services limit the payload to 4 kB.
+const MAX_PUSH_BODY_LENGTH: usize = 300;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[ts(export)]
+/// The payload of a push message, which the service worker of the client shows as notification.
+pub struct PushNotification {
+  pub type_: NotificationType,
+  pub title: String,
+  pub body: String,
+  /// The page to open when the notification is clicked.
+  pub link: String,
+}
+
+impl PushNotification {
+  pub fn new(type_: NotificationType, title: &str, body: &str, link: &str) -> Self {
+    let mut body = body.to_string();
+    if let Some((index, _)) = body.char_indices().nth(MAX_PUSH_BODY_LENGTH) {
+      body.truncate(index);
+      body.push('…');
+    }
+    PushNotification {
+      type_,
+      title: title.to_string(),
+      body,
+      link: link.to_string(),
+    }
+  }
+}
+
+/// Generates the keys for signing push messages if they don't exist yet.
+pub async fn init_vapid_keys(secret: Secret, pool: &mut DbPool<'_>) -> Result<Secret, LemmyError> {
+  if secret.vapid_private_key.is_some() && secret.vapid_public_key.is_some() {
+    return Ok(secret);
+  }
+  let (private_key, public_key) = generate_vapid_keys()?;
+  Ok(Secret::set_vapid_keys(pool, &private_key, &public_key).await?)
+}
+
+/// Returns a new P-256 key pair as base64url, the private key as raw scalar and the public key as
+/// uncompressed point, which is the format expected by browsers.
+fn generate_vapid_keys() -> Result<(String, String), LemmyError> {
+  let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1)?;
+  let key = EcKey::generate(&group)?;
+  let private_key = key.private_key().to_vec_padded(32)?;
+  let mut ctx = BigNumContext::new()?;
+  let public_key =
+    key
+      .public_key()
+      .to_bytes(&group, PointConversionForm::UNCOMPRESSED, &mut ctx)?;
+  Ok((
+    URL_SAFE_NO_PAD.encode(private_key),
+    URL_SAFE_NO_PAD.encode(public_key),
+  ))
+}
+
+/// Sends the notification to the live event streams of the user if they enabled in-app
+/// notifications of this type, and as push notification to all their devices if they enabled push
+/// notifications. Runs in the background.
+pub fn send_push_notification(
+  local_user_id: LocalUserId,
+  notification: PushNotification,
+  context: &LemmyContext,
+) {
+  let context = context.clone();
+  spawn_try_task(async move {
+    let preference =
+      NotificationPreference::read(&mut context.pool(), local_user_id, notification.type_).await?;
+    if preference.in_app {
+      LiveEvents::publish(LiveEventKind::Notification(
+        local_user_id,
+        notification.clone(),
+      ));
+    }
+    if !preference.push {
+      return Ok(());
+    }
+    let subscriptions =
+      PushSubscription::list_for_local_user(&mut context.pool(), local_user_id).await?;
+    if subscriptions.is_empty() {
+      return Ok(());
+    }
+    let payload = serde_json::to_vec(&notification)?;
+    for subscription in subscriptions {
+      send_push_message(&subscription, &payload, &context)
+        .await
+        .map_err(|e| warn!("Failed to send push message: {e}"))
+        .ok();
+    }
+    Ok(())
+  });
+}
+
+async fn send_push_message(
+  subscription: &PushSubscription,
+  payload: &[u8],
+  context: &LemmyContext,
+) -> Result<(), LemmyError> {
+  let private_key = context
+    .secret()
+    .vapid_private_key
+    .as_deref()
+    .ok_or(LemmyErrorType::PushSendFailed)?;
+  let info = SubscriptionInfo::new(
+    subscription.endpoint.as_str(),
+    subscription.p256dh.as_str(),
+    subscription.auth.as_str(),
+  );
+  let mut signature =
+    VapidSignatureBuilder::from_base64(private_key, web_push::URL_SAFE_NO_PAD, &info)
+      .with_lemmy_type(LemmyErrorType::PushSendFailed)?;
+  signature.add_claim("sub", context.settings().get_protocol_and_hostname());
+
+  let mut message = WebPushMessageBuilder::new(&info);
+  message.set_payload(ContentEncoding::Aes128Gcm, payload);
+  message.set_ttl(PUSH_MESSAGE_TTL);
+  message.set_vapid_signature(
+    signature
+      .build()
+      .with_lemmy_type(LemmyErrorType::PushSendFailed)?,
+  );
+  let message = message
+    .build()
+    .with_lemmy_type(LemmyErrorType::InvalidPushSubscription)?;
+
+  // The domain of the endpoint may point to a different address than when it was registered
+  let endpoint =
+    Url::parse(&subscription.endpoint).with_lemmy_type(LemmyErrorType::InvalidPushSubscription)?;
+  let client = public_url_client(&endpoint, context.settings()).await?;
+
+  let (parts, body) = request_builder::build_request::<Vec<u8>>(message).into_parts();
+  let res = client
+    .post(parts.uri.to_string())
+    .headers(parts.headers)
+    .body(body)
+    .send()
+    .await?;
+  let status = res.status();
+  let body = res.bytes().await?.to_vec();
+  match request_builder::parse_response(status, body) {
+    // The subscription expired or the user revoked the permission in their browser
+    Err(WebPushError::EndpointNotValid | WebPushError::EndpointNotFound) => {
+      PushSubscription::delete(
+        &mut context.pool(),
+        subscription.local_user_id,
+        &subscription.endpoint,
+      )
+      .await?;
+      Ok(())
+    }
+    res => res.with_lemmy_type(LemmyErrorType::PushSendFailed),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use super::{generate_vapid_keys, NotificationType, PushNotification};
+  use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+  use web_push::VapidSignatureBuilder;
+
+  #[test]
+  fn test_generate_vapid_keys() {
+    let (private_key, public_key) = generate_vapid_keys().unwrap();
+    assert_eq!(32, URL_SAFE_NO_PAD.decode(&private_key).unwrap().len());
+    let public_key = URL_SAFE_NO_PAD.decode(public_key).unwrap();
+    assert_eq!(65, public_key.len());
+    assert_eq!(4, public_key[0]);
+
+    // The public key must belong to the private key which is used for signing
+    let builder =
+      VapidSignatureBuilder::from_base64_no_sub(&private_key, web_push::URL_SAFE_NO_PAD).unwrap();
+    assert_eq!(public_key, builder.get_public_key());
+  }
+
+  #[test]
+  fn test_truncate_body() {
+    let body = "ä".repeat(1000);
+    let notification = PushNotification::new(NotificationType::Reply, "title", &body, "/");
+    assert_eq!(301, notification.body.chars().count());
+    let notification = PushNotification::new(NotificationType::Reply, "title", "short", "/");
+    assert_eq!("short", notification.body);
+  }
+}
@@ -4,6 +4,7 @@ use lemmy_api_common::{
   build_response::{build_comment_response, send_local_notifs},
   comment::{CommentResponse, CreateComment},
   context::LemmyContext,
+  live_events::{LiveEventKind, LiveEvents},
   send_activity::{ActivityChannel, SendActivityData},
   utils::{
     check_community_ban,
@@ -166,6 +167,7 @@ pub async fn create_comment(
     &context,
   )
   .await?;
+  LiveEvents::publish(LiveEventKind::NewComment(post.id, updated_comment.id));
 
   // If its a reply, mark the parent as read
   if let Some(parent) = parent_opt {
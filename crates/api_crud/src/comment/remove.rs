@@ -5,18 +5,21 @@ use lemmy_api_common::{
   comment::{CommentResponse, RemoveComment},
   context::LemmyContext,
   send_activity::{ActivityChannel, SendActivityData},
-  utils::{check_community_ban, is_mod_or_admin},
+  utils::{check_community_ban, is_mod_or_admin, send_mod_action_notification},
 };
 use lemmy_db_schema::{
   source::{
     comment::{Comment, CommentUpdateForm},
+    mod_action_notification::ModActionNotificationInsertForm,
     moderator::{ModRemoveComment, ModRemoveCommentForm},
     post::Post,
   },
   traits::Crud,
+  ModActionType,
 };
 use lemmy_db_views::structs::{CommentView, LocalUserView};
 use lemmy_utils::error::{LemmyError, LemmyErrorExt, LemmyErrorType};
+use tracing::warn;
 
 #[tracing::instrument(skip(context))]
 pub async fn remove_comment(
@@ -64,6 +67,26 @@ pub async fn remove_comment(
   };
   ModRemoveComment::create(&mut context.pool(), &form).await?;
 
+  send_mod_action_notification(
+    ModActionNotificationInsertForm {
+      recipient_id: orig_comment.creator.id,
+      mod_person_id: local_user_view.person.id,
+      action: if removed {
+        ModActionType::RemoveComment
+      } else {
+        ModActionType::RestoreComment
+      },
+      community_id: orig_comment.community.id,
+      post_id: Some(orig_comment.post.id),
+      comment_id: Some(comment_id),
+      reason: data.reason.clone(),
+    },
+    &context,
+  )
+  .await
+  .map_err(|e| warn!("Failed to send mod action notification: {e}"))
+  .ok();
+
   let post_id = updated_comment.post_id;
   let post = Post::read(&mut context.pool(), post_id).await?;
   let recipient_ids = send_local_notifs(
@@ -2,6 +2,7 @@ use actix_web::web::{Data, Json, Query};
 use lemmy_api_common::{
   community::{ListCommunities, ListCommunitiesResponse},
   context::LemmyContext,
+  image_proxy::ProxyImages,
   utils::{check_private_instance, is_admin},
 };
 use lemmy_db_schema::source::local_site::LocalSite;
@@ -29,7 +30,7 @@ pub async fn list_communities(
   let page = data.page;
   let limit = data.limit;
   let local_user = local_user_view.map(|l| l.local_user);
-  let communities = CommunityQuery {
+  let mut communities = CommunityQuery {
     listing_type,
     show_nsfw,
     sort,
@@ -41,6 +42,7 @@ pub async fn list_communities(
   }
   .list(&mut context.pool())
   .await?;
+  communities.proxy_images(&context);
 
   // Return the jwt
   Ok(Json(ListCommunitiesResponse { communities }))
@@ -73,6 +73,7 @@ pub async fn update_community(
     banner,
     nsfw: data.nsfw,
     posting_restricted_to_mods: data.posting_restricted_to_mods,
+    require_alt_text: data.require_alt_text,
     updated: Some(Some(naive_now())),
     ..Default::default()
   };
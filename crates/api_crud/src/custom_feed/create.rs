@@ -0,0 +1,67 @@
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use lemmy_api_common::{
+  context::LemmyContext,
+  custom_feed::{CreateCustomFeed, CustomFeedResponse},
+  image_proxy::ProxyImages,
+  utils::{local_site_to_slur_regex, sanitize_html_api, sanitize_html_api_opt},
+};
+use lemmy_db_schema::{
+  source::{
+    custom_feed::{CustomFeed, CustomFeedInsertForm},
+    local_site::LocalSite,
+  },
+  traits::Crud,
+};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_db_views_actor::structs::CustomFeedView;
+use lemmy_utils::{
+  error::{LemmyError, LemmyErrorExt, LemmyErrorType},
+  utils::{
+    slurs::{check_slurs, check_slurs_opt},
+    validation::{check_custom_feed_size, is_valid_body_field, is_valid_custom_feed_name},
+  },
+};
+
+#[tracing::instrument(skip(context))]
+pub async fn create_custom_feed(
+  data: Json<CreateCustomFeed>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> Result<Json<CustomFeedResponse>, LemmyError> {
+  let local_site = LocalSite::read(&mut context.pool()).await?;
+
+  let slur_regex = local_site_to_slur_regex(&local_site);
+  check_slurs(&data.name, &slur_regex)?;
+  check_slurs_opt(&data.description, &slur_regex)?;
+  is_valid_custom_feed_name(&data.name)?;
+  is_valid_body_field(&data.description, false)?;
+
+  let mut community_ids = data.community_ids.clone();
+  community_ids.sort_by_key(|id| id.0);
+  community_ids.dedup();
+  check_custom_feed_size(community_ids.len())?;
+
+  let form = CustomFeedInsertForm::builder()
+    .creator_id(local_user_view.person.id)
+    .name(sanitize_html_api(data.name.trim()))
+    .description(sanitize_html_api_opt(&data.description))
+    .public(data.public)
+    .build();
+  let custom_feed = CustomFeed::create(&mut context.pool(), &form)
+    .await
+    .with_lemmy_type(LemmyErrorType::CustomFeedAlreadyExists)?;
+
+  // Don't keep a half created feed if one of the communities doesn't exist
+  if let Err(e) =
+    CustomFeed::set_communities(&mut context.pool(), custom_feed.id, community_ids).await
+  {
+    CustomFeed::delete(&mut context.pool(), custom_feed.id).await?;
+    return Err(e).with_lemmy_type(LemmyErrorType::CouldntFindCommunity);
+  }
+
+  let mut custom_feed_view = CustomFeedView::read(&mut context.pool(), custom_feed.id).await?;
+  custom_feed_view.proxy_images(&context);
+
+  Ok(Json(CustomFeedResponse { custom_feed_view }))
+}
@@ -0,0 +1,30 @@
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use lemmy_api_common::{
+  context::LemmyContext,
+  custom_feed::{DeleteCustomFeed, DeleteCustomFeedResponse},
+  utils::read_custom_feed,
+};
+use lemmy_db_schema::{source::custom_feed::CustomFeed, traits::Crud};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::{LemmyError, LemmyErrorType};
+
+#[tracing::instrument(skip(context))]
+pub async fn delete_custom_feed(
+  data: Json<DeleteCustomFeed>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> Result<Json<DeleteCustomFeedResponse>, LemmyError> {
+  // Only the creator can delete the feed
+  let person_id = local_user_view.person.id;
+  let custom_feed = read_custom_feed(&mut context.pool(), data.id, Some(person_id)).await?;
+  if custom_feed.creator_id != person_id {
+    Err(LemmyErrorType::CustomFeedNotFound)?
+  }
+
+  CustomFeed::delete(&mut context.pool(), custom_feed.id).await?;
+  Ok(Json(DeleteCustomFeedResponse {
+    id: data.id,
+    success: true,
+  }))
+}
@@ -0,0 +1,35 @@
+use activitypub_federation::config::Data;
+use actix_web::web::{Json, Query};
+use lemmy_api_common::{
+  context::LemmyContext,
+  custom_feed::{ListCustomFeeds, ListCustomFeedsResponse},
+  image_proxy::ProxyImages,
+  utils::check_private_instance,
+};
+use lemmy_db_schema::source::local_site::LocalSite;
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_db_views_actor::structs::CustomFeedView;
+use lemmy_utils::error::{LemmyError, LemmyErrorType};
+
+/// Lists the custom feeds of a user. For other users only the public feeds are included.
+#[tracing::instrument(skip(context))]
+pub async fn list_custom_feeds(
+  data: Query<ListCustomFeeds>,
+  context: Data<LemmyContext>,
+  local_user_view: Option<LocalUserView>,
+) -> Result<Json<ListCustomFeedsResponse>, LemmyError> {
+  let local_site = LocalSite::read(&mut context.pool()).await?;
+  check_private_instance(&local_user_view, &local_site)?;
+
+  let my_person_id = local_user_view.as_ref().map(|l| l.person.id);
+  let Some(person_id) = data.person_id.or(my_person_id) else {
+    Err(LemmyErrorType::NotLoggedIn)?
+  };
+  let public_only = Some(person_id) != my_person_id;
+
+  let mut custom_feeds =
+    CustomFeedView::list_for_creator(&mut context.pool(), person_id, public_only).await?;
+  custom_feeds.proxy_images(&context);
+
+  Ok(Json(ListCustomFeedsResponse { custom_feeds }))
+}
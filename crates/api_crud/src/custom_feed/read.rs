@@ -0,0 +1,30 @@
+use activitypub_federation::config::Data;
+use actix_web::web::{Json, Query};
+use lemmy_api_common::{
+  context::LemmyContext,
+  custom_feed::{CustomFeedResponse, GetCustomFeed},
+  image_proxy::ProxyImages,
+  utils::{check_private_instance, read_custom_feed},
+};
+use lemmy_db_schema::source::local_site::LocalSite;
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_db_views_actor::structs::CustomFeedView;
+use lemmy_utils::error::LemmyError;
+
+#[tracing::instrument(skip(context))]
+pub async fn get_custom_feed(
+  data: Query<GetCustomFeed>,
+  context: Data<LemmyContext>,
+  local_user_view: Option<LocalUserView>,
+) -> Result<Json<CustomFeedResponse>, LemmyError> {
+  let local_site = LocalSite::read(&mut context.pool()).await?;
+  check_private_instance(&local_user_view, &local_site)?;
+
+  let person_id = local_user_view.as_ref().map(|l| l.person.id);
+  let custom_feed = read_custom_feed(&mut context.pool(), data.id, person_id).await?;
+
+  let mut custom_feed_view = CustomFeedView::read(&mut context.pool(), custom_feed.id).await?;
+  custom_feed_view.proxy_images(&context);
+
+  Ok(Json(CustomFeedResponse { custom_feed_view }))
+}
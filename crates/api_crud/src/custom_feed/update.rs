@@ -0,0 +1,75 @@
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use lemmy_api_common::{
+  context::LemmyContext,
+  custom_feed::{CustomFeedResponse, EditCustomFeed},
+  image_proxy::ProxyImages,
+  utils::{local_site_to_slur_regex, read_custom_feed, sanitize_html_api, sanitize_html_api_opt},
+};
+use lemmy_db_schema::{
+  source::{
+    custom_feed::{CustomFeed, CustomFeedUpdateForm},
+    local_site::LocalSite,
+  },
+  traits::Crud,
+  utils::{diesel_option_overwrite, naive_now},
+};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_db_views_actor::structs::CustomFeedView;
+use lemmy_utils::{
+  error::{LemmyError, LemmyErrorExt, LemmyErrorType},
+  utils::{
+    slurs::check_slurs_opt,
+    validation::{check_custom_feed_size, is_valid_body_field, is_valid_custom_feed_name},
+  },
+};
+
+#[tracing::instrument(skip(context))]
+pub async fn update_custom_feed(
+  data: Json<EditCustomFeed>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> Result<Json<CustomFeedResponse>, LemmyError> {
+  let local_site = LocalSite::read(&mut context.pool()).await?;
+
+  // Only the creator can edit the feed
+  let person_id = local_user_view.person.id;
+  let custom_feed = read_custom_feed(&mut context.pool(), data.id, Some(person_id)).await?;
+  if custom_feed.creator_id != person_id {
+    Err(LemmyErrorType::CustomFeedNotFound)?
+  }
+
+  let slur_regex = local_site_to_slur_regex(&local_site);
+  check_slurs_opt(&data.name, &slur_regex)?;
+  check_slurs_opt(&data.description, &slur_regex)?;
+  if let Some(name) = &data.name {
+    is_valid_custom_feed_name(name)?;
+  }
+  is_valid_body_field(&data.description, false)?;
+
+  if let Some(community_ids) = &data.community_ids {
+    let mut community_ids = community_ids.clone();
+    community_ids.sort_by_key(|id| id.0);
+    community_ids.dedup();
+    check_custom_feed_size(community_ids.len())?;
+    CustomFeed::set_communities(&mut context.pool(), custom_feed.id, community_ids)
+      .await
+      .with_lemmy_type(LemmyErrorType::CouldntFindCommunity)?;
+  }
+
+  let description = diesel_option_overwrite(sanitize_html_api_opt(&data.description));
+  let form = CustomFeedUpdateForm {
+    name: data.name.as_deref().map(|n| sanitize_html_api(n.trim())),
+    description,
+    public: data.public,
+    updated: Some(Some(naive_now())),
+  };
+  CustomFeed::update(&mut context.pool(), custom_feed.id, &form)
+    .await
+    .with_lemmy_type(LemmyErrorType::CustomFeedAlreadyExists)?;
+
+  let mut custom_feed_view = CustomFeedView::read(&mut context.pool(), custom_feed.id).await?;
+  custom_feed_view.proxy_images(&context);
+
+  Ok(Json(CustomFeedResponse { custom_feed_view }))
+}
@@ -1,7 +1,9 @@
 pub mod comment;
 pub mod community;
 pub mod custom_emoji;
+pub mod custom_feed;
 pub mod post;
 pub mod private_message;
+pub mod reply_by_email;
 pub mod site;
 pub mod user;
@@ -3,6 +3,7 @@ use actix_web::web::Json;
 use lemmy_api_common::{
   build_response::build_post_response,
   context::LemmyContext,
+  live_events::{LiveEventKind, LiveEvents},
   post::{CreatePost, PostResponse},
   request::fetch_site_data,
   send_activity::{ActivityChannel, SendActivityData},
@@ -13,6 +14,8 @@ use lemmy_api_common::{
     honeypot_check,
     local_site_to_slur_regex,
     mark_post_as_read,
+    post_alt_text,
+    post_gallery,
     sanitize_html_api,
     sanitize_html_api_opt,
     EndpointType,
@@ -25,6 +28,7 @@ use lemmy_db_schema::{
     community::Community,
     local_site::LocalSite,
     post::{Post, PostInsertForm, PostLike, PostLikeForm, PostUpdateForm},
+    post_gallery_image::PostGalleryImage,
   },
   traits::{Crud, Likeable},
 };
@@ -55,9 +59,6 @@ pub async fn create_post(
   check_slurs_opt(&data.body, &slur_regex)?;
   honeypot_check(&data.honeypot)?;
 
-  let data_url = data.url.as_ref();
-  let url = data_url.map(clean_url_params).map(Into::into); // TODO no good way to handle a "clear"
-
   is_valid_post_title(&data.name)?;
   is_valid_body_field(&data.body, true)?;
   check_url_scheme(&data.url)?;
@@ -85,17 +86,33 @@ pub async fn create_post(
     }
   }
 
+  let gallery = post_gallery(&data.gallery, &community, &context)
+    .await?
+    .filter(|g| !g.is_empty());
+  // The first gallery image is also the post url, for clients without gallery support
+  let (data_url, alt_text) = match gallery.as_ref().and_then(|g| g.first()) {
+    Some((url, alt_text)) => (Some(url), alt_text.clone()),
+    None => {
+      let alt_text = post_alt_text(
+        data.alt_text.clone(),
+        data.url.as_ref(),
+        &community,
+        &context,
+      )
+      .await?;
+      (data.url.as_ref(), alt_text)
+    }
+  };
+  let url = data_url.map(clean_url_params).map(Into::into); // TODO no good way to handle a "clear"
+
   // Fetch post links and pictrs cached image
-  let (metadata_res, thumbnail_url) =
-    fetch_site_data(context.client(), context.settings(), data_url, true).await;
-  let (embed_title, embed_description, embed_video_url) = metadata_res
-    .map(|u| (u.title, u.description, u.embed_video_url))
-    .unwrap_or_default();
+  let (metadata_res, thumbnail_url) = fetch_site_data(&context, data_url, true).await;
+  let metadata = metadata_res.unwrap_or_default();
 
   let name = sanitize_html_api(data.name.trim());
   let body = sanitize_html_api_opt(&data.body);
-  let embed_title = sanitize_html_api_opt(&embed_title);
-  let embed_description = sanitize_html_api_opt(&embed_description);
+  let embed_title = sanitize_html_api_opt(&metadata.title);
+  let embed_description = sanitize_html_api_opt(&metadata.description);
 
   // Only need to check if language is allowed in case user set it explicitly. When using default
   // language, it already only returns allowed languages.
@@ -128,9 +145,14 @@ pub async fn create_post(
     .nsfw(data.nsfw)
     .embed_title(embed_title)
     .embed_description(embed_description)
-    .embed_video_url(embed_video_url)
+    .embed_video_url(metadata.embed_video_url)
+    .url_content_type(metadata.content_type)
+    .embed_width(metadata.embed_width)
+    .embed_height(metadata.embed_height)
+    .embed_duration(metadata.embed_duration)
     .language_id(language_id)
     .thumbnail_url(thumbnail_url)
+    .alt_text(alt_text)
     .build();
 
   let inserted_post = Post::create(&mut context.pool(), &post_form)
@@ -155,6 +177,13 @@ pub async fn create_post(
   .await
   .with_lemmy_type(LemmyErrorType::CouldntCreatePost)?;
 
+  if let Some(gallery) = gallery {
+    let images = gallery.into_iter().map(|(u, a)| (u.into(), a)).collect();
+    PostGalleryImage::replace(&mut context.pool(), inserted_post_id, images)
+      .await
+      .with_lemmy_type(LemmyErrorType::CouldntCreatePost)?;
+  }
+
   // They like their own post by default
   let person_id = local_user_view.person.id;
   let post_id = inserted_post.id;
@@ -170,6 +199,7 @@ pub async fn create_post(
 
   ActivityChannel::submit_activity(SendActivityData::CreatePost(updated_post.clone()), &context)
     .await?;
+  LiveEvents::publish(LiveEventKind::NewPost(updated_post.community_id, post_id));
 
   // Mark the post as read
   mark_post_as_read(person_id, post_id, &mut context.pool()).await?;
@@ -1,6 +1,7 @@
 use actix_web::web::{Data, Json, Query};
 use lemmy_api_common::{
   context::LemmyContext,
+  image_proxy::ProxyImages,
   post::{GetPost, GetPostResponse},
   utils::{check_private_instance, is_mod_or_admin_opt, mark_post_as_read},
 };
@@ -50,7 +51,7 @@ pub async fn get_post(
   .await
   .is_ok();
 
-  let post_view = PostView::read(&mut context.pool(), post_id, person_id, is_mod_or_admin)
+  let mut post_view = PostView::read(&mut context.pool(), post_id, person_id, is_mod_or_admin)
     .await
     .with_lemmy_type(LemmyErrorType::CouldntFindPost)?;
 
@@ -61,7 +62,7 @@ pub async fn get_post(
   }
 
   // Necessary for the sidebar subscribed
-  let community_view = CommunityView::read(
+  let mut community_view = CommunityView::read(
     &mut context.pool(),
     community_id,
     person_id,
@@ -85,10 +86,11 @@ pub async fn get_post(
       .with_lemmy_type(LemmyErrorType::CouldntFindPost)?;
   }
 
-  let moderators = CommunityModeratorView::for_community(&mut context.pool(), community_id).await?;
+  let mut moderators =
+    CommunityModeratorView::for_community(&mut context.pool(), community_id).await?;
 
   // Fetch the cross_posts
-  let cross_posts = if let Some(url) = &post_view.post.url {
+  let mut cross_posts = if let Some(url) = &post_view.post.url {
     let mut x_posts = PostQuery {
       url_search: Some(url.inner().as_str().into()),
       ..Default::default()
@@ -103,6 +105,11 @@ pub async fn get_post(
     Vec::new()
   };
 
+  post_view.proxy_images(&context);
+  community_view.proxy_images(&context);
+  moderators.proxy_images(&context);
+  cross_posts.proxy_images(&context);
+
   // Return the jwt
   Ok(Json(GetPostResponse {
     post_view,
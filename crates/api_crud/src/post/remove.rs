@@ -5,17 +5,20 @@ use lemmy_api_common::{
   context::LemmyContext,
   post::{PostResponse, RemovePost},
   send_activity::{ActivityChannel, SendActivityData},
-  utils::{check_community_ban, is_mod_or_admin},
+  utils::{check_community_ban, is_mod_or_admin, send_mod_action_notification},
 };
 use lemmy_db_schema::{
   source::{
+    mod_action_notification::ModActionNotificationInsertForm,
     moderator::{ModRemovePost, ModRemovePostForm},
     post::{Post, PostUpdateForm},
   },
   traits::Crud,
+  ModActionType,
 };
 use lemmy_db_views::structs::LocalUserView;
 use lemmy_utils::error::LemmyError;
+use tracing::warn;
 
 #[tracing::instrument(skip(context))]
 pub async fn remove_post(
@@ -63,6 +66,26 @@ pub async fn remove_post(
   };
   ModRemovePost::create(&mut context.pool(), &form).await?;
 
+  send_mod_action_notification(
+    ModActionNotificationInsertForm {
+      recipient_id: orig_post.creator_id,
+      mod_person_id: local_user_view.person.id,
+      action: if removed {
+        ModActionType::RemovePost
+      } else {
+        ModActionType::RestorePost
+      },
+      community_id: orig_post.community_id,
+      post_id: Some(post_id),
+      comment_id: None,
+      reason: data.reason.clone(),
+    },
+    &context,
+  )
+  .await
+  .map_err(|e| warn!("Failed to send mod action notification: {e}"))
+  .ok();
+
   let person_id = local_user_view.person.id;
   ActivityChannel::submit_activity(
     SendActivityData::RemovePost(post, local_user_view.person, data.0),
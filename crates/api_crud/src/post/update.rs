@@ -4,15 +4,23 @@ use lemmy_api_common::{
   build_response::build_post_response,
   context::LemmyContext,
   post::{EditPost, PostResponse},
-  request::fetch_site_data,
+  request::{fetch_site_data, site_metadata_post_form},
   send_activity::{ActivityChannel, SendActivityData},
-  utils::{check_community_ban, local_site_to_slur_regex, sanitize_html_api_opt},
+  utils::{
+    check_community_ban,
+    local_site_to_slur_regex,
+    post_alt_text,
+    post_gallery,
+    sanitize_html_api_opt,
+  },
 };
 use lemmy_db_schema::{
   source::{
     actor_language::CommunityLanguage,
+    community::Community,
     local_site::LocalSite,
     post::{Post, PostUpdateForm},
+    post_gallery_image::PostGalleryImage,
   },
   traits::Crud,
   utils::{diesel_option_overwrite, naive_now},
@@ -35,12 +43,6 @@ pub async fn update_post(
 ) -> Result<Json<PostResponse>, LemmyError> {
   let local_site = LocalSite::read(&mut context.pool()).await?;
 
-  let data_url = data.url.as_ref();
-
-  // TODO No good way to handle a clear.
-  // Issue link: https://github.com/LemmyNet/lemmy/issues/2287
-  let url = Some(data_url.map(clean_url_params).map(Into::into));
-
   let slur_regex = local_site_to_slur_regex(&local_site);
   check_slurs_opt(&data.name, &slur_regex)?;
   check_slurs_opt(&data.body, &slur_regex)?;
@@ -67,19 +69,43 @@ pub async fn update_post(
     Err(LemmyErrorType::NoPostEditAllowed)?
   }
 
+  let community = Community::read(&mut context.pool(), orig_post.community_id).await?;
+  let gallery = post_gallery(&data.gallery, &community, &context).await?;
+  let new_url = data.url.as_ref().map(clean_url_params).map(Into::into);
+  let gallery = match gallery {
+    Some(gallery) => Some(gallery),
+    // Changing only the url, for example from a client without gallery support, removes the gallery
+    None if new_url != orig_post.url => Some(vec![]),
+    None => None,
+  };
+
+  // The first gallery image is also the post url, for clients without gallery support
+  let (data_url, alt_text) = match gallery.as_ref().and_then(|g| g.first()) {
+    Some((url, alt_text)) => (Some(url), alt_text.clone()),
+    None => {
+      // Keep the previous alt text, unless the image changed
+      let alt_text = match &data.alt_text {
+        Some(alt_text) => Some(alt_text.clone()),
+        None if new_url == orig_post.url => orig_post.alt_text.clone(),
+        None => None,
+      };
+      let alt_text = post_alt_text(alt_text, data.url.as_ref(), &community, &context).await?;
+      (data.url.as_ref(), alt_text)
+    }
+  };
+
+  // TODO No good way to handle a clear.
+  // Issue link: https://github.com/LemmyNet/lemmy/issues/2287
+  let url = Some(data_url.map(clean_url_params).map(Into::into));
+
   // Fetch post links and Pictrs cached image
-  let data_url = data.url.as_ref();
-  let (metadata_res, thumbnail_url) =
-    fetch_site_data(context.client(), context.settings(), data_url, true).await;
-  let (embed_title, embed_description, embed_video_url) = metadata_res
-    .map(|u| (Some(u.title), Some(u.description), Some(u.embed_video_url)))
-    .unwrap_or_default();
+  let (metadata_res, thumbnail_url) = fetch_site_data(&context, data_url, true).await;
+  let url_changed = new_url != orig_post.url;
+  let metadata_form = site_metadata_post_form(metadata_res, thumbnail_url, url_changed);
 
   let name = sanitize_html_api_opt(&data.name);
   let body = sanitize_html_api_opt(&data.body);
   let body = diesel_option_overwrite(body);
-  let embed_title = embed_title.map(|e| sanitize_html_api_opt(&e));
-  let embed_description = embed_description.map(|e| sanitize_html_api_opt(&e));
 
   let language_id = data.language_id;
   CommunityLanguage::is_allowed_community_language(
@@ -94,13 +120,10 @@ pub async fn update_post(
     url,
     body,
     nsfw: data.nsfw,
-    embed_title,
-    embed_description,
-    embed_video_url,
     language_id: data.language_id,
-    thumbnail_url: Some(thumbnail_url),
+    alt_text: Some(alt_text),
     updated: Some(Some(naive_now())),
-    ..Default::default()
+    ..metadata_form
   };
 
   let post_id = data.post_id;
@@ -108,6 +131,13 @@ pub async fn update_post(
     .await
     .with_lemmy_type(LemmyErrorType::CouldntUpdatePost)?;
 
+  if let Some(gallery) = gallery {
+    let images = gallery.into_iter().map(|(u, a)| (u.into(), a)).collect();
+    PostGalleryImage::replace(&mut context.pool(), post_id, images)
+      .await
+      .with_lemmy_type(LemmyErrorType::CouldntUpdatePost)?;
+  }
+
   ActivityChannel::submit_activity(SendActivityData::UpdatePost(updated_post), &context).await?;
 
   build_post_response(
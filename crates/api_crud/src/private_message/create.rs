@@ -2,29 +2,42 @@ use activitypub_federation::config::Data;
 use actix_web::web::Json;
 use lemmy_api_common::{
   context::LemmyContext,
+  image_proxy::ProxyImages,
   private_message::{CreatePrivateMessage, PrivateMessageResponse},
+  reply_by_email::ReplyTarget,
   send_activity::{ActivityChannel, SendActivityData},
   utils::{
+    build_email,
     check_person_block,
     generate_local_apub_endpoint,
-    get_interface_language,
+    get_notification_preference,
     local_site_to_slur_regex,
+    read_or_create_conversation,
     sanitize_html_api,
     send_email_to_user,
     EndpointType,
+    ENCRYPTED_MESSAGE_NOTICE,
   },
+  web_push::{send_push_notification, PushNotification},
 };
 use lemmy_db_schema::{
   source::{
+    conversation::ConversationParticipant,
     local_site::LocalSite,
+    person_device_key::PersonDeviceKey,
     private_message::{PrivateMessage, PrivateMessageInsertForm, PrivateMessageUpdateForm},
   },
   traits::Crud,
+  EmailTemplateType,
+  NotificationType,
 };
 use lemmy_db_views::structs::{LocalUserView, PrivateMessageView};
 use lemmy_utils::{
   error::{LemmyError, LemmyErrorExt, LemmyErrorType},
-  utils::{slurs::remove_slurs, validation::is_valid_body_field},
+  utils::{
+    slurs::remove_slurs,
+    validation::{check_conversation_size, is_valid_body_field, is_valid_encrypted_content},
+  },
 };
 
 #[tracing::instrument(skip(context))]
@@ -35,21 +48,74 @@ pub async fn create_private_message(
 ) -> Result<Json<PrivateMessageResponse>, LemmyError> {
   let local_site = LocalSite::read(&mut context.pool()).await?;
 
-  let content = sanitize_html_api(&data.content);
-  let content = remove_slurs(&content, &local_site_to_slur_regex(&local_site));
-  is_valid_body_field(&Some(content.clone()), false)?;
+  // Encrypted content can't be sanitized or checked for slurs by the server
+  let encrypted = data.encrypted.unwrap_or(false);
+  let content = if encrypted {
+    is_valid_encrypted_content(&data.content)?;
+    data.content.clone()
+  } else {
+    let content = sanitize_html_api(&data.content);
+    let content = remove_slurs(&content, &local_site_to_slur_regex(&local_site));
+    is_valid_body_field(&Some(content.clone()), false)?;
+    content
+  };
+
+  let creator_id = local_user_view.person.id;
+  let mut recipient_ids = vec![data.recipient_id];
+  for recipient_id in data.additional_recipient_ids.iter().flatten() {
+    if *recipient_id != creator_id && !recipient_ids.contains(recipient_id) {
+      recipient_ids.push(*recipient_id);
+    }
+  }
+  check_conversation_size(recipient_ids.len())?;
+  if encrypted
+    && PersonDeviceKey::count_persons_without_keys(&mut context.pool(), &recipient_ids).await? > 0
+  {
+    Err(LemmyErrorType::RecipientDoesNotSupportEncryption)?
+  }
 
-  check_person_block(
-    local_user_view.person.id,
-    data.recipient_id,
+  let mut local_recipients = vec![];
+  for recipient_id in &recipient_ids {
+    check_person_block(creator_id, *recipient_id, &mut context.pool()).await?;
+    if let Ok(local_recipient) =
+      LocalUserView::read_person(&mut context.pool(), *recipient_id).await
+    {
+      let preference = get_notification_preference(
+        &local_recipient,
+        creator_id,
+        NotificationType::PrivateMessage,
+        &mut context.pool(),
+      )
+      .await?;
+      local_recipients.push((local_recipient, preference));
+    }
+  }
+
+  let mut participant_ids = recipient_ids.clone();
+  participant_ids.push(creator_id);
+  let recipients = local_recipients.iter().map(|(r, _)| r).collect::<Vec<_>>();
+  let conversation = read_or_create_conversation(
+    &local_user_view.person,
+    local_user_view.local_user.admin,
+    &participant_ids,
+    &recipients,
     &mut context.pool(),
   )
   .await?;
 
+  // Without in-app notifications the message doesn't show up as unread. The read state is only
+  // stored for the first recipient.
+  let read = local_recipients
+    .iter()
+    .find(|(r, _)| r.person.id == data.recipient_id)
+    .map(|(_, preference)| !preference.as_ref().is_some_and(|p| p.in_app));
   let private_message_form = PrivateMessageInsertForm::builder()
     .content(content.clone())
-    .creator_id(local_user_view.person.id)
+    .creator_id(creator_id)
     .recipient_id(data.recipient_id)
+    .conversation_id(conversation.id)
+    .read(Some(read.unwrap_or(false)))
+    .encrypted(Some(encrypted))
     .build();
 
   let inserted_private_message = PrivateMessage::create(&mut context.pool(), &private_message_form)
@@ -74,22 +140,57 @@ pub async fn create_private_message(
   .await
   .with_lemmy_type(LemmyErrorType::CouldntCreatePrivateMessage)?;
 
-  let view = PrivateMessageView::read(&mut context.pool(), inserted_private_message.id).await?;
+  let mut view = PrivateMessageView::read(&mut context.pool(), inserted_private_message.id).await?;
 
-  // Send email to the local recipient, if one exists
-  if view.recipient.local {
-    let recipient_id = data.recipient_id;
-    let local_recipient = LocalUserView::read_person(&mut context.pool(), recipient_id).await?;
-    let lang = get_interface_language(&local_recipient);
+  // Send email to the local recipients, unless they muted the conversation or it is a message
+  // request
+  let notification_content = if encrypted {
+    ENCRYPTED_MESSAGE_NOTICE
+  } else {
+    &content
+  };
+  // Replies by email would be stored unencrypted, so they are only offered for plain messages
+  let reply_target =
+    (!encrypted).then_some(ReplyTarget::PrivateMessage(inserted_private_message_id));
+  for (local_recipient, preference) in local_recipients {
+    let Some(preference) = preference else {
+      continue;
+    };
+    let participant = ConversationParticipant::read(
+      &mut context.pool(),
+      conversation.id,
+      local_recipient.person.id,
+    )
+    .await?;
+    if participant.muted || participant.message_request {
+      continue;
+    }
     let inbox_link = format!("{}/inbox", context.settings().get_protocol_and_hostname());
-    let sender_name = &local_user_view.person.name;
-    send_email_to_user(
+    let (subject, body) = build_email(
+      EmailTemplateType::PrivateMessage,
       &local_recipient,
-      &lang.notification_private_message_subject(sender_name),
-      &lang.notification_private_message_body(inbox_link, &content, sender_name),
+      &[
+        ("sender", &local_user_view.person.name),
+        ("content", notification_content),
+        ("link", &inbox_link),
+      ],
+      &mut context.pool(),
       context.settings(),
     )
     .await;
+    if preference.email {
+      send_email_to_user(&local_recipient, &subject, &body, reply_target, &context).await;
+    }
+    send_push_notification(
+      local_recipient.local_user.id,
+      PushNotification::new(
+        NotificationType::PrivateMessage,
+        &subject,
+        notification_content,
+        &inbox_link,
+      ),
+      &context,
+    );
   }
 
   ActivityChannel::submit_activity(
@@ -98,6 +199,8 @@ pub async fn create_private_message(
   )
   .await?;
 
+  view.proxy_images(&context);
+
   Ok(Json(PrivateMessageResponse {
     private_message_view: view,
   }))
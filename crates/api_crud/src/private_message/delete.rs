@@ -2,6 +2,7 @@ use activitypub_federation::config::Data;
 use actix_web::web::Json;
 use lemmy_api_common::{
   context::LemmyContext,
+  image_proxy::ProxyImages,
   private_message::{DeletePrivateMessage, PrivateMessageResponse},
   send_activity::{ActivityChannel, SendActivityData},
 };
@@ -45,7 +46,9 @@ pub async fn delete_private_message(
   )
   .await?;
 
-  let view = PrivateMessageView::read(&mut context.pool(), private_message_id).await?;
+  let mut view = PrivateMessageView::read(&mut context.pool(), private_message_id).await?;
+  view.proxy_images(&context);
+
   Ok(Json(PrivateMessageResponse {
     private_message_view: view,
   }))
@@ -1,6 +1,7 @@
 use actix_web::web::{Data, Json, Query};
 use lemmy_api_common::{
   context::LemmyContext,
+  image_proxy::ProxyImages,
   private_message::{GetPrivateMessages, PrivateMessagesResponse},
 };
 use lemmy_db_views::{private_message_view::PrivateMessageQuery, structs::LocalUserView};
@@ -18,22 +19,27 @@ pub async fn get_private_message(
   let limit = data.limit;
   let unread_only = data.unread_only.unwrap_or_default();
   let creator_id = data.creator_id;
+  let conversation_id = data.conversation_id;
+  let message_requests = data.message_requests.unwrap_or_default();
   let mut messages = PrivateMessageQuery {
     page,
     limit,
     unread_only,
     creator_id,
+    conversation_id,
+    message_requests,
   }
   .list(&mut context.pool(), person_id)
   .await?;
 
   // Messages sent by ourselves should be marked as read. The `read` column in database is only
-  // for the recipient, and shouldnt be exposed to sender.
+  // for the (first) recipient, and shouldnt be exposed to sender or other group members.
   messages.iter_mut().for_each(|pmv| {
-    if pmv.creator.id == person_id {
+    if pmv.creator.id == person_id || pmv.recipient.id != person_id {
       pmv.private_message.read = true
     }
   });
+  messages.proxy_images(&context);
 
   Ok(Json(PrivateMessagesResponse {
     private_messages: messages,
@@ -2,6 +2,7 @@ use activitypub_federation::config::Data;
 use actix_web::web::Json;
 use lemmy_api_common::{
   context::LemmyContext,
+  image_proxy::ProxyImages,
   private_message::{EditPrivateMessage, PrivateMessageResponse},
   send_activity::{ActivityChannel, SendActivityData},
   utils::{local_site_to_slur_regex, sanitize_html_api},
@@ -17,7 +18,10 @@ use lemmy_db_schema::{
 use lemmy_db_views::structs::{LocalUserView, PrivateMessageView};
 use lemmy_utils::{
   error::{LemmyError, LemmyErrorExt, LemmyErrorType},
-  utils::{slurs::remove_slurs, validation::is_valid_body_field},
+  utils::{
+    slurs::remove_slurs,
+    validation::{is_valid_body_field, is_valid_encrypted_content},
+  },
 };
 
 #[tracing::instrument(skip(context))]
@@ -35,10 +39,16 @@ pub async fn update_private_message(
     Err(LemmyErrorType::EditPrivateMessageNotAllowed)?
   }
 
-  // Doing the update
-  let content = sanitize_html_api(&data.content);
-  let content = remove_slurs(&content, &local_site_to_slur_regex(&local_site));
-  is_valid_body_field(&Some(content.clone()), false)?;
+  // Doing the update. Encrypted messages stay encrypted, so the new content must be too.
+  let content = if orig_private_message.encrypted {
+    is_valid_encrypted_content(&data.content)?;
+    data.content.clone()
+  } else {
+    let content = sanitize_html_api(&data.content);
+    let content = remove_slurs(&content, &local_site_to_slur_regex(&local_site));
+    is_valid_body_field(&Some(content.clone()), false)?;
+    content
+  };
 
   let private_message_id = data.private_message_id;
   PrivateMessage::update(
@@ -53,7 +63,7 @@ pub async fn update_private_message(
   .await
   .with_lemmy_type(LemmyErrorType::CouldntUpdatePrivateMessage)?;
 
-  let view = PrivateMessageView::read(&mut context.pool(), private_message_id).await?;
+  let mut view = PrivateMessageView::read(&mut context.pool(), private_message_id).await?;
 
   ActivityChannel::submit_activity(
     SendActivityData::UpdatePrivateMessage(view.clone()),
@@ -61,6 +71,8 @@ pub async fn update_private_message(
   )
   .await?;
 
+  view.proxy_images(&context);
+
   Ok(Json(PrivateMessageResponse {
     private_message_view: view,
   }))
@@ -0,0 +1,230 @@
+use crate::{comment::create::create_comment, private_message::create::create_private_message};
+use activitypub_federation::config::Data;
+use actix_web::{
+  http::header::AUTHORIZATION,
+  web::{Bytes, Json, Query},
+  HttpRequest,
+  HttpResponse,
+};
+use lemmy_api_common::{
+  comment::CreateComment,
+  context::LemmyContext,
+  private_message::CreatePrivateMessage,
+  reply_by_email::{parse_reply_address, strip_quoted_reply, ReplyTarget},
+};
+use lemmy_db_schema::{
+  newtypes::LocalUserId,
+  source::{
+    comment::Comment,
+    conversation::{Conversation, ConversationParticipant},
+    private_message::PrivateMessage,
+  },
+  traits::Crud,
+};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::{LemmyError, LemmyErrorExt, LemmyErrorType, LemmyResult};
+use mailparse::{addrparse, DispositionType, MailAddr, MailHeaderMap, ParsedMail};
+use serde::Deserialize;
+use subtle::ConstantTimeEq;
+use tracing::info;
+
+#[derive(Deserialize)]
+pub struct InboundEmailQuery {
+  /// Envelope recipient of the mail, if the mail server passes it on. Otherwise the recipient is
+  /// taken from the mail headers.
+  recipient: Option<String>,
+}
+
+/// Receives a raw RFC 822 message from the local mail server, which was sent in reply to a
+/// notification email. The reply is posted as comment or private message of the user for whom the
+/// reply address was generated.
+#[tracing::instrument(skip_all)]
+pub async fn receive_reply_email(
+  body: Bytes,
+  req: HttpRequest,
+  query: Query<InboundEmailQuery>,
+  context: Data<LemmyContext>,
+) -> Result<HttpResponse, LemmyError> {
+  check_inbound_secret(&req, &context)?;
+
+  let mail = mailparse::parse_mail(&body).with_lemmy_type(LemmyErrorType::InvalidInboundEmail)?;
+  // Ignore vacation notices and other automatic responses
+  let auto_submitted = mail.headers.get_first_value("Auto-Submitted");
+  if auto_submitted.is_some_and(|a| !a.trim().eq_ignore_ascii_case("no")) {
+    info!("Ignoring automatically submitted reply email");
+    return Ok(HttpResponse::Ok().finish());
+  }
+
+  let (target, local_user_id) = reply_target(&mail, query.recipient.as_deref(), &context)?;
+  let local_user_view = LocalUserView::read(&mut context.pool(), local_user_id)
+    .await
+    .with_lemmy_type(LemmyErrorType::InvalidReplyAddress)?;
+
+  // The reply address is only valid when used from the mailbox it was sent to
+  let sender = mail
+    .headers
+    .get_first_value("From")
+    .and_then(|f| mail_addresses(&f).into_iter().next())
+    .ok_or(LemmyErrorType::InvalidInboundEmail)?;
+  let user_email = local_user_view
+    .local_user
+    .email
+    .as_deref()
+    .unwrap_or_default();
+  if !sender.eq_ignore_ascii_case(user_email) {
+    Err(LemmyErrorType::InvalidReplyAddress)?
+  }
+
+  let content = plain_text_body(&mail)
+    .map(|text| strip_quoted_reply(&text))
+    .filter(|text| !text.is_empty())
+    .ok_or(LemmyErrorType::InvalidInboundEmail)?;
+
+  match target {
+    ReplyTarget::Comment(comment_id) => {
+      let comment = Comment::read(&mut context.pool(), comment_id).await?;
+      let data = CreateComment {
+        content,
+        post_id: comment.post_id,
+        parent_id: Some(comment_id),
+        language_id: None,
+      };
+      create_comment(Json(data), context, local_user_view).await?;
+    }
+    ReplyTarget::PrivateMessage(private_message_id) => {
+      let private_message = PrivateMessage::read(&mut context.pool(), private_message_id).await?;
+      let conversation =
+        Conversation::read(&mut context.pool(), private_message.conversation_id).await?;
+      let person_id = local_user_view.person.id;
+      // Also fails if the user has left the conversation in the meantime
+      ConversationParticipant::read(&mut context.pool(), conversation.id, person_id)
+        .await
+        .with_lemmy_type(LemmyErrorType::InvalidReplyAddress)?;
+      let mut recipient_ids: Vec<_> =
+        ConversationParticipant::list_persons(&mut context.pool(), conversation.id)
+          .await?
+          .into_iter()
+          .map(|p| p.id)
+          .filter(|id| id != &person_id)
+          .collect();
+      // Answer the sender directly, and everyone else in the conversation as further recipients
+      recipient_ids.retain(|id| id != &private_message.creator_id);
+      let recipient_id = if private_message.creator_id == person_id {
+        recipient_ids
+          .pop()
+          .ok_or(LemmyErrorType::InvalidReplyAddress)?
+      } else {
+        private_message.creator_id
+      };
+      let data = CreatePrivateMessage {
+        content,
+        recipient_id,
+        additional_recipient_ids: Some(recipient_ids),
+        encrypted: None,
+      };
+      create_private_message(Json(data), context, local_user_view).await?;
+    }
+  }
+  Ok(HttpResponse::Ok().finish())
+}
+
+/// The mail server authenticates with the configured secret as bearer token.
+fn check_inbound_secret(req: &HttpRequest, context: &LemmyContext) -> LemmyResult<()> {
+  let secret = context
+    .settings()
+    .email
+    .as_ref()
+    .and_then(|e| e.reply_by_email.inbound_secret.as_ref())
+    .ok_or(LemmyErrorType::ReplyByEmailDisabled)?;
+  let token = req
+    .headers()
+    .get(AUTHORIZATION)
+    .and_then(|h| h.to_str().ok())
+    .and_then(|h| h.strip_prefix("Bearer "));
+  let token = token.unwrap_or_default().as_bytes();
+  if !bool::from(token.ct_eq(secret.as_bytes())) {
+    Err(LemmyErrorType::IncorrectLogin)?
+  }
+  Ok(())
+}
+
+/// Finds the reply address among the recipients of the mail and verifies it.
+fn reply_target(
+  mail: &ParsedMail,
+  envelope_recipient: Option<&str>,
+  context: &LemmyContext,
+) -> LemmyResult<(ReplyTarget, LocalUserId)> {
+  let mut candidates: Vec<String> = envelope_recipient
+    .map(ToString::to_string)
+    .into_iter()
+    .collect();
+  for header in ["Delivered-To", "To", "Cc"] {
+    for value in mail.headers.get_all_values(header) {
+      candidates.extend(mail_addresses(&value));
+    }
+  }
+  let target = candidates
+    .iter()
+    .find_map(|address| parse_reply_address(address, context).ok())
+    .ok_or(LemmyErrorType::InvalidReplyAddress)?;
+  Ok(target)
+}
+
+fn mail_addresses(header: &str) -> Vec<String> {
+  let Ok(list) = addrparse(header) else {
+    return vec![];
+  };
+  list
+    .iter()
+    .flat_map(|addr| match addr {
+      MailAddr::Single(single) => vec![single.addr.clone()],
+      MailAddr::Group(group) => group.addrs.iter().map(|s| s.addr.clone()).collect(),
+    })
+    .collect()
+}
+
+/// Returns the first plain text part of the mail which isn't an attachment.
+fn plain_text_body(mail: &ParsedMail) -> Option<String> {
+  if mail.get_content_disposition().disposition == DispositionType::Attachment {
+    return None;
+  }
+  if mail.subparts.is_empty() {
+    return (mail.ctype.mimetype == "text/plain")
+      .then(|| mail.get_body().ok())
+      .flatten();
+  }
+  mail.subparts.iter().find_map(plain_text_body)
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+
+  use super::*;
+
+  #[test]
+  fn test_plain_text_body() {
+    let raw = b"From: Alice <alice@example.com>\r\n\
+      To: reply+c1-2-abc@reply.example.com\r\n\
+      Content-Type: multipart/alternative; boundary=\"b\"\r\n\
+      \r\n\
+      --b\r\n\
+      Content-Type: text/plain; charset=utf-8\r\n\
+      \r\n\
+      My reply\r\n\
+      \r\n\
+      On Mon, Oct 9, 2023 at 10:00 AM Lemmy wrote:\r\n\
+      > quoted\r\n\
+      --b\r\n\
+      Content-Type: text/html; charset=utf-8\r\n\
+      \r\n\
+      <p>My reply</p>\r\n\
+      --b--\r\n";
+    let mail = mailparse::parse_mail(raw).unwrap();
+    let body = plain_text_body(&mail).unwrap();
+    assert_eq!("My reply", strip_quoted_reply(&body));
+
+    let from = mail.headers.get_first_value("From").unwrap();
+    assert_eq!(vec!["alice@example.com".to_string()], mail_addresses(&from));
+  }
+}
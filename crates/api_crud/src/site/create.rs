@@ -3,6 +3,7 @@ use activitypub_federation::http_signatures::generate_actor_keypair;
 use actix_web::web::{Data, Json};
 use lemmy_api_common::{
   context::LemmyContext,
+  image_proxy::ProxyImages,
   site::{CreateSite, SiteResponse},
   utils::{
     generate_site_inbox_url,
@@ -125,7 +126,7 @@ pub async fn create_site(
 
   LocalSiteRateLimit::update(&mut context.pool(), &local_site_rate_limit_form).await?;
 
-  let site_view = SiteView::read_local(&mut context.pool()).await?;
+  let mut site_view = SiteView::read_local(&mut context.pool()).await?;
 
   let new_taglines = data.taglines.clone();
   let taglines = Tagline::replace(&mut context.pool(), local_site.id, new_taglines).await?;
@@ -137,6 +138,8 @@ pub async fn create_site(
     .send(rate_limit_config)
     .await?;
 
+  site_view.proxy_images(&context);
+
   Ok(Json(SiteResponse {
     site_view,
     taglines,
@@ -531,6 +534,7 @@ mod tests {
       updated: None,
       registration_mode: site_registration_mode,
       reports_email_admins: false,
+      federate_person_blocks: false,
     }
   }
 
@@ -1,6 +1,7 @@
 use actix_web::web::{Data, Json};
 use lemmy_api_common::{
   context::LemmyContext,
+  image_proxy::ProxyImages,
   site::{GetSiteResponse, MyUserInfo},
 };
 use lemmy_db_schema::source::{
@@ -27,12 +28,12 @@ pub async fn get_site(
   context: Data<LemmyContext>,
   local_user_view: Option<LocalUserView>,
 ) -> Result<Json<GetSiteResponse>, LemmyError> {
-  let site_view = SiteView::read_local(&mut context.pool()).await?;
+  let mut site_view = SiteView::read_local(&mut context.pool()).await?;
 
-  let admins = PersonView::admins(&mut context.pool()).await?;
+  let mut admins = PersonView::admins(&mut context.pool()).await?;
 
   // Build the local user
-  let my_user = if let Some(local_user_view) = local_user_view {
+  let mut my_user = if let Some(local_user_view) = local_user_view {
     let person_id = local_user_view.person.id;
     let local_user_id = local_user_view.local_user.id;
 
@@ -81,6 +82,10 @@ pub async fn get_site(
   let custom_emojis =
     CustomEmojiView::get_all(&mut context.pool(), site_view.local_site.id).await?;
 
+  site_view.proxy_images(&context);
+  admins.proxy_images(&context);
+  my_user.proxy_images(&context);
+
   Ok(Json(GetSiteResponse {
     site_view,
     admins,
@@ -2,6 +2,7 @@ use crate::site::{application_question_check, site_default_post_listing_type_che
 use actix_web::web::{Data, Json};
 use lemmy_api_common::{
   context::LemmyContext,
+  image_proxy::ProxyImages,
   site::{EditSite, SiteResponse},
   utils::{is_admin, local_site_rate_limit_to_rate_limit_config, sanitize_html_api_opt},
 };
@@ -98,6 +99,7 @@ pub async fn update_site(
     captcha_enabled: data.captcha_enabled,
     captcha_difficulty: data.captcha_difficulty.clone(),
     reports_email_admins: data.reports_email_admins,
+    federate_person_blocks: data.federate_person_blocks,
     ..Default::default()
   };
 
@@ -161,7 +163,7 @@ pub async fn update_site(
   let new_taglines = data.taglines.clone();
   let taglines = Tagline::replace(&mut context.pool(), local_site.id, new_taglines).await?;
 
-  let site_view = SiteView::read_local(&mut context.pool()).await?;
+  let mut site_view = SiteView::read_local(&mut context.pool()).await?;
 
   let rate_limit_config =
     local_site_rate_limit_to_rate_limit_config(&site_view.local_site_rate_limit);
@@ -170,6 +172,8 @@ pub async fn update_site(
     .send(rate_limit_config)
     .await?;
 
+  site_view.proxy_images(&context);
+
   Ok(Json(SiteResponse {
     site_view,
     taglines,
@@ -525,6 +529,7 @@ mod tests {
       updated: None,
       registration_mode: site_registration_mode,
       reports_email_admins: false,
+      federate_person_blocks: false,
     }
   }
 
@@ -583,6 +588,7 @@ mod tests {
       taglines: None,
       registration_mode: site_registration_mode,
       reports_email_admins: None,
+      federate_person_blocks: None,
     }
   }
 }
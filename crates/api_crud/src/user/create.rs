@@ -13,6 +13,7 @@ use lemmy_api_common::{
     sanitize_html_api,
     sanitize_html_api_opt,
     send_new_applicant_email_to_admins,
+    send_new_applicant_push_notifications,
     send_verification_email,
     EndpointType,
   },
@@ -156,6 +157,7 @@ pub async fn register(
     };
 
     RegistrationApplication::create(&mut context.pool(), &form).await?;
+    send_new_applicant_push_notifications(&data.username, &form.answer, &context).await?;
   }
 
   // Email the admins
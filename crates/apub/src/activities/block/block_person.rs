@@ -0,0 +1,95 @@
+use crate::{
+  activities::{generate_activity_id, send_lemmy_activity, verify_person},
+  insert_received_activity,
+  objects::person::ApubPerson,
+  protocol::activities::block::block_person::BlockPerson,
+};
+use activitypub_federation::{
+  config::Data,
+  kinds::activity::BlockType,
+  protocol::verification::{verify_domains_match, verify_urls_match},
+  traits::{ActivityHandler, Actor},
+};
+use lemmy_api_common::context::LemmyContext;
+use lemmy_db_schema::{
+  source::{
+    activity::ActivitySendTargets,
+    person_block::{PersonBlock, PersonBlockForm},
+  },
+  traits::Blockable,
+};
+use lemmy_utils::error::LemmyError;
+use url::Url;
+
+impl BlockPerson {
+  pub(in crate::activities::block) fn new(
+    actor: &ApubPerson,
+    target: &ApubPerson,
+    context: &Data<LemmyContext>,
+  ) -> Result<BlockPerson, LemmyError> {
+    Ok(BlockPerson {
+      actor: actor.id().into(),
+      to: Some([target.id().into()]),
+      object: target.id().into(),
+      kind: BlockType::Block,
+      id: generate_activity_id(
+        BlockType::Block,
+        &context.settings().get_protocol_and_hostname(),
+      )?,
+      target: None,
+    })
+  }
+
+  #[tracing::instrument(skip_all)]
+  pub async fn send(
+    actor: &ApubPerson,
+    target: &ApubPerson,
+    context: &Data<LemmyContext>,
+  ) -> Result<(), LemmyError> {
+    let block = BlockPerson::new(actor, target, context)?;
+    let inbox = ActivitySendTargets::to_inbox(target.shared_inbox_or_inbox());
+    send_lemmy_activity(context, block, actor, inbox, true).await
+  }
+}
+
+/// A remote user blocked one of our users. This is stored as a normal person block, so that the
+/// local user can't send private messages to them anymore.
+#[async_trait::async_trait]
+impl ActivityHandler for BlockPerson {
+  type DataType = LemmyContext;
+  type Error = LemmyError;
+
+  fn id(&self) -> &Url {
+    &self.id
+  }
+
+  fn actor(&self) -> &Url {
+    self.actor.inner()
+  }
+
+  #[tracing::instrument(skip_all)]
+  async fn verify(&self, context: &Data<LemmyContext>) -> Result<(), LemmyError> {
+    insert_received_activity(&self.id, context).await?;
+    verify_domains_match(self.actor.inner(), &self.id)?;
+    verify_person(&self.actor, context).await?;
+    if let Some(to) = &self.to {
+      verify_urls_match(to[0].inner(), self.object.inner())?;
+    }
+    Ok(())
+  }
+
+  #[tracing::instrument(skip_all)]
+  async fn receive(self, context: &Data<LemmyContext>) -> Result<(), LemmyError> {
+    let actor = self.actor.dereference(context).await?;
+    let target = self.object.dereference(context).await?;
+    // Blocks between remote users are none of our business
+    if target.local {
+      let form = PersonBlockForm {
+        person_id: actor.id,
+        target_id: target.id,
+      };
+      PersonBlock::block(&mut context.pool(), &form).await?;
+    }
+    Ok(())
+  }
+}
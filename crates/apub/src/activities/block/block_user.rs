@@ -23,7 +23,12 @@ use anyhow::anyhow;
 use chrono::{DateTime, Utc};
 use lemmy_api_common::{
   context::LemmyContext,
-  utils::{remove_user_data, remove_user_data_in_community, sanitize_html_federation_opt},
+  utils::{
+    remove_user_data,
+    remove_user_data_in_community,
+    sanitize_html_federation_opt,
+    send_mod_action_notification,
+  },
 };
 use lemmy_db_schema::{
   source::{
@@ -34,12 +39,15 @@ use lemmy_db_schema::{
       CommunityPersonBan,
       CommunityPersonBanForm,
     },
+    mod_action_notification::ModActionNotificationInsertForm,
     moderator::{ModBan, ModBanForm, ModBanFromCommunity, ModBanFromCommunityForm},
     person::{Person, PersonUpdateForm},
   },
   traits::{Bannable, Crud, Followable},
+  ModActionType,
 };
 use lemmy_utils::error::LemmyError;
+use tracing::warn;
 use url::Url;
 
 impl BlockUser {
@@ -212,6 +220,21 @@ impl ActivityHandler for BlockUser {
           expires,
         };
         ModBanFromCommunity::create(&mut context.pool(), &form).await?;
+        send_mod_action_notification(
+          ModActionNotificationInsertForm {
+            recipient_id: blocked_person.id,
+            mod_person_id: mod_person.id,
+            action: ModActionType::BanFromCommunity,
+            community_id: community.id,
+            post_id: None,
+            comment_id: None,
+            reason: form.reason,
+          },
+          context,
+        )
+        .await
+        .map_err(|e| warn!("Failed to send mod action notification: {e}"))
+        .ok();
       }
     }
 
@@ -1,7 +1,12 @@
 use crate::{
-  objects::{community::ApubCommunity, instance::ApubSite},
+  objects::{community::ApubCommunity, instance::ApubSite, person::ApubPerson},
   protocol::{
-    activities::block::{block_user::BlockUser, undo_block_user::UndoBlockUser},
+    activities::block::{
+      block_person::BlockPerson,
+      block_user::BlockUser,
+      undo_block_person::UndoBlockPerson,
+      undo_block_user::UndoBlockUser,
+    },
     objects::{group::Group, instance::Instance},
   },
 };
@@ -14,7 +19,7 @@ use chrono::{DateTime, Utc};
 use lemmy_api_common::{community::BanFromCommunity, context::LemmyContext, person::BanPerson};
 use lemmy_db_schema::{
   newtypes::CommunityId,
-  source::{community::Community, person::Person, site::Site},
+  source::{community::Community, local_site::LocalSite, person::Person, site::Site},
   traits::Crud,
   utils::DbPool,
 };
@@ -26,7 +31,9 @@ use lemmy_utils::{
 use serde::Deserialize;
 use url::Url;
 
+pub mod block_person;
 pub mod block_user;
+pub mod undo_block_person;
 pub mod undo_block_user;
 
 #[derive(Clone, Debug)]
@@ -201,3 +208,24 @@ pub(crate) async fn send_ban_from_community(
     .await
   }
 }
+
+/// Lets the instance of a remote user know that a local user has blocked them, if enabled by the
+/// admin.
+pub(crate) async fn send_block_person(
+  actor: Person,
+  target: Person,
+  block: bool,
+  context: Data<LemmyContext>,
+) -> LemmyResult<()> {
+  let local_site = LocalSite::read(&mut context.pool()).await?;
+  if target.local || !local_site.federate_person_blocks {
+    return Ok(());
+  }
+  let actor: ApubPerson = actor.into();
+  let target: ApubPerson = target.into();
+  if block {
+    BlockPerson::send(&actor, &target, &context).await
+  } else {
+    UndoBlockPerson::send(&actor, &target, &context).await
+  }
+}
@@ -0,0 +1,84 @@
+use crate::{
+  activities::{generate_activity_id, send_lemmy_activity, verify_person},
+  insert_received_activity,
+  objects::person::ApubPerson,
+  protocol::activities::block::{block_person::BlockPerson, undo_block_person::UndoBlockPerson},
+};
+use activitypub_federation::{
+  config::Data,
+  kinds::activity::UndoType,
+  protocol::verification::{verify_domains_match, verify_urls_match},
+  traits::{ActivityHandler, Actor},
+};
+use lemmy_api_common::context::LemmyContext;
+use lemmy_db_schema::{
+  source::{
+    activity::ActivitySendTargets,
+    person_block::{PersonBlock, PersonBlockForm},
+  },
+  traits::Blockable,
+};
+use lemmy_utils::error::LemmyError;
+use url::Url;
+
+impl UndoBlockPerson {
+  #[tracing::instrument(skip_all)]
+  pub async fn send(
+    actor: &ApubPerson,
+    target: &ApubPerson,
+    context: &Data<LemmyContext>,
+  ) -> Result<(), LemmyError> {
+    let undo = UndoBlockPerson {
+      actor: actor.id().into(),
+      to: Some([target.id().into()]),
+      object: BlockPerson::new(actor, target, context)?,
+      kind: UndoType::Undo,
+      id: generate_activity_id(
+        UndoType::Undo,
+        &context.settings().get_protocol_and_hostname(),
+      )?,
+    };
+    let inbox = ActivitySendTargets::to_inbox(target.shared_inbox_or_inbox());
+    send_lemmy_activity(context, undo, actor, inbox, true).await
+  }
+}
+
+#[async_trait::async_trait]
+impl ActivityHandler for UndoBlockPerson {
+  type DataType = LemmyContext;
+  type Error = LemmyError;
+
+  fn id(&self) -> &Url {
+    &self.id
+  }
+
+  fn actor(&self) -> &Url {
+    self.actor.inner()
+  }
+
+  #[tracing::instrument(skip_all)]
+  async fn verify(&self, context: &Data<LemmyContext>) -> Result<(), LemmyError> {
+    insert_received_activity(&self.id, context).await?;
+    verify_domains_match(self.actor.inner(), &self.id)?;
+    verify_urls_match(self.actor.inner(), self.object.actor.inner())?;
+    verify_person(&self.actor, context).await?;
+    if let Some(to) = &self.to {
+      verify_urls_match(to[0].inner(), self.object.object.inner())?;
+    }
+    Ok(())
+  }
+
+  #[tracing::instrument(skip_all)]
+  async fn receive(self, context: &Data<LemmyContext>) -> Result<(), LemmyError> {
+    let actor = self.actor.dereference(context).await?;
+    let target = self.object.object.dereference(context).await?;
+    if target.local {
+      let form = PersonBlockForm {
+        person_id: actor.id,
+        target_id: target.id,
+      };
+      PersonBlock::unblock(&mut context.pool(), &form).await?;
+    }
+    Ok(())
+  }
+}
@@ -17,17 +17,23 @@ use activitypub_federation::{
   protocol::verification::verify_domains_match,
   traits::{ActivityHandler, Actor},
 };
-use lemmy_api_common::{context::LemmyContext, utils::sanitize_html_federation_opt};
+use lemmy_api_common::{
+  context::LemmyContext,
+  utils::{sanitize_html_federation_opt, send_mod_action_notification},
+};
 use lemmy_db_schema::{
   source::{
     activity::ActivitySendTargets,
     community::{CommunityPersonBan, CommunityPersonBanForm},
+    mod_action_notification::ModActionNotificationInsertForm,
     moderator::{ModBan, ModBanForm, ModBanFromCommunity, ModBanFromCommunityForm},
     person::{Person, PersonUpdateForm},
   },
   traits::{Bannable, Crud},
+  ModActionType,
 };
 use lemmy_utils::error::LemmyError;
+use tracing::warn;
 use url::Url;
 
 impl UndoBlockUser {
@@ -142,6 +148,21 @@ impl ActivityHandler for UndoBlockUser {
           expires,
         };
         ModBanFromCommunity::create(&mut context.pool(), &form).await?;
+        send_mod_action_notification(
+          ModActionNotificationInsertForm {
+            recipient_id: blocked_person.id,
+            mod_person_id: mod_person.id,
+            action: ModActionType::UnbanFromCommunity,
+            community_id: community.id,
+            post_id: None,
+            comment_id: None,
+            reason: form.reason,
+          },
+          context,
+        )
+        .await
+        .map_err(|e| warn!("Failed to send mod action notification: {e}"))
+        .ok();
       }
     }
 
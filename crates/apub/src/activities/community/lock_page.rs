@@ -21,17 +21,20 @@ use activitypub_federation::{
   kinds::{activity::UndoType, public},
   traits::ActivityHandler,
 };
-use lemmy_api_common::context::LemmyContext;
+use lemmy_api_common::{context::LemmyContext, utils::send_mod_action_notification};
 use lemmy_db_schema::{
   source::{
     activity::ActivitySendTargets,
     community::Community,
+    mod_action_notification::ModActionNotificationInsertForm,
     person::Person,
     post::{Post, PostUpdateForm},
   },
   traits::Crud,
+  ModActionType,
 };
 use lemmy_utils::error::LemmyError;
+use tracing::warn;
 use url::Url;
 
 #[async_trait::async_trait]
@@ -63,6 +66,22 @@ impl ActivityHandler for LockPage {
     };
     let post = self.object.dereference(context).await?;
     Post::update(&mut context.pool(), post.id, &form).await?;
+    let actor = self.actor.dereference(context).await?;
+    send_mod_action_notification(
+      ModActionNotificationInsertForm {
+        recipient_id: post.creator_id,
+        mod_person_id: actor.id,
+        action: ModActionType::LockPost,
+        community_id: post.community_id,
+        post_id: Some(post.id),
+        comment_id: None,
+        reason: None,
+      },
+      context,
+    )
+    .await
+    .map_err(|e| warn!("Failed to send mod action notification: {e}"))
+    .ok();
     Ok(())
   }
 }
@@ -103,6 +122,22 @@ impl ActivityHandler for UndoLockPage {
     };
     let post = self.object.object.dereference(context).await?;
     Post::update(&mut context.pool(), post.id, &form).await?;
+    let actor = self.actor.dereference(context).await?;
+    send_mod_action_notification(
+      ModActionNotificationInsertForm {
+        recipient_id: post.creator_id,
+        mod_person_id: actor.id,
+        action: ModActionType::UnlockPost,
+        community_id: post.community_id,
+        post_id: Some(post.id),
+        comment_id: None,
+        reason: None,
+      },
+      context,
+    )
+    .await
+    .map_err(|e| warn!("Failed to send mod action notification: {e}"))
+    .ok();
     Ok(())
   }
 }
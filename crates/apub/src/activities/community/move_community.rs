@@ -0,0 +1,269 @@
+use crate::{
+  activities::{generate_activity_id, send_lemmy_activity},
+  insert_received_activity,
+  objects::{community::ApubCommunity, person::ApubPerson},
+  protocol::activities::{community::move_community::MoveCommunity, following::follow::Follow},
+};
+use activitypub_federation::{
+  config::Data,
+  kinds::activity::MoveType,
+  protocol::verification::verify_urls_match,
+  traits::{ActivityHandler, Actor},
+};
+use lemmy_api_common::context::LemmyContext;
+use lemmy_db_schema::{
+  source::{
+    activity::ActivitySendTargets,
+    community::{Community, CommunityFollower, CommunityFollowerForm, CommunityUpdateForm},
+  },
+  traits::{Crud, Followable},
+};
+use lemmy_db_views_actor::structs::CommunityFollowerView;
+use lemmy_utils::error::{LemmyError, LemmyErrorType};
+use tracing::warn;
+use url::Url;
+
+pub(crate) async fn send_move_community(
+  community: &ApubCommunity,
+  target: &ApubCommunity,
+  context: &Data<LemmyContext>,
+) -> Result<(), LemmyError> {
+  let id = generate_activity_id(
+    MoveType::Move,
+    &context.settings().get_protocol_and_hostname(),
+  )?;
+  let move_ = MoveCommunity {
+    actor: community.id().into(),
+    to: vec![community.followers_url.clone().into()],
+    object: community.id().into(),
+    target: target.id().into(),
+    kind: MoveType::Move,
+    id,
+  };
+  let inboxes = ActivitySendTargets::to_local_community_followers(community.id);
+  send_lemmy_activity(context, move_, community, inboxes, false).await
+}
+
+/// Unfollows the old community for all local followers, and follows the new community instead.
+pub(crate) async fn migrate_local_followers(
+  community: &ApubCommunity,
+  target: &ApubCommunity,
+  context: &Data<LemmyContext>,
+) -> Result<(), LemmyError> {
+  let followers =
+    CommunityFollowerView::get_community_local_followers(&mut context.pool(), community.id).await?;
+  for follower in followers {
+    let follower: ApubPerson = follower.into();
+    // One failed follow shouldn't keep the remaining followers in the old community
+    if let Err(e) = migrate_local_follower(&follower, community, target, context).await {
+      warn!(
+        "Failed to move follower {} to {}: {e}",
+        follower.actor_id, target.actor_id
+      );
+    }
+  }
+  Ok(())
+}
+
+/// Follows the new community for a single follower, and only then unfollows the old one.
+async fn migrate_local_follower(
+  follower: &ApubPerson,
+  community: &ApubCommunity,
+  target: &ApubCommunity,
+  context: &Data<LemmyContext>,
+) -> Result<(), LemmyError> {
+  if target.local {
+    let form = CommunityFollowerForm {
+      community_id: target.id,
+      person_id: follower.id,
+      pending: false,
+    };
+    CommunityFollower::follow(&mut context.pool(), &form).await?;
+  } else {
+    Follow::send(follower, target, context).await?;
+  }
+  let form = CommunityFollowerForm {
+    community_id: community.id,
+    person_id: follower.id,
+    pending: false,
+  };
+  CommunityFollower::unfollow(&mut context.pool(), &form).await?;
+  Ok(())
+}
+
+#[async_trait::async_trait]
+impl ActivityHandler for MoveCommunity {
+  type DataType = LemmyContext;
+  type Error = LemmyError;
+
+  fn id(&self) -> &Url {
+    &self.id
+  }
+
+  fn actor(&self) -> &Url {
+    self.actor.inner()
+  }
+
+  #[tracing::instrument(skip_all)]
+  async fn verify(&self, context: &Data<Self::DataType>) -> Result<(), LemmyError> {
+    insert_received_activity(&self.id, context).await?;
+    // only the community itself can announce that it has moved
+    verify_urls_match(self.actor.inner(), self.object.inner())?;
+    if self.object == self.target {
+      Err(LemmyErrorType::CantMoveCommunityToItself)?
+    }
+    // the new community has to confirm that it continues the old one
+    let target = self.target.dereference(context).await?;
+    if target.moved_from.as_ref().map(|m| m.inner()) != Some(self.object.inner()) {
+      Err(LemmyErrorType::CommunityMoveNotConfirmed)?
+    }
+    Ok(())
+  }
+
+  #[tracing::instrument(skip_all)]
+  async fn receive(self, context: &Data<Self::DataType>) -> Result<(), LemmyError> {
+    let community = self.object.dereference(context).await?;
+    let target = self.target.dereference(context).await?;
+
+    let form = CommunityUpdateForm {
+      moved_to: Some(Some(target.actor_id.clone())),
+      ..Default::default()
+    };
+    Community::update(&mut context.pool(), community.id, &form).await?;
+
+    migrate_local_followers(&community, &target, context).await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use super::*;
+  use crate::objects::tests::init_context;
+  use activitypub_federation::fetch::object_id::ObjectId;
+  use lemmy_db_schema::source::{
+    community::CommunityInsertForm,
+    instance::Instance,
+    person::{Person, PersonInsertForm},
+  };
+  use serial_test::serial;
+
+  async fn create_community(
+    context: &Data<LemmyContext>,
+    actor_id: &str,
+    local: bool,
+    moved_from: Option<&str>,
+  ) -> Community {
+    let actor_id = Url::parse(actor_id).unwrap();
+    let instance =
+      Instance::read_or_create(&mut context.pool(), actor_id.domain().unwrap().to_string())
+        .await
+        .unwrap();
+    let form = CommunityInsertForm::builder()
+      .name(
+        actor_id
+          .path_segments()
+          .unwrap()
+          .next_back()
+          .unwrap()
+          .to_string(),
+      )
+      .title("moved community".to_string())
+      .public_key("pubkey".to_string())
+      .actor_id(Some(actor_id.into()))
+      .local(Some(local))
+      .moved_from(moved_from.map(|m| Url::parse(m).unwrap().into()))
+      .instance_id(instance.id)
+      .build();
+    Community::create(&mut context.pool(), &form).await.unwrap()
+  }
+
+  fn move_activity(id: &str, object: &Community, target: &Community) -> MoveCommunity {
+    MoveCommunity {
+      actor: ObjectId::from(object.actor_id.clone()),
+      to: vec![],
+      object: ObjectId::from(object.actor_id.clone()),
+      target: ObjectId::from(target.actor_id.clone()),
+      kind: MoveType::Move,
+      id: Url::parse(&format!("https://old.tld/activities/move/{id}")).unwrap(),
+    }
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_receive_move_community() {
+    let context = init_context().await;
+    let old = create_community(&context, "https://old.tld/c/moving", false, None).await;
+    let target = create_community(
+      &context,
+      "https://example.com/c/moved",
+      true,
+      Some("https://old.tld/c/moving"),
+    )
+    .await;
+    let other = create_community(&context, "https://example.com/c/unrelated", true, None).await;
+
+    let person_form = PersonInsertForm::builder()
+      .name("move_follower".into())
+      .public_key("pubkey".to_string())
+      .instance_id(target.instance_id)
+      .build();
+    let follower = Person::create(&mut context.pool(), &person_form)
+      .await
+      .unwrap();
+    let follower_form = CommunityFollowerForm {
+      community_id: old.id,
+      person_id: follower.id,
+      pending: false,
+    };
+    CommunityFollower::follow(&mut context.pool(), &follower_form)
+      .await
+      .unwrap();
+
+    // the community can't move to itself, or to a community which wasn't imported from it
+    let to_itself = move_activity("1", &old, &old);
+    assert_eq!(
+      Some(LemmyErrorType::CantMoveCommunityToItself),
+      to_itself.verify(&context).await.err().map(|e| e.error_type)
+    );
+    let unconfirmed = move_activity("2", &old, &other);
+    assert_eq!(
+      Some(LemmyErrorType::CommunityMoveNotConfirmed),
+      unconfirmed
+        .verify(&context)
+        .await
+        .err()
+        .map(|e| e.error_type)
+    );
+
+    // a valid move switches the local followers over to the new community
+    let move_ = move_activity("3", &old, &target);
+    move_.verify(&context).await.unwrap();
+    move_.receive(&context).await.unwrap();
+
+    let old = Community::read(&mut context.pool(), old.id).await.unwrap();
+    assert_eq!(Some(target.actor_id.clone()), old.moved_to);
+    let old_followers =
+      CommunityFollowerView::get_community_local_followers(&mut context.pool(), old.id)
+        .await
+        .unwrap();
+    assert!(old_followers.is_empty());
+    let new_followers =
+      CommunityFollowerView::get_community_local_followers(&mut context.pool(), target.id)
+        .await
+        .unwrap();
+    assert_eq!(
+      vec![follower.id],
+      new_followers.iter().map(|p| p.id).collect::<Vec<_>>()
+    );
+
+    Instance::delete(&mut context.pool(), old.instance_id)
+      .await
+      .unwrap();
+    Instance::delete(&mut context.pool(), target.instance_id)
+      .await
+      .unwrap();
+  }
+}
@@ -11,7 +11,11 @@ use activitypub_federation::{
   kinds::activity::FlagType,
   traits::{ActivityHandler, Actor},
 };
-use lemmy_api_common::{context::LemmyContext, utils::sanitize_html_federation};
+use lemmy_api_common::{
+  context::LemmyContext,
+  live_events::{LiveEventKind, LiveEvents},
+  utils::sanitize_html_federation,
+};
 use lemmy_db_schema::{
   source::{
     activity::ActivitySendTargets,
@@ -105,6 +109,8 @@ impl ActivityHandler for Report {
         CommentReport::report(&mut context.pool(), &report_form).await?;
       }
     };
+    let community = self.community(context).await?;
+    LiveEvents::publish(LiveEventKind::NewReport(Some(community.id)));
     Ok(())
   }
 }
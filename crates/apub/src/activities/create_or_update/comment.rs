@@ -25,6 +25,7 @@ use activitypub_federation::{
 use lemmy_api_common::{
   build_response::send_local_notifs,
   context::LemmyContext,
+  live_events::{LiveEventKind, LiveEvents},
   utils::{check_post_deleted_or_removed, is_mod_or_admin},
 };
 use lemmy_db_schema::{
@@ -170,6 +171,9 @@ impl ActivityHandler for CreateOrUpdateNote {
     // TODO: for compatibility with other projects, it would be much better to read this from cc or tags
     let mentions = scrape_text_for_mentions(&comment.content);
     send_local_notifs(mentions, &comment.0, &actor, &post, do_send_email, context).await?;
+    if self.kind == CreateOrUpdateType::Create {
+      LiveEvents::publish(LiveEventKind::NewComment(post.id, comment.id));
+    }
     Ok(())
   }
 }
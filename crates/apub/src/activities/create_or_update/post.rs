@@ -21,7 +21,10 @@ use activitypub_federation::{
   protocol::verification::{verify_domains_match, verify_urls_match},
   traits::{ActivityHandler, Actor, Object},
 };
-use lemmy_api_common::context::LemmyContext;
+use lemmy_api_common::{
+  context::LemmyContext,
+  live_events::{LiveEventKind, LiveEvents},
+};
 use lemmy_db_schema::{
   aggregates::structs::PostAggregates,
   newtypes::PersonId,
@@ -153,6 +156,9 @@ impl ActivityHandler for CreateOrUpdatePage {
     // Calculate initial hot_rank for post
     PostAggregates::update_ranks(&mut context.pool(), post.id).await?;
 
+    if self.kind == CreateOrUpdateType::Create {
+      LiveEvents::publish(LiveEventKind::NewPost(post.community_id, post.id));
+    }
     Ok(())
   }
 }
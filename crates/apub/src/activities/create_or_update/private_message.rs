@@ -12,8 +12,15 @@ use activitypub_federation::{
   protocol::verification::verify_domains_match,
   traits::{ActivityHandler, Actor, Object},
 };
-use lemmy_api_common::context::LemmyContext;
-use lemmy_db_schema::source::activity::ActivitySendTargets;
+use anyhow::anyhow;
+use lemmy_api_common::{
+  context::LemmyContext,
+  utils::{check_limited_instance, check_person_block, check_person_instance_block},
+};
+use lemmy_db_schema::source::{
+  activity::ActivitySendTargets,
+  conversation::ConversationParticipant,
+};
 use lemmy_db_views::structs::PrivateMessageView;
 use lemmy_utils::error::LemmyError;
 use url::Url;
@@ -24,22 +31,31 @@ pub(crate) async fn send_create_or_update_pm(
   context: Data<LemmyContext>,
 ) -> Result<(), LemmyError> {
   let actor: ApubPerson = pm_view.creator.into();
-  let recipient: ApubPerson = pm_view.recipient.into();
 
   let id = generate_activity_id(
     kind.clone(),
     &context.settings().get_protocol_and_hostname(),
   )?;
+  let object = ApubPrivateMessage(pm_view.private_message.clone())
+    .into_json(&context)
+    .await?;
+  let recipients = ConversationParticipant::list_persons(
+    &mut context.pool(),
+    pm_view.private_message.conversation_id,
+  )
+  .await?
+  .into_iter()
+  .filter(|p| p.id != actor.id)
+  .map(ApubPerson::from);
+  let mut inbox = ActivitySendTargets::empty();
+  inbox.add_inboxes(recipients.map(|r| r.shared_inbox_or_inbox()));
   let create_or_update = CreateOrUpdateChatMessage {
     id: id.clone(),
     actor: actor.id().into(),
-    to: [recipient.id().into()],
-    object: ApubPrivateMessage(pm_view.private_message.clone())
-      .into_json(&context)
-      .await?,
+    to: object.to.clone(),
+    object,
     kind,
   };
-  let inbox = ActivitySendTargets::to_inbox(recipient.shared_inbox_or_inbox());
   send_lemmy_activity(&context, create_or_update, &actor, inbox, true).await
 }
 
@@ -61,8 +77,19 @@ impl ActivityHandler for CreateOrUpdateChatMessage {
     insert_received_activity(&self.id, context).await?;
     verify_person(&self.actor, context).await?;
     verify_domains_match(self.actor.inner(), self.object.id.inner())?;
-    verify_domains_match(self.to[0].inner(), self.object.to[0].inner())?;
+    if self.to != self.object.to {
+      Err(anyhow!("Activity and object recipients don't match"))?
+    }
     ApubPrivateMessage::verify(&self.object, self.actor.inner(), context).await?;
+
+    // Reject messages from persons or instances which a recipient has blocked
+    let actor = self.actor.dereference(context).await?;
+    for recipient in &self.to {
+      let recipient = recipient.dereference(context).await?;
+      check_person_block(actor.id, recipient.id, &mut context.pool()).await?;
+      check_person_instance_block(actor.instance_id, recipient.id, &mut context.pool()).await?;
+      check_limited_instance(&actor, recipient.id, &mut context.pool()).await?;
+    }
     Ok(())
   }
 
@@ -8,11 +8,15 @@ use crate::{
   protocol::{activities::deletion::delete::Delete, IdOrNestedObject},
 };
 use activitypub_federation::{config::Data, kinds::activity::DeleteType, traits::ActivityHandler};
-use lemmy_api_common::{context::LemmyContext, utils::sanitize_html_federation_opt};
+use lemmy_api_common::{
+  context::LemmyContext,
+  utils::{sanitize_html_federation_opt, send_mod_action_notification},
+};
 use lemmy_db_schema::{
   source::{
     comment::{Comment, CommentUpdateForm},
     community::{Community, CommunityUpdateForm},
+    mod_action_notification::ModActionNotificationInsertForm,
     moderator::{
       ModRemoveComment,
       ModRemoveCommentForm,
@@ -24,8 +28,10 @@ use lemmy_db_schema::{
     post::{Post, PostUpdateForm},
   },
   traits::Crud,
+  ModActionType,
 };
 use lemmy_utils::error::{LemmyError, LemmyErrorType};
+use tracing::warn;
 use url::Url;
 
 #[async_trait::async_trait]
@@ -135,7 +141,7 @@ pub(in crate::activities) async fn receive_remove_action(
         mod_person_id: actor.id,
         post_id: post.id,
         removed: Some(true),
-        reason,
+        reason: reason.clone(),
       };
       ModRemovePost::create(&mut context.pool(), &form).await?;
       Post::update(
@@ -147,15 +153,31 @@ pub(in crate::activities) async fn receive_remove_action(
         },
       )
       .await?;
+      send_mod_action_notification(
+        ModActionNotificationInsertForm {
+          recipient_id: post.creator_id,
+          mod_person_id: actor.id,
+          action: ModActionType::RemovePost,
+          community_id: post.community_id,
+          post_id: Some(post.id),
+          comment_id: None,
+          reason,
+        },
+        context,
+      )
+      .await
+      .map_err(|e| warn!("Failed to send mod action notification: {e}"))
+      .ok();
     }
     DeletableObjects::Comment(comment) => {
       let form = ModRemoveCommentForm {
         mod_person_id: actor.id,
         comment_id: comment.id,
         removed: Some(true),
-        reason,
+        reason: reason.clone(),
       };
       ModRemoveComment::create(&mut context.pool(), &form).await?;
+      let post = Post::read(&mut context.pool(), comment.post_id).await?;
       Comment::update(
         &mut context.pool(),
         comment.id,
@@ -165,6 +187,21 @@ pub(in crate::activities) async fn receive_remove_action(
         },
       )
       .await?;
+      send_mod_action_notification(
+        ModActionNotificationInsertForm {
+          recipient_id: comment.creator_id,
+          mod_person_id: actor.id,
+          action: ModActionType::RemoveComment,
+          community_id: post.community_id,
+          post_id: Some(post.id),
+          comment_id: Some(comment.id),
+          reason,
+        },
+        context,
+      )
+      .await
+      .map_err(|e| warn!("Failed to send mod action notification: {e}"))
+      .ok();
     }
     DeletableObjects::PrivateMessage(_) => unimplemented!(),
   }
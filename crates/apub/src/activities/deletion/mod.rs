@@ -34,6 +34,7 @@ use lemmy_db_schema::{
     activity::ActivitySendTargets,
     comment::{Comment, CommentUpdateForm},
     community::{Community, CommunityUpdateForm},
+    conversation::ConversationParticipant,
     person::Person,
     post::{Post, PostUpdateForm},
     private_message::{PrivateMessage, PrivateMessageUpdateForm},
@@ -123,8 +124,15 @@ pub(crate) async fn send_apub_delete_private_message(
     .await?
     .into();
 
+  let mut inbox = ActivitySendTargets::empty();
+  inbox.add_inboxes(
+    ConversationParticipant::list_persons(&mut context.pool(), pm.conversation_id)
+      .await?
+      .into_iter()
+      .filter(|p| p.id != actor.id)
+      .map(|p| ApubPerson::from(p).shared_inbox_or_inbox()),
+  );
   let deletable = DeletableObjects::PrivateMessage(pm.into());
-  let inbox = ActivitySendTargets::to_inbox(recipient.shared_inbox_or_inbox());
   if deleted {
     let delete: Delete = Delete::new(actor, deletable, recipient.id(), None, None, &context)?;
     send_lemmy_activity(&context, delete, actor, inbox, true).await?;
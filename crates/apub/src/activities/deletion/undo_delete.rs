@@ -8,11 +8,12 @@ use crate::{
   protocol::activities::deletion::{delete::Delete, undo_delete::UndoDelete},
 };
 use activitypub_federation::{config::Data, kinds::activity::UndoType, traits::ActivityHandler};
-use lemmy_api_common::context::LemmyContext;
+use lemmy_api_common::{context::LemmyContext, utils::send_mod_action_notification};
 use lemmy_db_schema::{
   source::{
     comment::{Comment, CommentUpdateForm},
     community::{Community, CommunityUpdateForm},
+    mod_action_notification::ModActionNotificationInsertForm,
     moderator::{
       ModRemoveComment,
       ModRemoveCommentForm,
@@ -24,8 +25,10 @@ use lemmy_db_schema::{
     post::{Post, PostUpdateForm},
   },
   traits::Crud,
+  ModActionType,
 };
 use lemmy_utils::error::{LemmyError, LemmyErrorType};
+use tracing::warn;
 use url::Url;
 
 #[async_trait::async_trait]
@@ -137,6 +140,21 @@ impl UndoDelete {
           },
         )
         .await?;
+        send_mod_action_notification(
+          ModActionNotificationInsertForm {
+            recipient_id: post.creator_id,
+            mod_person_id: actor.id,
+            action: ModActionType::RestorePost,
+            community_id: post.community_id,
+            post_id: Some(post.id),
+            comment_id: None,
+            reason: None,
+          },
+          context,
+        )
+        .await
+        .map_err(|e| warn!("Failed to send mod action notification: {e}"))
+        .ok();
       }
       DeletableObjects::Comment(comment) => {
         let form = ModRemoveCommentForm {
@@ -146,6 +164,7 @@ impl UndoDelete {
           reason: None,
         };
         ModRemoveComment::create(&mut context.pool(), &form).await?;
+        let post = Post::read(&mut context.pool(), comment.post_id).await?;
         Comment::update(
           &mut context.pool(),
           comment.id,
@@ -155,6 +174,21 @@ impl UndoDelete {
           },
         )
         .await?;
+        send_mod_action_notification(
+          ModActionNotificationInsertForm {
+            recipient_id: comment.creator_id,
+            mod_person_id: actor.id,
+            action: ModActionType::RestoreComment,
+            community_id: post.community_id,
+            post_id: Some(post.id),
+            comment_id: Some(comment.id),
+            reason: None,
+          },
+          context,
+        )
+        .await
+        .map_err(|e| warn!("Failed to send mod action notification: {e}"))
+        .ok();
       }
       DeletableObjects::PrivateMessage(_) => unimplemented!(),
     }
@@ -1,5 +1,6 @@
 use crate::{
   activities::{generate_activity_id, send_lemmy_activity},
+  fetcher::backfill::spawn_backfill_community,
   insert_received_activity,
   protocol::activities::following::{accept::AcceptFollow, follow::Follow},
 };
@@ -10,10 +11,7 @@ use activitypub_federation::{
   traits::{ActivityHandler, Actor},
 };
 use lemmy_api_common::context::LemmyContext;
-use lemmy_db_schema::{
-  source::{activity::ActivitySendTargets, community::CommunityFollower},
-  traits::Followable,
-};
+use lemmy_db_schema::source::{activity::ActivitySendTargets, community::CommunityFollower};
 use lemmy_utils::error::LemmyError;
 use url::Url;
 
@@ -67,9 +65,14 @@ impl ActivityHandler for AcceptFollow {
     let community = self.actor.dereference(context).await?;
     let person = self.object.actor.dereference(context).await?;
     // This will throw an error if no follow was requested
-    let community_id = community.id;
-    let person_id = person.id;
-    CommunityFollower::follow_accepted(&mut context.pool(), community_id, person_id).await?;
+    let first_local_follower =
+      CommunityFollower::follow_accepted_first_local(&mut context.pool(), community.id, person.id)
+        .await?;
+
+    // Fetch older posts for the first local follower, so the community doesn't look empty
+    if first_local_follower {
+      spawn_backfill_community(community, context);
+    }
 
     Ok(())
   }
@@ -1,7 +1,7 @@
 use self::following::send_follow_community;
 use crate::{
   activities::{
-    block::{send_ban_from_community, send_ban_from_site},
+    block::{send_ban_from_community, send_ban_from_site, send_block_person},
     community::{
       collection_add::{send_add_mod_to_community, send_feature_post},
       lock_page::send_lock_post,
@@ -331,6 +331,7 @@ pub async fn match_outgoing_activities(
         send_ban_from_community(mod_, community_id, target, data, context).await
       }
       BanFromSite(mod_, target, data) => send_ban_from_site(mod_, target, data, context).await,
+      BlockPerson(actor, target, block) => send_block_person(actor, target, block, context).await,
       CreatePrivateMessage(pm) => {
         send_create_or_update_pm(pm, CreateOrUpdateType::Create, context).await
       }
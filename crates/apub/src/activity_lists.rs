@@ -2,12 +2,18 @@ use crate::{
   objects::community::ApubCommunity,
   protocol::{
     activities::{
-      block::{block_user::BlockUser, undo_block_user::UndoBlockUser},
+      block::{
+        block_person::BlockPerson,
+        block_user::BlockUser,
+        undo_block_person::UndoBlockPerson,
+        undo_block_user::UndoBlockUser,
+      },
       community::{
         announce::{AnnounceActivity, RawAnnouncableActivities},
         collection_add::CollectionAdd,
         collection_remove::CollectionRemove,
         lock_page::{LockPage, UndoLockPage},
+        move_community::MoveCommunity,
         report::Report,
         update::UpdateCommunity,
       },
@@ -45,6 +51,9 @@ pub enum SharedInboxActivities {
   CreateOrUpdatePrivateMessage(CreateOrUpdateChatMessage),
   Report(Report),
   AnnounceActivity(AnnounceActivity),
+  MoveCommunity(MoveCommunity),
+  BlockPerson(BlockPerson),
+  UndoBlockPerson(UndoBlockPerson),
   /// This is a catch-all and needs to be last
   RawAnnouncableActivities(RawAnnouncableActivities),
 }
@@ -73,6 +82,9 @@ pub enum PersonInboxActivities {
   Delete(Delete),
   UndoDelete(UndoDelete),
   AnnounceActivity(AnnounceActivity),
+  MoveCommunity(MoveCommunity),
+  BlockPerson(BlockPerson),
+  UndoBlockPerson(UndoBlockPerson),
   /// User can also receive some "announcable" activities, eg a comment mention.
   AnnouncableActivities(AnnouncableActivities),
 }
@@ -138,7 +150,12 @@ mod tests {
   #![allow(clippy::indexing_slicing)]
 
   use crate::{
-    activity_lists::{GroupInboxActivities, PersonInboxActivities, SiteInboxActivities},
+    activity_lists::{
+      AnnouncableActivities,
+      GroupInboxActivities,
+      PersonInboxActivities,
+      SiteInboxActivities,
+    },
     protocol::tests::{test_json, test_parse_lemmy_item},
   };
 
@@ -165,6 +182,21 @@ mod tests {
     )
     .unwrap();
     test_json::<PersonInboxActivities>("assets/mastodon/activities/follow.json").unwrap();
+
+    let block =
+      test_json::<PersonInboxActivities>("assets/mastodon/activities/block.json").unwrap();
+    assert!(matches!(
+      block.inner(),
+      PersonInboxActivities::BlockPerson(_)
+    ));
+    let ban = test_parse_lemmy_item::<PersonInboxActivities>(
+      "assets/lemmy/activities/block/block_user.json",
+    )
+    .unwrap();
+    assert!(matches!(
+      ban,
+      PersonInboxActivities::AnnouncableActivities(AnnouncableActivities::BlockUser(_))
+    ));
   }
 
   #[test]
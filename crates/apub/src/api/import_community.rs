@@ -0,0 +1,294 @@
+use crate::objects::person::ApubPerson;
+use activitypub_federation::{
+  config::Data,
+  fetch::object_id::ObjectId,
+  http_signatures::generate_actor_keypair,
+};
+use actix_web::web::Json;
+use lemmy_api_common::{
+  build_response::build_community_response,
+  community::{CommunityResponse, ImportCommunity},
+  context::LemmyContext,
+  utils::{
+    generate_followers_url,
+    generate_inbox_url,
+    generate_local_apub_endpoint,
+    generate_shared_inbox_url,
+    is_admin,
+    local_site_to_slur_regex,
+    sanitize_html_api,
+    sanitize_html_api_opt,
+    EndpointType,
+  },
+};
+use lemmy_db_schema::{
+  source::{
+    actor_language::{CommunityLanguage, SiteLanguage},
+    community::{
+      Community,
+      CommunityFollower,
+      CommunityFollowerForm,
+      CommunityInsertForm,
+      CommunityModerator,
+      CommunityModeratorForm,
+    },
+  },
+  traits::{ApubActor, Crud, Followable, Joinable},
+};
+use lemmy_db_views::structs::{LocalUserView, SiteView};
+use lemmy_utils::{
+  error::{LemmyError, LemmyErrorExt, LemmyErrorType},
+  utils::{
+    slurs::{check_slurs, check_slurs_opt},
+    validation::{is_valid_actor_name, is_valid_body_field},
+  },
+};
+use tracing::debug;
+use url::Url;
+
+/// Recreates a community which was exported from another instance with `ExportCommunity`. The
+/// importing user becomes top mod, the remaining moderators are fetched and added after that.
+/// Followers are not touched here, they are switched over once the old community sends `Move`.
+#[tracing::instrument(skip(context))]
+pub async fn import_community(
+  data: Json<ImportCommunity>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> Result<Json<CommunityResponse>, LemmyError> {
+  let site_view = SiteView::read_local(&mut context.pool()).await?;
+  let local_site = site_view.local_site;
+  let export = &data.export;
+
+  if local_site.community_creation_admin_only && is_admin(&local_user_view).is_err() {
+    Err(LemmyErrorType::OnlyAdminsCanCreateCommunities)?
+  }
+
+  let name = sanitize_html_api(&export.name);
+  let title = sanitize_html_api(&export.title);
+  let description = sanitize_html_api_opt(&export.description);
+
+  let slur_regex = local_site_to_slur_regex(&local_site);
+  check_slurs(&name, &slur_regex)?;
+  check_slurs(&title, &slur_regex)?;
+  check_slurs_opt(&description, &slur_regex)?;
+
+  is_valid_actor_name(&export.name, local_site.actor_name_max_length as usize)?;
+  is_valid_body_field(&export.description, false)?;
+
+  let community_actor_id = generate_local_apub_endpoint(
+    EndpointType::Community,
+    &export.name,
+    &context.settings().get_protocol_and_hostname(),
+  )?;
+  let community_dupe =
+    Community::read_from_apub_id(&mut context.pool(), &community_actor_id).await?;
+  if community_dupe.is_some() {
+    Err(LemmyErrorType::CommunityAlreadyExists)?
+  }
+
+  let keypair = generate_actor_keypair()?;
+  let community_form = CommunityInsertForm::builder()
+    .name(name)
+    .title(title)
+    .description(description)
+    .icon(export.icon.clone())
+    .banner(export.banner.clone())
+    .nsfw(Some(export.nsfw))
+    .actor_id(Some(community_actor_id.clone()))
+    .private_key(Some(keypair.private_key))
+    .public_key(keypair.public_key)
+    .followers_url(Some(generate_followers_url(&community_actor_id)?))
+    .inbox_url(Some(generate_inbox_url(&community_actor_id)?))
+    .shared_inbox_url(Some(generate_shared_inbox_url(&community_actor_id)?))
+    .posting_restricted_to_mods(Some(export.posting_restricted_to_mods))
+    .require_alt_text(Some(export.require_alt_text))
+    .moved_from(Some(export.actor_id.clone()))
+    .instance_id(site_view.site.instance_id)
+    .build();
+
+  let inserted_community = Community::create(&mut context.pool(), &community_form)
+    .await
+    .with_lemmy_type(LemmyErrorType::CommunityAlreadyExists)?;
+  let community_id = inserted_community.id;
+
+  // The importing user becomes top mod, followed by the previous mods in their original order
+  let mut moderators = vec![local_user_view.person.id];
+  for actor_id in &export.moderators {
+    let url: Url = actor_id.clone().into();
+    match ObjectId::<ApubPerson>::from(url)
+      .dereference(&context)
+      .await
+    {
+      Ok(person) if !moderators.contains(&person.id) => moderators.push(person.id),
+      Ok(_) => {}
+      Err(e) => debug!("Failed to fetch moderator {actor_id}: {e}"),
+    }
+  }
+  for person_id in moderators {
+    let form = CommunityModeratorForm {
+      community_id,
+      person_id,
+    };
+    CommunityModerator::join(&mut context.pool(), &form)
+      .await
+      .with_lemmy_type(LemmyErrorType::CommunityModeratorAlreadyExists)?;
+  }
+
+  let community_follower_form = CommunityFollowerForm {
+    community_id,
+    person_id: local_user_view.person.id,
+    pending: false,
+  };
+  CommunityFollower::follow(&mut context.pool(), &community_follower_form)
+    .await
+    .with_lemmy_type(LemmyErrorType::CommunityFollowerAlreadyExists)?;
+
+  // Only keep the languages which are also allowed on this instance
+  let site_languages = SiteLanguage::read_local_raw(&mut context.pool()).await?;
+  let languages = export
+    .discussion_languages
+    .iter()
+    .filter(|l| site_languages.contains(l))
+    .copied()
+    .collect();
+  CommunityLanguage::update(&mut context.pool(), languages, community_id).await?;
+
+  build_community_response(&context, local_user_view, community_id).await
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use super::*;
+  use crate::objects::tests::init_context;
+  use lemmy_api_common::community::CommunityExport;
+  use lemmy_db_schema::source::{
+    instance::Instance,
+    local_site::{LocalSite, LocalSiteInsertForm},
+    local_site_rate_limit::{LocalSiteRateLimit, LocalSiteRateLimitInsertForm},
+    local_user::{LocalUser, LocalUserInsertForm},
+    person::{Person, PersonInsertForm},
+    site::{Site, SiteInsertForm},
+  };
+  use lemmy_db_views_actor::structs::CommunityModeratorView;
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_import_community() {
+    let context = init_context().await;
+    let instance = Instance::read_or_create(&mut context.pool(), "example.com".to_string())
+      .await
+      .unwrap();
+    let site_form = SiteInsertForm::builder()
+      .name("import site".to_string())
+      .instance_id(instance.id)
+      .build();
+    let site = Site::create(&mut context.pool(), &site_form).await.unwrap();
+    let local_site_form = LocalSiteInsertForm::builder().site_id(site.id).build();
+    let local_site = LocalSite::create(&mut context.pool(), &local_site_form)
+      .await
+      .unwrap();
+    let rate_limit_form = LocalSiteRateLimitInsertForm::builder()
+      .local_site_id(local_site.id)
+      .build();
+    LocalSiteRateLimit::create(&mut context.pool(), &rate_limit_form)
+      .await
+      .unwrap();
+
+    let person_form = PersonInsertForm::builder()
+      .name("importer".into())
+      .public_key("pubkey".to_string())
+      .instance_id(instance.id)
+      .build();
+    let importer = Person::create(&mut context.pool(), &person_form)
+      .await
+      .unwrap();
+    let local_user_form = LocalUserInsertForm::builder()
+      .person_id(importer.id)
+      .password_encrypted("123456".to_string())
+      .build();
+    let local_user = LocalUser::create(&mut context.pool(), &local_user_form)
+      .await
+      .unwrap();
+    let local_user_view = LocalUserView::read(&mut context.pool(), local_user.id)
+      .await
+      .unwrap();
+
+    // the previous moderator is already known, so it doesn't need to be fetched
+    let old_instance = Instance::read_or_create(&mut context.pool(), "old.tld".to_string())
+      .await
+      .unwrap();
+    let old_mod_form = PersonInsertForm::builder()
+      .name("old_mod".into())
+      .public_key("pubkey".to_string())
+      .actor_id(Some(
+        Url::parse("https://old.tld/u/old_mod").unwrap().into(),
+      ))
+      .local(Some(false))
+      .instance_id(old_instance.id)
+      .build();
+    let old_mod = Person::create(&mut context.pool(), &old_mod_form)
+      .await
+      .unwrap();
+
+    let export = CommunityExport {
+      actor_id: Url::parse("https://old.tld/c/imported").unwrap().into(),
+      name: "imported".to_string(),
+      title: "Imported community".to_string(),
+      description: Some("From another instance".to_string()),
+      icon: None,
+      banner: None,
+      nsfw: true,
+      posting_restricted_to_mods: true,
+      require_alt_text: true,
+      discussion_languages: vec![],
+      moderators: vec![old_mod.actor_id.clone(), importer.actor_id.clone()],
+    };
+    let data = ImportCommunity {
+      export: export.clone(),
+    };
+    let response = import_community(
+      Json(data),
+      context.reset_request_count(),
+      local_user_view.clone(),
+    )
+    .await
+    .unwrap();
+    let community = &response.community_view.community;
+    assert!(community.local);
+    assert_eq!("Imported community", community.title);
+    assert!(community.nsfw);
+    assert!(community.posting_restricted_to_mods);
+    assert!(community.require_alt_text);
+    assert_eq!(Some(export.actor_id.clone()), community.moved_from);
+
+    // the importing user becomes top mod, followed by the previous mods
+    let moderators = CommunityModeratorView::for_community(&mut context.pool(), community.id)
+      .await
+      .unwrap();
+    let moderator_ids = moderators
+      .iter()
+      .map(|m| m.moderator.id)
+      .collect::<Vec<_>>();
+    assert_eq!(vec![importer.id, old_mod.id], moderator_ids);
+
+    let data = ImportCommunity { export };
+    let duplicate =
+      import_community(Json(data), context.reset_request_count(), local_user_view).await;
+    assert_eq!(
+      Some(LemmyErrorType::CommunityAlreadyExists),
+      duplicate.err().map(|e| e.error_type)
+    );
+
+    Site::delete(&mut context.pool(), site.id).await.unwrap();
+    Instance::delete(&mut context.pool(), instance.id)
+      .await
+      .unwrap();
+    Instance::delete(&mut context.pool(), old_instance.id)
+      .await
+      .unwrap();
+  }
+}
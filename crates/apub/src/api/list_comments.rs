@@ -8,6 +8,7 @@ use actix_web::web::{Json, Query};
 use lemmy_api_common::{
   comment::{GetComments, GetCommentsResponse},
   context::LemmyContext,
+  image_proxy::ProxyImages,
   utils::check_private_instance,
 };
 use lemmy_db_schema::{
@@ -61,7 +62,7 @@ pub async fn list_comments(
 
   let parent_path_cloned = parent_path.clone();
   let post_id = data.post_id;
-  let comments = CommentQuery {
+  let mut comments = CommentQuery {
     listing_type,
     sort,
     max_depth,
@@ -79,6 +80,7 @@ pub async fn list_comments(
   .list(&mut context.pool())
   .await
   .with_lemmy_type(LemmyErrorType::CouldntGetComments)?;
+  comments.proxy_images(&context);
 
   Ok(Json(GetCommentsResponse { comments }))
 }
@@ -7,10 +7,14 @@ use activitypub_federation::config::Data;
 use actix_web::web::{Json, Query};
 use lemmy_api_common::{
   context::LemmyContext,
+  image_proxy::ProxyImages,
   post::{GetPosts, GetPostsResponse},
-  utils::check_private_instance,
+  utils::{check_private_instance, read_custom_feed},
+};
+use lemmy_db_schema::{
+  source::{community::Community, local_site::LocalSite},
+  ListingType,
 };
-use lemmy_db_schema::source::{community::Community, local_site::LocalSite};
 use lemmy_db_views::{
   post_view::PostQuery,
   structs::{LocalUserView, PaginationCursor},
@@ -37,6 +41,17 @@ pub async fn list_posts(
   } else {
     data.community_id
   };
+  let custom_feed_id = match data.custom_feed_id {
+    Some(feed_id) => {
+      let person_id = local_user_view.as_ref().map(|l| l.person.id);
+      Some(
+        read_custom_feed(&mut context.pool(), feed_id, person_id)
+          .await?
+          .id,
+      )
+    }
+    None => None,
+  };
   let saved_only = data.saved_only.unwrap_or_default();
 
   let liked_only = data.liked_only.unwrap_or_default();
@@ -45,11 +60,16 @@ pub async fn list_posts(
     return Err(LemmyError::from(LemmyErrorType::ContradictingFilters));
   }
 
-  let listing_type = Some(listing_type_with_default(
-    data.type_,
-    &local_site,
-    community_id,
-  )?);
+  // A custom feed is shown like a single community
+  let listing_type = if custom_feed_id.is_some() {
+    Some(ListingType::All)
+  } else {
+    Some(listing_type_with_default(
+      data.type_,
+      &local_site,
+      community_id,
+    )?)
+  };
   // parse pagination token
   let page_after = if let Some(pa) = &data.page_cursor {
     Some(pa.read(&mut context.pool()).await?)
@@ -57,11 +77,12 @@ pub async fn list_posts(
     None
   };
 
-  let posts = PostQuery {
+  let mut posts = PostQuery {
     local_user: local_user_view.as_ref(),
     listing_type,
     sort,
     community_id,
+    custom_feed_id,
     saved_only,
     liked_only,
     disliked_only,
@@ -76,5 +97,6 @@ pub async fn list_posts(
 
   // if this page wasn't empty, then there is a next page after the last post on this page
   let next_page = posts.last().map(PaginationCursor::after_post);
+  posts.proxy_images(&context);
   Ok(Json(GetPostsResponse { posts, next_page }))
 }
@@ -1,8 +1,10 @@
 use lemmy_db_schema::{newtypes::CommunityId, source::local_site::LocalSite, ListingType};
 use lemmy_utils::error::LemmyError;
 
+pub mod import_community;
 pub mod list_comments;
 pub mod list_posts;
+pub mod move_community;
 pub mod read_community;
 pub mod read_person;
 pub mod resolve_object;
@@ -0,0 +1,71 @@
+use crate::{
+  activities::community::move_community::{migrate_local_followers, send_move_community},
+  fetcher::resolve_actor_identifier,
+  objects::community::ApubCommunity,
+};
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use lemmy_api_common::{
+  build_response::build_community_response,
+  community::{CommunityResponse, MoveCommunity},
+  context::LemmyContext,
+  utils::is_top_mod,
+};
+use lemmy_db_schema::{
+  source::community::{Community, CommunityUpdateForm},
+  traits::Crud,
+};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_db_views_actor::structs::CommunityModeratorView;
+use lemmy_utils::error::{LemmyError, LemmyErrorExt2, LemmyErrorType};
+
+/// Moves a local community to another instance, where it was previously recreated with
+/// `ImportCommunity`. All followers get switched over to the new community.
+#[tracing::instrument(skip(context))]
+pub async fn move_community(
+  data: Json<MoveCommunity>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> Result<Json<CommunityResponse>, LemmyError> {
+  let community_id = data.community_id;
+  let community_mods =
+    CommunityModeratorView::for_community(&mut context.pool(), community_id).await?;
+  is_top_mod(&local_user_view, &community_mods)?;
+
+  let community: ApubCommunity = Community::read(&mut context.pool(), community_id)
+    .await?
+    .into();
+  if !community.local {
+    Err(LemmyErrorType::ObjectNotLocal)?
+  }
+  if community.moved_to.is_some() {
+    Err(LemmyErrorType::CommunityWasMoved)?
+  }
+
+  let target: ApubCommunity = resolve_actor_identifier::<ApubCommunity, Community>(
+    &data.target,
+    &context,
+    &Some(local_user_view.clone()),
+    false,
+  )
+  .await
+  .with_lemmy_type(LemmyErrorType::CouldntFindCommunity)?;
+  if target.local {
+    Err(LemmyErrorType::CantMoveCommunityToSameInstance)?
+  }
+  // The target has to be imported from this community, otherwise followers could be moved anywhere
+  if target.moved_from.as_ref() != Some(&community.actor_id) {
+    Err(LemmyErrorType::CommunityMoveNotConfirmed)?
+  }
+
+  let form = CommunityUpdateForm {
+    moved_to: Some(Some(target.actor_id.clone())),
+    ..Default::default()
+  };
+  Community::update(&mut context.pool(), community_id, &form).await?;
+
+  send_move_community(&community, &target, &context).await?;
+  migrate_local_followers(&community, &target, &context).await?;
+
+  build_community_response(&context, local_user_view, community_id).await
+}
@@ -4,6 +4,7 @@ use actix_web::web::{Json, Query};
 use lemmy_api_common::{
   community::{GetCommunity, GetCommunityResponse},
   context::LemmyContext,
+  image_proxy::ProxyImages,
   utils::{check_private_instance, is_mod_or_admin_opt},
 };
 use lemmy_db_schema::source::{
@@ -51,7 +52,7 @@ pub async fn get_community(
   .await
   .is_ok();
 
-  let community_view = CommunityView::read(
+  let mut community_view = CommunityView::read(
     &mut context.pool(),
     community_id,
     person_id,
@@ -60,7 +61,7 @@ pub async fn get_community(
   .await
   .with_lemmy_type(LemmyErrorType::CouldntFindCommunity)?;
 
-  let moderators = CommunityModeratorView::for_community(&mut context.pool(), community_id)
+  let mut moderators = CommunityModeratorView::for_community(&mut context.pool(), community_id)
     .await
     .with_lemmy_type(LemmyErrorType::CouldntFindCommunity)?;
 
@@ -77,6 +78,8 @@ pub async fn get_community(
   let community_id = community_view.community.id;
   let discussion_languages = CommunityLanguage::read(&mut context.pool(), community_id).await?;
 
+  community_view.proxy_images(&context);
+  moderators.proxy_images(&context);
   Ok(Json(GetCommunityResponse {
     community_view,
     site,
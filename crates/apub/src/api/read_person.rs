@@ -3,6 +3,7 @@ use activitypub_federation::config::Data;
 use actix_web::web::{Json, Query};
 use lemmy_api_common::{
   context::LemmyContext,
+  image_proxy::ProxyImages,
   person::{GetPersonDetails, GetPersonDetailsResponse},
   utils::check_private_instance,
 };
@@ -45,7 +46,7 @@ pub async fn read_person(
 
   // You don't need to return settings for the user, since this comes back with GetSite
   // `my_user`
-  let person_view = PersonView::read(&mut context.pool(), person_details_id).await?;
+  let mut person_view = PersonView::read(&mut context.pool(), person_details_id).await?;
 
   let sort = data.sort;
   let page = data.page;
@@ -60,7 +61,7 @@ pub async fn read_person(
     None
   };
 
-  let posts = PostQuery {
+  let mut posts = PostQuery {
     sort,
     saved_only,
     local_user: local_user_view.as_ref(),
@@ -74,7 +75,7 @@ pub async fn read_person(
   .list(&mut context.pool())
   .await?;
 
-  let comments = CommentQuery {
+  let mut comments = CommentQuery {
     local_user: local_user_view.as_ref(),
     sort: sort.map(post_to_comment_sort_type),
     saved_only,
@@ -88,9 +89,14 @@ pub async fn read_person(
   .list(&mut context.pool())
   .await?;
 
-  let moderates =
+  let mut moderates =
     CommunityModeratorView::for_person(&mut context.pool(), person_details_id).await?;
 
+  person_view.proxy_images(&context);
+  posts.proxy_images(&context);
+  comments.proxy_images(&context);
+  moderates.proxy_images(&context);
+
   // Return the jwt
   Ok(Json(GetPersonDetailsResponse {
     person_view,
@@ -8,6 +8,7 @@ use actix_web::web::{Json, Query};
 use diesel::NotFound;
 use lemmy_api_common::{
   context::LemmyContext,
+  image_proxy::ProxyImages,
   site::{ResolveObject, ResolveObjectResponse},
   utils::check_private_instance,
 };
@@ -38,9 +39,14 @@ pub async fn resolve_object(
   }
   .with_lemmy_type(LemmyErrorType::CouldntFindObject)?;
 
-  convert_response(res, person_id, &mut context.pool())
+  let mut res = convert_response(res, person_id, &mut context.pool())
     .await
-    .with_lemmy_type(LemmyErrorType::CouldntFindObject)
+    .with_lemmy_type(LemmyErrorType::CouldntFindObject)?;
+  res.person.proxy_images(&context);
+  res.community.proxy_images(&context);
+  res.post.proxy_images(&context);
+  res.comment.proxy_images(&context);
+  Ok(res)
 }
 
 async fn convert_response(
@@ -3,6 +3,7 @@ use activitypub_federation::config::Data;
 use actix_web::web::{Json, Query};
 use lemmy_api_common::{
   context::LemmyContext,
+  image_proxy::ProxyImages,
   site::{Search, SearchResponse},
   utils::{check_private_instance, is_admin},
 };
@@ -103,6 +104,7 @@ pub async fn search(
       users = PersonQuery {
         sort: (sort.map(post_to_person_sort_type)),
         search_term: (Some(q)),
+        local_user: (local_user.as_ref()),
         page: (page),
         limit: (limit),
       }
@@ -173,6 +175,7 @@ pub async fn search(
         PersonQuery {
           sort: (sort.map(post_to_person_sort_type)),
           search_term: (Some(q)),
+          local_user: (local_user.as_ref()),
           page: (page),
           limit: (limit),
         }
@@ -196,6 +199,11 @@ pub async fn search(
     }
   };
 
+  posts.proxy_images(&context);
+  comments.proxy_images(&context);
+  communities.proxy_images(&context);
+  users.proxy_images(&context);
+
   // Return the jwt
   Ok(Json(SearchResponse {
     type_: search_type,
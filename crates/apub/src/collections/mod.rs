@@ -1,3 +1,4 @@
 pub(crate) mod community_featured;
 pub(crate) mod community_moderators;
 pub(crate) mod community_outbox;
+pub(crate) mod post_replies;
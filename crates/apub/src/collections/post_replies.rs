@@ -0,0 +1,100 @@
+use crate::{
+  objects::{comment::ApubComment, post::ApubPost},
+  protocol::{collections::post_replies::PostReplies, objects::note::Note},
+};
+use activitypub_federation::{
+  config::Data,
+  kinds::collection::OrderedCollectionType,
+  protocol::verification::verify_domains_match,
+  traits::{Collection, Object},
+};
+use futures::future::try_join_all;
+use lemmy_api_common::{context::LemmyContext, utils::generate_replies_url};
+use lemmy_db_schema::source::comment::Comment;
+use lemmy_utils::error::LemmyError;
+use std::time::Duration;
+use tracing::debug;
+use url::Url;
+
+#[derive(Clone, Debug)]
+pub(crate) struct ApubPostReplies(Vec<ApubComment>);
+
+#[async_trait::async_trait]
+impl Collection for ApubPostReplies {
+  type Owner = ApubPost;
+  type DataType = LemmyContext;
+  type Kind = PostReplies;
+  type Error = LemmyError;
+
+  #[tracing::instrument(skip_all)]
+  async fn read_local(
+    owner: &Self::Owner,
+    data: &Data<Self::DataType>,
+  ) -> Result<Self::Kind, LemmyError> {
+    let ordered_items = try_join_all(
+      Comment::list_for_post(&mut data.pool(), owner.id)
+        .await?
+        .into_iter()
+        .map(ApubComment::from)
+        .map(|c| c.into_json(data)),
+    )
+    .await?;
+    Ok(PostReplies {
+      r#type: OrderedCollectionType::OrderedCollection,
+      id: generate_replies_url(&owner.ap_id)?.into(),
+      total_items: ordered_items.len() as i32,
+      ordered_items,
+    })
+  }
+
+  #[tracing::instrument(skip_all)]
+  async fn verify(
+    apub: &Self::Kind,
+    expected_domain: &Url,
+    _data: &Data<Self::DataType>,
+  ) -> Result<(), LemmyError> {
+    verify_domains_match(expected_domain, &apub.id)?;
+    Ok(())
+  }
+
+  #[tracing::instrument(skip_all)]
+  async fn from_json(
+    apub: Self::Kind,
+    _owner: &Self::Owner,
+    data: &Data<Self::DataType>,
+  ) -> Result<Self, LemmyError> {
+    let backfill = &data.settings().backfill;
+    let delay = Duration::from_millis(backfill.request_delay_ms);
+
+    // Items are processed in order, so that parent comments are stored before their replies.
+    // Errors are ignored like in the community outbox, so that a single broken comment doesn't
+    // prevent the others from being received.
+    let items = apub.ordered_items.into_iter();
+    for note in items.take(backfill.max_comments_per_post) {
+      if verify_domains_match(note.id.inner(), &apub.id).is_ok() {
+        if let Err(e) = receive_note(note, &apub.id, data).await {
+          debug!("Failed to receive comment: {e}");
+        }
+      } else {
+        // Comments from other instances can't be trusted, so fetch them from their origin
+        if let Err(e) = note.id.dereference(data).await {
+          debug!("Failed to fetch comment: {e}");
+        }
+        tokio::time::sleep(delay).await;
+      }
+    }
+
+    // This return value is unused, so just set an empty vec
+    Ok(ApubPostReplies(Vec::new()))
+  }
+}
+
+async fn receive_note(
+  note: Note,
+  expected_domain: &Url,
+  data: &Data<LemmyContext>,
+) -> Result<(), LemmyError> {
+  ApubComment::verify(&note, expected_domain, data).await?;
+  ApubComment::from_json(note, data).await?;
+  Ok(())
+}
@@ -0,0 +1,153 @@
+use crate::{
+  activity_lists::AnnouncableActivities,
+  collections::post_replies::ApubPostReplies,
+  objects::{community::ApubCommunity, post::ApubPost},
+  protocol::{
+    activities::community::announce::AnnounceActivity,
+    collections::{group_featured::GroupFeatured, group_outbox::GroupOutboxPage},
+    objects::{group::Group, page::Page},
+    IdOrNestedObject,
+  },
+};
+use activitypub_federation::{
+  config::Data,
+  fetch::{collection_id::CollectionId, fetch_object_http, object_id::ObjectId},
+  protocol::verification::verify_domains_match,
+  traits::{ActivityHandler, Actor},
+};
+use lemmy_api_common::context::LemmyContext;
+use lemmy_utils::{error::LemmyError, spawn_try_task};
+use once_cell::sync::Lazy;
+use serde_json::Value;
+use std::{collections::HashSet, time::Duration};
+use tokio::{sync::Mutex, time::sleep};
+use tracing::debug;
+use url::Url;
+
+/// Only backfill a single community at a time, so that many new follows at once don't result in a
+/// flood of requests.
+static BACKFILL_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// Fetches older posts and their comments when a remote community gets its first local follower,
+/// so that it doesn't look empty until new posts arrive. Runs in the background, waits between
+/// requests and stops after the number of posts given in the backfill config.
+pub(crate) fn spawn_backfill_community(community: ApubCommunity, context: &Data<LemmyContext>) {
+  if context.settings().backfill.max_posts == 0 {
+    return;
+  }
+  let context = context.reset_request_count();
+  spawn_try_task(async move {
+    let _lock = BACKFILL_LOCK.lock().await;
+    backfill_community(&community, &context).await
+  });
+}
+
+#[tracing::instrument(skip_all)]
+async fn backfill_community(
+  community: &ApubCommunity,
+  context: &Data<LemmyContext>,
+) -> Result<(), LemmyError> {
+  let config = &context.settings().backfill;
+  let delay = Duration::from_millis(config.request_delay_ms);
+  let mut remaining_posts = config.max_posts;
+
+  // Refetch the group, as the outbox url is not stored in the database
+  let group: Group = fetch_object_http(&community.id(), context).await?;
+
+  // Featured posts come first, as they are the most relevant ones for new followers
+  if let Some(featured) = group.featured {
+    sleep(delay).await;
+    let featured: GroupFeatured = fetch_object_http(&featured.into(), context).await?;
+    for page in featured.ordered_items.into_iter().take(remaining_posts) {
+      remaining_posts -= 1;
+      backfill_page(page, &context.reset_request_count()).await;
+    }
+  }
+
+  // Walk back through the outbox. Lemmy includes all items in the collection itself, other
+  // software may split them into pages which are linked with `first` and `next`.
+  // Each page yields at least one post except the collection itself, so more pages than that
+  // point to empty or looping pages.
+  let mut remaining_pages = config.max_posts + 1;
+  let mut visited = HashSet::new();
+  let mut next: Option<Url> = Some(group.outbox.into());
+  while let Some(url) = next.take() {
+    if remaining_posts == 0 || remaining_pages == 0 || !visited.insert(url.clone()) {
+      break;
+    }
+    remaining_pages -= 1;
+    verify_domains_match(&url, &community.id())?;
+    sleep(delay).await;
+    let page: GroupOutboxPage = fetch_object_http(&url, &context.reset_request_count()).await?;
+    next = if page.ordered_items.is_empty() {
+      page.first
+    } else {
+      page.next
+    };
+    for item in page.ordered_items.into_iter().take(remaining_posts) {
+      remaining_posts -= 1;
+      backfill_announce(item, &context.reset_request_count()).await;
+    }
+  }
+  Ok(())
+}
+
+/// Receives a single outbox item, and fetches the comments of the contained post.
+async fn backfill_announce(item: Value, context: &Data<LemmyContext>) {
+  let Ok(announce) = serde_json::from_value::<AnnounceActivity>(item) else {
+    return;
+  };
+  let replies = match &announce.object {
+    IdOrNestedObject::NestedObject(raw) => match raw.clone().try_into() {
+      Ok(AnnouncableActivities::CreateOrUpdatePost(create)) => {
+        Some((create.object.id.clone(), create.object.replies.clone()))
+      }
+      _ => None,
+    },
+    IdOrNestedObject::Id(_) => None,
+  };
+
+  // Items which were already received earlier fail verification, but their comments may still
+  // be missing
+  if announce.verify(context).await.is_ok() {
+    if let Err(e) = announce.receive(context).await {
+      debug!("Failed to receive outbox item: {e}");
+    }
+  }
+  if let Some((post_id, replies)) = replies {
+    backfill_replies(post_id, replies, context).await;
+  }
+}
+
+/// Receives a single featured post, and fetches its comments.
+async fn backfill_page(page: Page, context: &Data<LemmyContext>) {
+  let post_id = page.id.clone();
+  let replies = page.replies.clone();
+  if page.verify(context).await.is_ok() {
+    if let Err(e) = page.receive(context).await {
+      debug!("Failed to receive featured post: {e}");
+    }
+  }
+  backfill_replies(post_id, replies, context).await;
+}
+
+async fn backfill_replies(
+  post_id: ObjectId<ApubPost>,
+  replies: Option<CollectionId<ApubPostReplies>>,
+  context: &Data<LemmyContext>,
+) {
+  let config = &context.settings().backfill;
+  let Some(replies) = replies else {
+    return;
+  };
+  if config.max_comments_per_post == 0 {
+    return;
+  }
+  let Ok(post) = post_id.dereference_local(context).await else {
+    return;
+  };
+  sleep(Duration::from_millis(config.request_delay_ms)).await;
+  if let Err(e) = replies.dereference(&post, context).await {
+    debug!("Failed to fetch comments: {e}");
+  }
+}
@@ -10,6 +10,7 @@ use lemmy_db_schema::traits::ApubActor;
 use lemmy_db_views::structs::LocalUserView;
 use lemmy_utils::error::LemmyError;
 
+pub(crate) mod backfill;
 pub mod post_or_comment;
 pub mod search;
 pub mod site_or_community_or_user;
@@ -1,12 +1,16 @@
 use crate::{
+  collections::post_replies::ApubPostReplies,
   http::{create_apub_response, create_apub_tombstone_response, err_object_not_local},
   objects::post::ApubPost,
 };
-use activitypub_federation::{config::Data, traits::Object};
+use activitypub_federation::{
+  config::Data,
+  traits::{Collection, Object},
+};
 use actix_web::{web, HttpResponse};
 use lemmy_api_common::context::LemmyContext;
 use lemmy_db_schema::{newtypes::PostId, source::post::Post, traits::Crud};
-use lemmy_utils::error::LemmyError;
+use lemmy_utils::error::{LemmyError, LemmyErrorType};
 use serde::Deserialize;
 
 #[derive(Deserialize)]
@@ -30,3 +34,21 @@ pub(crate) async fn get_apub_post(
     create_apub_tombstone_response(post.ap_id.clone())
   }
 }
+
+/// Returns the newest comments of a local post, so that other instances can backfill them.
+#[tracing::instrument(skip_all)]
+pub(crate) async fn get_apub_post_replies(
+  info: web::Path<PostQuery>,
+  context: Data<LemmyContext>,
+) -> Result<HttpResponse, LemmyError> {
+  let id = PostId(info.post_id.parse::<i32>()?);
+  let post: ApubPost = Post::read(&mut context.pool(), id).await?.into();
+  if !post.local {
+    Err(err_object_not_local())
+  } else if post.deleted || post.removed {
+    Err(LemmyErrorType::Deleted)?
+  } else {
+    let replies = ApubPostReplies::read_local(&post, &context).await?;
+    create_apub_response(&replies)
+  }
+}
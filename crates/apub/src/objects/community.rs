@@ -113,6 +113,8 @@ impl Object for ApubCommunity {
       updated: self.updated.map(convert_datetime),
       posting_restricted_to_mods: Some(self.posting_restricted_to_mods),
       attributed_to: Some(generate_moderators_url(&self.actor_id)?.into()),
+      moved_to: self.moved_to.clone().map(Into::into),
+      also_known_as: self.moved_from.clone().map(|m| vec![m.into()]),
     };
     Ok(group)
   }
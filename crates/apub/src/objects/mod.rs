@@ -99,6 +99,8 @@ pub(crate) mod tests {
     let secret = Secret {
       id: 0,
       jwt_secret: String::new(),
+      vapid_private_key: None,
+      vapid_public_key: None,
     };
 
     let rate_limit_config = RateLimitConfig::builder().build();
@@ -5,7 +5,7 @@ use crate::{
   objects::{instance::fetch_instance_actor_for_object, read_from_string_or_source_opt},
   protocol::{
     objects::{
-      person::{Person, UserTypes},
+      person::{DeviceKey, Person, UserTypes},
       Endpoints,
     },
     ImageObject,
@@ -31,6 +31,7 @@ use lemmy_db_schema::{
   source::{
     activity::ActorType,
     person::{Person as DbPerson, PersonInsertForm, PersonUpdateForm},
+    person_device_key::{PersonDeviceKey, PersonDeviceKeyForm},
   },
   traits::{ApubActor, Crud},
   utils::naive_now,
@@ -41,6 +42,7 @@ use lemmy_utils::{
     markdown::markdown_to_html,
     slurs::{check_slurs, check_slurs_opt},
     time::convert_datetime,
+    validation::{check_device_key_count, is_valid_device_key},
   },
 };
 use std::ops::Deref;
@@ -95,13 +97,22 @@ impl Object for ApubPerson {
   }
 
   #[tracing::instrument(skip_all)]
-  async fn into_json(self, _context: &Data<Self::DataType>) -> Result<Person, LemmyError> {
+  async fn into_json(self, context: &Data<Self::DataType>) -> Result<Person, LemmyError> {
     let kind = if self.bot_account {
       UserTypes::Service
     } else {
       UserTypes::Person
     };
 
+    let device_keys = PersonDeviceKey::list_for_person(&mut context.pool(), self.id)
+      .await?
+      .into_iter()
+      .map(|k| DeviceKey {
+        id: k.device_id,
+        public_key: k.public_key,
+      })
+      .collect();
+
     let person = Person {
       kind,
       id: self.actor_id.clone().into(),
@@ -112,6 +123,7 @@ impl Object for ApubPerson {
       icon: self.avatar.clone().map(ImageObject::new),
       image: self.banner.clone().map(ImageObject::new),
       matrix_user_id: self.matrix_user_id.clone(),
+      device_keys,
       published: Some(convert_datetime(self.published)),
       outbox: generate_outbox_url(&self.actor_id)?.into(),
       endpoints: self.shared_inbox_url.clone().map(|s| Endpoints {
@@ -140,6 +152,7 @@ impl Object for ApubPerson {
 
     let bio = read_from_string_or_source_opt(&person.summary, &None, &person.source);
     check_slurs_opt(&bio, slur_regex)?;
+    check_device_key_count(person.device_keys.len())?;
     Ok(())
   }
 
@@ -149,6 +162,7 @@ impl Object for ApubPerson {
     context: &Data<Self::DataType>,
   ) -> Result<ApubPerson, LemmyError> {
     let instance_id = fetch_instance_actor_for_object(&person.id, context).await?;
+    let person_device_keys = person.device_keys;
 
     let name = sanitize_html_federation(&person.preferred_username);
     let display_name = sanitize_html_federation_opt(&person.name);
@@ -183,6 +197,18 @@ impl Object for ApubPerson {
     };
     let person = DbPerson::upsert(&mut context.pool(), &person_form).await?;
 
+    // Keys which are invalid here can't be used by our clients anyway, so they are skipped
+    let device_key_forms = person_device_keys
+      .into_iter()
+      .filter(|k| is_valid_device_key(&k.id, &k.public_key).is_ok())
+      .map(|k| PersonDeviceKeyForm {
+        person_id: person.id,
+        device_id: k.id,
+        public_key: k.public_key,
+      })
+      .collect::<Vec<_>>();
+    PersonDeviceKey::replace_for_person(&mut context.pool(), person.id, &device_key_forms).await?;
+
     Ok(person.into())
   }
 }
@@ -26,6 +26,7 @@ use lemmy_api_common::{
   context::LemmyContext,
   request::fetch_site_data,
   utils::{
+    generate_replies_url,
     is_mod_or_admin,
     local_site_opt_to_sensitive,
     local_site_opt_to_slur_regex,
@@ -37,10 +38,12 @@ use lemmy_db_schema::{
   self,
   source::{
     community::Community,
+    instance::Instance,
     local_site::LocalSite,
     moderator::{ModLockPost, ModLockPostForm},
     person::Person,
     post::{Post, PostInsertForm, PostUpdateForm},
+    post_gallery_image::PostGalleryImage,
   },
   traits::Crud,
 };
@@ -50,13 +53,14 @@ use lemmy_utils::{
     markdown::markdown_to_html,
     slurs::{check_slurs_opt, remove_slurs},
     time::convert_datetime,
-    validation::check_url_scheme,
+    validation::{check_url_scheme, ALT_TEXT_MAX_LENGTH},
   },
 };
 use std::ops::Deref;
 use url::Url;
 
 const MAX_TITLE_LENGTH: usize = 200;
+const MAX_GALLERY_IMAGES: usize = 20;
 
 #[derive(Clone, Debug)]
 pub struct ApubPost(pub(crate) Post);
@@ -116,6 +120,27 @@ impl Object for ApubPost {
     let community_id = self.community_id;
     let community = Community::read(&mut context.pool(), community_id).await?;
     let language = LanguageTag::new_single(self.language_id, &mut context.pool()).await?;
+    // Comments of remote posts are only served by their own instance
+    let replies = if self.local {
+      Some(generate_replies_url(&self.ap_id)?.into())
+    } else {
+      None
+    };
+
+    let gallery = PostGalleryImage::list_for_post(&mut context.pool(), self.id).await?;
+    let attachment = if gallery.is_empty() {
+      self
+        .url
+        .clone()
+        .map(|url| Attachment::new(url, self.alt_text.clone()))
+        .into_iter()
+        .collect()
+    } else {
+      gallery
+        .into_iter()
+        .map(|i| Attachment::new_media(i.url, i.alt_text))
+        .collect()
+    };
 
     let page = Page {
       kind: PageType::Page,
@@ -127,7 +152,7 @@ impl Object for ApubPost {
       content: self.body.as_ref().map(|b| markdown_to_html(b)),
       media_type: Some(MediaTypeMarkdownOrHtml::Html),
       source: self.body.clone().map(Source::new),
-      attachment: self.url.clone().map(Attachment::new).into_iter().collect(),
+      attachment,
       image: self.thumbnail_url.clone().map(ImageObject::new),
       comments_enabled: Some(!self.locked),
       sensitive: Some(self.nsfw),
@@ -136,6 +161,7 @@ impl Object for ApubPost {
       updated: self.updated.map(convert_datetime),
       audience: Some(community.actor_id.into()),
       in_reply_to: None,
+      replies,
     };
     Ok(page)
   }
@@ -191,10 +217,35 @@ impl Object for ApubPost {
     // read existing, local post if any (for generating mod log)
     let old_post = page.id.dereference_local(context).await;
 
-    let form = if !page.is_mod_action(context).await? {
+    let (form, gallery) = if !page.is_mod_action(context).await? {
+      // Overlong descriptions from other platforms are cut off instead of rejecting the post
+      let sanitize_alt_text = |alt_text: Option<String>| {
+        alt_text.filter(|a| !a.trim().is_empty()).map(|a| {
+          sanitize_html_federation(&a.chars().take(ALT_TEXT_MAX_LENGTH).collect::<String>())
+        })
+      };
+      // Posts with multiple images, for example from Pixelfed or Mastodon, become galleries
+      let mut gallery = page
+        .attachment
+        .iter()
+        .filter(|a| a.is_media())
+        .cloned()
+        .map(Attachment::url)
+        .filter(|(url, _)| check_url_scheme(&Some(url.clone())).is_ok())
+        .take(MAX_GALLERY_IMAGES)
+        .map(|(url, alt_text)| (url.into(), sanitize_alt_text(alt_text)))
+        .collect::<Vec<_>>();
+      if gallery.len() < 2 {
+        gallery.clear();
+      }
+
       let first_attachment = page.attachment.into_iter().map(Attachment::url).next();
-      let url = if first_attachment.is_some() {
-        first_attachment
+      let (url, alt_text) = match first_attachment {
+        Some((url, alt_text)) => (Some(url), alt_text),
+        None => (None, None),
+      };
+      let url = if url.is_some() {
+        url
       } else if page.kind == PageType::Video {
         // we cant display videos directly, so insert a link to external video page
         Some(page.id.inner().clone())
@@ -206,29 +257,22 @@ impl Object for ApubPost {
       let local_site = LocalSite::read(&mut context.pool()).await.ok();
       let allow_sensitive = local_site_opt_to_sensitive(&local_site);
       let page_is_sensitive = page.sensitive.unwrap_or(false);
-      let include_image = allow_sensitive || !page_is_sensitive;
+      let is_limited = Instance::is_limited(&mut context.pool(), creator.instance_id).await?
+        || Instance::is_limited(&mut context.pool(), community.instance_id).await?;
+      let include_image = (allow_sensitive || !page_is_sensitive) && !is_limited;
 
       // Only fetch metadata if the post has a url and was not seen previously. We dont want to
       // waste resources by fetching metadata for the same post multiple times.
-      // Additionally, only fetch image if content is not sensitive or is allowed on local site.
+      // Additionally, only fetch image if content is not sensitive or is allowed on local site,
+      // and media of limited instances is never cached.
       let (metadata_res, thumbnail) = match &url {
-        Some(url) if old_post.is_err() => {
-          fetch_site_data(
-            context.client(),
-            context.settings(),
-            Some(url),
-            include_image,
-          )
-          .await
-        }
+        Some(url) if old_post.is_err() => fetch_site_data(context, Some(url), include_image).await,
         _ => (None, None),
       };
       // If no image was included with metadata, use post image instead when available.
       let thumbnail_url = thumbnail.or_else(|| page.image.map(|i| i.url.into()));
 
-      let (embed_title, embed_description, embed_video_url) = metadata_res
-        .map(|u| (u.title, u.description, u.embed_video_url))
-        .unwrap_or_default();
+      let metadata = metadata_res.unwrap_or_default();
       let slur_regex = &local_site_opt_to_slur_regex(&local_site);
 
       let body_slurs_removed =
@@ -239,10 +283,11 @@ impl Object for ApubPost {
 
       let name = sanitize_html_federation(&name);
       let body = sanitize_html_federation_opt(&body_slurs_removed);
-      let embed_title = sanitize_html_federation_opt(&embed_title);
-      let embed_description = sanitize_html_federation_opt(&embed_description);
+      let embed_title = sanitize_html_federation_opt(&metadata.title);
+      let embed_description = sanitize_html_federation_opt(&metadata.description);
+      let alt_text = sanitize_alt_text(alt_text);
 
-      PostInsertForm {
+      let form = PostInsertForm {
         name,
         url: url.map(Into::into),
         body,
@@ -256,27 +301,37 @@ impl Object for ApubPost {
         nsfw: page.sensitive,
         embed_title,
         embed_description,
-        embed_video_url,
+        embed_video_url: metadata.embed_video_url,
         thumbnail_url,
         ap_id: Some(page.id.clone().into()),
         local: Some(false),
         language_id,
         featured_community: None,
         featured_local: None,
-      }
+        alt_text,
+        url_content_type: metadata.content_type,
+        embed_width: metadata.embed_width,
+        embed_height: metadata.embed_height,
+        embed_duration: metadata.embed_duration,
+      };
+      (form, Some(gallery))
     } else {
       // if is mod action, only update locked/stickied fields, nothing else
-      PostInsertForm::builder()
+      let form = PostInsertForm::builder()
         .name(name)
         .creator_id(creator.id)
         .community_id(community.id)
         .ap_id(Some(page.id.clone().into()))
         .locked(page.comments_enabled.map(|e| !e))
         .updated(page.updated.map(Into::into))
-        .build()
+        .build();
+      (form, None)
     };
 
     let post = Post::create(&mut context.pool(), &form).await?;
+    if let Some(gallery) = gallery {
+      PostGalleryImage::replace(&mut context.pool(), post.id, gallery).await?;
+    }
 
     // write mod log entry for lock
     if Page::is_locked_changed(&old_post, &page.comments_enabled) {
@@ -328,6 +383,7 @@ mod tests {
     assert_eq!(post.body.as_ref().unwrap().len(), 45);
     assert!(!post.locked);
     assert!(!post.featured_community);
+    assert_eq!(post.alt_text.as_deref(), Some("A starship in orbit"));
     assert_eq!(context.request_count(), 0);
 
     Post::delete(&mut context.pool(), post.id).await.unwrap();
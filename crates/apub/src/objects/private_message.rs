@@ -14,18 +14,29 @@ use activitypub_federation::{
 use chrono::{DateTime, Utc};
 use lemmy_api_common::{
   context::LemmyContext,
-  utils::{check_person_block, sanitize_html_federation},
+  utils::{
+    check_person_block,
+    read_or_create_conversation,
+    sanitize_html_federation,
+    ENCRYPTED_MESSAGE_NOTICE,
+  },
 };
 use lemmy_db_schema::{
   source::{
+    conversation::ConversationParticipant,
     person::Person,
     private_message::{PrivateMessage, PrivateMessageInsertForm},
   },
   traits::Crud,
 };
+use lemmy_db_views::structs::LocalUserView;
 use lemmy_utils::{
   error::{LemmyError, LemmyErrorType},
-  utils::{markdown::markdown_to_html, time::convert_datetime},
+  utils::{
+    markdown::markdown_to_html,
+    time::convert_datetime,
+    validation::{check_conversation_size, is_valid_encrypted_content},
+  },
 };
 use std::ops::Deref;
 use url::Url;
@@ -80,15 +91,43 @@ impl Object for ApubPrivateMessage {
 
     let recipient_id = self.recipient_id;
     let recipient = Person::read(&mut context.pool(), recipient_id).await?;
+    // The first recipient is followed by the other members of a group conversation
+    let mut to = vec![recipient.actor_id.into()];
+    to.extend(
+      ConversationParticipant::list_persons(&mut context.pool(), self.conversation_id)
+        .await?
+        .into_iter()
+        .filter(|p| p.id != creator_id && p.id != recipient_id)
+        .map(|p| p.actor_id.into()),
+    );
+
+    // Software which doesn't know about encryption shows the notice instead of the ciphertext
+    let (content, source, encrypted, ciphertext) = if self.encrypted {
+      (
+        markdown_to_html(ENCRYPTED_MESSAGE_NOTICE),
+        None,
+        Some(true),
+        Some(self.content.clone()),
+      )
+    } else {
+      (
+        markdown_to_html(&self.content),
+        Some(Source::new(self.content.clone())),
+        None,
+        None,
+      )
+    };
 
     let note = ChatMessage {
       r#type: ChatMessageType::ChatMessage,
       id: self.ap_id.clone().into(),
       attributed_to: creator.actor_id.into(),
-      to: [recipient.actor_id.into()],
-      content: markdown_to_html(&self.content),
+      to,
+      content,
+      encrypted,
+      ciphertext,
       media_type: Some(MediaTypeHtml::Html),
-      source: Some(Source::new(self.content.clone())),
+      source,
       published: Some(convert_datetime(self.published)),
       updated: self.updated.map(convert_datetime),
     };
@@ -105,6 +144,10 @@ impl Object for ApubPrivateMessage {
     verify_domains_match(note.attributed_to.inner(), note.id.inner())?;
 
     check_apub_id_valid_with_strictness(note.id.inner(), false, context).await?;
+    check_conversation_size(note.to.len())?;
+    if note.encrypted == Some(true) {
+      is_valid_encrypted_content(note.ciphertext.as_deref().unwrap_or_default())?;
+    }
     let person = note.attributed_to.dereference(context).await?;
     if person.banned {
       Err(LemmyErrorType::PersonIsBannedFromSite(
@@ -121,15 +164,45 @@ impl Object for ApubPrivateMessage {
     context: &Data<Self::DataType>,
   ) -> Result<ApubPrivateMessage, LemmyError> {
     let creator = note.attributed_to.dereference(context).await?;
-    let recipient = note.to[0].dereference(context).await?;
-    check_person_block(creator.id, recipient.id, &mut context.pool()).await?;
+    let mut participant_ids = vec![creator.id];
+    let mut local_recipients = vec![];
+    for recipient in &note.to {
+      let recipient = recipient.dereference(context).await?;
+      check_person_block(creator.id, recipient.id, &mut context.pool()).await?;
+      participant_ids.push(recipient.id);
+      if let Ok(local_recipient) =
+        LocalUserView::read_person(&mut context.pool(), recipient.id).await
+      {
+        local_recipients.push(local_recipient);
+      }
+    }
+    let recipient_id = *participant_ids
+      .get(1)
+      .ok_or(LemmyErrorType::CouldntCreatePrivateMessage)?;
 
-    let content = read_from_string_or_source(&note.content, &None, &note.source);
-    let content = sanitize_html_federation(&content);
+    let recipients = local_recipients.iter().collect::<Vec<_>>();
+    let conversation = read_or_create_conversation(
+      &creator,
+      false,
+      &participant_ids,
+      &recipients,
+      &mut context.pool(),
+    )
+    .await?;
+
+    let encrypted = note.encrypted == Some(true);
+    let content = match (encrypted, note.ciphertext) {
+      // Validated in verify()
+      (true, Some(ciphertext)) => ciphertext,
+      _ => {
+        let content = read_from_string_or_source(&note.content, &None, &note.source);
+        sanitize_html_federation(&content)
+      }
+    };
 
     let form = PrivateMessageInsertForm {
       creator_id: creator.id,
-      recipient_id: recipient.id,
+      recipient_id,
       content,
       published: note.published.map(Into::into),
       updated: note.updated.map(Into::into),
@@ -137,6 +210,8 @@ impl Object for ApubPrivateMessage {
       read: None,
       ap_id: Some(note.id.into()),
       local: Some(false),
+      conversation_id: conversation.id,
+      encrypted: Some(encrypted),
     };
     let pm = PrivateMessage::create(&mut context.pool(), &form).await?;
     Ok(pm.into())
@@ -223,6 +298,41 @@ mod tests {
     cleanup(data, &context).await;
   }
 
+  #[tokio::test]
+  #[serial]
+  async fn test_parse_encrypted_pm() {
+    let context = init_context().await;
+    let url = Url::parse("https://enterprise.lemmy.ml/private_message/1621").unwrap();
+    let data = prepare_comment_test(&url, &context).await;
+    let mut json: ChatMessage =
+      file_to_json_object("assets/lemmy/objects/chat_message.json").unwrap();
+    json.encrypted = Some(true);
+    assert!(ApubPrivateMessage::verify(&json, &url, &context)
+      .await
+      .is_err());
+    json.ciphertext = Some("aGVsbG8=.c2VjcmV0".to_string());
+    ApubPrivateMessage::verify(&json, &url, &context)
+      .await
+      .unwrap();
+    let pm = ApubPrivateMessage::from_json(json, &context).await.unwrap();
+
+    assert!(pm.encrypted);
+    assert_eq!("aGVsbG8=.c2VjcmV0", pm.content);
+
+    // The ciphertext is federated separately from the readable notice
+    let pm_id = pm.id;
+    let to_apub = pm.into_json(&context).await.unwrap();
+    assert_eq!(Some(true), to_apub.encrypted);
+    assert_eq!(Some("aGVsbG8=.c2VjcmV0".to_string()), to_apub.ciphertext);
+    assert!(to_apub.content.contains(ENCRYPTED_MESSAGE_NOTICE));
+    assert!(to_apub.source.is_none());
+
+    PrivateMessage::delete(&mut context.pool(), pm_id)
+      .await
+      .unwrap();
+    cleanup(data, &context).await;
+  }
+
   #[tokio::test]
   #[serial]
   async fn test_parse_pleroma_pm() {
@@ -0,0 +1,28 @@
+use crate::{objects::person::ApubPerson, protocol::objects::page::deserialize_not_present};
+use activitypub_federation::{
+  fetch::object_id::ObjectId,
+  kinds::activity::BlockType,
+  protocol::helpers::deserialize_skip_error,
+};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+use url::Url;
+
+/// Block of one user by another, in the same format as sent by Mastodon. This is unrelated to
+/// `BlockUser`, which is used for bans and always has a target.
+#[skip_serializing_none]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockPerson {
+  pub(crate) actor: ObjectId<ApubPerson>,
+  /// Optional, for compatibility with platforms that always expect recipient field
+  #[serde(deserialize_with = "deserialize_skip_error", default)]
+  pub(crate) to: Option<[ObjectId<ApubPerson>; 1]>,
+  pub(crate) object: ObjectId<ApubPerson>,
+  #[serde(rename = "type")]
+  pub(crate) kind: BlockType,
+  pub(crate) id: Url,
+  /// Ensures that bans are never parsed as personal blocks
+  #[serde(deserialize_with = "deserialize_not_present", default)]
+  pub(crate) target: Option<String>,
+}
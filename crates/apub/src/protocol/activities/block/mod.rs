@@ -1,4 +1,6 @@
+pub mod block_person;
 pub mod block_user;
+pub mod undo_block_person;
 pub mod undo_block_user;
 
 #[cfg(test)]
@@ -7,7 +9,12 @@ mod tests {
   #![allow(clippy::indexing_slicing)]
 
   use crate::protocol::{
-    activities::block::{block_user::BlockUser, undo_block_user::UndoBlockUser},
+    activities::block::{
+      block_person::BlockPerson,
+      block_user::BlockUser,
+      undo_block_person::UndoBlockPerson,
+      undo_block_user::UndoBlockUser,
+    },
     tests::test_parse_lemmy_item,
   };
 
@@ -16,5 +23,17 @@ mod tests {
     test_parse_lemmy_item::<BlockUser>("assets/lemmy/activities/block/block_user.json").unwrap();
     test_parse_lemmy_item::<UndoBlockUser>("assets/lemmy/activities/block/undo_block_user.json")
       .unwrap();
+    test_parse_lemmy_item::<BlockPerson>("assets/lemmy/activities/block/block_person.json")
+      .unwrap();
+    test_parse_lemmy_item::<UndoBlockPerson>(
+      "assets/lemmy/activities/block/undo_block_person.json",
+    )
+    .unwrap();
+
+    // bans must not be mistaken for personal blocks
+    assert!(
+      test_parse_lemmy_item::<BlockPerson>("assets/lemmy/activities/block/block_user.json")
+        .is_err()
+    );
   }
 }
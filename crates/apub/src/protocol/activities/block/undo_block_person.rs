@@ -0,0 +1,21 @@
+use crate::{objects::person::ApubPerson, protocol::activities::block::block_person::BlockPerson};
+use activitypub_federation::{
+  fetch::object_id::ObjectId,
+  kinds::activity::UndoType,
+  protocol::helpers::deserialize_skip_error,
+};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UndoBlockPerson {
+  pub(crate) actor: ObjectId<ApubPerson>,
+  /// Optional, for compatibility with platforms that always expect recipient field
+  #[serde(deserialize_with = "deserialize_skip_error", default)]
+  pub(crate) to: Option<[ObjectId<ApubPerson>; 1]>,
+  pub(crate) object: BlockPerson,
+  #[serde(rename = "type")]
+  pub(crate) kind: UndoType,
+  pub(crate) id: Url,
+}
@@ -2,6 +2,7 @@ pub mod announce;
 pub mod collection_add;
 pub mod collection_remove;
 pub mod lock_page;
+pub mod move_community;
 pub mod report;
 pub mod update;
 
@@ -16,6 +17,7 @@ mod tests {
       collection_add::CollectionAdd,
       collection_remove::CollectionRemove,
       lock_page::{LockPage, UndoLockPage},
+      move_community::MoveCommunity,
       report::Report,
       update::UpdateCommunity,
     },
@@ -53,5 +55,8 @@ mod tests {
     .unwrap();
 
     test_parse_lemmy_item::<Report>("assets/lemmy/activities/community/report_page.json").unwrap();
+
+    test_parse_lemmy_item::<MoveCommunity>("assets/lemmy/activities/community/move_community.json")
+      .unwrap();
   }
 }
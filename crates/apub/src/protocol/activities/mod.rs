@@ -21,6 +21,7 @@ mod tests {
 
   use crate::protocol::{
     activities::{
+      block::{block_person::BlockPerson, undo_block_person::UndoBlockPerson},
       community::announce::AnnounceActivity,
       create_or_update::{note::CreateOrUpdateNote, page::CreateOrUpdatePage},
       deletion::delete::Delete,
@@ -50,6 +51,8 @@ mod tests {
     test_json::<UndoFollow>("assets/mastodon/activities/undo_follow.json").unwrap();
     test_json::<Vote>("assets/mastodon/activities/like_page.json").unwrap();
     test_json::<UndoVote>("assets/mastodon/activities/undo_like_page.json").unwrap();
+    test_json::<BlockPerson>("assets/mastodon/activities/block.json").unwrap();
+    test_json::<UndoBlockPerson>("assets/mastodon/activities/undo_block.json").unwrap();
   }
 
   #[test]
@@ -1,6 +1,10 @@
 use crate::protocol::activities::community::announce::AnnounceActivity;
-use activitypub_federation::kinds::collection::OrderedCollectionType;
+use activitypub_federation::{
+  kinds::collection::OrderedCollectionType,
+  protocol::helpers::deserialize_skip_error,
+};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use url::Url;
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -11,3 +15,17 @@ pub struct GroupOutbox {
   pub(crate) total_items: i32,
   pub(crate) ordered_items: Vec<AnnounceActivity>,
 }
+
+/// Generic view of an outbox collection or one of its pages, used to walk back through paginated
+/// outboxes during backfill. Items are kept as raw json so that they can be parsed one by one.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupOutboxPage {
+  #[serde(default)]
+  pub(crate) ordered_items: Vec<Value>,
+  /// Only set on the collection itself, if items are not included inline
+  #[serde(deserialize_with = "deserialize_skip_error", default)]
+  pub(crate) first: Option<Url>,
+  #[serde(deserialize_with = "deserialize_skip_error", default)]
+  pub(crate) next: Option<Url>,
+}
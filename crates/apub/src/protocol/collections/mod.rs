@@ -3,6 +3,7 @@ pub(crate) mod group_featured;
 pub(crate) mod group_followers;
 pub(crate) mod group_moderators;
 pub(crate) mod group_outbox;
+pub(crate) mod post_replies;
 
 #[cfg(test)]
 mod tests {
@@ -15,9 +16,10 @@ mod tests {
       group_featured::GroupFeatured,
       group_followers::GroupFollowers,
       group_moderators::GroupModerators,
-      group_outbox::GroupOutbox,
+      group_outbox::{GroupOutbox, GroupOutboxPage},
+      post_replies::PostReplies,
     },
-    tests::{test_json, test_parse_lemmy_item},
+    tests::{file_to_json_object, test_json, test_parse_lemmy_item},
   };
 
   #[test]
@@ -32,6 +34,16 @@ mod tests {
     test_parse_lemmy_item::<GroupModerators>("assets/lemmy/collections/group_moderators.json")
       .unwrap();
     test_parse_lemmy_item::<EmptyOutbox>("assets/lemmy/collections/person_outbox.json").unwrap();
+    let replies =
+      test_parse_lemmy_item::<PostReplies>("assets/lemmy/collections/post_replies.json").unwrap();
+    assert_eq!(replies.ordered_items.len() as i32, replies.total_items);
+
+    // unpaginated outbox can also be walked by backfill
+    let page =
+      file_to_json_object::<GroupOutboxPage>("assets/lemmy/collections/group_outbox.json").unwrap();
+    assert_eq!(2, page.ordered_items.len());
+    assert!(page.first.is_none());
+    assert!(page.next.is_none());
   }
 
   #[test]
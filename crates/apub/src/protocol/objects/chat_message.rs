@@ -5,7 +5,7 @@ use crate::{
 use activitypub_federation::{
   fetch::object_id::ObjectId,
   protocol::{
-    helpers::{deserialize_one, deserialize_skip_error},
+    helpers::{deserialize_one_or_many, deserialize_skip_error},
     values::MediaTypeHtml,
   },
 };
@@ -20,9 +20,14 @@ pub struct ChatMessage {
   pub(crate) r#type: ChatMessageType,
   pub(crate) id: ObjectId<ApubPrivateMessage>,
   pub(crate) attributed_to: ObjectId<ApubPerson>,
-  #[serde(deserialize_with = "deserialize_one")]
-  pub(crate) to: [ObjectId<ApubPerson>; 1],
+  /// More than one recipient for group conversations
+  #[serde(deserialize_with = "deserialize_one_or_many")]
+  pub(crate) to: Vec<ObjectId<ApubPerson>>,
   pub(crate) content: String,
+  /// Marks end-to-end encrypted messages. For these, `content` only has a notice for software
+  /// which doesn't support encryption, and the actual message is in `ciphertext`.
+  pub(crate) encrypted: Option<bool>,
+  pub(crate) ciphertext: Option<String>,
 
   pub(crate) media_type: Option<MediaTypeHtml>,
   #[serde(deserialize_with = "deserialize_skip_error", default)]
@@ -75,6 +75,12 @@ pub struct Group {
   pub(crate) language: Vec<LanguageTag>,
   pub(crate) published: Option<DateTime<Utc>>,
   pub(crate) updated: Option<DateTime<Utc>>,
+  /// Set if the community was moved to another instance, points to the new community
+  #[serde(deserialize_with = "deserialize_skip_error", default)]
+  pub(crate) moved_to: Option<ObjectId<ApubCommunity>>,
+  /// Set if the community was moved here from another instance, points to the old community
+  #[serde(deserialize_with = "deserialize_skip_error", default)]
+  pub(crate) also_known_as: Option<Vec<Url>>,
 }
 
 impl Group {
@@ -126,6 +132,12 @@ impl Group {
       posting_restricted_to_mods: self.posting_restricted_to_mods,
       instance_id,
       featured_url: self.featured.map(Into::into),
+      moved_to: self.moved_to.map(Into::into),
+      require_alt_text: None,
+      moved_from: self
+        .also_known_as
+        .and_then(|a| a.into_iter().next())
+        .map(Into::into),
     }
   }
 
@@ -156,6 +168,14 @@ impl Group {
       moderators_url: self.attributed_to.map(Into::into),
       posting_restricted_to_mods: self.posting_restricted_to_mods,
       featured_url: self.featured.map(Into::into),
+      moved_to: Some(self.moved_to.map(Into::into)),
+      require_alt_text: None,
+      moved_from: Some(
+        self
+          .also_known_as
+          .and_then(|a| a.into_iter().next())
+          .map(Into::into),
+      ),
     }
   }
 }
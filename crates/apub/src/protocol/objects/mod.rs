@@ -142,6 +142,20 @@ mod tests {
     test_json::<Page>("assets/mastodon/objects/page.json").unwrap();
   }
 
+  #[test]
+  fn test_parse_objects_pixelfed() {
+    let page = test_json::<Page>("assets/pixelfed/objects/page.json").unwrap();
+    assert_eq!(
+      2,
+      page
+        .inner()
+        .attachment
+        .iter()
+        .filter(|a| a.is_media())
+        .count()
+    );
+  }
+
   #[test]
   fn test_parse_objects_lotide() {
     test_json::<Group>("assets/lotide/objects/group.json").unwrap();
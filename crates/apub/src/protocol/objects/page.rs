@@ -1,12 +1,13 @@
 use crate::{
   activities::verify_community_matches,
+  collections::post_replies::ApubPostReplies,
   fetcher::user_or_community::{PersonOrGroupType, UserOrCommunity},
   objects::{community::ApubCommunity, person::ApubPerson, post::ApubPost},
   protocol::{objects::LanguageTag, ImageObject, InCommunity, Source},
 };
 use activitypub_federation::{
   config::Data,
-  fetch::object_id::ObjectId,
+  fetch::{collection_id::CollectionId, object_id::ObjectId},
   kinds::{
     link::LinkType,
     object::{DocumentType, ImageType},
@@ -57,7 +58,7 @@ pub struct Page {
   #[serde(deserialize_with = "deserialize_skip_error", default)]
   pub(crate) source: Option<Source>,
   /// most software uses array type for attachment field, so we do the same. nevertheless, we only
-  /// use the first item
+  /// use the first item, and its name as alt text
   #[serde(default)]
   pub(crate) attachment: Vec<Attachment>,
   pub(crate) image: Option<ImageObject>,
@@ -67,6 +68,9 @@ pub struct Page {
   pub(crate) updated: Option<DateTime<Utc>>,
   pub(crate) language: Option<LanguageTag>,
   pub(crate) audience: Option<ObjectId<ApubCommunity>>,
+  /// Collection with the newest comments, used for backfill
+  #[serde(deserialize_with = "deserialize_skip_error", default)]
+  pub(crate) replies: Option<CollectionId<ApubPostReplies>>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -76,20 +80,26 @@ pub(crate) struct Link {
   pub(crate) r#type: LinkType,
 }
 
+#[skip_serializing_none]
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct Image {
   #[serde(rename = "type")]
   pub(crate) kind: ImageType,
   pub(crate) url: Url,
+  /// Alt text of the image
+  pub(crate) name: Option<String>,
 }
 
+#[skip_serializing_none]
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct Document {
   #[serde(rename = "type")]
   pub(crate) kind: DocumentType,
   pub(crate) url: Url,
+  /// Alt text of the video or image
+  pub(crate) name: Option<String>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -101,14 +111,20 @@ pub(crate) enum Attachment {
 }
 
 impl Attachment {
-  pub(crate) fn url(self) -> Url {
+  /// Whether this is an image or video, as opposed to a link to a website.
+  pub(crate) fn is_media(&self) -> bool {
+    matches!(self, Attachment::Image(_) | Attachment::Document(_))
+  }
+
+  /// Returns the url and the alt text, if any.
+  pub(crate) fn url(self) -> (Url, Option<String>) {
     match self {
       // url as sent by Lemmy (new)
-      Attachment::Link(l) => l.href,
-      // image sent by lotide
-      Attachment::Image(i) => i.url,
-      // sent by mobilizon
-      Attachment::Document(d) => d.url,
+      Attachment::Link(l) => (l.href, None),
+      // image sent by lotide, or by Lemmy with alt text
+      Attachment::Image(i) => (i.url, i.name),
+      // sent by mobilizon, or by Lemmy for videos with alt text
+      Attachment::Document(d) => (d.url, d.name),
     }
   }
 }
@@ -167,14 +183,42 @@ impl Page {
 }
 
 impl Attachment {
-  pub(crate) fn new(url: DbUrl) -> Attachment {
-    Attachment::Link(Link {
-      href: url.into(),
-      r#type: Default::default(),
-    })
+  /// Links are sent as images or documents if they have alt text, so that other platforms like
+  /// Mastodon show the description.
+  pub(crate) fn new(url: DbUrl, alt_text: Option<String>) -> Attachment {
+    match alt_text {
+      Some(_) => Attachment::new_media(url, alt_text),
+      None => Attachment::Link(Link {
+        href: url.into(),
+        r#type: Default::default(),
+      }),
+    }
+  }
+
+  /// Used for the images of gallery posts, which are always sent as media.
+  pub(crate) fn new_media(url: DbUrl, alt_text: Option<String>) -> Attachment {
+    let url: Url = url.into();
+    if is_video_url(&url) {
+      Attachment::Document(Document {
+        kind: Default::default(),
+        url,
+        name: alt_text,
+      })
+    } else {
+      Attachment::Image(Image {
+        kind: Default::default(),
+        url,
+        name: alt_text,
+      })
+    }
   }
 }
 
+fn is_video_url(url: &Url) -> bool {
+  let path = url.path().to_lowercase();
+  path.ends_with(".mp4") || path.ends_with(".webm")
+}
+
 // Used for community outbox, so that it can be compatible with Pleroma/Mastodon.
 #[async_trait::async_trait]
 impl ActivityHandler for Page {
@@ -236,7 +280,7 @@ where
   let result: Option<String> = Deserialize::deserialize(deserializer)?;
   match result {
     None => Ok(None),
-    Some(_) => Err(D::Error::custom("Post must not have inReplyTo property")),
+    Some(_) => Err(D::Error::custom("Field must not be present")),
   }
 }
 
@@ -42,7 +42,17 @@ pub struct Person {
   /// user banner
   pub(crate) image: Option<ImageObject>,
   pub(crate) matrix_user_id: Option<String>,
+  /// Public keys of the devices which can read end-to-end encrypted private messages
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub(crate) device_keys: Vec<DeviceKey>,
   pub(crate) endpoints: Option<Endpoints>,
   pub(crate) published: Option<DateTime<Utc>>,
   pub(crate) updated: Option<DateTime<Utc>>,
 }
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceKey {
+  pub(crate) id: String,
+  pub(crate) public_key: String,
+}
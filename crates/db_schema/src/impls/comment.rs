@@ -1,20 +1,33 @@
 use crate::{
-  newtypes::{CommentId, DbUrl, PersonId},
-  schema::comment::dsl::{ap_id, comment, content, creator_id, deleted, path, removed, updated},
+  newtypes::{CommentId, DbUrl, PersonId, PostId},
+  schema::comment::dsl::{
+    ap_id,
+    comment,
+    content,
+    creator_id,
+    deleted,
+    path,
+    post_id,
+    published,
+    removed,
+    updated,
+  },
   source::comment::{
     Comment,
     CommentInsertForm,
     CommentLike,
     CommentLikeForm,
+    CommentMute,
+    CommentMuteForm,
     CommentSaved,
     CommentSavedForm,
     CommentUpdateForm,
   },
   traits::{Crud, Likeable, Saveable},
-  utils::{get_conn, naive_now, DbPool, DELETED_REPLACEMENT_TEXT},
+  utils::{get_conn, naive_now, DbPool, DELETED_REPLACEMENT_TEXT, FETCH_LIMIT_MAX},
 };
 use diesel::{
-  dsl::{insert_into, sql_query},
+  dsl::{exists, insert_into, select, sql_query},
   result::Error,
   ExpressionMethods,
   QueryDsl,
@@ -52,6 +65,25 @@ impl Comment {
       .await
   }
 
+  /// Returns the newest comments of the post, ordered from old to new so that parent comments
+  /// always come before their replies.
+  pub async fn list_for_post(
+    pool: &mut DbPool<'_>,
+    the_post_id: PostId,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let mut comments = comment
+      .filter(post_id.eq(the_post_id))
+      .filter(deleted.eq(false))
+      .filter(removed.eq(false))
+      .order_by(published.desc())
+      .limit(FETCH_LIMIT_MAX)
+      .load::<Self>(conn)
+      .await?;
+    comments.reverse();
+    Ok(comments)
+  }
+
   pub async fn create(
     pool: &mut DbPool<'_>,
     comment_form: &CommentInsertForm,
@@ -149,6 +181,18 @@ where ca.comment_id = c.id"
       None
     }
   }
+
+  /// The ids of all comments above this one in the comment tree.
+  pub fn ancestor_ids(&self) -> Vec<CommentId> {
+    self
+      .path
+      .0
+      .split('.')
+      .skip(1) // The first is always 0
+      .filter_map(|p| p.parse::<i32>().map(CommentId).ok())
+      .filter(|ancestor_id| *ancestor_id != self.id)
+      .collect()
+  }
 }
 
 #[async_trait]
@@ -240,6 +284,47 @@ impl Saveable for CommentSaved {
   }
 }
 
+impl CommentMute {
+  pub async fn mute(pool: &mut DbPool<'_>, form: &CommentMuteForm) -> Result<usize, Error> {
+    use crate::schema::comment_mute::dsl::comment_mute;
+    let conn = &mut get_conn(pool).await?;
+    insert_into(comment_mute)
+      .values(form)
+      .on_conflict_do_nothing()
+      .execute(conn)
+      .await
+  }
+
+  pub async fn unmute(pool: &mut DbPool<'_>, form: &CommentMuteForm) -> Result<usize, Error> {
+    use crate::schema::comment_mute::dsl::{comment_id, comment_mute, person_id};
+    let conn = &mut get_conn(pool).await?;
+    diesel::delete(
+      comment_mute
+        .filter(comment_id.eq(form.comment_id))
+        .filter(person_id.eq(form.person_id)),
+    )
+    .execute(conn)
+    .await
+  }
+
+  /// Checks if the person muted any of the given comments.
+  pub async fn is_muted(
+    pool: &mut DbPool<'_>,
+    for_person_id: PersonId,
+    for_comment_ids: &[CommentId],
+  ) -> Result<bool, Error> {
+    use crate::schema::comment_mute::dsl::{comment_id, comment_mute, person_id};
+    let conn = &mut get_conn(pool).await?;
+    select(exists(
+      comment_mute
+        .filter(person_id.eq(for_person_id))
+        .filter(comment_id.eq_any(for_comment_ids)),
+    ))
+    .get_result(conn)
+    .await
+  }
+}
+
 #[cfg(test)]
 mod tests {
   #![allow(clippy::unwrap_used)]
@@ -253,6 +338,8 @@ mod tests {
         CommentInsertForm,
         CommentLike,
         CommentLikeForm,
+        CommentMute,
+        CommentMuteForm,
         CommentSaved,
         CommentSavedForm,
         CommentUpdateForm,
@@ -382,12 +469,30 @@ mod tests {
       .unwrap();
 
     let read_comment = Comment::read(pool, inserted_comment.id).await.unwrap();
+    let post_comments = Comment::list_for_post(pool, inserted_post.id)
+      .await
+      .unwrap();
     let like_removed = CommentLike::remove(pool, inserted_person.id, inserted_comment.id)
       .await
       .unwrap();
     let saved_removed = CommentSaved::unsave(pool, &comment_saved_form)
       .await
       .unwrap();
+
+    // muting a comment mutes the whole chain below it
+    let comment_mute_form = CommentMuteForm {
+      person_id: inserted_person.id,
+      comment_id: inserted_comment.id,
+    };
+    CommentMute::mute(pool, &comment_mute_form).await.unwrap();
+    let child_muted = CommentMute::is_muted(
+      pool,
+      inserted_person.id,
+      &inserted_child_comment.ancestor_ids(),
+    )
+    .await
+    .unwrap();
+    let unmuted = CommentMute::unmute(pool, &comment_mute_form).await.unwrap();
     let num_deleted = Comment::delete(pool, inserted_comment.id).await.unwrap();
     Comment::delete(pool, inserted_child_comment.id)
       .await
@@ -408,8 +513,19 @@ mod tests {
       format!("0.{}.{}", expected_comment.id, inserted_child_comment.id),
       inserted_child_comment.path.0,
     );
+    // parent comes before its reply
+    assert_eq!(
+      vec![inserted_comment.id, inserted_child_comment.id],
+      post_comments.iter().map(|c| c.id).collect::<Vec<_>>()
+    );
     assert_eq!(1, like_removed);
     assert_eq!(1, saved_removed);
+    assert_eq!(
+      vec![inserted_comment.id],
+      inserted_child_comment.ancestor_ids()
+    );
+    assert!(child_muted);
+    assert_eq!(1, unmuted);
     assert_eq!(1, num_deleted);
   }
 }
@@ -1,6 +1,6 @@
 use crate::{
   newtypes::{CommunityId, DbUrl, PersonId},
-  schema::{community, community_follower, instance},
+  schema::{community, community_follower, instance, person},
   source::{
     actor_language::CommunityLanguage,
     community::{
@@ -229,6 +229,49 @@ impl CommunityFollower {
   pub fn select_subscribed_type() -> dsl::Nullable<community_follower::pending> {
     community_follower::pending.nullable()
   }
+
+  /// Accepts a pending follow, and returns if the person became the first local follower of the
+  /// community. Accepts for the same community are serialized with a row lock, so that exactly
+  /// one of several concurrent accepts is the first, and repeated accepts never are.
+  pub async fn follow_accepted_first_local(
+    pool: &mut DbPool<'_>,
+    community_id: CommunityId,
+    person_id: PersonId,
+  ) -> Result<bool, Error> {
+    let conn = &mut get_conn(pool).await?;
+    conn
+      .build_transaction()
+      .run(|conn| {
+        Box::pin(async move {
+          community::table
+            .find(community_id)
+            .select(community::id)
+            .for_update()
+            .first::<CommunityId>(conn)
+            .await?;
+          // This will throw an error if no follow was requested
+          let follower = community_follower::table
+            .filter(community_follower::community_id.eq(community_id))
+            .filter(community_follower::person_id.eq(person_id))
+            .first::<Self>(conn)
+            .await?;
+          CommunityFollower::follow_accepted(&mut conn.into(), community_id, person_id).await?;
+          if !follower.pending {
+            return Ok(false);
+          }
+          let local_followers = community_follower::table
+            .inner_join(person::table)
+            .filter(community_follower::community_id.eq(community_id))
+            .filter(community_follower::pending.eq(false))
+            .filter(person::local)
+            .count()
+            .get_result::<i64>(conn)
+            .await?;
+          Ok(local_followers == 1)
+        }) as _
+      })
+      .await
+  }
 }
 
 impl Queryable<sql_types::Nullable<sql_types::Bool>, Pg> for SubscribedType {
@@ -419,6 +462,9 @@ mod tests {
       hidden: false,
       posting_restricted_to_mods: false,
       instance_id: inserted_instance.id,
+      moved_to: None,
+      require_alt_text: false,
+      moved_from: None,
     };
 
     let community_follower_form = CommunityFollowerForm {
@@ -510,4 +556,61 @@ mod tests {
     // assert_eq!(2, loaded_count);
     assert_eq!(1, num_deleted);
   }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_follow_accepted_first_local() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+    let community_form = CommunityInsertForm::builder()
+      .name("first_follower_community".into())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(instance.id)
+      .build();
+    let community = Community::create(pool, &community_form).await.unwrap();
+
+    let mut persons = vec![];
+    for name in ["first_follower_1", "first_follower_2"] {
+      let person_form = PersonInsertForm::builder()
+        .name(name.into())
+        .public_key("pubkey".to_string())
+        .instance_id(instance.id)
+        .build();
+      let person = Person::create(pool, &person_form).await.unwrap();
+      let follower_form = CommunityFollowerForm {
+        community_id: community.id,
+        person_id: person.id,
+        pending: true,
+      };
+      CommunityFollower::follow(pool, &follower_form)
+        .await
+        .unwrap();
+      persons.push(person);
+    }
+
+    let first = CommunityFollower::follow_accepted_first_local(pool, community.id, persons[0].id)
+      .await
+      .unwrap();
+    assert!(first);
+    // A repeated accept doesn't count again
+    let repeated =
+      CommunityFollower::follow_accepted_first_local(pool, community.id, persons[0].id)
+        .await
+        .unwrap();
+    assert!(!repeated);
+    let second = CommunityFollower::follow_accepted_first_local(pool, community.id, persons[1].id)
+      .await
+      .unwrap();
+    assert!(!second);
+
+    Community::delete(pool, community.id).await.unwrap();
+    for person in persons {
+      Person::delete(pool, person.id).await.unwrap();
+    }
+  }
 }
@@ -0,0 +1,421 @@
+use crate::{
+  newtypes::{ConversationId, PersonId},
+  schema::{conversation, conversation_participant, person, private_message},
+  source::{
+    conversation::{
+      Conversation,
+      ConversationParticipant,
+      ConversationParticipantInsertForm,
+      ConversationParticipantUpdateForm,
+    },
+    person::Person,
+  },
+  utils::{get_conn, naive_now, DbPool},
+};
+use chrono::{DateTime, Duration, Utc};
+use diesel::{
+  dsl::{count_star, insert_into, sql_query},
+  result::Error,
+  sql_types::Text,
+  BoolExpressionMethods,
+  ExpressionMethods,
+  OptionalExtension,
+  QueryDsl,
+};
+use diesel_async::RunQueryDsl;
+
+impl Conversation {
+  pub async fn read(pool: &mut DbPool<'_>, conversation_id: ConversationId) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    conversation::table
+      .find(conversation_id)
+      .first::<Self>(conn)
+      .await
+  }
+
+  /// Returns the conversation between exactly the given persons, if it exists.
+  pub async fn read_for_participants(
+    pool: &mut DbPool<'_>,
+    participant_ids: &[PersonId],
+  ) -> Result<Option<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let mut participant_ids = participant_ids.to_vec();
+    participant_ids.sort_by_key(|p| p.0);
+    participant_ids.dedup();
+    let participant_count = i64::try_from(participant_ids.len()).unwrap_or(i64::MAX);
+
+    // Conversations which include all of the persons
+    let candidates = conversation_participant::table
+      .filter(conversation_participant::person_id.eq_any(&participant_ids))
+      .group_by(conversation_participant::conversation_id)
+      .having(count_star().eq(participant_count))
+      .select(conversation_participant::conversation_id)
+      .load::<ConversationId>(conn)
+      .await?;
+    // Of those, the one which doesn't include anyone else
+    let existing = conversation_participant::table
+      .filter(conversation_participant::conversation_id.eq_any(candidates))
+      .group_by(conversation_participant::conversation_id)
+      .having(count_star().eq(participant_count))
+      .select(conversation_participant::conversation_id)
+      .first::<ConversationId>(conn)
+      .await
+      .optional()?;
+    match existing {
+      Some(conversation_id) => conversation::table
+        .find(conversation_id)
+        .first::<Self>(conn)
+        .await
+        .optional(),
+      None => Ok(None),
+    }
+  }
+
+  /// Returns the conversation between exactly the given persons, and creates it if it doesn't
+  /// exist yet. For a new conversation, it is a message request for the given participants.
+  pub async fn read_or_create(
+    pool: &mut DbPool<'_>,
+    participant_ids: &[PersonId],
+    message_request_ids: &[PersonId],
+  ) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let mut participant_ids = participant_ids.to_vec();
+    participant_ids.sort_by_key(|p| p.0);
+    participant_ids.dedup();
+    let message_request_ids = message_request_ids.to_vec();
+    // There is no unique key for a set of participants, so concurrent creation for the same
+    // persons is serialized with a lock on the participant ids instead.
+    let lock_key = participant_ids
+      .iter()
+      .map(|p| p.0.to_string())
+      .collect::<Vec<_>>()
+      .join(",");
+    conn
+      .build_transaction()
+      .run(|conn| {
+        Box::pin(async move {
+          sql_query("SELECT pg_advisory_xact_lock(hashtext('conversation:' || $1))")
+            .bind::<Text, _>(lock_key)
+            .execute(conn)
+            .await?;
+          if let Some(existing) =
+            Self::read_for_participants(&mut conn.into(), &participant_ids).await?
+          {
+            return Ok(existing);
+          }
+
+          let inserted = insert_into(conversation::table)
+            .default_values()
+            .get_result::<Self>(conn)
+            .await?;
+          let forms = participant_ids
+            .into_iter()
+            .map(|person_id| ConversationParticipantInsertForm {
+              conversation_id: inserted.id,
+              person_id,
+              message_request: message_request_ids.contains(&person_id),
+            })
+            .collect::<Vec<_>>();
+          insert_into(conversation_participant::table)
+            .values(forms)
+            .execute(conn)
+            .await?;
+          Ok(inserted)
+        }) as _
+      })
+      .await
+  }
+}
+
+impl ConversationParticipant {
+  pub async fn read(
+    pool: &mut DbPool<'_>,
+    conversation_id: ConversationId,
+    person_id: PersonId,
+  ) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    conversation_participant::table
+      .find((conversation_id, person_id))
+      .first::<Self>(conn)
+      .await
+  }
+
+  pub async fn update(
+    pool: &mut DbPool<'_>,
+    conversation_id: ConversationId,
+    person_id: PersonId,
+    form: &ConversationParticipantUpdateForm,
+  ) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(conversation_participant::table.find((conversation_id, person_id)))
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  /// Moves the conversation from the message requests of the person to their inbox. Returns the
+  /// number of updated rows, which is zero if it wasn't a message request.
+  pub async fn accept_message_request(
+    pool: &mut DbPool<'_>,
+    conversation_id: ConversationId,
+    person_id: PersonId,
+  ) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(
+      conversation_participant::table
+        .find((conversation_id, person_id))
+        .filter(conversation_participant::message_request.eq(true)),
+    )
+    .set(conversation_participant::message_request.eq(false))
+    .execute(conn)
+    .await
+  }
+
+  /// Returns all persons taking part in the conversation.
+  pub async fn list_persons(
+    pool: &mut DbPool<'_>,
+    conversation_id: ConversationId,
+  ) -> Result<Vec<Person>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    conversation_participant::table
+      .inner_join(person::table)
+      .filter(conversation_participant::conversation_id.eq(conversation_id))
+      .order_by(person::id)
+      .select(person::all_columns)
+      .load::<Person>(conn)
+      .await
+  }
+
+  /// Marks all messages in the conversation as read for the person.
+  pub async fn mark_as_read(
+    pool: &mut DbPool<'_>,
+    conversation_id: ConversationId,
+    person_id: PersonId,
+  ) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(
+      private_message::table
+        .filter(private_message::conversation_id.eq(conversation_id))
+        .filter(private_message::recipient_id.eq(person_id))
+        .filter(private_message::read.eq(false)),
+    )
+    .set(private_message::read.eq(true))
+    .execute(conn)
+    .await?;
+    diesel::update(conversation_participant::table.find((conversation_id, person_id)))
+      .set(conversation_participant::last_read.eq(naive_now()))
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  /// Moves the read marker of the person, so that a message with the given publish time counts as
+  /// read or unread in the conversation.
+  pub async fn mark_message_as_read(
+    pool: &mut DbPool<'_>,
+    conversation_id: ConversationId,
+    person_id: PersonId,
+    published: DateTime<Utc>,
+    read: bool,
+  ) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let participant = conversation_participant::table.find((conversation_id, person_id));
+    if read {
+      diesel::update(
+        participant.filter(
+          conversation_participant::last_read
+            .is_null()
+            .or(conversation_participant::last_read.lt(published)),
+        ),
+      )
+      .set(conversation_participant::last_read.eq(published))
+      .execute(conn)
+      .await?;
+    } else {
+      diesel::update(participant.filter(conversation_participant::last_read.ge(published)))
+        .set(conversation_participant::last_read.eq(published - Duration::microseconds(1)))
+        .execute(conn)
+        .await?;
+    }
+    participant.first::<Self>(conn).await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use crate::{
+    source::{
+      conversation::{Conversation, ConversationParticipant, ConversationParticipantUpdateForm},
+      instance::Instance,
+      person::{Person, PersonInsertForm},
+    },
+    traits::Crud,
+    utils::build_db_pool_for_tests,
+  };
+  use chrono::Duration;
+  use futures_util::future::join_all;
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_crud() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+    let mut persons = vec![];
+    for name in ["conversation_a", "conversation_b", "conversation_c"] {
+      let form = PersonInsertForm::builder()
+        .name(name.into())
+        .public_key("pubkey".to_string())
+        .instance_id(inserted_instance.id)
+        .build();
+      persons.push(Person::create(pool, &form).await.unwrap());
+    }
+    let (a, b, c) = (persons[0].id, persons[1].id, persons[2].id);
+
+    // the same set of participants always gives the same conversation
+    let a_b = Conversation::read_or_create(pool, &[a, b], &[])
+      .await
+      .unwrap();
+    let b_a = Conversation::read_or_create(pool, &[b, a], &[])
+      .await
+      .unwrap();
+    assert_eq!(a_b, b_a);
+
+    // but smaller or larger groups are separate conversations
+    assert!(Conversation::read_for_participants(pool, &[a, b, c])
+      .await
+      .unwrap()
+      .is_none());
+    let a_b_c = Conversation::read_or_create(pool, &[a, b, c], &[c])
+      .await
+      .unwrap();
+    let a_only = Conversation::read_or_create(pool, &[a, a], &[])
+      .await
+      .unwrap();
+    assert_ne!(a_b.id, a_b_c.id);
+    assert_ne!(a_b.id, a_only.id);
+    assert_eq!(
+      a_b_c,
+      Conversation::read_or_create(pool, &[c, a, b], &[])
+        .await
+        .unwrap()
+    );
+
+    let participants = ConversationParticipant::list_persons(pool, a_b_c.id)
+      .await
+      .unwrap();
+    assert_eq!(persons, participants);
+    assert!(
+      ConversationParticipant::read(pool, a_b_c.id, c)
+        .await
+        .unwrap()
+        .message_request
+    );
+    assert!(
+      !ConversationParticipant::read(pool, a_b_c.id, a)
+        .await
+        .unwrap()
+        .message_request
+    );
+    assert_eq!(
+      1,
+      ConversationParticipant::accept_message_request(pool, a_b_c.id, c)
+        .await
+        .unwrap()
+    );
+    assert_eq!(
+      0,
+      ConversationParticipant::accept_message_request(pool, a_b_c.id, c)
+        .await
+        .unwrap()
+    );
+    assert_eq!(
+      1,
+      ConversationParticipant::list_persons(pool, a_only.id)
+        .await
+        .unwrap()
+        .len()
+    );
+
+    let form = ConversationParticipantUpdateForm {
+      muted: Some(true),
+      ..Default::default()
+    };
+    let updated = ConversationParticipant::update(pool, a_b.id, a, &form)
+      .await
+      .unwrap();
+    assert!(updated.muted);
+    assert!(!updated.archived);
+    assert!(updated.last_read.is_none());
+    let read = ConversationParticipant::mark_as_read(pool, a_b.id, a)
+      .await
+      .unwrap();
+    assert!(read.last_read.is_some());
+
+    // marking a single message moves the read marker just past or before it
+    let published = read.last_read.unwrap() - Duration::seconds(10);
+    let unread = ConversationParticipant::mark_message_as_read(pool, a_b.id, a, published, false)
+      .await
+      .unwrap();
+    assert!(unread.last_read.unwrap() < published);
+    let read = ConversationParticipant::mark_message_as_read(pool, a_b.id, a, published, true)
+      .await
+      .unwrap();
+    assert_eq!(Some(published), read.last_read);
+    let older = published - Duration::seconds(10);
+    let still_read = ConversationParticipant::mark_message_as_read(pool, a_b.id, a, older, true)
+      .await
+      .unwrap();
+    assert_eq!(Some(published), still_read.last_read);
+    assert!(
+      !ConversationParticipant::read(pool, a_b.id, b)
+        .await
+        .unwrap()
+        .muted
+    );
+
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_read_or_create_concurrently() {
+    let pool = &build_db_pool_for_tests().await;
+
+    let inserted_instance = Instance::read_or_create(&mut pool.into(), "my_domain.tld".to_string())
+      .await
+      .unwrap();
+    let mut persons = vec![];
+    for name in ["conversation_concurrent_a", "conversation_concurrent_b"] {
+      let form = PersonInsertForm::builder()
+        .name(name.into())
+        .public_key("pubkey".to_string())
+        .instance_id(inserted_instance.id)
+        .build();
+      persons.push(Person::create(&mut pool.into(), &form).await.unwrap().id);
+    }
+
+    let conversations = join_all((0..5).map(|_| {
+      let pool = pool.clone();
+      let persons = persons.clone();
+      tokio::spawn(async move {
+        Conversation::read_or_create(&mut (&pool).into(), &persons, &[])
+          .await
+          .unwrap()
+      })
+    }))
+    .await;
+    let first = conversations[0].as_ref().unwrap();
+    assert!(conversations.iter().all(|c| c.as_ref().unwrap() == first));
+
+    Instance::delete(&mut pool.into(), inserted_instance.id)
+      .await
+      .unwrap();
+  }
+}
@@ -0,0 +1,162 @@
+use crate::{
+  newtypes::{CommunityId, CustomFeedId},
+  schema::{
+    custom_feed::dsl::custom_feed,
+    custom_feed_community::dsl::{custom_feed_community, custom_feed_id},
+  },
+  source::custom_feed::{
+    CustomFeed,
+    CustomFeedCommunity,
+    CustomFeedCommunityForm,
+    CustomFeedInsertForm,
+    CustomFeedUpdateForm,
+  },
+  traits::Crud,
+  utils::{get_conn, DbPool},
+};
+use diesel::{dsl::insert_into, result::Error, ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+
+#[async_trait]
+impl Crud for CustomFeed {
+  type InsertForm = CustomFeedInsertForm;
+  type UpdateForm = CustomFeedUpdateForm;
+  type IdType = CustomFeedId;
+
+  async fn create(pool: &mut DbPool<'_>, form: &Self::InsertForm) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(custom_feed)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  async fn update(
+    pool: &mut DbPool<'_>,
+    feed_id: CustomFeedId,
+    form: &Self::UpdateForm,
+  ) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(custom_feed.find(feed_id))
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+}
+
+impl CustomFeed {
+  /// Replaces the communities of the feed.
+  pub async fn set_communities(
+    pool: &mut DbPool<'_>,
+    feed_id: CustomFeedId,
+    community_ids: Vec<CommunityId>,
+  ) -> Result<Vec<CustomFeedCommunity>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let forms = community_ids
+      .into_iter()
+      .map(|community_id| CustomFeedCommunityForm {
+        custom_feed_id: feed_id,
+        community_id,
+      })
+      .collect::<Vec<_>>();
+    conn
+      .build_transaction()
+      .run(|conn| {
+        Box::pin(async move {
+          diesel::delete(custom_feed_community.filter(custom_feed_id.eq(feed_id)))
+            .execute(conn)
+            .await?;
+          if forms.is_empty() {
+            return Ok(vec![]);
+          }
+          insert_into(custom_feed_community)
+            .values(forms)
+            .on_conflict_do_nothing()
+            .get_results::<CustomFeedCommunity>(conn)
+            .await
+        }) as _
+      })
+      .await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use crate::{
+    source::{
+      community::{Community, CommunityInsertForm},
+      custom_feed::{CustomFeed, CustomFeedInsertForm, CustomFeedUpdateForm},
+      instance::Instance,
+      person::{Person, PersonInsertForm},
+    },
+    traits::Crud,
+    utils::build_db_pool_for_tests,
+  };
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_crud() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+    let new_person = PersonInsertForm::builder()
+      .name("custom_feed_creator".into())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_person = Person::create(pool, &new_person).await.unwrap();
+    let new_community = CommunityInsertForm::builder()
+      .name("custom_feed_community".into())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_community = Community::create(pool, &new_community).await.unwrap();
+
+    let form = CustomFeedInsertForm::builder()
+      .creator_id(inserted_person.id)
+      .name("Linux".to_string())
+      .build();
+    let inserted_feed = CustomFeed::create(pool, &form).await.unwrap();
+    assert!(!inserted_feed.public);
+
+    let communities = CustomFeed::set_communities(
+      pool,
+      inserted_feed.id,
+      vec![inserted_community.id, inserted_community.id],
+    )
+    .await
+    .unwrap();
+    assert_eq!(1, communities.len());
+    assert_eq!(inserted_community.id, communities[0].community_id);
+    let communities = CustomFeed::set_communities(pool, inserted_feed.id, vec![])
+      .await
+      .unwrap();
+    assert!(communities.is_empty());
+
+    let form = CustomFeedUpdateForm {
+      public: Some(true),
+      ..Default::default()
+    };
+    let updated_feed = CustomFeed::update(pool, inserted_feed.id, &form)
+      .await
+      .unwrap();
+    assert!(updated_feed.public);
+    assert_eq!("Linux", updated_feed.name);
+
+    // The feed is removed together with its creator
+    Person::delete(pool, inserted_person.id).await.unwrap();
+    assert!(CustomFeed::read(pool, inserted_feed.id).await.is_err());
+    Community::delete(pool, inserted_community.id)
+      .await
+      .unwrap();
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
+}
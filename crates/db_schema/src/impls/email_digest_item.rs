@@ -0,0 +1,186 @@
+use crate::{
+  newtypes::LocalUserId,
+  schema::{email_digest_item, local_user},
+  source::email_digest_item::{EmailDigestItem, EmailDigestItemForm},
+  utils::{get_conn, now, DbPool},
+  EmailDigestMode,
+};
+use diesel::{
+  dsl::IntervalDsl,
+  insert_into,
+  result::Error,
+  BoolExpressionMethods,
+  ExpressionMethods,
+  NullableExpressionMethods,
+  QueryDsl,
+};
+use diesel_async::RunQueryDsl;
+
+impl EmailDigestItem {
+  pub async fn create(pool: &mut DbPool<'_>, form: &EmailDigestItemForm) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(email_digest_item::table)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  /// Lists the users with pending items whose digest is due. Users who turned digests off still
+  /// get their remaining items, so that nothing is left behind.
+  pub async fn list_due_local_users(pool: &mut DbPool<'_>) -> Result<Vec<LocalUserId>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    email_digest_item::table
+      .inner_join(local_user::table)
+      .filter(
+        local_user::email_digest
+          .eq(EmailDigestMode::Off)
+          .or(
+            local_user::email_digest.eq(EmailDigestMode::Daily).and(
+              local_user::last_email_digest
+                .is_null()
+                .or(local_user::last_email_digest.le(now().nullable() - 1.days())),
+            ),
+          )
+          .or(
+            local_user::email_digest.eq(EmailDigestMode::Weekly).and(
+              local_user::last_email_digest
+                .is_null()
+                .or(local_user::last_email_digest.le(now().nullable() - 1.weeks())),
+            ),
+          ),
+      )
+      .select(local_user::id)
+      .distinct()
+      .load::<LocalUserId>(conn)
+      .await
+  }
+
+  /// Removes and returns the pending items of a user, oldest first.
+  pub async fn take_for_user(
+    pool: &mut DbPool<'_>,
+    local_user_id: LocalUserId,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let mut items = diesel::delete(
+      email_digest_item::table.filter(email_digest_item::local_user_id.eq(local_user_id)),
+    )
+    .get_results::<Self>(conn)
+    .await?;
+    items.sort_by_key(|i| i.published);
+    Ok(items)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use crate::{
+    source::{
+      email_digest_item::{EmailDigestItem, EmailDigestItemForm},
+      instance::Instance,
+      local_user::{LocalUser, LocalUserInsertForm, LocalUserUpdateForm},
+      person::{Person, PersonInsertForm},
+    },
+    traits::Crud,
+    utils::{build_db_pool_for_tests, naive_now, DbPool},
+    EmailDigestMode,
+  };
+  use chrono::Duration;
+  use serial_test::serial;
+
+  async fn create_digest_user(
+    pool: &mut DbPool<'_>,
+    name: &str,
+    mode: EmailDigestMode,
+    last_digest_hours_ago: Option<i64>,
+  ) -> LocalUser {
+    let instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+    let person_form = PersonInsertForm::builder()
+      .name(name.into())
+      .public_key("pubkey".to_string())
+      .instance_id(instance.id)
+      .build();
+    let person = Person::create(pool, &person_form).await.unwrap();
+    let local_user_form = LocalUserInsertForm::builder()
+      .person_id(person.id)
+      .password_encrypted("pass".to_string())
+      .email_digest(Some(mode))
+      .build();
+    let local_user = LocalUser::create(pool, &local_user_form).await.unwrap();
+    let update_form = LocalUserUpdateForm {
+      last_email_digest: Some(last_digest_hours_ago.map(|h| naive_now() - Duration::hours(h))),
+      ..Default::default()
+    };
+    LocalUser::update(pool, local_user.id, &update_form)
+      .await
+      .unwrap();
+
+    let item_form = EmailDigestItemForm {
+      local_user_id: local_user.id,
+      subject: format!("subject for {name}"),
+      html: "<p>body</p>".into(),
+    };
+    EmailDigestItem::create(pool, &item_form).await.unwrap();
+    EmailDigestItem::create(pool, &item_form).await.unwrap();
+    local_user
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_list_due_and_take() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let daily_new =
+      create_digest_user(pool, "digest_daily_new", EmailDigestMode::Daily, None).await;
+    let daily_recent =
+      create_digest_user(pool, "digest_daily_recent", EmailDigestMode::Daily, Some(1)).await;
+    let daily_old =
+      create_digest_user(pool, "digest_daily_old", EmailDigestMode::Daily, Some(25)).await;
+    let weekly_recent = create_digest_user(
+      pool,
+      "digest_weekly_recent",
+      EmailDigestMode::Weekly,
+      Some(48),
+    )
+    .await;
+    let switched_off =
+      create_digest_user(pool, "digest_switched_off", EmailDigestMode::Off, Some(1)).await;
+
+    let due = EmailDigestItem::list_due_local_users(pool).await.unwrap();
+    assert!(due.contains(&daily_new.id));
+    assert!(!due.contains(&daily_recent.id));
+    assert!(due.contains(&daily_old.id));
+    assert!(!due.contains(&weekly_recent.id));
+    assert!(due.contains(&switched_off.id));
+
+    let items = EmailDigestItem::take_for_user(pool, daily_old.id)
+      .await
+      .unwrap();
+    assert_eq!(2, items.len());
+    assert!(items[0].published <= items[1].published);
+    assert!(items.iter().all(|i| i.local_user_id == daily_old.id));
+
+    // Taken items are gone, so the user is no longer due
+    let items_again = EmailDigestItem::take_for_user(pool, daily_old.id)
+      .await
+      .unwrap();
+    assert!(items_again.is_empty());
+    let due = EmailDigestItem::list_due_local_users(pool).await.unwrap();
+    assert!(!due.contains(&daily_old.id));
+
+    for local_user in [
+      daily_new,
+      daily_recent,
+      daily_old,
+      weekly_recent,
+      switched_off,
+    ] {
+      Person::delete(pool, local_user.person_id).await.unwrap();
+    }
+  }
+}
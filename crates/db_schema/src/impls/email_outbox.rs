@@ -0,0 +1,142 @@
+use crate::{
+  schema::email_outbox::dsl::{dead, email_outbox, id, next_attempt, published},
+  source::email_outbox::{EmailOutbox, EmailOutboxFailureForm, EmailOutboxForm},
+  utils::{get_conn, now, DbPool},
+};
+use diesel::{dsl::IntervalDsl, insert_into, result::Error, ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+
+impl EmailOutbox {
+  pub async fn create(pool: &mut DbPool<'_>, form: &EmailOutboxForm) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(email_outbox)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  /// Takes emails which are due for sending. Their next attempt is moved a few minutes into the
+  /// future, so that other workers skip them while they are being sent. If the worker dies in
+  /// between, they are picked up again afterwards.
+  pub async fn claim_due(pool: &mut DbPool<'_>, limit: i64) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    conn
+      .build_transaction()
+      .run(|conn| {
+        Box::pin(async move {
+          let due = email_outbox
+            .select(id)
+            .filter(dead.eq(false))
+            .filter(next_attempt.le(now()))
+            .order_by(next_attempt)
+            .limit(limit)
+            .for_update()
+            .skip_locked()
+            .load::<i32>(conn)
+            .await?;
+          diesel::update(email_outbox.filter(id.eq_any(due)))
+            .set(next_attempt.eq(now() + 10.minutes()))
+            .get_results::<Self>(conn)
+            .await
+        }) as _
+      })
+      .await
+  }
+
+  /// Removes an email from the outbox after it was delivered.
+  pub async fn mark_sent(pool: &mut DbPool<'_>, email_id: i32) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::delete(email_outbox.find(email_id))
+      .execute(conn)
+      .await
+  }
+
+  pub async fn mark_failed(
+    pool: &mut DbPool<'_>,
+    email_id: i32,
+    form: &EmailOutboxFailureForm,
+  ) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(email_outbox.find(email_id))
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  /// Dead letters are kept for a month, so that admins can look into delivery problems.
+  pub async fn delete_old_dead_letters(pool: &mut DbPool<'_>) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::delete(
+      email_outbox
+        .filter(dead.eq(true))
+        .filter(published.lt(now() - 1.months())),
+    )
+    .execute(conn)
+    .await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use crate::{
+    source::email_outbox::{EmailOutbox, EmailOutboxFailureForm, EmailOutboxForm},
+    utils::{build_db_pool_for_tests, naive_now},
+  };
+  use chrono::Duration;
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_claim_due() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let form = EmailOutboxForm {
+      local_user_id: None,
+      recipient_email: "outbox@example.com".into(),
+      recipient_name: "outbox".into(),
+      subject: "subject".into(),
+      html: "<p>body</p>".into(),
+      reply_to: None,
+    };
+    let inserted = EmailOutbox::create(pool, &form).await.unwrap();
+
+    let claimed = EmailOutbox::claim_due(pool, 100).await.unwrap();
+    assert!(claimed.iter().any(|e| e.id == inserted.id));
+
+    // Claimed emails are not handed out a second time
+    let claimed_again = EmailOutbox::claim_due(pool, 100).await.unwrap();
+    assert!(!claimed_again.iter().any(|e| e.id == inserted.id));
+
+    // A failed email becomes due again after its backoff
+    let failure_form = EmailOutboxFailureForm {
+      attempts: 1,
+      next_attempt: naive_now() - Duration::seconds(1),
+      last_error: Some("connection refused".into()),
+      dead: false,
+    };
+    let failed = EmailOutbox::mark_failed(pool, inserted.id, &failure_form)
+      .await
+      .unwrap();
+    assert_eq!(1, failed.attempts);
+    let claimed_retry = EmailOutbox::claim_due(pool, 100).await.unwrap();
+    assert!(claimed_retry.iter().any(|e| e.id == inserted.id));
+
+    // Dead letters are never sent again
+    let dead_form = EmailOutboxFailureForm {
+      dead: true,
+      ..failure_form
+    };
+    EmailOutbox::mark_failed(pool, inserted.id, &dead_form)
+      .await
+      .unwrap();
+    let claimed_dead = EmailOutbox::claim_due(pool, 100).await.unwrap();
+    assert!(!claimed_dead.iter().any(|e| e.id == inserted.id));
+
+    let deleted = EmailOutbox::mark_sent(pool, inserted.id).await.unwrap();
+    assert_eq!(1, deleted);
+  }
+}
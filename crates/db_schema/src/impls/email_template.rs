@@ -0,0 +1,159 @@
+use crate::{
+  impls::actor_language::UNDETERMINED_ID,
+  newtypes::LanguageId,
+  schema::email_template,
+  source::email_template::{EmailTemplate, EmailTemplateForm},
+  utils::{get_conn, naive_now, DbPool},
+  EmailTemplateType,
+};
+use diesel::{insert_into, result::Error, ExpressionMethods, OptionalExtension, QueryDsl};
+use diesel_async::RunQueryDsl;
+
+impl EmailTemplate {
+  /// Stores a template. If there already is one for the type and language, it is replaced.
+  pub async fn upsert(pool: &mut DbPool<'_>, form: &EmailTemplateForm) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(email_template::table)
+      .values(form)
+      .on_conflict((email_template::template_type, email_template::language_id))
+      .do_update()
+      .set((
+        email_template::subject.eq(&form.subject),
+        email_template::body.eq(&form.body),
+        email_template::updated.eq(naive_now()),
+      ))
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  pub async fn delete(
+    pool: &mut DbPool<'_>,
+    template_type: EmailTemplateType,
+    language_id: LanguageId,
+  ) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::delete(email_template::table.find((template_type, language_id)))
+      .execute(conn)
+      .await
+  }
+
+  pub async fn list(pool: &mut DbPool<'_>) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    email_template::table
+      .order_by((email_template::template_type, email_template::language_id))
+      .load::<Self>(conn)
+      .await
+  }
+
+  /// Returns the template to use for the given language. This is the template of the language
+  /// itself if there is one, and otherwise the one of the undetermined language.
+  pub async fn read_for_language(
+    pool: &mut DbPool<'_>,
+    template_type: EmailTemplateType,
+    language_id: Option<LanguageId>,
+  ) -> Result<Option<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let language_ids = [language_id.unwrap_or(UNDETERMINED_ID), UNDETERMINED_ID];
+    email_template::table
+      .filter(email_template::template_type.eq(template_type))
+      .filter(email_template::language_id.eq_any(language_ids))
+      // The undetermined language has the lowest id
+      .order_by(email_template::language_id.desc())
+      .first::<Self>(conn)
+      .await
+      .optional()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+
+  use crate::{
+    impls::actor_language::UNDETERMINED_ID,
+    source::{
+      email_template::{EmailTemplate, EmailTemplateForm},
+      language::Language,
+    },
+    utils::build_db_pool_for_tests,
+    EmailTemplateType,
+  };
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_crud() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let german = Language::read_id_from_code(pool, Some("de"))
+      .await
+      .unwrap()
+      .unwrap();
+    let french = Language::read_id_from_code(pool, Some("fr"))
+      .await
+      .unwrap()
+      .unwrap();
+    let template_type = EmailTemplateType::PasswordReset;
+
+    let none = EmailTemplate::read_for_language(pool, template_type, Some(german))
+      .await
+      .unwrap();
+    assert!(none.is_none());
+
+    let mut form = EmailTemplateForm {
+      template_type,
+      language_id: UNDETERMINED_ID,
+      subject: "Reset".into(),
+      body: "{{ link }}".into(),
+    };
+    let inserted = EmailTemplate::upsert(pool, &form).await.unwrap();
+    assert!(inserted.updated.is_none());
+
+    // Saving the same type and language again replaces the template
+    form.subject = "Password reset".into();
+    let updated = EmailTemplate::upsert(pool, &form).await.unwrap();
+    assert_eq!("Password reset", updated.subject);
+    assert!(updated.updated.is_some());
+
+    form.language_id = german;
+    form.subject = "Passwort".into();
+    EmailTemplate::upsert(pool, &form).await.unwrap();
+    assert_eq!(2, EmailTemplate::list(pool).await.unwrap().len());
+
+    // Languages without a template of their own use the undetermined one
+    let for_german = EmailTemplate::read_for_language(pool, template_type, Some(german))
+      .await
+      .unwrap()
+      .unwrap();
+    assert_eq!("Passwort", for_german.subject);
+    let for_french = EmailTemplate::read_for_language(pool, template_type, Some(french))
+      .await
+      .unwrap()
+      .unwrap();
+    assert_eq!("Password reset", for_french.subject);
+    let for_unknown = EmailTemplate::read_for_language(pool, template_type, None)
+      .await
+      .unwrap()
+      .unwrap();
+    assert_eq!("Password reset", for_unknown.subject);
+    let other_type =
+      EmailTemplate::read_for_language(pool, EmailTemplateType::Mention, Some(german))
+        .await
+        .unwrap();
+    assert!(other_type.is_none());
+
+    assert_eq!(
+      1,
+      EmailTemplate::delete(pool, template_type, german)
+        .await
+        .unwrap()
+    );
+    assert_eq!(
+      1,
+      EmailTemplate::delete(pool, template_type, UNDETERMINED_ID)
+        .await
+        .unwrap()
+    );
+  }
+}
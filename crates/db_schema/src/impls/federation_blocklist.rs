@@ -1,15 +1,26 @@
 use crate::{
-  schema::federation_blocklist,
+  newtypes::{DbUrl, InstanceId},
+  schema::{federation_blocklist, federation_blocklist_subscription, instance},
   source::{
-    federation_blocklist::{FederationBlockList, FederationBlockListForm},
+    federation_blocklist::{
+      FederationBlockList,
+      FederationBlockListForm,
+      FederationBlocklistSubscription,
+      FederationBlocklistSubscriptionInsertForm,
+      FederationBlocklistSubscriptionUpdateForm,
+    },
     instance::Instance,
   },
+  traits::Crud,
   utils::{get_conn, DbPool},
+  FederationBlockSeverity,
 };
-use diesel::{dsl::insert_into, result::Error};
-use diesel_async::{AsyncPgConnection, RunQueryDsl};
+use diesel::{dsl::insert_into, result::Error, ExpressionMethods, OptionalExtension, QueryDsl};
+use diesel_async::RunQueryDsl;
 
 impl FederationBlockList {
+  /// Replaces the entries which were added by listing domains in the site settings. Entries which
+  /// were imported with a severity or comments, or which belong to a subscription, are kept.
   pub async fn replace(pool: &mut DbPool<'_>, list_opt: Option<Vec<String>>) -> Result<(), Error> {
     let conn = &mut get_conn(pool).await?;
     conn
@@ -17,21 +28,34 @@ impl FederationBlockList {
       .run(|conn| {
         Box::pin(async move {
           if let Some(list) = list_opt {
-            Self::clear(conn).await?;
-
+            let mut instance_ids = vec![];
             for domain in list {
               // Upsert all of these as instances
               let instance = Instance::read_or_create(&mut conn.into(), domain).await?;
+              instance_ids.push(instance.id);
 
               let form = FederationBlockListForm {
                 instance_id: instance.id,
-                updated: None,
+                ..Default::default()
               };
               insert_into(federation_blocklist::table)
                 .values(form)
-                .get_result::<Self>(conn)
+                .on_conflict(federation_blocklist::instance_id)
+                .do_nothing()
+                .execute(conn)
                 .await?;
             }
+
+            diesel::delete(
+              federation_blocklist::table
+                .filter(federation_blocklist::subscription_id.is_null())
+                .filter(federation_blocklist::severity.eq(FederationBlockSeverity::Suspend))
+                .filter(federation_blocklist::public_comment.is_null())
+                .filter(federation_blocklist::private_comment.is_null())
+                .filter(federation_blocklist::instance_id.ne_all(instance_ids)),
+            )
+            .execute(conn)
+            .await?;
             Ok(())
           } else {
             Ok(())
@@ -41,9 +65,143 @@ impl FederationBlockList {
       .await
   }
 
-  async fn clear(conn: &mut AsyncPgConnection) -> Result<usize, Error> {
-    diesel::delete(federation_blocklist::table)
-      .execute(conn)
+  /// Returns all entries together with their instance, ordered by domain.
+  pub async fn list(pool: &mut DbPool<'_>) -> Result<Vec<(Self, Instance)>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    federation_blocklist::table
+      .inner_join(instance::table)
+      .select((federation_blocklist::all_columns, instance::all_columns))
+      .order_by(instance::domain)
+      .load(conn)
       .await
   }
+
+  /// Adds an instance to the blocklist, or overwrites its existing entry.
+  pub async fn upsert(
+    pool: &mut DbPool<'_>,
+    form: &FederationBlockListForm,
+  ) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(federation_blocklist::table)
+      .values(form)
+      .on_conflict(federation_blocklist::instance_id)
+      .do_update()
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  pub async fn delete(pool: &mut DbPool<'_>, for_instance_id: InstanceId) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::delete(
+      federation_blocklist::table.filter(federation_blocklist::instance_id.eq(for_instance_id)),
+    )
+    .execute(conn)
+    .await
+  }
+}
+
+#[async_trait]
+impl Crud for FederationBlocklistSubscription {
+  type InsertForm = FederationBlocklistSubscriptionInsertForm;
+  type UpdateForm = FederationBlocklistSubscriptionUpdateForm;
+  type IdType = i32;
+
+  async fn create(pool: &mut DbPool<'_>, form: &Self::InsertForm) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(federation_blocklist_subscription::table)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  async fn update(
+    pool: &mut DbPool<'_>,
+    subscription_id: i32,
+    form: &Self::UpdateForm,
+  ) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(federation_blocklist_subscription::table.find(subscription_id))
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+}
+
+impl FederationBlocklistSubscription {
+  pub async fn read_from_url(pool: &mut DbPool<'_>, url: &DbUrl) -> Result<Option<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    federation_blocklist_subscription::table
+      .filter(federation_blocklist_subscription::url.eq(url))
+      .first::<Self>(conn)
+      .await
+      .optional()
+  }
+
+  pub async fn list(pool: &mut DbPool<'_>) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    federation_blocklist_subscription::table
+      .order_by(federation_blocklist_subscription::id)
+      .load(conn)
+      .await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use crate::{
+    source::{
+      federation_blocklist::{FederationBlockList, FederationBlockListForm},
+      instance::Instance,
+    },
+    utils::build_db_pool_for_tests,
+    FederationBlockSeverity,
+  };
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_replace_keeps_imported_entries() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let silenced = Instance::read_or_create(pool, "silenced.tld".to_string())
+      .await
+      .unwrap();
+    let form = FederationBlockListForm {
+      instance_id: silenced.id,
+      severity: FederationBlockSeverity::Silence,
+      public_comment: Some("spam".to_string()),
+      ..Default::default()
+    };
+    FederationBlockList::upsert(pool, &form).await.unwrap();
+
+    let list = Some(vec!["blocked1.tld".to_string(), "blocked2.tld".to_string()]);
+    FederationBlockList::replace(pool, list).await.unwrap();
+    let list = Some(vec!["blocked2.tld".to_string()]);
+    FederationBlockList::replace(pool, list).await.unwrap();
+
+    let domains: Vec<_> = FederationBlockList::list(pool)
+      .await
+      .unwrap()
+      .into_iter()
+      .map(|(entry, instance)| (instance.domain, entry.severity))
+      .collect();
+    assert_eq!(
+      vec![
+        ("blocked2.tld".to_string(), FederationBlockSeverity::Suspend),
+        ("silenced.tld".to_string(), FederationBlockSeverity::Silence),
+      ],
+      domains
+    );
+
+    let blocklist = Instance::blocklist(pool).await.unwrap();
+    assert_eq!(1, blocklist.len());
+    assert_eq!("blocked2.tld", blocklist[0].domain);
+
+    Instance::delete_all(pool).await.unwrap();
+  }
 }
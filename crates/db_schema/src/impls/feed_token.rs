@@ -0,0 +1,114 @@
+use crate::{
+  newtypes::LocalUserId,
+  schema::feed_token::dsl::{feed_token, local_user_id, published, token},
+  source::feed_token::{FeedToken, FeedTokenForm},
+  utils::{get_conn, DbPool},
+};
+use diesel::{dsl::now, insert_into, result::Error, ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+
+impl FeedToken {
+  /// Sets a new token for the user. An older token stops working.
+  pub async fn regenerate(pool: &mut DbPool<'_>, form: &FeedTokenForm) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(feed_token)
+      .values(form)
+      .on_conflict(local_user_id)
+      .do_update()
+      .set((token.eq(&form.token), published.eq(now)))
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  pub async fn read(pool: &mut DbPool<'_>, for_local_user_id: LocalUserId) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    feed_token.find(for_local_user_id).first::<Self>(conn).await
+  }
+
+  pub async fn read_from_token(pool: &mut DbPool<'_>, token_: &str) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    feed_token
+      .filter(token.eq(token_))
+      .first::<Self>(conn)
+      .await
+  }
+
+  pub async fn delete(
+    pool: &mut DbPool<'_>,
+    for_local_user_id: LocalUserId,
+  ) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::delete(feed_token.find(for_local_user_id))
+      .execute(conn)
+      .await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use crate::{
+    source::{
+      feed_token::{FeedToken, FeedTokenForm},
+      instance::Instance,
+      local_user::{LocalUser, LocalUserInsertForm},
+      person::{Person, PersonInsertForm},
+    },
+    traits::Crud,
+    utils::build_db_pool_for_tests,
+  };
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_regenerate() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+    let new_person = PersonInsertForm::builder()
+      .name("feed_token_user".into())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_person = Person::create(pool, &new_person).await.unwrap();
+    let new_local_user = LocalUserInsertForm::builder()
+      .person_id(inserted_person.id)
+      .password_encrypted("pass".to_string())
+      .build();
+    let inserted_local_user = LocalUser::create(pool, &new_local_user).await.unwrap();
+
+    let form = FeedTokenForm {
+      local_user_id: inserted_local_user.id,
+      token: "first".to_string(),
+    };
+    FeedToken::regenerate(pool, &form).await.unwrap();
+    let form = FeedTokenForm {
+      token: "second".to_string(),
+      ..form
+    };
+    let regenerated = FeedToken::regenerate(pool, &form).await.unwrap();
+
+    // Only the newest token is valid
+    assert!(FeedToken::read_from_token(pool, "first").await.is_err());
+    let read = FeedToken::read_from_token(pool, "second").await.unwrap();
+    assert_eq!(regenerated, read);
+    assert_eq!(
+      regenerated,
+      FeedToken::read(pool, inserted_local_user.id).await.unwrap()
+    );
+
+    let num_deleted = FeedToken::delete(pool, inserted_local_user.id)
+      .await
+      .unwrap();
+    assert_eq!(1, num_deleted);
+    assert!(FeedToken::read(pool, inserted_local_user.id).await.is_err());
+
+    Person::delete(pool, inserted_person.id).await.unwrap();
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
+}
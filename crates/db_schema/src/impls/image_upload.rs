@@ -1,6 +1,6 @@
 use crate::{
   newtypes::{ImageUploadId, LocalUserId},
-  schema::image_upload::dsl::{image_upload, local_user_id, pictrs_alias},
+  schema::image_upload::dsl::{alt_text, image_upload, local_user_id, pictrs_alias, size},
   source::image_upload::{ImageUpload, ImageUploadForm},
   utils::{get_conn, DbPool},
 };
@@ -28,6 +28,38 @@ impl ImageUpload {
       .await
   }
 
+  /// Returns the number of uploads and their total size in bytes.
+  pub async fn usage(pool: &mut DbPool<'_>, user_id: LocalUserId) -> Result<(i64, i64), Error> {
+    let conn = &mut get_conn(pool).await?;
+    let sizes = image_upload
+      .filter(local_user_id.eq(user_id))
+      .select(size)
+      .load::<Option<i64>>(conn)
+      .await?;
+    let total_size = sizes.iter().map(|s| s.unwrap_or(0)).sum();
+    Ok((sizes.len() as i64, total_size))
+  }
+
+  pub async fn read_by_alias(pool: &mut DbPool<'_>, alias: &str) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    image_upload
+      .filter(pictrs_alias.eq(alias))
+      .first::<Self>(conn)
+      .await
+  }
+
+  pub async fn update_alt_text(
+    pool: &mut DbPool<'_>,
+    image_upload_id: ImageUploadId,
+    new_alt_text: Option<String>,
+  ) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(image_upload.find(image_upload_id))
+      .set(alt_text.eq(new_alt_text))
+      .get_result::<Self>(conn)
+      .await
+  }
+
   pub async fn delete(
     pool: &mut DbPool<'_>,
     image_upload_id: ImageUploadId,
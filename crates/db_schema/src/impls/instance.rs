@@ -4,12 +4,15 @@ use crate::{
   schema::{federation_allowlist, federation_blocklist, instance, local_site, site},
   source::instance::{Instance, InstanceForm},
   utils::{functions::lower, get_conn, naive_now, now, DbPool},
+  FederationBlockSeverity,
 };
 use diesel::{
-  dsl::{count_star, insert_into},
+  dsl::{count_star, exists, insert_into, select},
   result::Error,
   sql_types::{Nullable, Timestamptz},
+  BoolExpressionMethods,
   ExpressionMethods,
+  JoinOnDsl,
   NullableExpressionMethods,
   QueryDsl,
   SelectableHelper,
@@ -78,15 +81,30 @@ impl Instance {
       .await
   }
 
+  /// Instances which are suspended, meaning that there is no federation with them at all.
   pub async fn blocklist(pool: &mut DbPool<'_>) -> Result<Vec<Self>, Error> {
     let conn = &mut get_conn(pool).await?;
     instance::table
       .inner_join(federation_blocklist::table)
+      .filter(federation_blocklist::severity.eq(FederationBlockSeverity::Suspend))
       .select(instance::all_columns)
       .get_results(conn)
       .await
   }
 
+  /// Limited instances are still federated with, but their content is hidden from users who
+  /// don't follow it.
+  pub async fn is_limited(pool: &mut DbPool<'_>, instance_id: InstanceId) -> Result<bool, Error> {
+    let conn = &mut get_conn(pool).await?;
+    select(exists(
+      federation_blocklist::table
+        .filter(federation_blocklist::instance_id.eq(instance_id))
+        .filter(federation_blocklist::severity.eq(FederationBlockSeverity::Silence)),
+    ))
+    .get_result(conn)
+    .await
+  }
+
   /// returns a list of all instances, each with a flag of whether the instance is allowed or not and dead or not
   /// ordered by id
   pub async fn read_all_with_blocked_and_dead(
@@ -113,7 +131,14 @@ impl Instance {
         .await
     } else {
       instance::table
-        .left_join(federation_blocklist::table)
+        // silenced instances are still federated with
+        .left_join(
+          federation_blocklist::table.on(
+            federation_blocklist::instance_id
+              .eq(instance::id)
+              .and(federation_blocklist::severity.eq(FederationBlockSeverity::Suspend)),
+          ),
+        )
         .select((
           Self::as_select(),
           federation_blocklist::id.nullable().is_null(),
@@ -131,8 +156,14 @@ impl Instance {
       // omit instance representing the local site
       .left_join(site::table.inner_join(local_site::table))
       .filter(local_site::id.is_null())
-      // omit instances in the blocklist
-      .left_join(federation_blocklist::table)
+      // omit suspended instances
+      .left_join(
+        federation_blocklist::table.on(
+          federation_blocklist::instance_id
+            .eq(instance::id)
+            .and(federation_blocklist::severity.eq(FederationBlockSeverity::Suspend)),
+        ),
+      )
       .filter(federation_blocklist::id.is_null())
       .select(instance::all_columns)
       .get_results(conn)
@@ -1,4 +1,5 @@
 use crate::{
+  newtypes::{InstanceId, PersonId},
   schema::instance_block::dsl::{instance_block, instance_id, person_id},
   source::instance_block::{InstanceBlock, InstanceBlockForm},
   traits::Blockable,
@@ -7,6 +8,21 @@ use crate::{
 use diesel::{dsl::insert_into, result::Error, ExpressionMethods, QueryDsl};
 use diesel_async::RunQueryDsl;
 
+impl InstanceBlock {
+  pub async fn read(
+    pool: &mut DbPool<'_>,
+    for_person_id: PersonId,
+    for_instance_id: InstanceId,
+  ) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    instance_block
+      .filter(person_id.eq(for_person_id))
+      .filter(instance_id.eq(for_instance_id))
+      .first::<Self>(conn)
+      .await
+  }
+}
+
 #[async_trait]
 impl Blockable for InstanceBlock {
   type Form = InstanceBlockForm;
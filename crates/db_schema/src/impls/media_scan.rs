@@ -0,0 +1,126 @@
+use crate::{
+  newtypes::{MediaScanId, PersonId},
+  schema::media_scan::dsl::{media_scan, pictrs_alias, resolved, resolver_id, result},
+  source::media_scan::{MediaScan, MediaScanForm},
+  utils::{get_conn, DbPool},
+  MediaScanResult,
+};
+use diesel::{dsl::exists, insert_into, result::Error, select, ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+
+impl MediaScan {
+  pub async fn create(pool: &mut DbPool<'_>, form: &MediaScanForm) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(media_scan)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  pub async fn read(pool: &mut DbPool<'_>, id: MediaScanId) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    media_scan.find(id).first::<Self>(conn).await
+  }
+
+  /// Marks a denied or quarantined media as reviewed. For quarantined media this means that it is
+  /// served again, unless the admin also purged it.
+  pub async fn resolve(
+    pool: &mut DbPool<'_>,
+    id: MediaScanId,
+    by_resolver_id: PersonId,
+  ) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(media_scan.find(id))
+      .set((resolved.eq(true), resolver_id.eq(by_resolver_id)))
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  /// Whether the media is waiting for review by an admin, and must not be served until then.
+  pub async fn is_quarantined(pool: &mut DbPool<'_>, alias: &str) -> Result<bool, Error> {
+    let conn = &mut get_conn(pool).await?;
+    select(exists(
+      media_scan
+        .filter(pictrs_alias.eq(alias))
+        .filter(result.eq(MediaScanResult::Quarantine))
+        .filter(resolved.eq(false)),
+    ))
+    .get_result(conn)
+    .await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use crate::{
+    source::{
+      instance::Instance,
+      media_scan::{MediaScan, MediaScanForm},
+      person::{Person, PersonInsertForm},
+    },
+    traits::Crud,
+    utils::build_db_pool_for_tests,
+    MediaScanResult,
+  };
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_quarantine() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+    let new_person = PersonInsertForm::builder()
+      .name("media_scan_admin".into())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_person = Person::create(pool, &new_person).await.unwrap();
+
+    let form = MediaScanForm {
+      pictrs_alias: "allowed.png".to_string(),
+      person_id: Some(inserted_person.id),
+      remote_url: None,
+      result: MediaScanResult::Allow,
+      reason: None,
+      resolved: true,
+    };
+    MediaScan::create(pool, &form).await.unwrap();
+    let form = MediaScanForm {
+      pictrs_alias: "quarantined.png".to_string(),
+      result: MediaScanResult::Quarantine,
+      reason: Some("possible match".to_string()),
+      resolved: false,
+      ..form
+    };
+    let quarantined = MediaScan::create(pool, &form).await.unwrap();
+
+    assert!(!MediaScan::is_quarantined(pool, "allowed.png")
+      .await
+      .unwrap());
+    assert!(MediaScan::is_quarantined(pool, "quarantined.png")
+      .await
+      .unwrap());
+
+    // Resolving releases the media
+    let resolved = MediaScan::resolve(pool, quarantined.id, inserted_person.id)
+      .await
+      .unwrap();
+    assert_eq!(Some(inserted_person.id), resolved.resolver_id);
+    assert!(!MediaScan::is_quarantined(pool, "quarantined.png")
+      .await
+      .unwrap());
+
+    // The audit entries stay when the person is deleted
+    Person::delete(pool, inserted_person.id).await.unwrap();
+    let read = MediaScan::read(pool, quarantined.id).await.unwrap();
+    assert_eq!(None, read.person_id);
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
+}
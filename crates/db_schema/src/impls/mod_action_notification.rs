@@ -0,0 +1,152 @@
+use crate::{
+  newtypes::{ModActionNotificationId, PersonId},
+  schema::mod_action_notification::dsl::{mod_action_notification, read, recipient_id},
+  source::mod_action_notification::{
+    ModActionNotification,
+    ModActionNotificationInsertForm,
+    ModActionNotificationUpdateForm,
+  },
+  traits::Crud,
+  utils::{get_conn, DbPool},
+};
+use diesel::{dsl::insert_into, result::Error, ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+
+#[async_trait]
+impl Crud for ModActionNotification {
+  type InsertForm = ModActionNotificationInsertForm;
+  type UpdateForm = ModActionNotificationUpdateForm;
+  type IdType = ModActionNotificationId;
+
+  async fn create(pool: &mut DbPool<'_>, form: &Self::InsertForm) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(mod_action_notification)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  async fn update(
+    pool: &mut DbPool<'_>,
+    notification_id: ModActionNotificationId,
+    form: &Self::UpdateForm,
+  ) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(mod_action_notification.find(notification_id))
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+}
+
+impl ModActionNotification {
+  pub async fn mark_all_as_read(
+    pool: &mut DbPool<'_>,
+    for_recipient_id: PersonId,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(
+      mod_action_notification
+        .filter(recipient_id.eq(for_recipient_id))
+        .filter(read.eq(false)),
+    )
+    .set(read.eq(true))
+    .get_results::<Self>(conn)
+    .await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use crate::{
+    source::{
+      community::{Community, CommunityInsertForm},
+      instance::Instance,
+      mod_action_notification::{
+        ModActionNotification,
+        ModActionNotificationInsertForm,
+        ModActionNotificationUpdateForm,
+      },
+      person::{Person, PersonInsertForm},
+      post::{Post, PostInsertForm},
+    },
+    traits::Crud,
+    utils::build_db_pool_for_tests,
+    ModActionType,
+  };
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_crud() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+    let person_form = PersonInsertForm::builder()
+      .name("mod_action_recipient".into())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let recipient = Person::create(pool, &person_form).await.unwrap();
+    let mod_form = PersonInsertForm::builder()
+      .name("mod_action_mod".into())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let moderator = Person::create(pool, &mod_form).await.unwrap();
+    let community_form = CommunityInsertForm::builder()
+      .name("mod_action_community".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let community = Community::create(pool, &community_form).await.unwrap();
+    let post_form = PostInsertForm::builder()
+      .name("A removed post".into())
+      .creator_id(recipient.id)
+      .community_id(community.id)
+      .build();
+    let post = Post::create(pool, &post_form).await.unwrap();
+
+    let form = ModActionNotificationInsertForm {
+      recipient_id: recipient.id,
+      mod_person_id: moderator.id,
+      action: ModActionType::RemovePost,
+      community_id: community.id,
+      post_id: Some(post.id),
+      comment_id: None,
+      reason: Some("spam".to_string()),
+    };
+    let inserted = ModActionNotification::create(pool, &form).await.unwrap();
+    assert_eq!(ModActionType::RemovePost, inserted.action);
+    assert_eq!(Some(post.id), inserted.post_id);
+    assert!(!inserted.read);
+
+    let updated = ModActionNotification::update(
+      pool,
+      inserted.id,
+      &ModActionNotificationUpdateForm { read: Some(true) },
+    )
+    .await
+    .unwrap();
+    assert!(updated.read);
+
+    ModActionNotification::create(pool, &form).await.unwrap();
+    let marked = ModActionNotification::mark_all_as_read(pool, recipient.id)
+      .await
+      .unwrap();
+    assert_eq!(1, marked.len());
+
+    Post::delete(pool, post.id).await.unwrap();
+    Community::delete(pool, community.id).await.unwrap();
+    Person::delete(pool, recipient.id).await.unwrap();
+    Person::delete(pool, moderator.id).await.unwrap();
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
+}
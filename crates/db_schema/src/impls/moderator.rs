@@ -1,5 +1,7 @@
 use crate::{
   source::moderator::{
+    AdminBlockInstance,
+    AdminBlockInstanceForm,
     AdminPurgeComment,
     AdminPurgeCommentForm,
     AdminPurgeCommunity,
@@ -464,6 +466,35 @@ impl Crud for AdminPurgeComment {
   }
 }
 
+#[async_trait]
+impl Crud for AdminBlockInstance {
+  type InsertForm = AdminBlockInstanceForm;
+  type UpdateForm = AdminBlockInstanceForm;
+  type IdType = i32;
+
+  async fn create(pool: &mut DbPool<'_>, form: &Self::InsertForm) -> Result<Self, Error> {
+    use crate::schema::admin_block_instance::dsl::admin_block_instance;
+    let conn = &mut get_conn(pool).await?;
+    insert_into(admin_block_instance)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  async fn update(
+    pool: &mut DbPool<'_>,
+    from_id: i32,
+    form: &Self::InsertForm,
+  ) -> Result<Self, Error> {
+    use crate::schema::admin_block_instance::dsl::admin_block_instance;
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(admin_block_instance.find(from_id))
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+}
+
 #[cfg(test)]
 mod tests {
   #![allow(clippy::unwrap_used)]
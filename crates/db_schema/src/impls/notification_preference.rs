@@ -0,0 +1,139 @@
+use crate::{
+  newtypes::LocalUserId,
+  schema::notification_preference::dsl::{
+    local_user_id,
+    notification_preference,
+    notification_type,
+  },
+  source::notification_preference::{NotificationPreference, NotificationPreferenceForm},
+  utils::{get_conn, DbPool},
+  NotificationType,
+};
+use diesel::{insert_into, result::Error, ExpressionMethods, OptionalExtension, QueryDsl};
+use diesel_async::RunQueryDsl;
+use strum::IntoEnumIterator;
+
+impl NotificationPreference {
+  /// Returns the preference of the user for this type, or the default if they never changed it.
+  pub async fn read(
+    pool: &mut DbPool<'_>,
+    for_local_user_id: LocalUserId,
+    for_notification_type: NotificationType,
+  ) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let preference = notification_preference
+      .find((for_local_user_id, for_notification_type))
+      .first::<Self>(conn)
+      .await
+      .optional()?;
+    Ok(preference.unwrap_or_else(|| Self::default_for(for_local_user_id, for_notification_type)))
+  }
+
+  /// Returns the preferences of the user for all notification types.
+  pub async fn list(
+    pool: &mut DbPool<'_>,
+    for_local_user_id: LocalUserId,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let stored = notification_preference
+      .filter(local_user_id.eq(for_local_user_id))
+      .load::<Self>(conn)
+      .await?;
+    Ok(
+      NotificationType::iter()
+        .map(|t| {
+          stored
+            .iter()
+            .find(|p| p.notification_type == t)
+            .cloned()
+            .unwrap_or_else(|| Self::default_for(for_local_user_id, t))
+        })
+        .collect(),
+    )
+  }
+
+  pub async fn upsert(
+    pool: &mut DbPool<'_>,
+    form: &NotificationPreferenceForm,
+  ) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(notification_preference)
+      .values(form)
+      .on_conflict((local_user_id, notification_type))
+      .do_update()
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use crate::{
+    source::{
+      instance::Instance,
+      local_user::{LocalUser, LocalUserInsertForm},
+      notification_preference::{NotificationPreference, NotificationPreferenceForm},
+      person::{Person, PersonInsertForm},
+    },
+    traits::Crud,
+    utils::build_db_pool_for_tests,
+    NotificationType,
+  };
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_defaults_and_upsert() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+    let new_person = PersonInsertForm::builder()
+      .name("notification_preferrer".into())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_person = Person::create(pool, &new_person).await.unwrap();
+    let local_user_form = LocalUserInsertForm::builder()
+      .person_id(inserted_person.id)
+      .password_encrypted("password".to_string())
+      .build();
+    let local_user_id = LocalUser::create(pool, &local_user_form).await.unwrap().id;
+
+    let preferences = NotificationPreference::list(pool, local_user_id)
+      .await
+      .unwrap();
+    assert_eq!(6, preferences.len());
+    assert!(preferences.iter().all(|p| p.push && p.in_app && p.email));
+    assert!(preferences.iter().all(|p| !p.only_from_followed));
+
+    let form = NotificationPreferenceForm {
+      local_user_id,
+      notification_type: NotificationType::Mention,
+      push: Some(false),
+      in_app: None,
+      email: Some(false),
+      only_from_followed: None,
+    };
+    NotificationPreference::upsert(pool, &form).await.unwrap();
+    let mention = NotificationPreference::read(pool, local_user_id, NotificationType::Mention)
+      .await
+      .unwrap();
+    assert!(!mention.push);
+    assert!(mention.in_app);
+    assert!(!mention.email);
+    let reply = NotificationPreference::read(pool, local_user_id, NotificationType::Reply)
+      .await
+      .unwrap();
+    assert!(reply.push);
+
+    Person::delete(pool, inserted_person.id).await.unwrap();
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
+}
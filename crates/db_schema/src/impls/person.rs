@@ -12,7 +12,13 @@ use crate::{
   utils::{functions::lower, get_conn, naive_now, DbPool},
 };
 use chrono::{DateTime, Utc};
-use diesel::{dsl::insert_into, result::Error, ExpressionMethods, JoinOnDsl, QueryDsl};
+use diesel::{
+  dsl::{exists, insert_into, select},
+  result::Error,
+  ExpressionMethods,
+  JoinOnDsl,
+  QueryDsl,
+};
 use diesel_async::RunQueryDsl;
 
 #[async_trait]
@@ -180,6 +186,21 @@ impl Followable for PersonFollower {
 }
 
 impl PersonFollower {
+  pub async fn is_following(
+    pool: &mut DbPool<'_>,
+    for_person_id: PersonId,
+    for_follower_id: PersonId,
+  ) -> Result<bool, Error> {
+    let conn = &mut get_conn(pool).await?;
+    select(exists(
+      person_follower::table
+        .filter(person_follower::person_id.eq(for_person_id))
+        .filter(person_follower::follower_id.eq(for_follower_id)),
+    ))
+    .get_result(conn)
+    .await
+  }
+
   pub async fn list_followers(
     pool: &mut DbPool<'_>,
     for_person_id: PersonId,
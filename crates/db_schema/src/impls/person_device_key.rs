@@ -0,0 +1,179 @@
+use crate::{
+  newtypes::PersonId,
+  schema::person_device_key,
+  source::person_device_key::{PersonDeviceKey, PersonDeviceKeyForm},
+  utils::{get_conn, DbPool},
+};
+use diesel::{insert_into, result::Error, ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+
+impl PersonDeviceKey {
+  /// Stores the key of a device. If the device already has a key, it is replaced.
+  pub async fn upsert(pool: &mut DbPool<'_>, form: &PersonDeviceKeyForm) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(person_device_key::table)
+      .values(form)
+      .on_conflict((person_device_key::person_id, person_device_key::device_id))
+      .do_update()
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  pub async fn delete(
+    pool: &mut DbPool<'_>,
+    for_person_id: PersonId,
+    for_device_id: &str,
+  ) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::delete(person_device_key::table.find((for_person_id, for_device_id)))
+      .execute(conn)
+      .await
+  }
+
+  pub async fn list_for_person(
+    pool: &mut DbPool<'_>,
+    for_person_id: PersonId,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    person_device_key::table
+      .filter(person_device_key::person_id.eq(for_person_id))
+      .order_by(person_device_key::published)
+      .load::<Self>(conn)
+      .await
+  }
+
+  /// Returns how many of the given persons have no device keys, and so can't receive encrypted
+  /// messages.
+  pub async fn count_persons_without_keys(
+    pool: &mut DbPool<'_>,
+    person_ids: &[PersonId],
+  ) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let with_keys = person_device_key::table
+      .filter(person_device_key::person_id.eq_any(person_ids))
+      .select(person_device_key::person_id)
+      .distinct()
+      .load::<PersonId>(conn)
+      .await?;
+    Ok(person_ids.iter().filter(|p| !with_keys.contains(p)).count())
+  }
+
+  /// Replaces all keys of a remote person with the ones published on its actor.
+  pub async fn replace_for_person(
+    pool: &mut DbPool<'_>,
+    for_person_id: PersonId,
+    forms: &[PersonDeviceKeyForm],
+  ) -> Result<(), Error> {
+    let conn = &mut get_conn(pool).await?;
+    let forms = forms.to_vec();
+    conn
+      .build_transaction()
+      .run(|conn| {
+        Box::pin(async move {
+          diesel::delete(
+            person_device_key::table.filter(person_device_key::person_id.eq(for_person_id)),
+          )
+          .execute(conn)
+          .await?;
+          insert_into(person_device_key::table)
+            .values(forms)
+            .execute(conn)
+            .await?;
+          Ok(())
+        }) as _
+      })
+      .await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use crate::{
+    source::{
+      instance::Instance,
+      person::{Person, PersonInsertForm},
+      person_device_key::{PersonDeviceKey, PersonDeviceKeyForm},
+    },
+    traits::Crud,
+    utils::build_db_pool_for_tests,
+  };
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_crud() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+    let mut persons = vec![];
+    for name in ["device_key_alice", "device_key_bob"] {
+      let form = PersonInsertForm::builder()
+        .name(name.into())
+        .public_key("pubkey".to_string())
+        .instance_id(inserted_instance.id)
+        .build();
+      persons.push(Person::create(pool, &form).await.unwrap());
+    }
+    let (alice, bob) = (persons[0].id, persons[1].id);
+
+    let mut form = PersonDeviceKeyForm {
+      person_id: alice,
+      device_id: "phone".into(),
+      public_key: "key1".into(),
+    };
+    PersonDeviceKey::upsert(pool, &form).await.unwrap();
+
+    // Adding the same device again replaces its key
+    form.public_key = "key2".into();
+    PersonDeviceKey::upsert(pool, &form).await.unwrap();
+    let keys = PersonDeviceKey::list_for_person(pool, alice).await.unwrap();
+    assert_eq!(1, keys.len());
+    assert_eq!("key2", keys[0].public_key);
+    assert_eq!(
+      1,
+      PersonDeviceKey::count_persons_without_keys(pool, &[alice, bob])
+        .await
+        .unwrap()
+    );
+
+    let remote_keys = vec![
+      PersonDeviceKeyForm {
+        person_id: bob,
+        device_id: "laptop".into(),
+        public_key: "key3".into(),
+      },
+      PersonDeviceKeyForm {
+        person_id: bob,
+        device_id: "phone".into(),
+        public_key: "key4".into(),
+      },
+    ];
+    PersonDeviceKey::replace_for_person(pool, bob, &remote_keys)
+      .await
+      .unwrap();
+    PersonDeviceKey::replace_for_person(pool, bob, &remote_keys[1..])
+      .await
+      .unwrap();
+    let keys = PersonDeviceKey::list_for_person(pool, bob).await.unwrap();
+    assert_eq!(1, keys.len());
+    assert_eq!("phone", keys[0].device_id);
+    assert_eq!(
+      0,
+      PersonDeviceKey::count_persons_without_keys(pool, &[alice, bob])
+        .await
+        .unwrap()
+    );
+
+    let deleted = PersonDeviceKey::delete(pool, alice, "phone").await.unwrap();
+    assert_eq!(1, deleted);
+
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
+}
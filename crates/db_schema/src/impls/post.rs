@@ -22,10 +22,14 @@ use crate::{
     PostInsertForm,
     PostLike,
     PostLikeForm,
+    PostMute,
+    PostMuteForm,
     PostRead,
     PostReadForm,
     PostSaved,
     PostSavedForm,
+    PostSubscription,
+    PostSubscriptionForm,
     PostUpdateForm,
   },
   traits::{Crud, Likeable, Readable, Saveable},
@@ -33,7 +37,13 @@ use crate::{
 };
 use ::url::Url;
 use chrono::{Duration, Utc};
-use diesel::{dsl::insert_into, result::Error, ExpressionMethods, QueryDsl, TextExpressionMethods};
+use diesel::{
+  dsl::{exists, insert_into, select},
+  result::Error,
+  ExpressionMethods,
+  QueryDsl,
+  TextExpressionMethods,
+};
 use diesel_async::RunQueryDsl;
 
 #[async_trait]
@@ -336,6 +346,106 @@ impl Readable for PostRead {
   }
 }
 
+impl PostSubscription {
+  pub async fn subscribe(
+    pool: &mut DbPool<'_>,
+    form: &PostSubscriptionForm,
+  ) -> Result<usize, Error> {
+    use crate::schema::post_subscription::dsl::post_subscription;
+    let conn = &mut get_conn(pool).await?;
+    insert_into(post_subscription)
+      .values(form)
+      .on_conflict_do_nothing()
+      .execute(conn)
+      .await
+  }
+
+  pub async fn unsubscribe(
+    pool: &mut DbPool<'_>,
+    form: &PostSubscriptionForm,
+  ) -> Result<usize, Error> {
+    use crate::schema::post_subscription::dsl::{person_id, post_id, post_subscription};
+    let conn = &mut get_conn(pool).await?;
+    diesel::delete(
+      post_subscription
+        .filter(post_id.eq(form.post_id))
+        .filter(person_id.eq(form.person_id)),
+    )
+    .execute(conn)
+    .await
+  }
+
+  pub async fn is_subscribed(
+    pool: &mut DbPool<'_>,
+    for_person_id: PersonId,
+    for_post_id: PostId,
+  ) -> Result<bool, Error> {
+    use crate::schema::post_subscription::dsl::{person_id, post_id, post_subscription};
+    let conn = &mut get_conn(pool).await?;
+    select(exists(
+      post_subscription
+        .filter(person_id.eq(for_person_id))
+        .filter(post_id.eq(for_post_id)),
+    ))
+    .get_result(conn)
+    .await
+  }
+
+  /// Returns the ids of all persons subscribed to the post.
+  pub async fn list_subscribers(
+    pool: &mut DbPool<'_>,
+    for_post_id: PostId,
+  ) -> Result<Vec<PersonId>, Error> {
+    use crate::schema::post_subscription::dsl::{person_id, post_id, post_subscription};
+    let conn = &mut get_conn(pool).await?;
+    post_subscription
+      .filter(post_id.eq(for_post_id))
+      .select(person_id)
+      .load(conn)
+      .await
+  }
+}
+
+impl PostMute {
+  pub async fn mute(pool: &mut DbPool<'_>, form: &PostMuteForm) -> Result<usize, Error> {
+    use crate::schema::post_mute::dsl::post_mute;
+    let conn = &mut get_conn(pool).await?;
+    insert_into(post_mute)
+      .values(form)
+      .on_conflict_do_nothing()
+      .execute(conn)
+      .await
+  }
+
+  pub async fn unmute(pool: &mut DbPool<'_>, form: &PostMuteForm) -> Result<usize, Error> {
+    use crate::schema::post_mute::dsl::{person_id, post_id, post_mute};
+    let conn = &mut get_conn(pool).await?;
+    diesel::delete(
+      post_mute
+        .filter(post_id.eq(form.post_id))
+        .filter(person_id.eq(form.person_id)),
+    )
+    .execute(conn)
+    .await
+  }
+
+  pub async fn is_muted(
+    pool: &mut DbPool<'_>,
+    for_person_id: PersonId,
+    for_post_id: PostId,
+  ) -> Result<bool, Error> {
+    use crate::schema::post_mute::dsl::{person_id, post_id, post_mute};
+    let conn = &mut get_conn(pool).await?;
+    select(exists(
+      post_mute
+        .filter(person_id.eq(for_person_id))
+        .filter(post_id.eq(for_post_id)),
+    ))
+    .get_result(conn)
+    .await
+  }
+}
+
 #[cfg(test)]
 mod tests {
   #![allow(clippy::unwrap_used)]
@@ -351,10 +461,14 @@ mod tests {
         PostInsertForm,
         PostLike,
         PostLikeForm,
+        PostMute,
+        PostMuteForm,
         PostRead,
         PostReadForm,
         PostSaved,
         PostSavedForm,
+        PostSubscription,
+        PostSubscriptionForm,
         PostUpdateForm,
       },
     },
@@ -420,6 +534,11 @@ mod tests {
       language_id: Default::default(),
       featured_community: false,
       featured_local: false,
+      alt_text: None,
+      url_content_type: None,
+      embed_width: None,
+      embed_height: None,
+      embed_duration: None,
     };
 
     // Post Like
@@ -469,6 +588,26 @@ mod tests {
       published: inserted_post_read.published,
     };
 
+    // Post Subscription and Mute
+    let post_subscription_form = PostSubscriptionForm {
+      person_id: inserted_person.id,
+      post_id: inserted_post.id,
+    };
+    PostSubscription::subscribe(pool, &post_subscription_form)
+      .await
+      .unwrap();
+    let subscribers = PostSubscription::list_subscribers(pool, inserted_post.id)
+      .await
+      .unwrap();
+    let post_mute_form = PostMuteForm {
+      person_id: inserted_person.id,
+      post_id: inserted_post.id,
+    };
+    PostMute::mute(pool, &post_mute_form).await.unwrap();
+    let muted = PostMute::is_muted(pool, inserted_person.id, inserted_post.id)
+      .await
+      .unwrap();
+
     let read_post = Post::read(pool, inserted_post.id).await.unwrap();
 
     let new_post_update = PostUpdateForm {
@@ -483,6 +622,10 @@ mod tests {
       .await
       .unwrap();
     let saved_removed = PostSaved::unsave(pool, &post_saved_form).await.unwrap();
+    let unsubscribed = PostSubscription::unsubscribe(pool, &post_subscription_form)
+      .await
+      .unwrap();
+    let unmuted = PostMute::unmute(pool, &post_mute_form).await.unwrap();
     let read_removed = PostRead::mark_as_unread(pool, &post_read_form)
       .await
       .unwrap();
@@ -501,6 +644,10 @@ mod tests {
     assert_eq!(expected_post_read, inserted_post_read);
     assert_eq!(1, like_removed);
     assert_eq!(1, saved_removed);
+    assert_eq!(vec![inserted_person.id], subscribers);
+    assert!(muted);
+    assert_eq!(1, unsubscribed);
+    assert_eq!(1, unmuted);
     assert_eq!(1, read_removed);
     assert_eq!(1, num_deleted);
   }
@@ -0,0 +1,155 @@
+use crate::{
+  newtypes::{DbUrl, PostId},
+  schema::post_gallery_image::dsl::{position, post_gallery_image, post_id},
+  source::post_gallery_image::{PostGalleryImage, PostGalleryImageForm},
+  utils::{get_conn, DbPool},
+};
+use diesel::{insert_into, result::Error, ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+
+impl PostGalleryImage {
+  /// Replaces all gallery images of the post with the given urls and alt texts, in that order.
+  pub async fn replace(
+    pool: &mut DbPool<'_>,
+    for_post_id: PostId,
+    images: Vec<(DbUrl, Option<String>)>,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let forms = images
+      .into_iter()
+      .enumerate()
+      .map(|(i, (url, alt_text))| PostGalleryImageForm {
+        post_id: for_post_id,
+        url,
+        alt_text,
+        position: i as i32,
+      })
+      .collect::<Vec<_>>();
+    conn
+      .build_transaction()
+      .run(|conn| {
+        Box::pin(async move {
+          diesel::delete(post_gallery_image.filter(post_id.eq(for_post_id)))
+            .execute(conn)
+            .await?;
+          if forms.is_empty() {
+            return Ok(vec![]);
+          }
+          insert_into(post_gallery_image)
+            .values(forms)
+            .get_results::<Self>(conn)
+            .await
+        }) as _
+      })
+      .await
+  }
+
+  pub async fn list_for_post(
+    pool: &mut DbPool<'_>,
+    for_post_id: PostId,
+  ) -> Result<Vec<Self>, Error> {
+    Self::list_for_posts(pool, &[for_post_id]).await
+  }
+
+  /// Loads the galleries of multiple posts at once, ordered by post and position.
+  pub async fn list_for_posts(
+    pool: &mut DbPool<'_>,
+    post_ids: &[PostId],
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    post_gallery_image
+      .filter(post_id.eq_any(post_ids))
+      .order_by((post_id, position))
+      .load::<Self>(conn)
+      .await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use crate::{
+    newtypes::DbUrl,
+    source::{
+      community::{Community, CommunityInsertForm},
+      instance::Instance,
+      person::{Person, PersonInsertForm},
+      post::{Post, PostInsertForm},
+      post_gallery_image::PostGalleryImage,
+    },
+    traits::Crud,
+    utils::build_db_pool_for_tests,
+  };
+  use serial_test::serial;
+  use url::Url;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_replace() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+
+    let new_person = PersonInsertForm::builder()
+      .name("gallery_person".into())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_person = Person::create(pool, &new_person).await.unwrap();
+
+    let new_community = CommunityInsertForm::builder()
+      .name("gallery_community".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_community = Community::create(pool, &new_community).await.unwrap();
+
+    let new_post = PostInsertForm::builder()
+      .name("A gallery".into())
+      .creator_id(inserted_person.id)
+      .community_id(inserted_community.id)
+      .build();
+    let inserted_post = Post::create(pool, &new_post).await.unwrap();
+
+    let url = |u: &str| -> DbUrl { Url::parse(u).unwrap().into() };
+    let images = vec![
+      (url("https://example.com/1.png"), Some("first".to_string())),
+      (url("https://example.com/2.png"), None),
+    ];
+    let inserted = PostGalleryImage::replace(pool, inserted_post.id, images)
+      .await
+      .unwrap();
+    assert_eq!(2, inserted.len());
+
+    // Replacing reorders and removes images
+    let images = vec![(url("https://example.com/2.png"), Some("second".to_string()))];
+    PostGalleryImage::replace(pool, inserted_post.id, images)
+      .await
+      .unwrap();
+    let gallery = PostGalleryImage::list_for_post(pool, inserted_post.id)
+      .await
+      .unwrap();
+    assert_eq!(1, gallery.len());
+    assert_eq!(0, gallery[0].position);
+    assert_eq!(Some("second".to_string()), gallery[0].alt_text);
+
+    // Gallery is deleted together with the post
+    Post::delete(pool, inserted_post.id).await.unwrap();
+    assert!(PostGalleryImage::list_for_post(pool, inserted_post.id)
+      .await
+      .unwrap()
+      .is_empty());
+
+    Person::delete(pool, inserted_person.id).await.unwrap();
+    Community::delete(pool, inserted_community.id)
+      .await
+      .unwrap();
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
+}
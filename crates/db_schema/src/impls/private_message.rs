@@ -1,9 +1,12 @@
 use crate::{
   newtypes::{DbUrl, PersonId, PrivateMessageId},
-  schema::private_message::dsl::{ap_id, private_message, read, recipient_id},
+  schema::{
+    conversation_participant,
+    private_message::dsl::{ap_id, private_message, read, recipient_id},
+  },
   source::private_message::{PrivateMessage, PrivateMessageInsertForm, PrivateMessageUpdateForm},
   traits::Crud,
-  utils::{get_conn, DbPool},
+  utils::{get_conn, naive_now, DbPool},
 };
 use diesel::{dsl::insert_into, result::Error, ExpressionMethods, QueryDsl};
 use diesel_async::RunQueryDsl;
@@ -41,19 +44,29 @@ impl Crud for PrivateMessage {
 }
 
 impl PrivateMessage {
+  /// Marks all messages to the person as read, including messages in their group conversations
+  /// which are addressed to someone else.
   pub async fn mark_all_as_read(
     pool: &mut DbPool<'_>,
     for_recipient_id: PersonId,
   ) -> Result<Vec<PrivateMessage>, Error> {
     let conn = &mut get_conn(pool).await?;
-    diesel::update(
+    let updated = diesel::update(
       private_message
         .filter(recipient_id.eq(for_recipient_id))
         .filter(read.eq(false)),
     )
     .set(read.eq(true))
     .get_results::<Self>(conn)
-    .await
+    .await?;
+    diesel::update(
+      conversation_participant::table
+        .filter(conversation_participant::person_id.eq(for_recipient_id)),
+    )
+    .set(conversation_participant::last_read.eq(naive_now()))
+    .execute(conn)
+    .await?;
+    Ok(updated)
   }
 
   pub async fn read_from_apub_id(
@@ -80,6 +93,7 @@ mod tests {
 
   use crate::{
     source::{
+      conversation::{Conversation, ConversationParticipant},
       instance::Instance,
       person::{Person, PersonInsertForm},
       private_message::{PrivateMessage, PrivateMessageInsertForm, PrivateMessageUpdateForm},
@@ -115,10 +129,16 @@ mod tests {
 
     let inserted_recipient = Person::create(pool, &recipient_form).await.unwrap();
 
+    let conversation =
+      Conversation::read_or_create(pool, &[inserted_creator.id, inserted_recipient.id], &[])
+        .await
+        .unwrap();
+
     let private_message_form = PrivateMessageInsertForm::builder()
       .content("A test private message".into())
       .creator_id(inserted_creator.id)
       .recipient_id(inserted_recipient.id)
+      .conversation_id(conversation.id)
       .build();
 
     let inserted_private_message = PrivateMessage::create(pool, &private_message_form)
@@ -136,6 +156,8 @@ mod tests {
       published: inserted_private_message.published,
       ap_id: inserted_private_message.ap_id.clone(),
       local: true,
+      conversation_id: conversation.id,
+      encrypted: false,
     };
 
     let read_private_message = PrivateMessage::read(pool, inserted_private_message.id)
@@ -174,6 +196,12 @@ mod tests {
     )
     .await
     .unwrap();
+    PrivateMessage::mark_all_as_read(pool, inserted_creator.id)
+      .await
+      .unwrap();
+    let participant = ConversationParticipant::read(pool, conversation.id, inserted_creator.id)
+      .await
+      .unwrap();
     Person::delete(pool, inserted_creator.id).await.unwrap();
     Person::delete(pool, inserted_recipient.id).await.unwrap();
     Instance::delete(pool, inserted_instance.id).await.unwrap();
@@ -183,5 +211,6 @@ mod tests {
     assert_eq!(expected_private_message, inserted_private_message);
     assert!(deleted_private_message.deleted);
     assert!(marked_read_private_message.read);
+    assert!(participant.last_read.is_some());
   }
 }
@@ -0,0 +1,117 @@
+use crate::{
+  newtypes::LocalUserId,
+  schema::push_subscription::dsl::{endpoint, local_user_id, published, push_subscription},
+  source::push_subscription::{PushSubscription, PushSubscriptionForm},
+  utils::{get_conn, DbPool},
+};
+use diesel::{insert_into, result::Error, ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+
+impl PushSubscription {
+  /// Stores the subscription of a device. If the browser subscribed again with the same endpoint,
+  /// for example after the keys were rotated, the existing subscription is updated.
+  pub async fn upsert(pool: &mut DbPool<'_>, form: &PushSubscriptionForm) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(push_subscription)
+      .values(form)
+      .on_conflict(endpoint)
+      .do_update()
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  pub async fn list_for_local_user(
+    pool: &mut DbPool<'_>,
+    for_local_user_id: LocalUserId,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    push_subscription
+      .filter(local_user_id.eq(for_local_user_id))
+      .order_by(published)
+      .load::<Self>(conn)
+      .await
+  }
+
+  /// Removes a subscription, either on request of its user or because the push service says that
+  /// it expired.
+  pub async fn delete(
+    pool: &mut DbPool<'_>,
+    for_local_user_id: LocalUserId,
+    for_endpoint: &str,
+  ) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::delete(
+      push_subscription
+        .filter(local_user_id.eq(for_local_user_id))
+        .filter(endpoint.eq(for_endpoint)),
+    )
+    .execute(conn)
+    .await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use crate::{
+    source::{
+      instance::Instance,
+      local_user::{LocalUser, LocalUserInsertForm},
+      person::{Person, PersonInsertForm},
+      push_subscription::{PushSubscription, PushSubscriptionForm},
+    },
+    traits::Crud,
+    utils::build_db_pool_for_tests,
+  };
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_upsert() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+    let new_person = PersonInsertForm::builder()
+      .name("push_subscriber".into())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_person = Person::create(pool, &new_person).await.unwrap();
+    let local_user_form = LocalUserInsertForm::builder()
+      .person_id(inserted_person.id)
+      .password_encrypted("password".to_string())
+      .build();
+    let inserted_local_user = LocalUser::create(pool, &local_user_form).await.unwrap();
+
+    let mut form = PushSubscriptionForm {
+      local_user_id: inserted_local_user.id,
+      endpoint: "https://push.example.com/abc".into(),
+      p256dh: "key1".into(),
+      auth: "auth1".into(),
+    };
+    PushSubscription::upsert(pool, &form).await.unwrap();
+
+    // Subscribing again with the same endpoint replaces the keys
+    form.p256dh = "key2".into();
+    PushSubscription::upsert(pool, &form).await.unwrap();
+    let subscriptions = PushSubscription::list_for_local_user(pool, inserted_local_user.id)
+      .await
+      .unwrap();
+    assert_eq!(1, subscriptions.len());
+    assert_eq!("key2", subscriptions[0].p256dh);
+
+    let deleted = PushSubscription::delete(pool, inserted_local_user.id, &form.endpoint)
+      .await
+      .unwrap();
+    assert_eq!(1, deleted);
+
+    Person::delete(pool, inserted_person.id).await.unwrap();
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
+}
@@ -0,0 +1,71 @@
+use crate::{
+  schema::remote_image::dsl::{link, remote_image},
+  source::remote_image::{RemoteImage, RemoteImageForm},
+  utils::{get_conn, DbPool},
+};
+use diesel::{insert_into, result::Error, QueryDsl};
+use diesel_async::RunQueryDsl;
+
+impl RemoteImage {
+  /// Saves the cached copy of a remote image, replacing an older one for the same link.
+  pub async fn upsert(pool: &mut DbPool<'_>, form: &RemoteImageForm) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(remote_image)
+      .values(form)
+      .on_conflict(link)
+      .do_update()
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  pub async fn read(pool: &mut DbPool<'_>, image_link: &str) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    remote_image.find(image_link).first::<Self>(conn).await
+  }
+
+  pub async fn delete(pool: &mut DbPool<'_>, image_link: &str) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::delete(remote_image.find(image_link))
+      .execute(conn)
+      .await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use crate::{
+    source::remote_image::{RemoteImage, RemoteImageForm},
+    utils::build_db_pool_for_tests,
+  };
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_crud() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let link = "https://example.com/image.png".to_string();
+    let form = RemoteImageForm {
+      link: link.clone(),
+      pictrs_alias: "first.png".to_string(),
+    };
+    RemoteImage::upsert(pool, &form).await.unwrap();
+
+    // The image was fetched again, for example because the cached copy was deleted
+    let form = RemoteImageForm {
+      pictrs_alias: "second.png".to_string(),
+      ..form
+    };
+    let updated = RemoteImage::upsert(pool, &form).await.unwrap();
+    assert_eq!("second.png", updated.pictrs_alias);
+    assert_eq!(updated, RemoteImage::read(pool, &link).await.unwrap());
+
+    assert_eq!(1, RemoteImage::delete(pool, &link).await.unwrap());
+    assert!(RemoteImage::read(pool, &link).await.is_err());
+  }
+}
@@ -1,9 +1,9 @@
 use crate::{
-  schema::secret::dsl::secret,
+  schema::secret::dsl::{secret, vapid_private_key, vapid_public_key},
   source::secret::Secret,
   utils::{get_conn, DbPool},
 };
-use diesel::result::Error;
+use diesel::{result::Error, ExpressionMethods};
 use diesel_async::RunQueryDsl;
 
 impl Secret {
@@ -13,6 +13,22 @@ impl Secret {
     Self::read_secrets(pool).await
   }
 
+  /// Stores the keys for signing push messages, which are generated on first startup.
+  pub async fn set_vapid_keys(
+    pool: &mut DbPool<'_>,
+    private_key: &str,
+    public_key: &str,
+  ) -> Result<Secret, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(secret)
+      .set((
+        vapid_private_key.eq(private_key),
+        vapid_public_key.eq(public_key),
+      ))
+      .get_result::<Secret>(conn)
+      .await
+  }
+
   async fn read_secrets(pool: &mut DbPool<'_>) -> Result<Secret, Error> {
     let conn = &mut get_conn(pool).await?;
     secret.first::<Secret>(conn).await
@@ -40,7 +40,7 @@ pub mod traits;
 pub mod utils;
 
 use serde::{Deserialize, Serialize};
-use strum_macros::{Display, EnumString};
+use strum_macros::{Display, EnumIter, EnumString};
 #[cfg(feature = "full")]
 use ts_rs::TS;
 
@@ -163,6 +163,156 @@ pub enum PostListingMode {
   SmallCard,
 }
 
+#[derive(
+  EnumString, Display, Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq,
+)]
+#[cfg_attr(feature = "full", derive(DbEnum, TS))]
+#[cfg_attr(
+  feature = "full",
+  ExistingTypePath = "crate::schema::sql_types::FederationBlockSeverityEnum"
+)]
+#[cfg_attr(feature = "full", DbValueStyle = "verbatim")]
+#[cfg_attr(feature = "full", ts(export))]
+/// How strictly an instance in the federation blocklist is cut off.
+pub enum FederationBlockSeverity {
+  /// Federation continues, but the instance is limited: its content is hidden from `All` for users
+  /// who don't follow it, its users can only reach our users who follow them, and its media is not
+  /// cached.
+  Silence,
+  #[default]
+  /// No federation with the instance at all.
+  Suspend,
+}
+
+#[derive(EnumString, Display, Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "full", derive(DbEnum, TS))]
+#[cfg_attr(
+  feature = "full",
+  ExistingTypePath = "crate::schema::sql_types::MediaScanResultEnum"
+)]
+#[cfg_attr(feature = "full", DbValueStyle = "verbatim")]
+#[cfg_attr(feature = "full", ts(export))]
+/// The verdict of the media scanner.
+pub enum MediaScanResult {
+  Allow,
+  /// The media was deleted right away.
+  Deny,
+  /// The media is stored, but not served until an admin released it.
+  Quarantine,
+}
+
+#[derive(
+  EnumString, Display, Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, EnumIter,
+)]
+#[cfg_attr(feature = "full", derive(DbEnum, TS))]
+#[cfg_attr(
+  feature = "full",
+  ExistingTypePath = "crate::schema::sql_types::NotificationTypeEnum"
+)]
+#[cfg_attr(feature = "full", DbValueStyle = "verbatim")]
+#[cfg_attr(feature = "full", ts(export))]
+/// The kinds of notifications, which users can configure separately.
+pub enum NotificationType {
+  Reply,
+  Mention,
+  PrivateMessage,
+  /// A new report or registration application, for moderators and admins.
+  ModeratorEvent,
+  /// A moderator took action against your content or account.
+  ModeratorAction,
+  /// A new comment in a post you subscribed to.
+  PostSubscription,
+}
+
+#[derive(EnumString, Display, Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(DbEnum, TS))]
+#[cfg_attr(
+  feature = "full",
+  ExistingTypePath = "crate::schema::sql_types::ModActionTypeEnum"
+)]
+#[cfg_attr(feature = "full", DbValueStyle = "verbatim")]
+#[cfg_attr(feature = "full", ts(export))]
+/// The moderator actions which users are notified about.
+pub enum ModActionType {
+  RemovePost,
+  RestorePost,
+  RemoveComment,
+  RestoreComment,
+  LockPost,
+  UnlockPost,
+  BanFromCommunity,
+  UnbanFromCommunity,
+}
+
+#[derive(
+  EnumString, Display, Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash,
+)]
+#[cfg_attr(feature = "full", derive(DbEnum, TS))]
+#[cfg_attr(
+  feature = "full",
+  ExistingTypePath = "crate::schema::sql_types::EmailDigestModeEnum"
+)]
+#[cfg_attr(feature = "full", DbValueStyle = "verbatim")]
+#[cfg_attr(feature = "full", ts(export))]
+/// How notification emails are delivered to a user.
+pub enum EmailDigestMode {
+  #[default]
+  /// Send an email for each notification.
+  Off,
+  /// Bundle notifications into one email per day.
+  Daily,
+  /// Bundle notifications into one email per week.
+  Weekly,
+}
+
+#[derive(EnumString, Display, Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(DbEnum, TS))]
+#[cfg_attr(
+  feature = "full",
+  ExistingTypePath = "crate::schema::sql_types::EmailTemplateTypeEnum"
+)]
+#[cfg_attr(feature = "full", DbValueStyle = "verbatim")]
+#[cfg_attr(feature = "full", ts(export))]
+/// The emails for which admins can replace the built-in text with a template.
+pub enum EmailTemplateType {
+  VerifyEmail,
+  PasswordReset,
+  ApplicationApproved,
+  ApplicationDenied,
+  /// A reply to a comment of the user.
+  CommentReply,
+  /// A top-level comment in a post of the user, or in a post which they subscribed to.
+  PostReply,
+  Mention,
+  PrivateMessage,
+  /// A moderator removed, restored or locked content of the user, or banned them from a community.
+  ModAction,
+  /// The collected notifications of users who receive them as daily or weekly digest.
+  EmailDigest,
+}
+
+#[derive(
+  EnumString, Display, Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash,
+)]
+#[cfg_attr(feature = "full", derive(DbEnum, TS))]
+#[cfg_attr(
+  feature = "full",
+  ExistingTypePath = "crate::schema::sql_types::PrivateMessagePolicyEnum"
+)]
+#[cfg_attr(feature = "full", DbValueStyle = "verbatim")]
+#[cfg_attr(feature = "full", ts(export))]
+/// Who can start a private message conversation with a user. Conversations from persons the user
+/// doesn't follow end up in the message requests.
+pub enum PrivateMessagePolicy {
+  #[default]
+  Everyone,
+  /// Only persons which the user follows.
+  Following,
+  /// Only users of the same instance.
+  Local,
+  Nobody,
+}
+
 #[derive(EnumString, Display, Debug, Serialize, Deserialize, Clone, Copy)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
@@ -207,6 +357,7 @@ pub enum ModlogActionType {
   AdminPurgeCommunity,
   AdminPurgePost,
   AdminPurgeComment,
+  AdminBlockInstance,
 }
 
 #[derive(
@@ -5,6 +5,18 @@ pub mod sql_types {
     #[diesel(postgres_type(name = "actor_type_enum"))]
     pub struct ActorTypeEnum;
 
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "email_digest_mode_enum"))]
+    pub struct EmailDigestModeEnum;
+
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "email_template_type_enum"))]
+    pub struct EmailTemplateTypeEnum;
+
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "federation_block_severity_enum"))]
+    pub struct FederationBlockSeverityEnum;
+
     #[derive(diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "listing_type_enum"))]
     pub struct ListingTypeEnum;
@@ -13,10 +25,26 @@ pub mod sql_types {
     #[diesel(postgres_type(name = "ltree"))]
     pub struct Ltree;
 
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "media_scan_result_enum"))]
+    pub struct MediaScanResultEnum;
+
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "mod_action_type_enum"))]
+    pub struct ModActionTypeEnum;
+
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "notification_type_enum"))]
+    pub struct NotificationTypeEnum;
+
     #[derive(diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "post_listing_mode_enum"))]
     pub struct PostListingModeEnum;
 
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "private_message_policy_enum"))]
+    pub struct PrivateMessagePolicyEnum;
+
     #[derive(diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "registration_mode_enum"))]
     pub struct RegistrationModeEnum;
@@ -26,6 +54,21 @@ pub mod sql_types {
     pub struct SortTypeEnum;
 }
 
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::FederationBlockSeverityEnum;
+
+    admin_block_instance (id) {
+        id -> Int4,
+        admin_person_id -> Int4,
+        instance_id -> Int4,
+        severity -> FederationBlockSeverityEnum,
+        blocked -> Bool,
+        reason -> Nullable<Text>,
+        when_ -> Timestamptz,
+    }
+}
+
 diesel::table! {
     admin_purge_comment (id) {
         id -> Int4,
@@ -120,6 +163,14 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    comment_mute (person_id, comment_id) {
+        person_id -> Int4,
+        comment_id -> Int4,
+        published -> Timestamptz,
+    }
+}
+
 diesel::table! {
     comment_reply (id) {
         id -> Int4,
@@ -187,6 +238,11 @@ diesel::table! {
         moderators_url -> Nullable<Varchar>,
         #[max_length = 255]
         featured_url -> Nullable<Varchar>,
+        #[max_length = 255]
+        moved_to -> Nullable<Varchar>,
+        #[max_length = 255]
+        moved_from -> Nullable<Varchar>,
+        require_alt_text -> Bool,
     }
 }
 
@@ -252,6 +308,26 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    conversation (id) {
+        id -> Int4,
+        published -> Timestamptz,
+        last_message_published -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    conversation_participant (conversation_id, person_id) {
+        conversation_id -> Int4,
+        person_id -> Int4,
+        muted -> Bool,
+        archived -> Bool,
+        last_read -> Nullable<Timestamptz>,
+        published -> Timestamptz,
+        message_request -> Bool,
+    }
+}
+
 diesel::table! {
     custom_emoji (id) {
         id -> Int4,
@@ -275,6 +351,67 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    custom_feed (id) {
+        id -> Int4,
+        creator_id -> Int4,
+        #[max_length = 255]
+        name -> Varchar,
+        description -> Nullable<Text>,
+        public -> Bool,
+        published -> Timestamptz,
+        updated -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    custom_feed_community (custom_feed_id, community_id) {
+        custom_feed_id -> Int4,
+        community_id -> Int4,
+    }
+}
+
+diesel::table! {
+    email_digest_item (id) {
+        id -> Int4,
+        local_user_id -> Int4,
+        subject -> Text,
+        html -> Text,
+        published -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    email_outbox (id) {
+        id -> Int4,
+        local_user_id -> Nullable<Int4>,
+        recipient_email -> Text,
+        recipient_name -> Text,
+        subject -> Text,
+        html -> Text,
+        attempts -> Int4,
+        next_attempt -> Timestamptz,
+        last_error -> Nullable<Text>,
+        dead -> Bool,
+        published -> Timestamptz,
+        reply_to -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::EmailTemplateTypeEnum;
+
+    email_template (template_type, language_id) {
+        template_type -> EmailTemplateTypeEnum,
+        language_id -> Int4,
+        subject -> Text,
+        body -> Text,
+        published -> Timestamptz,
+        updated -> Nullable<Timestamptz>,
+    }
+}
+
 diesel::table! {
     email_verification (id) {
         id -> Int4,
@@ -295,11 +432,28 @@ diesel::table! {
 }
 
 diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::FederationBlockSeverityEnum;
+
     federation_blocklist (id) {
         id -> Int4,
         instance_id -> Int4,
         published -> Timestamptz,
         updated -> Nullable<Timestamptz>,
+        severity -> FederationBlockSeverityEnum,
+        public_comment -> Nullable<Text>,
+        private_comment -> Nullable<Text>,
+        subscription_id -> Nullable<Int4>,
+    }
+}
+
+diesel::table! {
+    federation_blocklist_subscription (id) {
+        id -> Int4,
+        url -> Text,
+        admin_person_id -> Int4,
+        published -> Timestamptz,
+        updated -> Nullable<Timestamptz>,
     }
 }
 
@@ -313,6 +467,14 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    feed_token (local_user_id) {
+        local_user_id -> Int4,
+        token -> Text,
+        published -> Timestamptz,
+    }
+}
+
 diesel::table! {
     image_upload (id) {
         id -> Int4,
@@ -320,6 +482,8 @@ diesel::table! {
         pictrs_alias -> Text,
         pictrs_delete_token -> Text,
         published -> Timestamptz,
+        size -> Nullable<Int8>,
+        alt_text -> Nullable<Text>,
     }
 }
 
@@ -385,6 +549,7 @@ diesel::table! {
         updated -> Nullable<Timestamptz>,
         registration_mode -> RegistrationModeEnum,
         reports_email_admins -> Bool,
+        federate_person_blocks -> Bool,
     }
 }
 
@@ -414,6 +579,8 @@ diesel::table! {
     use super::sql_types::SortTypeEnum;
     use super::sql_types::ListingTypeEnum;
     use super::sql_types::PostListingModeEnum;
+    use super::sql_types::EmailDigestModeEnum;
+    use super::sql_types::PrivateMessagePolicyEnum;
 
     local_user (id) {
         id -> Int4,
@@ -443,6 +610,11 @@ diesel::table! {
         admin -> Bool,
         post_listing_mode -> PostListingModeEnum,
         totp_2fa_enabled -> Bool,
+        upload_quota_max_total_size -> Nullable<Int8>,
+        upload_quota_max_files -> Nullable<Int4>,
+        email_digest -> EmailDigestModeEnum,
+        private_message_policy -> PrivateMessagePolicyEnum,
+        last_email_digest -> Nullable<Timestamptz>,
     }
 }
 
@@ -454,6 +626,41 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::MediaScanResultEnum;
+
+    media_scan (id) {
+        id -> Int4,
+        pictrs_alias -> Text,
+        person_id -> Nullable<Int4>,
+        remote_url -> Nullable<Text>,
+        result -> MediaScanResultEnum,
+        reason -> Nullable<Text>,
+        resolved -> Bool,
+        resolver_id -> Nullable<Int4>,
+        published -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::ModActionTypeEnum;
+
+    mod_action_notification (id) {
+        id -> Int4,
+        recipient_id -> Int4,
+        mod_person_id -> Int4,
+        action -> ModActionTypeEnum,
+        community_id -> Int4,
+        post_id -> Nullable<Int4>,
+        comment_id -> Nullable<Int4>,
+        reason -> Nullable<Text>,
+        read -> Bool,
+        published -> Timestamptz,
+    }
+}
+
 diesel::table! {
     mod_add (id) {
         id -> Int4,
@@ -576,6 +783,20 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::NotificationTypeEnum;
+
+    notification_preference (local_user_id, notification_type) {
+        local_user_id -> Int4,
+        notification_type -> NotificationTypeEnum,
+        push -> Bool,
+        in_app -> Bool,
+        email -> Bool,
+        only_from_followed -> Bool,
+    }
+}
+
 diesel::table! {
     password_reset_request (id) {
         id -> Int4,
@@ -644,6 +865,16 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    person_device_key (person_id, device_id) {
+        person_id -> Int4,
+        #[max_length = 255]
+        device_id -> Varchar,
+        public_key -> Text,
+        published -> Timestamptz,
+    }
+}
+
 diesel::table! {
     person_follower (id) {
         id -> Int4,
@@ -700,6 +931,11 @@ diesel::table! {
         language_id -> Int4,
         featured_community -> Bool,
         featured_local -> Bool,
+        alt_text -> Nullable<Text>,
+        url_content_type -> Nullable<Text>,
+        embed_width -> Nullable<Int4>,
+        embed_height -> Nullable<Int4>,
+        embed_duration -> Nullable<Int4>,
     }
 }
 
@@ -726,6 +962,17 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    post_gallery_image (id) {
+        id -> Int4,
+        post_id -> Int4,
+        url -> Text,
+        alt_text -> Nullable<Text>,
+        position -> Int4,
+        published -> Timestamptz,
+    }
+}
+
 diesel::table! {
     post_like (id) {
         id -> Int4,
@@ -736,6 +983,14 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    post_mute (person_id, post_id) {
+        person_id -> Int4,
+        post_id -> Int4,
+        published -> Timestamptz,
+    }
+}
+
 diesel::table! {
     post_read (id) {
         id -> Int4,
@@ -771,6 +1026,14 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    post_subscription (person_id, post_id) {
+        person_id -> Int4,
+        post_id -> Int4,
+        published -> Timestamptz,
+    }
+}
+
 diesel::table! {
     private_message (id) {
         id -> Int4,
@@ -784,6 +1047,8 @@ diesel::table! {
         #[max_length = 255]
         ap_id -> Varchar,
         local -> Bool,
+        conversation_id -> Int4,
+        encrypted -> Bool,
     }
 }
 
@@ -801,6 +1066,17 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    push_subscription (id) {
+        id -> Int4,
+        local_user_id -> Int4,
+        endpoint -> Text,
+        p256dh -> Text,
+        auth -> Text,
+        published -> Timestamptz,
+    }
+}
+
 diesel::table! {
     received_activity (id) {
         id -> Int8,
@@ -820,10 +1096,20 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    remote_image (link) {
+        link -> Text,
+        pictrs_alias -> Text,
+        published -> Timestamptz,
+    }
+}
+
 diesel::table! {
     secret (id) {
         id -> Int4,
         jwt_secret -> Varchar,
+        vapid_private_key -> Nullable<Text>,
+        vapid_public_key -> Nullable<Text>,
     }
 }
 
@@ -901,6 +1187,8 @@ diesel::table! {
     }
 }
 
+diesel::joinable!(admin_block_instance -> instance (instance_id));
+diesel::joinable!(admin_block_instance -> person (admin_person_id));
 diesel::joinable!(admin_purge_comment -> person (admin_person_id));
 diesel::joinable!(admin_purge_comment -> post (post_id));
 diesel::joinable!(admin_purge_community -> person (admin_person_id));
@@ -914,6 +1202,8 @@ diesel::joinable!(comment_aggregates -> comment (comment_id));
 diesel::joinable!(comment_like -> comment (comment_id));
 diesel::joinable!(comment_like -> person (person_id));
 diesel::joinable!(comment_like -> post (post_id));
+diesel::joinable!(comment_mute -> comment (comment_id));
+diesel::joinable!(comment_mute -> person (person_id));
 diesel::joinable!(comment_reply -> comment (comment_id));
 diesel::joinable!(comment_reply -> person (recipient_id));
 diesel::joinable!(comment_report -> comment (comment_id));
@@ -931,12 +1221,23 @@ diesel::joinable!(community_moderator -> community (community_id));
 diesel::joinable!(community_moderator -> person (person_id));
 diesel::joinable!(community_person_ban -> community (community_id));
 diesel::joinable!(community_person_ban -> person (person_id));
+diesel::joinable!(conversation_participant -> conversation (conversation_id));
+diesel::joinable!(conversation_participant -> person (person_id));
 diesel::joinable!(custom_emoji -> local_site (local_site_id));
 diesel::joinable!(custom_emoji_keyword -> custom_emoji (custom_emoji_id));
+diesel::joinable!(custom_feed -> person (creator_id));
+diesel::joinable!(custom_feed_community -> community (community_id));
+diesel::joinable!(custom_feed_community -> custom_feed (custom_feed_id));
+diesel::joinable!(email_digest_item -> local_user (local_user_id));
+diesel::joinable!(email_outbox -> local_user (local_user_id));
+diesel::joinable!(email_template -> language (language_id));
 diesel::joinable!(email_verification -> local_user (local_user_id));
 diesel::joinable!(federation_allowlist -> instance (instance_id));
+diesel::joinable!(federation_blocklist -> federation_blocklist_subscription (subscription_id));
 diesel::joinable!(federation_blocklist -> instance (instance_id));
+diesel::joinable!(federation_blocklist_subscription -> person (admin_person_id));
 diesel::joinable!(federation_queue_state -> instance (instance_id));
+diesel::joinable!(feed_token -> local_user (local_user_id));
 diesel::joinable!(image_upload -> local_user (local_user_id));
 diesel::joinable!(instance_block -> instance (instance_id));
 diesel::joinable!(instance_block -> person (person_id));
@@ -945,6 +1246,9 @@ diesel::joinable!(local_site_rate_limit -> local_site (local_site_id));
 diesel::joinable!(local_user -> person (person_id));
 diesel::joinable!(local_user_language -> language (language_id));
 diesel::joinable!(local_user_language -> local_user (local_user_id));
+diesel::joinable!(mod_action_notification -> comment (comment_id));
+diesel::joinable!(mod_action_notification -> community (community_id));
+diesel::joinable!(mod_action_notification -> post (post_id));
 diesel::joinable!(mod_add_community -> community (community_id));
 diesel::joinable!(mod_ban_from_community -> community (community_id));
 diesel::joinable!(mod_feature_post -> person (mod_person_id));
@@ -960,10 +1264,12 @@ diesel::joinable!(mod_remove_community -> person (mod_person_id));
 diesel::joinable!(mod_remove_post -> person (mod_person_id));
 diesel::joinable!(mod_remove_post -> post (post_id));
 diesel::joinable!(mod_transfer_community -> community (community_id));
+diesel::joinable!(notification_preference -> local_user (local_user_id));
 diesel::joinable!(password_reset_request -> local_user (local_user_id));
 diesel::joinable!(person -> instance (instance_id));
 diesel::joinable!(person_aggregates -> person (person_id));
 diesel::joinable!(person_ban -> person (person_id));
+diesel::joinable!(person_device_key -> person (person_id));
 diesel::joinable!(person_mention -> comment (comment_id));
 diesel::joinable!(person_mention -> person (recipient_id));
 diesel::joinable!(person_post_aggregates -> person (person_id));
@@ -975,14 +1281,21 @@ diesel::joinable!(post_aggregates -> community (community_id));
 diesel::joinable!(post_aggregates -> instance (instance_id));
 diesel::joinable!(post_aggregates -> person (creator_id));
 diesel::joinable!(post_aggregates -> post (post_id));
+diesel::joinable!(post_gallery_image -> post (post_id));
 diesel::joinable!(post_like -> person (person_id));
 diesel::joinable!(post_like -> post (post_id));
+diesel::joinable!(post_mute -> person (person_id));
+diesel::joinable!(post_mute -> post (post_id));
 diesel::joinable!(post_read -> person (person_id));
 diesel::joinable!(post_read -> post (post_id));
 diesel::joinable!(post_report -> post (post_id));
 diesel::joinable!(post_saved -> person (person_id));
 diesel::joinable!(post_saved -> post (post_id));
+diesel::joinable!(post_subscription -> person (person_id));
+diesel::joinable!(post_subscription -> post (post_id));
+diesel::joinable!(private_message -> conversation (conversation_id));
 diesel::joinable!(private_message_report -> private_message (private_message_id));
+diesel::joinable!(push_subscription -> local_user (local_user_id));
 diesel::joinable!(registration_application -> local_user (local_user_id));
 diesel::joinable!(registration_application -> person (admin_id));
 diesel::joinable!(site -> instance (instance_id));
@@ -992,6 +1305,7 @@ diesel::joinable!(site_language -> site (site_id));
 diesel::joinable!(tagline -> local_site (local_site_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
+    admin_block_instance,
     admin_purge_comment,
     admin_purge_community,
     admin_purge_person,
@@ -1000,6 +1314,7 @@ diesel::allow_tables_to_appear_in_same_query!(
     comment,
     comment_aggregates,
     comment_like,
+    comment_mute,
     comment_reply,
     comment_report,
     comment_saved,
@@ -1010,12 +1325,21 @@ diesel::allow_tables_to_appear_in_same_query!(
     community_language,
     community_moderator,
     community_person_ban,
+    conversation,
+    conversation_participant,
     custom_emoji,
     custom_emoji_keyword,
+    custom_feed,
+    custom_feed_community,
+    email_digest_item,
+    email_outbox,
+    email_template,
     email_verification,
     federation_allowlist,
     federation_blocklist,
+    federation_blocklist_subscription,
     federation_queue_state,
+    feed_token,
     image_upload,
     instance,
     instance_block,
@@ -1024,6 +1348,8 @@ diesel::allow_tables_to_appear_in_same_query!(
     local_site_rate_limit,
     local_user,
     local_user_language,
+    media_scan,
+    mod_action_notification,
     mod_add,
     mod_add_community,
     mod_ban,
@@ -1035,24 +1361,31 @@ diesel::allow_tables_to_appear_in_same_query!(
     mod_remove_community,
     mod_remove_post,
     mod_transfer_community,
+    notification_preference,
     password_reset_request,
     person,
     person_aggregates,
     person_ban,
     person_block,
+    person_device_key,
     person_follower,
     person_mention,
     person_post_aggregates,
     post,
     post_aggregates,
+    post_gallery_image,
     post_like,
+    post_mute,
     post_read,
     post_report,
     post_saved,
+    post_subscription,
     private_message,
     private_message_report,
+    push_subscription,
     received_activity,
     registration_application,
+    remote_image,
     secret,
     sent_activity,
     site,
@@ -2,7 +2,7 @@
 use crate::newtypes::LtreeDef;
 use crate::newtypes::{CommentId, DbUrl, LanguageId, PersonId, PostId};
 #[cfg(feature = "full")]
-use crate::schema::{comment, comment_like, comment_saved};
+use crate::schema::{comment, comment_like, comment_mute, comment_saved};
 use chrono::{DateTime, Utc};
 #[cfg(feature = "full")]
 use diesel_ltree::Ltree;
@@ -122,3 +122,22 @@ pub struct CommentSavedForm {
   pub comment_id: CommentId,
   pub person_id: PersonId,
 }
+
+#[derive(PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "full", derive(Identifiable, Queryable, Associations))]
+#[cfg_attr(feature = "full", diesel(belongs_to(crate::source::comment::Comment)))]
+#[cfg_attr(feature = "full", diesel(table_name = comment_mute))]
+#[cfg_attr(feature = "full", diesel(primary_key(person_id, comment_id)))]
+/// The person isn't notified about replies to the comment, or anywhere below it.
+pub struct CommentMute {
+  pub person_id: PersonId,
+  pub comment_id: CommentId,
+  pub published: DateTime<Utc>,
+}
+
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = comment_mute))]
+pub struct CommentMuteForm {
+  pub person_id: PersonId,
+  pub comment_id: CommentId,
+}
@@ -65,6 +65,12 @@ pub struct Community {
   /// Url where featured posts collection is served over Activitypub
   #[serde(skip)]
   pub featured_url: Option<DbUrl>,
+  /// If the community was moved to another instance, the actor id of the new community.
+  pub moved_to: Option<DbUrl>,
+  /// If the community was moved here from another instance, the actor id of the old community.
+  pub moved_from: Option<DbUrl>,
+  /// Whether posts with an image need alt text.
+  pub require_alt_text: bool,
 }
 
 #[derive(Debug, Clone, TypedBuilder)]
@@ -98,6 +104,9 @@ pub struct CommunityInsertForm {
   pub posting_restricted_to_mods: Option<bool>,
   #[builder(!default)]
   pub instance_id: InstanceId,
+  pub moved_to: Option<DbUrl>,
+  pub moved_from: Option<DbUrl>,
+  pub require_alt_text: Option<bool>,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -125,6 +134,9 @@ pub struct CommunityUpdateForm {
   pub featured_url: Option<DbUrl>,
   pub hidden: Option<bool>,
   pub posting_restricted_to_mods: Option<bool>,
+  pub moved_to: Option<Option<DbUrl>>,
+  pub moved_from: Option<Option<DbUrl>>,
+  pub require_alt_text: Option<bool>,
 }
 
 #[derive(PartialEq, Eq, Debug)]
@@ -0,0 +1,63 @@
+use crate::newtypes::{ConversationId, PersonId};
+#[cfg(feature = "full")]
+use crate::schema::{conversation, conversation_participant};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+#[cfg(feature = "full")]
+use ts_rs::TS;
+
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = conversation))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A private message conversation between a fixed set of participants.
+pub struct Conversation {
+  pub id: ConversationId,
+  pub published: DateTime<Utc>,
+  pub last_message_published: DateTime<Utc>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Identifiable, Associations, TS))]
+#[cfg_attr(
+  feature = "full",
+  diesel(belongs_to(crate::source::conversation::Conversation))
+)]
+#[cfg_attr(feature = "full", diesel(table_name = conversation_participant))]
+#[cfg_attr(feature = "full", diesel(primary_key(conversation_id, person_id)))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The settings of a participant for a conversation.
+pub struct ConversationParticipant {
+  pub conversation_id: ConversationId,
+  pub person_id: PersonId,
+  /// Don't send notifications for new messages, and don't count them as unread.
+  pub muted: bool,
+  /// Hide the conversation from the list, until a new message arrives.
+  pub archived: bool,
+  /// Messages up to this time are read.
+  pub last_read: Option<DateTime<Utc>>,
+  pub published: DateTime<Utc>,
+  /// The conversation was started by someone the participant doesn't follow, and is shown with
+  /// the message requests until they accept it.
+  pub message_request: bool,
+}
+
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = conversation_participant))]
+pub struct ConversationParticipantInsertForm {
+  pub conversation_id: ConversationId,
+  pub person_id: PersonId,
+  pub message_request: bool,
+}
+
+#[derive(Clone, Default)]
+#[cfg_attr(feature = "full", derive(AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = conversation_participant))]
+pub struct ConversationParticipantUpdateForm {
+  pub muted: Option<bool>,
+  pub archived: Option<bool>,
+  pub last_read: Option<Option<DateTime<Utc>>>,
+  pub message_request: Option<bool>,
+}
@@ -0,0 +1,74 @@
+use crate::newtypes::{CommunityId, CustomFeedId, PersonId};
+#[cfg(feature = "full")]
+use crate::schema::{custom_feed, custom_feed_community};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+#[cfg(feature = "full")]
+use ts_rs::TS;
+use typed_builder::TypedBuilder;
+
+#[skip_serializing_none]
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Associations, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = custom_feed))]
+#[cfg_attr(
+  feature = "full",
+  diesel(belongs_to(crate::source::person::Person, foreign_key = creator_id))
+)]
+#[cfg_attr(feature = "full", ts(export))]
+/// A named group of communities, which is browsed like a single community.
+pub struct CustomFeed {
+  pub id: CustomFeedId,
+  pub creator_id: PersonId,
+  pub name: String,
+  pub description: Option<String>,
+  /// Whether others can view the feed by its link.
+  pub public: bool,
+  pub published: DateTime<Utc>,
+  pub updated: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, TypedBuilder)]
+#[builder(field_defaults(default))]
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = custom_feed))]
+pub struct CustomFeedInsertForm {
+  #[builder(!default)]
+  pub creator_id: PersonId,
+  #[builder(!default)]
+  pub name: String,
+  pub description: Option<String>,
+  pub public: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "full", derive(AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = custom_feed))]
+pub struct CustomFeedUpdateForm {
+  pub name: Option<String>,
+  pub description: Option<Option<String>>,
+  pub public: Option<bool>,
+  pub updated: Option<Option<DateTime<Utc>>>,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "full", derive(Queryable, Associations, Identifiable))]
+#[cfg_attr(feature = "full", diesel(table_name = custom_feed_community))]
+#[cfg_attr(feature = "full", diesel(primary_key(custom_feed_id, community_id)))]
+#[cfg_attr(
+  feature = "full",
+  diesel(belongs_to(crate::source::custom_feed::CustomFeed))
+)]
+pub struct CustomFeedCommunity {
+  pub custom_feed_id: CustomFeedId,
+  pub community_id: CommunityId,
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = custom_feed_community))]
+pub struct CustomFeedCommunityForm {
+  pub custom_feed_id: CustomFeedId,
+  pub community_id: CommunityId,
+}
@@ -0,0 +1,25 @@
+use crate::newtypes::LocalUserId;
+#[cfg(feature = "full")]
+use crate::schema::email_digest_item;
+use chrono::{DateTime, Utc};
+
+/// A notification for a user in digest mode, which is sent later together with the others.
+#[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "full", derive(Queryable, Identifiable))]
+#[cfg_attr(feature = "full", diesel(table_name = email_digest_item))]
+pub struct EmailDigestItem {
+  pub id: i32,
+  pub local_user_id: LocalUserId,
+  pub subject: String,
+  pub html: String,
+  pub published: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = email_digest_item))]
+pub struct EmailDigestItemForm {
+  pub local_user_id: LocalUserId,
+  pub subject: String,
+  pub html: String,
+}
@@ -0,0 +1,48 @@
+use crate::newtypes::LocalUserId;
+#[cfg(feature = "full")]
+use crate::schema::email_outbox;
+use chrono::{DateTime, Utc};
+
+/// An email which is waiting to be sent by the background worker, or which couldn't be delivered.
+#[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "full", derive(Queryable, Identifiable))]
+#[cfg_attr(feature = "full", diesel(table_name = email_outbox))]
+pub struct EmailOutbox {
+  pub id: i32,
+  pub local_user_id: Option<LocalUserId>,
+  pub recipient_email: String,
+  pub recipient_name: String,
+  pub subject: String,
+  pub html: String,
+  /// How often sending was tried already.
+  pub attempts: i32,
+  pub next_attempt: DateTime<Utc>,
+  pub last_error: Option<String>,
+  /// The email won't be retried anymore.
+  pub dead: bool,
+  pub published: DateTime<Utc>,
+  /// Replies to this address are turned into a comment or private message of the recipient.
+  pub reply_to: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = email_outbox))]
+pub struct EmailOutboxForm {
+  pub local_user_id: Option<LocalUserId>,
+  pub recipient_email: String,
+  pub recipient_name: String,
+  pub subject: String,
+  pub html: String,
+  pub reply_to: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "full", derive(AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = email_outbox))]
+pub struct EmailOutboxFailureForm {
+  pub attempts: i32,
+  pub next_attempt: DateTime<Utc>,
+  pub last_error: Option<String>,
+  pub dead: bool,
+}
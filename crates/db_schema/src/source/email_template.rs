@@ -0,0 +1,37 @@
+#[cfg(feature = "full")]
+use crate::schema::email_template;
+use crate::{newtypes::LanguageId, EmailTemplateType};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+#[cfg(feature = "full")]
+use ts_rs::TS;
+
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = email_template))]
+#[cfg_attr(feature = "full", diesel(primary_key(template_type, language_id)))]
+#[cfg_attr(feature = "full", ts(export))]
+/// An email text set by the admins, which replaces the built-in translation.
+pub struct EmailTemplate {
+  pub template_type: EmailTemplateType,
+  /// The undetermined language stands for all languages without a template of their own.
+  pub language_id: LanguageId,
+  /// Plain text subject, may contain `{{ variable }}` placeholders.
+  pub subject: String,
+  /// Html body, may contain `{{ variable }}` placeholders.
+  pub body: String,
+  pub published: DateTime<Utc>,
+  pub updated: Option<DateTime<Utc>>,
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = email_template))]
+pub struct EmailTemplateForm {
+  pub template_type: EmailTemplateType,
+  pub language_id: LanguageId,
+  pub subject: String,
+  pub body: String,
+}
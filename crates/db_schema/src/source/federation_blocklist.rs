@@ -1,9 +1,15 @@
-use crate::newtypes::InstanceId;
 #[cfg(feature = "full")]
-use crate::schema::federation_blocklist;
+use crate::schema::{federation_blocklist, federation_blocklist_subscription};
+use crate::{
+  newtypes::{DbUrl, InstanceId, PersonId},
+  FederationBlockSeverity,
+};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
 use std::fmt::Debug;
+#[cfg(feature = "full")]
+use ts_rs::TS;
 
 #[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
 #[cfg_attr(feature = "full", derive(Queryable, Associations, Identifiable))]
@@ -17,12 +23,55 @@ pub struct FederationBlockList {
   pub instance_id: InstanceId,
   pub published: DateTime<Utc>,
   pub updated: Option<DateTime<Utc>>,
+  pub severity: FederationBlockSeverity,
+  /// Shown to everyone who views the blocklist.
+  pub public_comment: Option<String>,
+  /// Only shown to admins.
+  pub private_comment: Option<String>,
+  /// The shared blocklist which this entry was imported from, if any.
+  pub subscription_id: Option<i32>,
 }
 
 #[derive(Clone, Default)]
 #[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
 #[cfg_attr(feature = "full", diesel(table_name = federation_blocklist))]
+#[cfg_attr(feature = "full", diesel(treat_none_as_null = true))]
 pub struct FederationBlockListForm {
   pub instance_id: InstanceId,
   pub updated: Option<DateTime<Utc>>,
+  pub severity: FederationBlockSeverity,
+  pub public_comment: Option<String>,
+  pub private_comment: Option<String>,
+  pub subscription_id: Option<i32>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = federation_blocklist_subscription))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A shared blocklist which is fetched again every day, and applied to the federation blocklist.
+pub struct FederationBlocklistSubscription {
+  pub id: i32,
+  pub url: DbUrl,
+  /// The admin who subscribed to the list. Changes from refreshing it are logged in their name.
+  pub admin_person_id: PersonId,
+  pub published: DateTime<Utc>,
+  /// The last time the list was fetched successfully.
+  pub updated: Option<DateTime<Utc>>,
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = federation_blocklist_subscription))]
+pub struct FederationBlocklistSubscriptionInsertForm {
+  pub url: DbUrl,
+  pub admin_person_id: PersonId,
+}
+
+#[derive(Clone, Default)]
+#[cfg_attr(feature = "full", derive(AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = federation_blocklist_subscription))]
+pub struct FederationBlocklistSubscriptionUpdateForm {
+  pub updated: Option<Option<DateTime<Utc>>>,
 }
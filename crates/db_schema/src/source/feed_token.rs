@@ -0,0 +1,23 @@
+use crate::newtypes::LocalUserId;
+#[cfg(feature = "full")]
+use crate::schema::feed_token;
+use chrono::{DateTime, Utc};
+
+/// A token which gives access to the private feeds of a user, like the front page and inbox.
+#[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "full", derive(Queryable, Identifiable))]
+#[cfg_attr(feature = "full", diesel(table_name = feed_token))]
+#[cfg_attr(feature = "full", diesel(primary_key(local_user_id)))]
+pub struct FeedToken {
+  pub local_user_id: LocalUserId,
+  pub token: String,
+  pub published: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = feed_token))]
+pub struct FeedTokenForm {
+  pub local_user_id: LocalUserId,
+  pub token: String,
+}
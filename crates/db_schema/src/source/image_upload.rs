@@ -24,6 +24,10 @@ pub struct ImageUpload {
   pub pictrs_alias: String,
   pub pictrs_delete_token: String,
   pub published: DateTime<Utc>,
+  /// The file size in bytes.
+  pub size: Option<i64>,
+  /// A description of the image, for screen readers.
+  pub alt_text: Option<String>,
 }
 
 #[derive(Debug, Clone, TypedBuilder)]
@@ -33,4 +37,6 @@ pub struct ImageUploadForm {
   pub local_user_id: LocalUserId,
   pub pictrs_alias: String,
   pub pictrs_delete_token: String,
+  pub size: Option<i64>,
+  pub alt_text: Option<String>,
 }
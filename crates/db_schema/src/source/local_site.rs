@@ -60,6 +60,8 @@ pub struct LocalSite {
   pub registration_mode: RegistrationMode,
   /// Whether to email admins on new reports.
   pub reports_email_admins: bool,
+  /// Whether to notify remote instances when a local user blocks one of their users.
+  pub federate_person_blocks: bool,
 }
 
 #[derive(Clone, TypedBuilder)]
@@ -88,6 +90,7 @@ pub struct LocalSiteInsertForm {
   pub captcha_difficulty: Option<String>,
   pub registration_mode: Option<RegistrationMode>,
   pub reports_email_admins: Option<bool>,
+  pub federate_person_blocks: Option<bool>,
 }
 
 #[derive(Clone, Default)]
@@ -113,5 +116,6 @@ pub struct LocalSiteUpdateForm {
   pub captcha_difficulty: Option<String>,
   pub registration_mode: Option<RegistrationMode>,
   pub reports_email_admins: Option<bool>,
+  pub federate_person_blocks: Option<bool>,
   pub updated: Option<Option<DateTime<Utc>>>,
 }
@@ -2,8 +2,10 @@
 use crate::schema::local_user;
 use crate::{
   newtypes::{LocalUserId, PersonId},
+  EmailDigestMode,
   ListingType,
   PostListingMode,
+  PrivateMessagePolicy,
   SortType,
 };
 use chrono::{DateTime, Utc};
@@ -61,6 +63,16 @@ pub struct LocalUser {
   pub admin: bool,
   pub post_listing_mode: PostListingMode,
   pub totp_2fa_enabled: bool,
+  /// Maximum total size of uploads in bytes, if an admin changed it from the site default.
+  pub upload_quota_max_total_size: Option<i64>,
+  /// Maximum number of uploads, if an admin changed it from the site default.
+  pub upload_quota_max_files: Option<i32>,
+  /// Whether notification emails are sent right away, or bundled into a digest.
+  pub email_digest: EmailDigestMode,
+  /// Who can start a private message conversation with the user.
+  pub private_message_policy: PrivateMessagePolicy,
+  #[serde(skip)]
+  pub last_email_digest: Option<DateTime<Utc>>,
 }
 
 #[derive(Clone, TypedBuilder)]
@@ -94,6 +106,10 @@ pub struct LocalUserInsertForm {
   pub admin: Option<bool>,
   pub post_listing_mode: Option<PostListingMode>,
   pub totp_2fa_enabled: Option<bool>,
+  pub upload_quota_max_total_size: Option<Option<i64>>,
+  pub upload_quota_max_files: Option<Option<i32>>,
+  pub email_digest: Option<EmailDigestMode>,
+  pub private_message_policy: Option<PrivateMessagePolicy>,
 }
 
 #[derive(Clone, Default)]
@@ -123,4 +139,9 @@ pub struct LocalUserUpdateForm {
   pub admin: Option<bool>,
   pub post_listing_mode: Option<PostListingMode>,
   pub totp_2fa_enabled: Option<bool>,
+  pub upload_quota_max_total_size: Option<Option<i64>>,
+  pub upload_quota_max_files: Option<Option<i32>>,
+  pub email_digest: Option<EmailDigestMode>,
+  pub private_message_policy: Option<PrivateMessagePolicy>,
+  pub last_email_digest: Option<Option<DateTime<Utc>>>,
 }
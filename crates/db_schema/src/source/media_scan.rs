@@ -0,0 +1,47 @@
+#[cfg(feature = "full")]
+use crate::schema::media_scan;
+use crate::{
+  newtypes::{DbUrl, MediaScanId, PersonId},
+  MediaScanResult,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+#[cfg(feature = "full")]
+use ts_rs::TS;
+
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = media_scan))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The result of scanning an upload or cached remote image, kept for audit.
+pub struct MediaScan {
+  pub id: MediaScanId,
+  pub pictrs_alias: String,
+  /// The uploader, empty for remote images.
+  pub person_id: Option<PersonId>,
+  #[cfg_attr(feature = "full", ts(type = "string"))]
+  /// The original link of a remote image.
+  pub remote_url: Option<DbUrl>,
+  pub result: MediaScanResult,
+  /// The explanation given by the scanner.
+  pub reason: Option<String>,
+  /// Whether an admin reviewed a denied or quarantined media.
+  pub resolved: bool,
+  pub resolver_id: Option<PersonId>,
+  pub published: DateTime<Utc>,
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = media_scan))]
+pub struct MediaScanForm {
+  pub pictrs_alias: String,
+  pub person_id: Option<PersonId>,
+  pub remote_url: Option<DbUrl>,
+  pub result: MediaScanResult,
+  pub reason: Option<String>,
+  /// Allowed media doesn't need to be reviewed
+  pub resolved: bool,
+}
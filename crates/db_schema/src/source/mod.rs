@@ -10,11 +10,17 @@ pub mod comment_reply;
 pub mod comment_report;
 pub mod community;
 pub mod community_block;
+pub mod conversation;
 pub mod custom_emoji;
 pub mod custom_emoji_keyword;
+pub mod custom_feed;
+pub mod email_digest_item;
+pub mod email_outbox;
+pub mod email_template;
 pub mod email_verification;
 pub mod federation_allowlist;
 pub mod federation_blocklist;
+pub mod feed_token;
 pub mod image_upload;
 pub mod instance;
 pub mod instance_block;
@@ -22,16 +28,23 @@ pub mod language;
 pub mod local_site;
 pub mod local_site_rate_limit;
 pub mod local_user;
+pub mod media_scan;
+pub mod mod_action_notification;
 pub mod moderator;
+pub mod notification_preference;
 pub mod password_reset_request;
 pub mod person;
 pub mod person_block;
+pub mod person_device_key;
 pub mod person_mention;
 pub mod post;
+pub mod post_gallery_image;
 pub mod post_report;
 pub mod private_message;
 pub mod private_message_report;
+pub mod push_subscription;
 pub mod registration_application;
+pub mod remote_image;
 pub mod secret;
 pub mod site;
 pub mod tagline;
@@ -0,0 +1,49 @@
+#[cfg(feature = "full")]
+use crate::schema::mod_action_notification;
+use crate::{
+  newtypes::{CommentId, CommunityId, ModActionNotificationId, PersonId, PostId},
+  ModActionType,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+#[cfg(feature = "full")]
+use ts_rs::TS;
+
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = mod_action_notification))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Tells a user that a moderator took action against their content or account.
+pub struct ModActionNotification {
+  pub id: ModActionNotificationId,
+  pub recipient_id: PersonId,
+  pub mod_person_id: PersonId,
+  pub action: ModActionType,
+  pub community_id: CommunityId,
+  pub post_id: Option<PostId>,
+  pub comment_id: Option<CommentId>,
+  pub reason: Option<String>,
+  pub read: bool,
+  pub published: DateTime<Utc>,
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = mod_action_notification))]
+pub struct ModActionNotificationInsertForm {
+  pub recipient_id: PersonId,
+  pub mod_person_id: PersonId,
+  pub action: ModActionType,
+  pub community_id: CommunityId,
+  pub post_id: Option<PostId>,
+  pub comment_id: Option<CommentId>,
+  pub reason: Option<String>,
+}
+
+#[cfg_attr(feature = "full", derive(AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = mod_action_notification))]
+pub struct ModActionNotificationUpdateForm {
+  pub read: Option<bool>,
+}
@@ -1,6 +1,6 @@
-use crate::newtypes::{CommentId, CommunityId, PersonId, PostId};
 #[cfg(feature = "full")]
 use crate::schema::{
+  admin_block_instance,
   admin_purge_comment,
   admin_purge_community,
   admin_purge_person,
@@ -17,6 +17,10 @@ use crate::schema::{
   mod_remove_post,
   mod_transfer_community,
 };
+use crate::{
+  newtypes::{CommentId, CommunityId, InstanceId, PersonId, PostId},
+  FederationBlockSeverity,
+};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
@@ -367,3 +371,29 @@ pub struct AdminPurgeCommentForm {
   pub post_id: PostId,
   pub reason: Option<String>,
 }
+
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = admin_block_instance))]
+#[cfg_attr(feature = "full", ts(export))]
+/// When an admin adds an instance to the federation blocklist, or removes it.
+pub struct AdminBlockInstance {
+  pub id: i32,
+  pub admin_person_id: PersonId,
+  pub instance_id: InstanceId,
+  pub severity: FederationBlockSeverity,
+  pub blocked: bool,
+  pub reason: Option<String>,
+  pub when_: DateTime<Utc>,
+}
+
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = admin_block_instance))]
+pub struct AdminBlockInstanceForm {
+  pub admin_person_id: PersonId,
+  pub instance_id: InstanceId,
+  pub severity: FederationBlockSeverity,
+  pub blocked: bool,
+  pub reason: Option<String>,
+}
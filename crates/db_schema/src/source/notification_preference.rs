@@ -0,0 +1,55 @@
+#[cfg(feature = "full")]
+use crate::schema::notification_preference;
+use crate::{newtypes::LocalUserId, NotificationType};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "full")]
+use ts_rs::TS;
+
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = notification_preference))]
+#[cfg_attr(
+  feature = "full",
+  diesel(primary_key(local_user_id, notification_type))
+)]
+#[cfg_attr(feature = "full", ts(export))]
+/// How a user wants to be notified about one type of notification.
+pub struct NotificationPreference {
+  #[serde(skip)]
+  pub local_user_id: LocalUserId,
+  pub notification_type: NotificationType,
+  /// Send push notifications to the devices of the user.
+  pub push: bool,
+  /// Show the notification in the inbox and live event stream.
+  pub in_app: bool,
+  /// Send an email, if email notifications are enabled.
+  pub email: bool,
+  /// Ignore notifications from users you don't follow.
+  pub only_from_followed: bool,
+}
+
+impl NotificationPreference {
+  /// The preference which applies if the user didn't change anything.
+  pub fn default_for(local_user_id: LocalUserId, notification_type: NotificationType) -> Self {
+    NotificationPreference {
+      local_user_id,
+      notification_type,
+      push: true,
+      in_app: true,
+      email: true,
+      only_from_followed: false,
+    }
+  }
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = notification_preference))]
+pub struct NotificationPreferenceForm {
+  pub local_user_id: LocalUserId,
+  pub notification_type: NotificationType,
+  pub push: Option<bool>,
+  pub in_app: Option<bool>,
+  pub email: Option<bool>,
+  pub only_from_followed: Option<bool>,
+}
@@ -0,0 +1,30 @@
+use crate::newtypes::PersonId;
+#[cfg(feature = "full")]
+use crate::schema::person_device_key;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "full")]
+use ts_rs::TS;
+
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = person_device_key))]
+#[cfg_attr(feature = "full", diesel(primary_key(person_id, device_id)))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The public key of a device, which other clients use to encrypt private messages for it.
+pub struct PersonDeviceKey {
+  pub person_id: PersonId,
+  /// Chosen by the client, unique for each person.
+  pub device_id: String,
+  pub public_key: String,
+  pub published: DateTime<Utc>,
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = person_device_key))]
+pub struct PersonDeviceKeyForm {
+  pub person_id: PersonId,
+  pub device_id: String,
+  pub public_key: String,
+}
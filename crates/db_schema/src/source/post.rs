@@ -1,6 +1,6 @@
 use crate::newtypes::{CommunityId, DbUrl, LanguageId, PersonId, PostId};
 #[cfg(feature = "full")]
-use crate::schema::{post, post_like, post_read, post_saved};
+use crate::schema::{post, post_like, post_mute, post_read, post_saved, post_subscription};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
@@ -54,6 +54,16 @@ pub struct Post {
   pub featured_community: bool,
   /// Whether the post is featured to its site.
   pub featured_local: bool,
+  /// A description of the image at the post url, for screen readers.
+  pub alt_text: Option<String>,
+  /// The content type of the url, for example `video/mp4`.
+  pub url_content_type: Option<String>,
+  /// The width of the embedded video or image, in pixels.
+  pub embed_width: Option<i32>,
+  /// The height of the embedded video or image, in pixels.
+  pub embed_height: Option<i32>,
+  /// The duration of the linked video or audio, in seconds.
+  pub embed_duration: Option<i32>,
 }
 
 #[derive(Debug, Clone, TypedBuilder)]
@@ -84,6 +94,11 @@ pub struct PostInsertForm {
   pub language_id: Option<LanguageId>,
   pub featured_community: Option<bool>,
   pub featured_local: Option<bool>,
+  pub alt_text: Option<String>,
+  pub url_content_type: Option<String>,
+  pub embed_width: Option<i32>,
+  pub embed_height: Option<i32>,
+  pub embed_duration: Option<i32>,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -108,6 +123,11 @@ pub struct PostUpdateForm {
   pub language_id: Option<LanguageId>,
   pub featured_community: Option<bool>,
   pub featured_local: Option<bool>,
+  pub alt_text: Option<Option<String>>,
+  pub url_content_type: Option<Option<String>>,
+  pub embed_width: Option<Option<i32>>,
+  pub embed_height: Option<Option<i32>>,
+  pub embed_duration: Option<Option<i32>>,
 }
 
 #[derive(PartialEq, Eq, Debug)]
@@ -149,6 +169,44 @@ pub struct PostSavedForm {
   pub person_id: PersonId,
 }
 
+#[derive(PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "full", derive(Identifiable, Queryable, Associations))]
+#[cfg_attr(feature = "full", diesel(belongs_to(crate::source::post::Post)))]
+#[cfg_attr(feature = "full", diesel(table_name = post_subscription))]
+#[cfg_attr(feature = "full", diesel(primary_key(person_id, post_id)))]
+/// The person is notified about all new comments in the post.
+pub struct PostSubscription {
+  pub person_id: PersonId,
+  pub post_id: PostId,
+  pub published: DateTime<Utc>,
+}
+
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = post_subscription))]
+pub struct PostSubscriptionForm {
+  pub person_id: PersonId,
+  pub post_id: PostId,
+}
+
+#[derive(PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "full", derive(Identifiable, Queryable, Associations))]
+#[cfg_attr(feature = "full", diesel(belongs_to(crate::source::post::Post)))]
+#[cfg_attr(feature = "full", diesel(table_name = post_mute))]
+#[cfg_attr(feature = "full", diesel(primary_key(person_id, post_id)))]
+/// The person isn't notified about replies in the post.
+pub struct PostMute {
+  pub person_id: PersonId,
+  pub post_id: PostId,
+  pub published: DateTime<Utc>,
+}
+
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = post_mute))]
+pub struct PostMuteForm {
+  pub person_id: PersonId,
+  pub post_id: PostId,
+}
+
 #[derive(PartialEq, Eq, Debug)]
 #[cfg_attr(feature = "full", derive(Identifiable, Queryable, Associations))]
 #[cfg_attr(feature = "full", diesel(belongs_to(crate::source::post::Post)))]
@@ -0,0 +1,37 @@
+use crate::newtypes::{DbUrl, PostId};
+#[cfg(feature = "full")]
+use crate::schema::post_gallery_image;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+#[cfg(feature = "full")]
+use ts_rs::TS;
+
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Associations, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = post_gallery_image))]
+#[cfg_attr(feature = "full", diesel(belongs_to(crate::source::post::Post)))]
+#[cfg_attr(feature = "full", ts(export))]
+/// An image of a gallery post.
+pub struct PostGalleryImage {
+  pub id: i32,
+  pub post_id: PostId,
+  #[cfg_attr(feature = "full", ts(type = "string"))]
+  pub url: DbUrl,
+  /// A description of the image, for screen readers.
+  pub alt_text: Option<String>,
+  /// The position of the image in the gallery, starting at 0.
+  pub position: i32,
+  pub published: DateTime<Utc>,
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = post_gallery_image))]
+pub struct PostGalleryImageForm {
+  pub post_id: PostId,
+  pub url: DbUrl,
+  pub alt_text: Option<String>,
+  pub position: i32,
+}
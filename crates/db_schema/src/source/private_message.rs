@@ -1,4 +1,4 @@
-use crate::newtypes::{DbUrl, PersonId, PrivateMessageId};
+use crate::newtypes::{ConversationId, DbUrl, PersonId, PrivateMessageId};
 #[cfg(feature = "full")]
 use crate::schema::private_message;
 use chrono::{DateTime, Utc};
@@ -21,6 +21,7 @@ use typed_builder::TypedBuilder;
 pub struct PrivateMessage {
   pub id: PrivateMessageId,
   pub creator_id: PersonId,
+  /// For group conversations, the first of the recipients.
   pub recipient_id: PersonId,
   pub content: String,
   pub deleted: bool,
@@ -29,6 +30,10 @@ pub struct PrivateMessage {
   pub updated: Option<DateTime<Utc>>,
   pub ap_id: DbUrl,
   pub local: bool,
+  pub conversation_id: ConversationId,
+  /// The content is end-to-end encrypted, and can only be read on the devices of the
+  /// participants.
+  pub encrypted: bool,
 }
 
 #[derive(Clone, TypedBuilder)]
@@ -48,6 +53,9 @@ pub struct PrivateMessageInsertForm {
   pub updated: Option<DateTime<Utc>>,
   pub ap_id: Option<DbUrl>,
   pub local: Option<bool>,
+  #[builder(!default)]
+  pub conversation_id: ConversationId,
+  pub encrypted: Option<bool>,
 }
 
 #[derive(Clone, Default)]
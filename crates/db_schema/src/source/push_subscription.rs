@@ -0,0 +1,34 @@
+use crate::newtypes::{LocalUserId, PushSubscriptionId};
+#[cfg(feature = "full")]
+use crate::schema::push_subscription;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "full")]
+use ts_rs::TS;
+
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = push_subscription))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A device or browser which receives push notifications for a user.
+pub struct PushSubscription {
+  pub id: PushSubscriptionId,
+  pub local_user_id: LocalUserId,
+  /// The url of the push service, which is unique for each subscription.
+  pub endpoint: String,
+  #[serde(skip)]
+  pub p256dh: String,
+  #[serde(skip)]
+  pub auth: String,
+  pub published: DateTime<Utc>,
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = push_subscription))]
+pub struct PushSubscriptionForm {
+  pub local_user_id: LocalUserId,
+  pub endpoint: String,
+  pub p256dh: String,
+  pub auth: String,
+}
@@ -0,0 +1,24 @@
+#[cfg(feature = "full")]
+use crate::schema::remote_image;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+
+/// A remote image which was cached by the image proxy.
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Identifiable))]
+#[cfg_attr(feature = "full", diesel(table_name = remote_image))]
+#[cfg_attr(feature = "full", diesel(primary_key(link)))]
+pub struct RemoteImage {
+  pub link: String,
+  pub pictrs_alias: String,
+  pub published: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = remote_image))]
+pub struct RemoteImageForm {
+  pub link: String,
+  pub pictrs_alias: String,
+}
@@ -7,4 +7,8 @@ use crate::schema::secret;
 pub struct Secret {
   pub id: i32,
   pub jwt_secret: String,
+  /// Private key for signing push messages, base64url encoded
+  pub vapid_private_key: Option<String>,
+  /// Public key for push messages, which browsers need to subscribe
+  pub vapid_public_key: Option<String>,
 }
@@ -375,6 +375,9 @@ mod tests {
         shared_inbox_url: inserted_community.shared_inbox_url,
         moderators_url: inserted_community.moderators_url,
         featured_url: inserted_community.featured_url,
+        moved_to: None,
+        require_alt_text: false,
+        moved_from: None,
         instance_id: inserted_instance.id,
       },
       creator: Person {
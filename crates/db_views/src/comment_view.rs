@@ -1,5 +1,6 @@
 use crate::structs::{CommentView, LocalUserView};
 use diesel::{
+  dsl::{exists, not},
   pg::Pg,
   result::Error,
   BoolExpressionMethods,
@@ -23,15 +24,18 @@ use lemmy_db_schema::{
     community_follower,
     community_moderator,
     community_person_ban,
+    federation_blocklist,
     instance_block,
     local_user_language,
     person,
     person_block,
+    person_follower,
     post,
   },
   source::community::CommunityFollower,
   utils::{fuzzy_search, limit_and_offset, DbConn, DbPool, ListFn, Queries, ReadFn},
   CommentSortType,
+  FederationBlockSeverity,
   ListingType,
 };
 
@@ -188,6 +192,34 @@ fn queries<'a>() -> Queries<
       }
     }
 
+    // Content from limited instances only shows up in `All` for those who follow the community or
+    // the creator
+    if options.community_id.is_none()
+      && options.post_id.is_none()
+      && options.creator_id.is_none()
+      && matches!(options.listing_type, None | Some(ListingType::All))
+    {
+      let is_limited = exists(
+        federation_blocklist::table.filter(
+          federation_blocklist::severity
+            .eq(FederationBlockSeverity::Silence)
+            .and(
+              federation_blocklist::instance_id
+                .eq(community::instance_id)
+                .or(federation_blocklist::instance_id.eq(person::instance_id)),
+            ),
+        ),
+      );
+      let is_followed = community_follower::person_id.eq(person_id_join).or(exists(
+        person_follower::table.filter(
+          comment::creator_id
+            .eq(person_follower::person_id)
+            .and(person_follower::follower_id.eq(person_id_join)),
+        ),
+      ));
+      query = query.filter(not(is_limited).or(is_followed));
+    }
+
     if options.saved_only {
       query = query.filter(comment_saved::comment_id.is_not_null());
     }
@@ -860,6 +892,11 @@ mod tests {
         language_id: Default::default(),
         featured_community: false,
         featured_local: false,
+        alt_text: None,
+        url_content_type: None,
+        embed_width: None,
+        embed_height: None,
+        embed_duration: None,
       },
       community: Community {
         id: data.inserted_community.id,
@@ -886,6 +923,9 @@ mod tests {
         shared_inbox_url: data.inserted_community.shared_inbox_url.clone(),
         moderators_url: data.inserted_community.moderators_url.clone(),
         featured_url: data.inserted_community.featured_url.clone(),
+        moved_to: None,
+        require_alt_text: false,
+        moved_from: None,
       },
       counts: CommentAggregates {
         id: agg.id,
@@ -0,0 +1,427 @@
+use crate::structs::ConversationView;
+use diesel::{
+  dsl::count_star,
+  result::Error,
+  BoolExpressionMethods,
+  ExpressionMethods,
+  JoinOnDsl,
+  NullableExpressionMethods,
+  QueryDsl,
+};
+use diesel_async::RunQueryDsl;
+use lemmy_db_schema::{
+  newtypes::{ConversationId, PersonId},
+  schema::{conversation, conversation_participant, person, private_message},
+  source::{
+    conversation::{Conversation, ConversationParticipant},
+    person::Person,
+    private_message::PrivateMessage,
+  },
+  utils::{get_conn, limit_and_offset, DbPool},
+};
+use std::collections::HashMap;
+
+type ConversationTuple = (Conversation, ConversationParticipant);
+
+impl ConversationView {
+  /// Reads the conversation as seen by the given participant. Returns `NotFound` if the person
+  /// doesn't take part in it.
+  pub async fn read(
+    pool: &mut DbPool<'_>,
+    conversation_id: ConversationId,
+    my_person_id: PersonId,
+  ) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let tuple = conversation::table
+      .inner_join(conversation_participant::table)
+      .filter(conversation::id.eq(conversation_id))
+      .filter(conversation_participant::person_id.eq(my_person_id))
+      .select((
+        conversation::all_columns,
+        conversation_participant::all_columns,
+      ))
+      .first::<ConversationTuple>(conn)
+      .await?;
+    Self::from_tuples(pool, vec![tuple], my_person_id)
+      .await?
+      .pop()
+      .ok_or(Error::NotFound)
+  }
+
+  /// The number of conversations which wait for the person to accept them.
+  pub async fn get_message_request_count(
+    pool: &mut DbPool<'_>,
+    my_person_id: PersonId,
+  ) -> Result<i64, Error> {
+    let conn = &mut get_conn(pool).await?;
+    conversation_participant::table
+      .filter(conversation_participant::person_id.eq(my_person_id))
+      .filter(conversation_participant::message_request.eq(true))
+      .select(count_star())
+      .first::<i64>(conn)
+      .await
+  }
+
+  /// Adds the participants, last message and unread count to the conversations.
+  async fn from_tuples(
+    pool: &mut DbPool<'_>,
+    tuples: Vec<ConversationTuple>,
+    my_person_id: PersonId,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let conversation_ids = tuples.iter().map(|t| t.0.id).collect::<Vec<_>>();
+
+    let mut participants = HashMap::<ConversationId, Vec<Person>>::new();
+    conversation_participant::table
+      .inner_join(person::table)
+      .filter(conversation_participant::conversation_id.eq_any(&conversation_ids))
+      .order_by(person::id)
+      .select((
+        conversation_participant::conversation_id,
+        person::all_columns,
+      ))
+      .load::<(ConversationId, Person)>(conn)
+      .await?
+      .into_iter()
+      .for_each(|(id, person)| participants.entry(id).or_default().push(person));
+
+    let mut last_messages = private_message::table
+      .filter(private_message::conversation_id.eq_any(&conversation_ids))
+      .filter(private_message::deleted.eq(false))
+      .distinct_on(private_message::conversation_id)
+      .order_by((
+        private_message::conversation_id,
+        private_message::published.desc(),
+      ))
+      .load::<PrivateMessage>(conn)
+      .await?
+      .into_iter()
+      .map(|pm| (pm.conversation_id, pm))
+      .collect::<HashMap<_, _>>();
+
+    let unread_counts = private_message::table
+      .inner_join(
+        conversation_participant::table.on(
+          conversation_participant::conversation_id
+            .eq(private_message::conversation_id)
+            .and(conversation_participant::person_id.eq(my_person_id)),
+        ),
+      )
+      .filter(private_message::conversation_id.eq_any(&conversation_ids))
+      .filter(private_message::creator_id.ne(my_person_id))
+      .filter(
+        private_message::read
+          .eq(false)
+          .or(private_message::recipient_id.ne(my_person_id)),
+      )
+      .filter(
+        conversation_participant::last_read.is_null().or(
+          private_message::published
+            .nullable()
+            .gt(conversation_participant::last_read),
+        ),
+      )
+      .filter(private_message::deleted.eq(false))
+      .group_by(private_message::conversation_id)
+      .select((private_message::conversation_id, count_star()))
+      .load::<(ConversationId, i64)>(conn)
+      .await?
+      .into_iter()
+      .collect::<HashMap<_, _>>();
+
+    Ok(
+      tuples
+        .into_iter()
+        .map(|(conversation, participant)| ConversationView {
+          participants: participants.remove(&conversation.id).unwrap_or_default(),
+          last_message: last_messages.remove(&conversation.id),
+          unread_count: unread_counts.get(&conversation.id).copied().unwrap_or(0),
+          conversation,
+          participant,
+        })
+        .collect(),
+    )
+  }
+}
+
+#[derive(Default)]
+pub struct ConversationQuery {
+  /// List archived conversations instead of the others
+  pub archived: bool,
+  /// List the message requests instead of the inbox
+  pub message_requests: bool,
+  pub page: Option<i64>,
+  pub limit: Option<i64>,
+}
+
+impl ConversationQuery {
+  /// Lists the conversations of the person, with the most recent messages first.
+  pub async fn list(
+    self,
+    pool: &mut DbPool<'_>,
+    my_person_id: PersonId,
+  ) -> Result<Vec<ConversationView>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let (limit, offset) = limit_and_offset(self.page, self.limit)?;
+    let tuples = conversation::table
+      .inner_join(conversation_participant::table)
+      .filter(conversation_participant::person_id.eq(my_person_id))
+      .filter(conversation_participant::archived.eq(self.archived))
+      .filter(conversation_participant::message_request.eq(self.message_requests))
+      .order_by(conversation::last_message_published.desc())
+      .then_order_by(conversation::id.desc())
+      .limit(limit)
+      .offset(offset)
+      .select((
+        conversation::all_columns,
+        conversation_participant::all_columns,
+      ))
+      .load::<ConversationTuple>(conn)
+      .await?;
+    ConversationView::from_tuples(pool, tuples, my_person_id).await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use crate::{
+    conversation_view::ConversationQuery,
+    private_message_view::PrivateMessageQuery,
+    structs::{ConversationView, PrivateMessageView},
+  };
+  use lemmy_db_schema::{
+    source::{
+      conversation::{Conversation, ConversationParticipant, ConversationParticipantUpdateForm},
+      instance::Instance,
+      person::{Person, PersonInsertForm},
+      private_message::{PrivateMessage, PrivateMessageInsertForm},
+    },
+    traits::Crud,
+    utils::build_db_pool_for_tests,
+  };
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_conversations() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+    let mut persons = vec![];
+    for name in [
+      "conversation_timmy",
+      "conversation_sara",
+      "conversation_jess",
+    ] {
+      let form = PersonInsertForm::builder()
+        .name(name.into())
+        .public_key("pubkey".to_string())
+        .instance_id(instance.id)
+        .build();
+      persons.push(Person::create(pool, &form).await.unwrap());
+    }
+    let (timmy, sara, jess) = (persons[0].id, persons[1].id, persons[2].id);
+
+    let direct = Conversation::read_or_create(pool, &[timmy, sara], &[])
+      .await
+      .unwrap();
+    let group = Conversation::read_or_create(pool, &[timmy, sara, jess], &[])
+      .await
+      .unwrap();
+    for (creator_id, recipient_id, conversation_id) in [
+      (sara, timmy, direct.id),
+      (sara, timmy, group.id),
+      (jess, timmy, group.id),
+      (timmy, sara, group.id),
+    ] {
+      let form = PrivateMessageInsertForm::builder()
+        .creator_id(creator_id)
+        .recipient_id(recipient_id)
+        .content("hello".to_string())
+        .conversation_id(conversation_id)
+        .build();
+      PrivateMessage::create(pool, &form).await.unwrap();
+    }
+
+    // the group conversation has the most recent message
+    let timmy_conversations = ConversationQuery::default()
+      .list(pool, timmy)
+      .await
+      .unwrap();
+    assert_eq!(2, timmy_conversations.len());
+    assert_eq!(group.id, timmy_conversations[0].conversation.id);
+    assert_eq!(3, timmy_conversations[0].participants.len());
+    assert_eq!(
+      Some(timmy),
+      timmy_conversations[0]
+        .last_message
+        .as_ref()
+        .map(|m| m.creator_id)
+    );
+    assert_eq!(2, timmy_conversations[0].unread_count);
+    assert_eq!(1, timmy_conversations[1].unread_count);
+    assert_eq!(
+      3,
+      PrivateMessageView::get_unread_messages(pool, timmy)
+        .await
+        .unwrap()
+    );
+
+    // jess only sees the group conversation, including messages to others
+    let jess_conversations = ConversationQuery::default().list(pool, jess).await.unwrap();
+    assert_eq!(1, jess_conversations.len());
+    assert_eq!(2, jess_conversations[0].unread_count);
+    let jess_messages = PrivateMessageQuery {
+      conversation_id: Some(group.id),
+      ..Default::default()
+    }
+    .list(pool, jess)
+    .await
+    .unwrap();
+    assert_eq!(3, jess_messages.len());
+    assert!(ConversationView::read(pool, direct.id, jess).await.is_err());
+
+    // reading the conversation resets the unread count
+    ConversationParticipant::mark_as_read(pool, group.id, timmy)
+      .await
+      .unwrap();
+    let timmy_group = ConversationView::read(pool, group.id, timmy).await.unwrap();
+    assert_eq!(0, timmy_group.unread_count);
+
+    // muted conversations don't count as unread, and archived ones are listed separately
+    let form = ConversationParticipantUpdateForm {
+      muted: Some(true),
+      archived: Some(true),
+      ..Default::default()
+    };
+    ConversationParticipant::update(pool, direct.id, timmy, &form)
+      .await
+      .unwrap();
+    assert_eq!(
+      0,
+      PrivateMessageView::get_unread_messages(pool, timmy)
+        .await
+        .unwrap()
+    );
+    let archived = ConversationQuery {
+      archived: true,
+      ..Default::default()
+    }
+    .list(pool, timmy)
+    .await
+    .unwrap();
+    assert_eq!(1, archived.len());
+    assert_eq!(direct.id, archived[0].conversation.id);
+
+    // a new message brings the conversation back out of the archive
+    let form = PrivateMessageInsertForm::builder()
+      .creator_id(sara)
+      .recipient_id(timmy)
+      .content("are you there?".to_string())
+      .conversation_id(direct.id)
+      .build();
+    PrivateMessage::create(pool, &form).await.unwrap();
+    let timmy_conversations = ConversationQuery::default()
+      .list(pool, timmy)
+      .await
+      .unwrap();
+    assert_eq!(direct.id, timmy_conversations[0].conversation.id);
+    assert!(timmy_conversations[0].participant.muted);
+
+    // a conversation from a stranger goes to the message requests instead
+    let form = PersonInsertForm::builder()
+      .name("conversation_stranger".into())
+      .public_key("pubkey".to_string())
+      .instance_id(instance.id)
+      .build();
+    let stranger = Person::create(pool, &form).await.unwrap().id;
+    let request = Conversation::read_or_create(pool, &[stranger, timmy], &[timmy])
+      .await
+      .unwrap();
+    let form = PrivateMessageInsertForm::builder()
+      .creator_id(stranger)
+      .recipient_id(timmy)
+      .content("hi".to_string())
+      .conversation_id(request.id)
+      .build();
+    PrivateMessage::create(pool, &form).await.unwrap();
+    let timmy_conversations = ConversationQuery::default()
+      .list(pool, timmy)
+      .await
+      .unwrap();
+    assert!(timmy_conversations
+      .iter()
+      .all(|c| c.conversation.id != request.id));
+    let requests = ConversationQuery {
+      message_requests: true,
+      ..Default::default()
+    }
+    .list(pool, timmy)
+    .await
+    .unwrap();
+    assert_eq!(1, requests.len());
+    assert_eq!(request.id, requests[0].conversation.id);
+    assert_eq!(
+      1,
+      ConversationView::get_message_request_count(pool, timmy)
+        .await
+        .unwrap()
+    );
+    assert_eq!(
+      0,
+      PrivateMessageView::get_unread_messages(pool, timmy)
+        .await
+        .unwrap()
+    );
+
+    // the messages of the request are listed separately from the inbox
+    let inbox = PrivateMessageQuery::default()
+      .list(pool, timmy)
+      .await
+      .unwrap();
+    assert!(inbox
+      .iter()
+      .all(|m| m.private_message.creator_id != stranger));
+    let request_messages = PrivateMessageQuery {
+      message_requests: true,
+      ..Default::default()
+    }
+    .list(pool, timmy)
+    .await
+    .unwrap();
+    assert_eq!(1, request_messages.len());
+    assert_eq!(stranger, request_messages[0].private_message.creator_id);
+
+    // accepting moves the conversation and its unread message to the inbox
+    ConversationParticipant::accept_message_request(pool, request.id, timmy)
+      .await
+      .unwrap();
+    assert_eq!(
+      0,
+      ConversationView::get_message_request_count(pool, timmy)
+        .await
+        .unwrap()
+    );
+    assert_eq!(
+      1,
+      PrivateMessageView::get_unread_messages(pool, timmy)
+        .await
+        .unwrap()
+    );
+    let inbox = PrivateMessageQuery::default()
+      .list(pool, timmy)
+      .await
+      .unwrap();
+    assert!(inbox
+      .iter()
+      .any(|m| m.private_message.creator_id == stranger));
+
+    Instance::delete(pool, instance.id).await.unwrap();
+  }
+}
@@ -0,0 +1,198 @@
+use crate::structs::ImageUploadView;
+use diesel::{
+  result::Error,
+  BoolExpressionMethods,
+  ExpressionMethods,
+  JoinOnDsl,
+  PgTextExpressionMethods,
+  QueryDsl,
+};
+use diesel_async::RunQueryDsl;
+use lemmy_db_schema::{
+  newtypes::{LocalUserId, PersonId, PostId},
+  schema::{image_upload, local_user, person, post},
+  source::{image_upload::ImageUpload, person::Person},
+  utils::{get_conn, limit_and_offset, DbPool},
+};
+
+type ImageUploadTuple = (ImageUpload, Person);
+type PostImagesTuple = (PostId, PersonId, Option<String>, Option<String>);
+
+impl ImageUploadView {
+  /// Lists the uploads of a single user, or of all users if `for_local_user_id` is empty. Newest
+  /// uploads come first.
+  pub async fn list(
+    pool: &mut DbPool<'_>,
+    for_local_user_id: Option<LocalUserId>,
+    page: Option<i64>,
+    limit: Option<i64>,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let (limit, offset) = limit_and_offset(page, limit)?;
+    let mut query = image_upload::table
+      .inner_join(local_user::table)
+      .inner_join(person::table.on(local_user::person_id.eq(person::id)))
+      .select((image_upload::all_columns, person::all_columns))
+      .into_boxed();
+    if let Some(for_local_user_id) = for_local_user_id {
+      query = query.filter(image_upload::local_user_id.eq(for_local_user_id));
+    }
+    let uploads = query
+      .order_by(image_upload::published.desc())
+      .limit(limit)
+      .offset(offset)
+      .load::<ImageUploadTuple>(conn)
+      .await?;
+
+    // Uploaded images can only be used by their uploader, so only the posts of those persons
+    // which link to an image of this instance need to be checked
+    let creator_ids: Vec<PersonId> = uploads.iter().map(|(_, p)| p.id).collect();
+    let image_pattern = "%/pictrs/image/%";
+    let posts = post::table
+      .filter(post::creator_id.eq_any(creator_ids))
+      .filter(post::deleted.eq(false))
+      .filter(
+        post::url
+          .ilike(image_pattern)
+          .or(post::body.ilike(image_pattern)),
+      )
+      .select((post::id, post::creator_id, post::url, post::body))
+      .load::<PostImagesTuple>(conn)
+      .await?;
+
+    Ok(
+      uploads
+        .into_iter()
+        .map(|(image_upload, person)| {
+          let alias = image_upload.pictrs_alias.as_str();
+          let post_ids = posts
+            .iter()
+            .filter(|(_, creator_id, url, body)| {
+              *creator_id == person.id
+                && [url, body]
+                  .iter()
+                  .any(|s| s.as_ref().is_some_and(|s| s.contains(alias)))
+            })
+            .map(|(id, ..)| *id)
+            .collect();
+          ImageUploadView {
+            image_upload,
+            person,
+            post_ids,
+          }
+        })
+        .collect(),
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use crate::structs::ImageUploadView;
+  use lemmy_db_schema::{
+    source::{
+      community::{Community, CommunityInsertForm},
+      image_upload::{ImageUpload, ImageUploadForm},
+      instance::Instance,
+      local_user::{LocalUser, LocalUserInsertForm},
+      person::{Person, PersonInsertForm},
+      post::{Post, PostInsertForm},
+    },
+    traits::Crud,
+    utils::build_db_pool_for_tests,
+  };
+  use serial_test::serial;
+  use url::Url;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_list_with_posts() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+
+    let person_form = PersonInsertForm::builder()
+      .name("uploader".into())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_person = Person::create(pool, &person_form).await.unwrap();
+
+    let local_user_form = LocalUserInsertForm::builder()
+      .person_id(inserted_person.id)
+      .password_encrypted("123456".to_string())
+      .build();
+    let inserted_local_user = LocalUser::create(pool, &local_user_form).await.unwrap();
+
+    let community_form = CommunityInsertForm::builder()
+      .name("test_community_media".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_community = Community::create(pool, &community_form).await.unwrap();
+
+    let mut uploads = vec![];
+    for alias in ["linked.jpg", "embedded.png", "unused.gif"] {
+      let form = ImageUploadForm {
+        local_user_id: inserted_local_user.id,
+        pictrs_alias: alias.to_string(),
+        pictrs_delete_token: "token".to_string(),
+        size: Some(1024),
+        alt_text: None,
+      };
+      uploads.push(ImageUpload::create(pool, &form).await.unwrap());
+    }
+
+    let url = Url::parse("https://my_domain.tld/pictrs/image/linked.jpg").unwrap();
+    let post_form = PostInsertForm::builder()
+      .name("image post".to_string())
+      .url(Some(url.into()))
+      .creator_id(inserted_person.id)
+      .community_id(inserted_community.id)
+      .build();
+    let link_post = Post::create(pool, &post_form).await.unwrap();
+
+    let post_form = PostInsertForm::builder()
+      .name("text post".to_string())
+      .body(Some(
+        "look: ![](https://my_domain.tld/pictrs/image/embedded.png)".to_string(),
+      ))
+      .creator_id(inserted_person.id)
+      .community_id(inserted_community.id)
+      .build();
+    let body_post = Post::create(pool, &post_form).await.unwrap();
+
+    let images = ImageUploadView::list(pool, Some(inserted_local_user.id), None, None)
+      .await
+      .unwrap();
+    assert_eq!(3, images.len());
+    let post_ids = |alias: &str| {
+      images
+        .iter()
+        .find(|i| i.image_upload.pictrs_alias == alias)
+        .unwrap()
+        .post_ids
+        .clone()
+    };
+    assert_eq!(vec![link_post.id], post_ids("linked.jpg"));
+    assert_eq!(vec![body_post.id], post_ids("embedded.png"));
+    assert!(post_ids("unused.gif").is_empty());
+    assert_eq!(inserted_person.id, images[0].person.id);
+
+    let all_images = ImageUploadView::list(pool, None, None, None).await.unwrap();
+    assert_eq!(3, all_images.len());
+
+    Community::delete(pool, inserted_community.id)
+      .await
+      .unwrap();
+    Person::delete(pool, inserted_person.id).await.unwrap();
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
+}
@@ -6,10 +6,16 @@ pub mod comment_report_view;
 #[cfg(feature = "full")]
 pub mod comment_view;
 #[cfg(feature = "full")]
+pub mod conversation_view;
+#[cfg(feature = "full")]
 pub mod custom_emoji_view;
 #[cfg(feature = "full")]
+pub mod image_upload_view;
+#[cfg(feature = "full")]
 pub mod local_user_view;
 #[cfg(feature = "full")]
+pub mod media_scan_view;
+#[cfg(feature = "full")]
 pub mod post_report_view;
 #[cfg(feature = "full")]
 pub mod post_view;
@@ -3,8 +3,8 @@ use actix_web::{dev::Payload, FromRequest, HttpMessage, HttpRequest};
 use diesel::{result::Error, BoolExpressionMethods, ExpressionMethods, JoinOnDsl, QueryDsl};
 use diesel_async::RunQueryDsl;
 use lemmy_db_schema::{
-  newtypes::{LocalUserId, PersonId},
-  schema::{local_user, person, person_aggregates},
+  newtypes::{CommunityId, LocalUserId, PersonId},
+  schema::{community_moderator, local_user, person, person_aggregates},
   utils::{functions::lower, DbConn, DbPool, ListFn, Queries, ReadFn},
 };
 use lemmy_utils::error::{LemmyError, LemmyErrorType};
@@ -20,6 +20,8 @@ enum ReadBy<'a> {
 
 enum ListMode {
   AdminsWithEmails,
+  Admins,
+  CommunityModerators(CommunityId),
 }
 
 fn queries<'a>(
@@ -67,6 +69,25 @@ fn queries<'a>(
           .load::<LocalUserView>(&mut conn)
           .await
       }
+      ListMode::Admins => {
+        local_user::table
+          .filter(local_user::admin.eq(true))
+          .inner_join(person::table)
+          .inner_join(person_aggregates::table.on(person::id.eq(person_aggregates::person_id)))
+          .select(selection)
+          .load::<LocalUserView>(&mut conn)
+          .await
+      }
+      ListMode::CommunityModerators(community_id) => {
+        local_user::table
+          .inner_join(person::table)
+          .inner_join(person_aggregates::table.on(person::id.eq(person_aggregates::person_id)))
+          .inner_join(community_moderator::table.on(community_moderator::person_id.eq(person::id)))
+          .filter(community_moderator::community_id.eq(community_id))
+          .select(selection)
+          .load::<LocalUserView>(&mut conn)
+          .await
+      }
     }
   };
 
@@ -102,6 +123,20 @@ impl LocalUserView {
   pub async fn list_admins_with_emails(pool: &mut DbPool<'_>) -> Result<Vec<Self>, Error> {
     queries().list(pool, ListMode::AdminsWithEmails).await
   }
+
+  pub async fn list_admins(pool: &mut DbPool<'_>) -> Result<Vec<Self>, Error> {
+    queries().list(pool, ListMode::Admins).await
+  }
+
+  /// The moderators of a community which are local users.
+  pub async fn list_community_moderators(
+    pool: &mut DbPool<'_>,
+    community_id: CommunityId,
+  ) -> Result<Vec<Self>, Error> {
+    queries()
+      .list(pool, ListMode::CommunityModerators(community_id))
+      .await
+  }
 }
 
 impl FromRequest for LocalUserView {
@@ -0,0 +1,34 @@
+use crate::structs::MediaScanView;
+use diesel::{result::Error, ExpressionMethods, JoinOnDsl, NullableExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+use lemmy_db_schema::{
+  schema::{media_scan, person},
+  utils::{get_conn, limit_and_offset, DbPool},
+};
+
+impl MediaScanView {
+  /// Lists scan results for admins, newest first. With `unresolved_only` these are the denied and
+  /// quarantined media which still need to be reviewed.
+  pub async fn list(
+    pool: &mut DbPool<'_>,
+    unresolved_only: bool,
+    page: Option<i64>,
+    limit: Option<i64>,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let (limit, offset) = limit_and_offset(page, limit)?;
+    let mut query = media_scan::table
+      .left_join(person::table.on(media_scan::person_id.eq(person::id.nullable())))
+      .select((media_scan::all_columns, person::all_columns.nullable()))
+      .into_boxed();
+    if unresolved_only {
+      query = query.filter(media_scan::resolved.eq(false));
+    }
+    query
+      .order_by(media_scan::published.desc())
+      .limit(limit)
+      .offset(offset)
+      .load::<Self>(conn)
+      .await
+  }
+}
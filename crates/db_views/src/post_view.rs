@@ -1,6 +1,7 @@
 use crate::structs::{LocalUserView, PaginationCursor, PostView};
 use diesel::{
   debug_query,
+  deserialize::{self, FromStaticSqlRow},
   dsl::{self, exists, not, IntervalDsl},
   expression::AsExpression,
   pg::Pg,
@@ -17,21 +18,25 @@ use diesel::{
   OptionalExtension,
   PgTextExpressionMethods,
   QueryDsl,
+  Queryable,
 };
 use diesel_async::RunQueryDsl;
 use lemmy_db_schema::{
   aggregates::structs::PostAggregates,
-  newtypes::{CommunityId, LocalUserId, PersonId, PostId},
+  newtypes::{CommunityId, CustomFeedId, LocalUserId, PersonId, PostId},
   schema::{
     community,
     community_block,
     community_follower,
     community_moderator,
     community_person_ban,
+    custom_feed_community,
+    federation_blocklist,
     instance_block,
     local_user_language,
     person,
     person_block,
+    person_follower,
     person_post_aggregates,
     post,
     post_aggregates::{self, newest_comment_time},
@@ -39,9 +44,17 @@ use lemmy_db_schema::{
     post_read,
     post_saved,
   },
+  source::{
+    community::Community,
+    person::Person,
+    post::Post,
+    post_gallery_image::PostGalleryImage,
+  },
   utils::{fuzzy_search, get_conn, limit_and_offset, DbConn, DbPool, ListFn, Queries, ReadFn},
+  FederationBlockSeverity,
   ListingType,
   SortType,
+  SubscribedType,
 };
 use tracing::debug;
 
@@ -320,6 +333,16 @@ fn queries<'a>() -> Queries<
       query = query.filter(post_aggregates::community_id.eq(community_id));
     }
 
+    if let Some(custom_feed_id) = options.custom_feed_id {
+      query = query.filter(exists(
+        custom_feed_community::table.filter(
+          custom_feed_community::custom_feed_id
+            .eq(custom_feed_id)
+            .and(custom_feed_community::community_id.eq(post_aggregates::community_id)),
+        ),
+      ));
+    }
+
     if let Some(creator_id) = options.creator_id {
       query = query.filter(post_aggregates::creator_id.eq(creator_id));
     }
@@ -352,6 +375,41 @@ fn queries<'a>() -> Queries<
       }
     }
 
+    // Content from limited instances only shows up in `All` for those who follow the community or
+    // the creator
+    if options.community_id.is_none()
+      && options.creator_id.is_none()
+      && options.custom_feed_id.is_none()
+      && matches!(options.listing_type, None | Some(ListingType::All))
+    {
+      let is_limited = exists(
+        federation_blocklist::table.filter(
+          federation_blocklist::severity
+            .eq(FederationBlockSeverity::Silence)
+            .and(
+              federation_blocklist::instance_id
+                .eq(post_aggregates::instance_id)
+                .or(federation_blocklist::instance_id.eq(person::instance_id)),
+            ),
+        ),
+      );
+      let is_followed = exists(
+        community_follower::table.filter(
+          post_aggregates::community_id
+            .eq(community_follower::community_id)
+            .and(community_follower::person_id.eq(person_id_join)),
+        ),
+      )
+      .or(exists(
+        person_follower::table.filter(
+          post_aggregates::creator_id
+            .eq(person_follower::person_id)
+            .and(person_follower::follower_id.eq(person_id_join)),
+        ),
+      ));
+      query = query.filter(not(is_limited).or(is_followed));
+    }
+
     if let Some(url_search) = &options.url_search {
       query = query.filter(post::url.eq(url_search));
     }
@@ -535,6 +593,55 @@ fn queries<'a>() -> Queries<
   Queries::new(read, list)
 }
 
+type PostViewTuple = (
+  Post,
+  Person,
+  Community,
+  bool,
+  PostAggregates,
+  SubscribedType,
+  bool,
+  bool,
+  bool,
+  Option<i16>,
+  i64,
+);
+
+/// The gallery is loaded with a separate query, see [load_galleries].
+impl<ST> Queryable<ST, Pg> for PostView
+where
+  PostViewTuple: FromStaticSqlRow<ST, Pg>,
+{
+  type Row = PostViewTuple;
+
+  fn build(row: Self::Row) -> deserialize::Result<Self> {
+    Ok(PostView {
+      post: row.0,
+      creator: row.1,
+      community: row.2,
+      creator_banned_from_community: row.3,
+      counts: row.4,
+      subscribed: row.5,
+      saved: row.6,
+      read: row.7,
+      creator_blocked: row.8,
+      my_vote: row.9,
+      unread_comments: row.10,
+      gallery: vec![],
+    })
+  }
+}
+
+async fn load_galleries(pool: &mut DbPool<'_>, posts: &mut [PostView]) -> Result<(), Error> {
+  let post_ids = posts.iter().map(|p| p.post.id).collect::<Vec<_>>();
+  for image in PostGalleryImage::list_for_posts(pool, &post_ids).await? {
+    if let Some(p) = posts.iter_mut().find(|p| p.post.id == image.post_id) {
+      p.gallery.push(image);
+    }
+  }
+  Ok(())
+}
+
 impl PostView {
   pub async fn read(
     pool: &mut DbPool<'_>,
@@ -552,6 +659,7 @@ impl PostView {
       res.my_vote = Some(0)
     };
 
+    load_galleries(pool, std::slice::from_mut(&mut res)).await?;
     Ok(res)
   }
 }
@@ -592,6 +700,8 @@ pub struct PostQuery<'a> {
   pub community_id: Option<CommunityId>,
   // if true, the query should be handled as if community_id was not given except adding the literal filter
   pub community_id_just_for_prefetch: bool,
+  /// Only posts from the communities of this feed
+  pub custom_feed_id: Option<CustomFeedId>,
   pub local_user: Option<&'a LocalUserView>,
   pub search_term: Option<String>,
   pub url_search: Option<String>,
@@ -680,19 +790,21 @@ impl<'a> PostQuery<'a> {
   }
 
   pub async fn list(self, pool: &mut DbPool<'_>) -> Result<Vec<PostView>, Error> {
-    if self.listing_type == Some(ListingType::Subscribed)
+    let mut posts = if self.listing_type == Some(ListingType::Subscribed)
       && self.community_id.is_none()
       && self.local_user.is_some()
       && self.page_before_or_equal.is_none()
     {
       if let Some(query) = self.prefetch_upper_bound_for_page_before(pool).await? {
-        queries().list(pool, query).await
+        queries().list(pool, query).await?
       } else {
-        Ok(vec![])
+        vec![]
       }
     } else {
-      queries().list(pool, self).await
-    }
+      queries().list(pool, self).await?
+    };
+    load_galleries(pool, &mut posts).await?;
+    Ok(posts)
   }
 }
 
@@ -711,8 +823,10 @@ mod tests {
     newtypes::LanguageId,
     source::{
       actor_language::LocalUserLanguage,
-      community::{Community, CommunityInsertForm},
+      community::{Community, CommunityFollower, CommunityFollowerForm, CommunityInsertForm},
       community_block::{CommunityBlock, CommunityBlockForm},
+      custom_feed::{CustomFeed, CustomFeedInsertForm},
+      federation_blocklist::{FederationBlockList, FederationBlockListForm},
       instance::Instance,
       instance_block::{InstanceBlock, InstanceBlockForm},
       language::Language,
@@ -721,8 +835,9 @@ mod tests {
       person_block::{PersonBlock, PersonBlockForm},
       post::{Post, PostInsertForm, PostLike, PostLikeForm, PostUpdateForm},
     },
-    traits::{Blockable, Crud, Likeable},
+    traits::{Blockable, Crud, Followable, Likeable},
     utils::{build_db_pool_for_tests, DbPool},
+    FederationBlockSeverity,
     SortType,
     SubscribedType,
   };
@@ -1326,6 +1441,148 @@ mod tests {
     cleanup(data, pool).await;
   }
 
+  #[tokio::test]
+  #[serial]
+  async fn post_listing_limited_instance() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+    let data = init_data(pool).await;
+
+    let limited_instance = Instance::read_or_create(pool, "limited_domain.tld".to_string())
+      .await
+      .unwrap();
+    let block_form = FederationBlockListForm {
+      instance_id: limited_instance.id,
+      severity: FederationBlockSeverity::Silence,
+      ..Default::default()
+    };
+    FederationBlockList::upsert(pool, &block_form)
+      .await
+      .unwrap();
+
+    let community_form = CommunityInsertForm::builder()
+      .name("test_community_5".to_string())
+      .title("none".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(limited_instance.id)
+      .build();
+    let inserted_community = Community::create(pool, &community_form).await.unwrap();
+
+    let post_form = PostInsertForm::builder()
+      .name("limited instance post".to_string())
+      .creator_id(data.inserted_bot.id)
+      .community_id(inserted_community.id)
+      .language_id(Some(LanguageId(1)))
+      .build();
+    let post_from_limited_instance = Post::create(pool, &post_form).await.unwrap();
+
+    // the post is hidden from `All`, but still shown in the community itself
+    let post_listings_all = PostQuery {
+      local_user: Some(&data.local_user_view),
+      ..Default::default()
+    }
+    .list(pool)
+    .await
+    .unwrap();
+    assert_eq!(post_listings_all.len(), 2);
+    assert!(post_listings_all
+      .iter()
+      .all(|p| p.post.id != post_from_limited_instance.id));
+
+    let post_listings_community = PostQuery {
+      local_user: Some(&data.local_user_view),
+      community_id: Some(inserted_community.id),
+      ..Default::default()
+    }
+    .list(pool)
+    .await
+    .unwrap();
+    assert_eq!(post_listings_community.len(), 1);
+
+    // after following the community it shows up in `All` again
+    let follower_form = CommunityFollowerForm {
+      community_id: inserted_community.id,
+      person_id: data.local_user_view.person.id,
+      pending: false,
+    };
+    CommunityFollower::follow(pool, &follower_form)
+      .await
+      .unwrap();
+    let post_listings_all = PostQuery {
+      local_user: Some(&data.local_user_view),
+      ..Default::default()
+    }
+    .list(pool)
+    .await
+    .unwrap();
+    assert_eq!(post_listings_all.len(), 3);
+
+    Instance::delete(pool, limited_instance.id).await.unwrap();
+    cleanup(data, pool).await;
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn post_listing_custom_feed() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+    let data = init_data(pool).await;
+
+    let community_form = CommunityInsertForm::builder()
+      .name("test_community_6".to_string())
+      .title("none".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(data.inserted_instance.id)
+      .build();
+    let other_community = Community::create(pool, &community_form).await.unwrap();
+    let post_form = PostInsertForm::builder()
+      .name("post outside of the feed".to_string())
+      .creator_id(data.inserted_bot.id)
+      .community_id(other_community.id)
+      .build();
+    let other_post = Post::create(pool, &post_form).await.unwrap();
+
+    let feed_form = CustomFeedInsertForm::builder()
+      .creator_id(data.local_user_view.person.id)
+      .name("My feed".to_string())
+      .build();
+    let custom_feed = CustomFeed::create(pool, &feed_form).await.unwrap();
+    CustomFeed::set_communities(pool, custom_feed.id, vec![data.inserted_community.id])
+      .await
+      .unwrap();
+
+    // only the posts of communities in the feed are listed
+    let post_listings_feed = PostQuery {
+      sort: (Some(SortType::New)),
+      custom_feed_id: (Some(custom_feed.id)),
+      ..Default::default()
+    }
+    .list(pool)
+    .await
+    .unwrap();
+    assert_eq!(3, post_listings_feed.len());
+    assert!(post_listings_feed
+      .iter()
+      .all(|p| p.community.id == data.inserted_community.id));
+
+    CustomFeed::set_communities(pool, custom_feed.id, vec![other_community.id])
+      .await
+      .unwrap();
+    let post_listings_feed = PostQuery {
+      sort: (Some(SortType::New)),
+      custom_feed_id: (Some(custom_feed.id)),
+      ..Default::default()
+    }
+    .list(pool)
+    .await
+    .unwrap();
+    assert_eq!(1, post_listings_feed.len());
+    assert_eq!(other_post.id, post_listings_feed[0].post.id);
+
+    Community::delete(pool, other_community.id).await.unwrap();
+    cleanup(data, pool).await;
+  }
+
   async fn cleanup(data: Data, pool: &mut DbPool<'_>) {
     let num_deleted = Post::delete(pool, data.inserted_post.id).await.unwrap();
     Community::delete(pool, data.inserted_community.id)
@@ -1375,9 +1632,15 @@ mod tests {
         language_id: LanguageId(47),
         featured_community: false,
         featured_local: false,
+        alt_text: None,
+        url_content_type: None,
+        embed_width: None,
+        embed_height: None,
+        embed_duration: None,
       },
       my_vote: None,
       unread_comments: 0,
+      gallery: vec![],
       creator: Person {
         id: inserted_person.id,
         name: inserted_person.name.clone(),
@@ -1427,6 +1690,9 @@ mod tests {
         shared_inbox_url: inserted_community.shared_inbox_url.clone(),
         moderators_url: inserted_community.moderators_url.clone(),
         featured_url: inserted_community.featured_url.clone(),
+        moved_to: None,
+        require_alt_text: false,
+        moved_from: None,
       },
       counts: PostAggregates {
         id: agg.id,
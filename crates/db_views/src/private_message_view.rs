@@ -6,13 +6,14 @@ use diesel::{
   BoolExpressionMethods,
   ExpressionMethods,
   JoinOnDsl,
+  NullableExpressionMethods,
   QueryDsl,
 };
 use diesel_async::RunQueryDsl;
 use lemmy_db_schema::{
   aliases,
-  newtypes::{PersonId, PrivateMessageId},
-  schema::{person, private_message},
+  newtypes::{ConversationId, PersonId, PrivateMessageId},
+  schema::{conversation_participant, person, private_message},
   utils::{get_conn, limit_and_offset, DbConn, DbPool, ListFn, Queries, ReadFn},
 };
 use tracing::debug;
@@ -44,31 +45,51 @@ fn queries<'a>() -> Queries<
   };
 
   let list = move |mut conn: DbConn<'a>,
-                   (options, recipient_id): (PrivateMessageQuery, PersonId)| async move {
-    let mut query = all_joins(private_message::table.into_boxed()).select(selection);
+                   (options, my_person_id): (PrivateMessageQuery, PersonId)| async move {
+    // Only messages from conversations which I take part in
+    let mut query = all_joins(private_message::table.into_boxed())
+      .inner_join(
+        conversation_participant::table.on(
+          conversation_participant::conversation_id
+            .eq(private_message::conversation_id)
+            .and(conversation_participant::person_id.eq(my_person_id)),
+        ),
+      )
+      .select(selection);
+
+    if let Some(conversation_id) = options.conversation_id {
+      query = query.filter(private_message::conversation_id.eq(conversation_id));
+    } else {
+      query = query.filter(conversation_participant::message_request.eq(options.message_requests));
+    }
 
     // If its unread, I only want the ones to me
     if options.unread_only {
-      query = query.filter(private_message::read.eq(false));
+      query = query
+        .filter(private_message::creator_id.ne(my_person_id))
+        .filter(
+          private_message::read
+            .eq(false)
+            .or(private_message::recipient_id.ne(my_person_id)),
+        )
+        .filter(
+          conversation_participant::last_read.is_null().or(
+            private_message::published
+              .nullable()
+              .gt(conversation_participant::last_read),
+          ),
+        );
       if let Some(i) = options.creator_id {
         query = query.filter(private_message::creator_id.eq(i))
       }
-      query = query.filter(private_message::recipient_id.eq(recipient_id));
     }
     // Otherwise, I want the ALL view to show both sent and received
-    else {
+    else if let Some(i) = options.creator_id {
       query = query.filter(
-        private_message::recipient_id
-          .eq(recipient_id)
-          .or(private_message::creator_id.eq(recipient_id)),
-      );
-      if let Some(i) = options.creator_id {
-        query = query.filter(
-          private_message::creator_id
-            .eq(i)
-            .or(private_message::recipient_id.eq(i)),
-        )
-      }
+        private_message::creator_id
+          .eq(i)
+          .or(private_message::recipient_id.eq(i)),
+      )
     }
 
     let (limit, offset) = limit_and_offset(options.page, options.limit)?;
@@ -98,7 +119,7 @@ impl PrivateMessageView {
     queries().read(pool, private_message_id).await
   }
 
-  /// Gets the number of unread messages
+  /// Gets the number of unread messages, excluding muted conversations and message requests
   pub async fn get_unread_messages(
     pool: &mut DbPool<'_>,
     my_person_id: PersonId,
@@ -106,8 +127,28 @@ impl PrivateMessageView {
     use diesel::dsl::count;
     let conn = &mut get_conn(pool).await?;
     private_message::table
-      .filter(private_message::read.eq(false))
-      .filter(private_message::recipient_id.eq(my_person_id))
+      .inner_join(
+        conversation_participant::table.on(
+          conversation_participant::conversation_id
+            .eq(private_message::conversation_id)
+            .and(conversation_participant::person_id.eq(my_person_id)),
+        ),
+      )
+      .filter(conversation_participant::muted.eq(false))
+      .filter(conversation_participant::message_request.eq(false))
+      .filter(private_message::creator_id.ne(my_person_id))
+      .filter(
+        private_message::read
+          .eq(false)
+          .or(private_message::recipient_id.ne(my_person_id)),
+      )
+      .filter(
+        conversation_participant::last_read.is_null().or(
+          private_message::published
+            .nullable()
+            .gt(conversation_participant::last_read),
+        ),
+      )
       .filter(private_message::deleted.eq(false))
       .select(count(private_message::id))
       .first::<i64>(conn)
@@ -121,15 +162,19 @@ pub struct PrivateMessageQuery {
   pub page: Option<i64>,
   pub limit: Option<i64>,
   pub creator_id: Option<PersonId>,
+  pub conversation_id: Option<ConversationId>,
+  /// List the messages from message requests instead of the inbox. Ignored when listing a single
+  /// conversation.
+  pub message_requests: bool,
 }
 
 impl PrivateMessageQuery {
   pub async fn list(
     self,
     pool: &mut DbPool<'_>,
-    recipient_id: PersonId,
+    my_person_id: PersonId,
   ) -> Result<Vec<PrivateMessageView>, Error> {
-    queries().list(pool, (self, recipient_id)).await
+    queries().list(pool, (self, my_person_id)).await
   }
 }
 
@@ -141,6 +186,7 @@ mod tests {
   use crate::private_message_view::PrivateMessageQuery;
   use lemmy_db_schema::{
     source::{
+      conversation::Conversation,
       instance::Instance,
       person::{Person, PersonInsertForm},
       private_message::{PrivateMessage, PrivateMessageInsertForm},
@@ -189,6 +235,12 @@ mod tests {
       .creator_id(sara.id)
       .recipient_id(timmy.id)
       .content(message_content.clone())
+      .conversation_id(
+        Conversation::read_or_create(pool, &[sara.id, timmy.id], &[])
+          .await
+          .unwrap()
+          .id,
+      )
       .build();
     let _inserted_sara_timmy_message_form = PrivateMessage::create(pool, &sara_timmy_message_form)
       .await
@@ -198,6 +250,12 @@ mod tests {
       .creator_id(sara.id)
       .recipient_id(jess.id)
       .content(message_content.clone())
+      .conversation_id(
+        Conversation::read_or_create(pool, &[sara.id, jess.id], &[])
+          .await
+          .unwrap()
+          .id,
+      )
       .build();
     let _inserted_sara_jess_message_form = PrivateMessage::create(pool, &sara_jess_message_form)
       .await
@@ -207,6 +265,12 @@ mod tests {
       .creator_id(timmy.id)
       .recipient_id(sara.id)
       .content(message_content.clone())
+      .conversation_id(
+        Conversation::read_or_create(pool, &[timmy.id, sara.id], &[])
+          .await
+          .unwrap()
+          .id,
+      )
       .build();
     let _inserted_timmy_sara_message_form = PrivateMessage::create(pool, &timmy_sara_message_form)
       .await
@@ -216,6 +280,12 @@ mod tests {
       .creator_id(jess.id)
       .recipient_id(timmy.id)
       .content(message_content.clone())
+      .conversation_id(
+        Conversation::read_or_create(pool, &[jess.id, timmy.id], &[])
+          .await
+          .unwrap()
+          .id,
+      )
       .build();
     let _inserted_jess_timmy_message_form = PrivateMessage::create(pool, &jess_timmy_message_form)
       .await
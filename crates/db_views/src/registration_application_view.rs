@@ -268,6 +268,11 @@ mod tests {
         admin: false,
         post_listing_mode: inserted_sara_local_user.post_listing_mode,
         totp_2fa_enabled: inserted_sara_local_user.totp_2fa_enabled,
+        upload_quota_max_total_size: None,
+        upload_quota_max_files: None,
+        email_digest: inserted_sara_local_user.email_digest,
+        private_message_policy: inserted_sara_local_user.private_message_policy,
+        last_email_digest: None,
       },
       creator: Person {
         id: inserted_sara_person.id,
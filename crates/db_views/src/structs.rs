@@ -2,17 +2,22 @@
 use diesel::Queryable;
 use lemmy_db_schema::{
   aggregates::structs::{CommentAggregates, PersonAggregates, PostAggregates, SiteAggregates},
+  newtypes::PostId,
   source::{
     comment::Comment,
     comment_report::CommentReport,
     community::Community,
+    conversation::{Conversation, ConversationParticipant},
     custom_emoji::CustomEmoji,
     custom_emoji_keyword::CustomEmojiKeyword,
+    image_upload::ImageUpload,
     local_site::LocalSite,
     local_site_rate_limit::LocalSiteRateLimit,
     local_user::LocalUser,
+    media_scan::MediaScan,
     person::Person,
     post::Post,
+    post_gallery_image::PostGalleryImage,
     post_report::PostReport,
     private_message::PrivateMessage,
     private_message_report::PrivateMessageReport,
@@ -62,6 +67,27 @@ pub struct CommentView {
   pub my_vote: Option<i16>,
 }
 
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// An image which was uploaded by a local user.
+pub struct ImageUploadView {
+  pub image_upload: ImageUpload,
+  pub person: Person,
+  /// The posts of the uploader which link to the image or embed it in their body.
+  pub post_ids: Vec<PostId>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS, Queryable))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A media scan result, with the uploader.
+pub struct MediaScanView {
+  pub media_scan: MediaScan,
+  pub uploader: Option<Person>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[cfg_attr(feature = "full", derive(TS, Queryable))]
 #[cfg_attr(feature = "full", ts(export))]
@@ -99,7 +125,7 @@ pub struct PaginationCursor(pub(crate) String);
 
 #[skip_serializing_none]
 #[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
-#[cfg_attr(feature = "full", derive(TS, Queryable))]
+#[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
 /// A post view.
 pub struct PostView {
@@ -114,6 +140,9 @@ pub struct PostView {
   pub creator_blocked: bool,
   pub my_vote: Option<i16>,
   pub unread_comments: i64,
+  /// The images of a gallery post, in order. Empty for other posts.
+  #[serde(default)]
+  pub gallery: Vec<PostGalleryImage>,
 }
 
 #[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
@@ -126,6 +155,20 @@ pub struct PrivateMessageView {
   pub recipient: Person,
 }
 
+#[skip_serializing_none]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A private message conversation, with your own settings for it.
+pub struct ConversationView {
+  pub conversation: Conversation,
+  pub participant: ConversationParticipant,
+  /// Everyone taking part in the conversation, including yourself.
+  pub participants: Vec<Person>,
+  pub last_message: Option<PrivateMessage>,
+  pub unread_count: i64,
+}
+
 #[skip_serializing_none]
 #[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
 #[cfg_attr(feature = "full", derive(TS, Queryable))]
@@ -10,6 +10,7 @@ use diesel_async::RunQueryDsl;
 use lemmy_db_schema::{
   newtypes::{CommunityId, DbUrl, InstanceId, PersonId},
   schema::{community, community_follower, person},
+  source::person::Person,
   utils::{functions::coalesce, get_conn, DbPool},
 };
 
@@ -58,6 +59,20 @@ impl CommunityFollowerView {
 
     Ok(res)
   }
+  pub async fn get_community_local_followers(
+    pool: &mut DbPool<'_>,
+    community_id: CommunityId,
+  ) -> Result<Vec<Person>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    community_follower::table
+      .filter(community_follower::community_id.eq(community_id))
+      .inner_join(person::table)
+      .filter(person::local)
+      .select(person::all_columns)
+      .load::<Person>(conn)
+      .await
+  }
+
   pub async fn count_community_followers(
     pool: &mut DbPool<'_>,
     community_id: CommunityId,
@@ -1,5 +1,6 @@
 use crate::structs::{CommunityModeratorView, CommunityView, PersonView};
 use diesel::{
+  dsl::{exists, not},
   pg::Pg,
   result::Error,
   BoolExpressionMethods,
@@ -17,11 +18,13 @@ use lemmy_db_schema::{
     community_aggregates,
     community_block,
     community_follower,
+    federation_blocklist,
     instance_block,
     local_user,
   },
   source::{community::CommunityFollower, local_user::LocalUser},
   utils::{fuzzy_search, limit_and_offset, DbConn, DbPool, ListFn, Queries, ReadFn},
+  FederationBlockSeverity,
   ListingType,
   SortType,
 };
@@ -145,6 +148,18 @@ fn queries<'a>() -> Queries<
       };
     }
 
+    // Communities of limited instances only show up in `All` for their followers
+    if !options.is_mod_or_admin && matches!(options.listing_type, None | Some(ListingType::All)) {
+      let is_limited = exists(
+        federation_blocklist::table.filter(
+          federation_blocklist::instance_id
+            .eq(community::instance_id)
+            .and(federation_blocklist::severity.eq(FederationBlockSeverity::Silence)),
+        ),
+      );
+      query = query.filter(not(is_limited).or(community_follower::person_id.eq(person_id_join)));
+    }
+
     // Don't show blocked communities and communities on blocked instances. nsfw communities are
     // also hidden (based on profile setting)
     if options.local_user.is_some() {
@@ -0,0 +1,81 @@
+use crate::structs::CustomFeedView;
+use diesel::{result::Error, ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+use lemmy_db_schema::{
+  newtypes::{CustomFeedId, PersonId},
+  schema::{community, custom_feed, custom_feed_community, person},
+  source::{community::Community, custom_feed::CustomFeed, person::Person},
+  utils::{get_conn, DbPool},
+};
+
+impl CustomFeedView {
+  pub async fn read(pool: &mut DbPool<'_>, feed_id: CustomFeedId) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let feed = custom_feed::table
+      .find(feed_id)
+      .inner_join(person::table)
+      .select((custom_feed::all_columns, person::all_columns))
+      .first::<(CustomFeed, Person)>(conn)
+      .await?;
+    Self::with_communities(pool, vec![feed])
+      .await?
+      .pop()
+      .ok_or(Error::NotFound)
+  }
+
+  /// Lists the feeds of a user, optionally only the public ones.
+  pub async fn list_for_creator(
+    pool: &mut DbPool<'_>,
+    creator_id: PersonId,
+    public_only: bool,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let mut query = custom_feed::table
+      .inner_join(person::table)
+      .filter(custom_feed::creator_id.eq(creator_id))
+      .select((custom_feed::all_columns, person::all_columns))
+      .into_boxed();
+    if public_only {
+      query = query.filter(custom_feed::public.eq(true));
+    }
+    let feeds = query
+      .order_by(custom_feed::name)
+      .load::<(CustomFeed, Person)>(conn)
+      .await?;
+    Self::with_communities(pool, feeds).await
+  }
+
+  async fn with_communities(
+    pool: &mut DbPool<'_>,
+    feeds: Vec<(CustomFeed, Person)>,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let feed_ids = feeds.iter().map(|(f, _)| f.id).collect::<Vec<_>>();
+    let communities = custom_feed_community::table
+      .inner_join(community::table)
+      .filter(custom_feed_community::custom_feed_id.eq_any(feed_ids))
+      .filter(community::deleted.eq(false))
+      .filter(community::removed.eq(false))
+      .order_by(community::title)
+      .select((
+        custom_feed_community::custom_feed_id,
+        community::all_columns,
+      ))
+      .load::<(CustomFeedId, Community)>(conn)
+      .await?;
+    Ok(
+      feeds
+        .into_iter()
+        .map(|(custom_feed, creator)| CustomFeedView {
+          communities: communities
+            .iter()
+            .filter(|(id, _)| id == &custom_feed.id)
+            .map(|(_, c)| c.clone())
+            .collect(),
+          custom_feed,
+          creator,
+        })
+        .collect(),
+    )
+  }
+}
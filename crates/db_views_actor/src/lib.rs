@@ -11,8 +11,12 @@ pub mod community_person_ban_view;
 #[cfg(feature = "full")]
 pub mod community_view;
 #[cfg(feature = "full")]
+pub mod custom_feed_view;
+#[cfg(feature = "full")]
 pub mod instance_block_view;
 #[cfg(feature = "full")]
+pub mod mod_action_notification_view;
+#[cfg(feature = "full")]
 pub mod person_block_view;
 #[cfg(feature = "full")]
 pub mod person_mention_view;
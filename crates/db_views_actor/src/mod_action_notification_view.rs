@@ -0,0 +1,115 @@
+use crate::structs::ModActionNotificationView;
+use diesel::{
+  result::Error,
+  BoolExpressionMethods,
+  ExpressionMethods,
+  JoinOnDsl,
+  NullableExpressionMethods,
+  QueryDsl,
+};
+use diesel_async::RunQueryDsl;
+use lemmy_db_schema::{
+  newtypes::{ModActionNotificationId, PersonId},
+  schema::{comment, community, mod_action_notification, person, post},
+  source::{
+    comment::Comment,
+    community::Community,
+    mod_action_notification::ModActionNotification,
+    person::Person,
+    post::Post,
+  },
+  utils::{get_conn, limit_and_offset, DbPool},
+};
+
+type ModActionNotificationViewTuple = (
+  ModActionNotification,
+  Person,
+  Community,
+  Option<Post>,
+  Option<Comment>,
+);
+
+macro_rules! mod_action_notification_query {
+  () => {
+    mod_action_notification::table
+      .inner_join(person::table.on(mod_action_notification::mod_person_id.eq(person::id)))
+      .inner_join(community::table)
+      .left_join(post::table.on(mod_action_notification::post_id.eq(post::id.nullable())))
+      .left_join(comment::table.on(mod_action_notification::comment_id.eq(comment::id.nullable())))
+      .select((
+        mod_action_notification::all_columns,
+        person::all_columns,
+        community::all_columns,
+        post::all_columns.nullable(),
+        comment::all_columns.nullable(),
+      ))
+  };
+}
+
+impl ModActionNotificationView {
+  pub async fn read(
+    pool: &mut DbPool<'_>,
+    notification_id: ModActionNotificationId,
+  ) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let tuple = mod_action_notification_query!()
+      .filter(mod_action_notification::id.eq(notification_id))
+      .first::<ModActionNotificationViewTuple>(conn)
+      .await?;
+    Ok(Self::from_tuple(tuple))
+  }
+
+  /// Lists the notifications of the person, newest first.
+  pub async fn list(
+    pool: &mut DbPool<'_>,
+    recipient_id: PersonId,
+    unread_only: bool,
+    page: Option<i64>,
+    limit: Option<i64>,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let (limit, offset) = limit_and_offset(page, limit)?;
+    let mut query = mod_action_notification_query!()
+      .filter(mod_action_notification::recipient_id.eq(recipient_id))
+      .into_boxed();
+    if unread_only {
+      query = query.filter(mod_action_notification::read.eq(false));
+    }
+    let res = query
+      .order_by(mod_action_notification::published.desc())
+      .limit(limit)
+      .offset(offset)
+      .load::<ModActionNotificationViewTuple>(conn)
+      .await?;
+    Ok(res.into_iter().map(Self::from_tuple).collect())
+  }
+
+  pub async fn get_unread_count(
+    pool: &mut DbPool<'_>,
+    recipient_id: PersonId,
+  ) -> Result<i64, Error> {
+    use diesel::dsl::count;
+    let conn = &mut get_conn(pool).await?;
+    mod_action_notification::table
+      .filter(
+        mod_action_notification::recipient_id
+          .eq(recipient_id)
+          .and(mod_action_notification::read.eq(false)),
+      )
+      .select(count(mod_action_notification::id))
+      .first::<i64>(conn)
+      .await
+  }
+
+  fn from_tuple(
+    (mod_action_notification, moderator, community, post, comment): ModActionNotificationViewTuple,
+  ) -> Self {
+    Self {
+      mod_action_notification,
+      moderator,
+      community,
+      post,
+      comment,
+    }
+  }
+}
@@ -1,5 +1,6 @@
 use crate::structs::PersonView;
 use diesel::{
+  dsl::{exists, not},
   pg::Pg,
   result::Error,
   BoolExpressionMethods,
@@ -12,19 +13,21 @@ use diesel_async::RunQueryDsl;
 use lemmy_db_schema::{
   newtypes::PersonId,
   schema,
-  schema::{local_user, person, person_aggregates},
+  schema::{federation_blocklist, local_user, person, person_aggregates, person_follower},
+  source::local_user::LocalUser,
   utils::{fuzzy_search, get_conn, limit_and_offset, now, DbConn, DbPool, ListFn, Queries, ReadFn},
+  FederationBlockSeverity,
   PersonSortType,
 };
 
-enum ListMode {
+enum ListMode<'a> {
   Admins,
   Banned,
-  Query(PersonQuery),
+  Query(PersonQuery<'a>),
 }
 
 fn queries<'a>(
-) -> Queries<impl ReadFn<'a, PersonView, PersonId>, impl ListFn<'a, PersonView, ListMode>> {
+) -> Queries<impl ReadFn<'a, PersonView, PersonId>, impl ListFn<'a, PersonView, ListMode<'a>>> {
   let all_joins = |query: person::BoxedQuery<'a, Pg>| {
     query
       .inner_join(person_aggregates::table)
@@ -38,7 +41,7 @@ fn queries<'a>(
       .await
   };
 
-  let list = move |mut conn: DbConn<'a>, mode: ListMode| async move {
+  let list = move |mut conn: DbConn<'a>, mode: ListMode<'a>| async move {
     let mut query = all_joins(person::table.into_boxed());
     match mode {
       ListMode::Admins => {
@@ -66,6 +69,27 @@ fn queries<'a>(
             .or_filter(person::display_name.ilike(searcher));
         }
 
+        // Users of limited instances only show up for their followers
+        let person_id_join = options
+          .local_user
+          .map(|l| l.person_id)
+          .unwrap_or(PersonId(-1));
+        let is_limited = exists(
+          federation_blocklist::table.filter(
+            federation_blocklist::instance_id
+              .eq(person::instance_id)
+              .and(federation_blocklist::severity.eq(FederationBlockSeverity::Silence)),
+          ),
+        );
+        let is_followed = exists(
+          person_follower::table.filter(
+            person::id
+              .eq(person_follower::person_id)
+              .and(person_follower::follower_id.eq(person_id_join)),
+          ),
+        );
+        query = query.filter(not(is_limited).or(is_followed));
+
         query = match options.sort.unwrap_or(PersonSortType::CommentScore) {
           PersonSortType::New => query.order_by(person::published.desc()),
           PersonSortType::Old => query.order_by(person::published.asc()),
@@ -115,14 +139,15 @@ impl PersonView {
 }
 
 #[derive(Default)]
-pub struct PersonQuery {
+pub struct PersonQuery<'a> {
   pub sort: Option<PersonSortType>,
   pub search_term: Option<String>,
+  pub local_user: Option<&'a LocalUser>,
   pub page: Option<i64>,
   pub limit: Option<i64>,
 }
 
-impl PersonQuery {
+impl<'a> PersonQuery<'a> {
   pub async fn list(self, pool: &mut DbPool<'_>) -> Result<Vec<PersonView>, Error> {
     queries().list(pool, ListMode::Query(self)).await
   }
@@ -6,7 +6,9 @@ use lemmy_db_schema::{
     comment::Comment,
     comment_reply::CommentReply,
     community::Community,
+    custom_feed::CustomFeed,
     instance::Instance,
+    mod_action_notification::ModActionNotification,
     person::Person,
     person_mention::PersonMention,
     post::Post,
@@ -28,6 +30,29 @@ pub struct CommunityBlockView {
   pub community: Community,
 }
 
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A notification about a moderator action, with the affected content.
+pub struct ModActionNotificationView {
+  pub mod_action_notification: ModActionNotification,
+  pub moderator: Person,
+  pub community: Community,
+  pub post: Option<Post>,
+  pub comment: Option<Comment>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A custom feed with its communities.
+pub struct CustomFeedView {
+  pub custom_feed: CustomFeed,
+  pub creator: Person,
+  pub communities: Vec<Community>,
+}
+
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[cfg_attr(feature = "full", derive(TS, Queryable))]
@@ -5,7 +5,9 @@ use lemmy_db_schema::{
   source::{
     comment::Comment,
     community::Community,
+    instance::Instance,
     moderator::{
+      AdminBlockInstance,
       AdminPurgeComment,
       AdminPurgeCommunity,
       AdminPurgePerson,
@@ -172,6 +174,17 @@ pub struct AdminPurgeCommentView {
   pub post: Post,
 }
 
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS, Queryable))]
+#[cfg_attr(feature = "full", ts(export))]
+/// When an admin adds an instance to the federation blocklist, or removes it.
+pub struct AdminBlockInstanceView {
+  pub admin_block_instance: AdminBlockInstance,
+  pub admin: Option<Person>,
+  pub instance: Instance,
+}
+
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[cfg_attr(feature = "full", derive(TS, Queryable))]
@@ -0,0 +1,247 @@
+use actix_web::{
+  http::header::{CacheControl, CacheDirective, ContentEncoding},
+  web,
+  web::{Data, Query},
+  HttpRequest,
+  HttpResponse,
+};
+use bytes::Bytes;
+use futures::stream;
+use lemmy_api_common::{
+  context::LemmyContext,
+  live_events::{LiveEvent, LiveEventConnection, LiveEventData, LiveEventKind, LiveEvents},
+  utils::get_report_count,
+};
+use lemmy_db_schema::newtypes::{CommunityId, LocalUserId, PersonId, PostId};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_db_views_actor::structs::CommunityView;
+use lemmy_utils::error::{LemmyError, LemmyErrorType};
+use serde::Deserialize;
+use std::{collections::HashSet, convert::Infallible, hash::Hash, sync::Arc, time::Duration};
+use tokio::{
+  sync::broadcast,
+  time::{interval, Interval, MissedTickBehavior},
+};
+use tracing::warn;
+
+/// Maximum number of posts and communities which a single stream can follow.
+const MAX_LIVE_EVENT_FILTERS: usize = 20;
+
+/// Interval for sending comments, so that proxies don't close idle streams.
+const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(30);
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+  cfg.service(web::resource("/api/v3/events").route(web::get().to(get_events)));
+}
+
+#[derive(Deserialize)]
+struct EventsQuery {
+  /// Comma separated ids of posts to receive new comments for
+  post_ids: Option<String>,
+  /// Comma separated ids of communities to receive new posts for
+  community_ids: Option<String>,
+}
+
+/// Decides which events are sent to a stream, and converts them to the data sent to the client.
+struct EventFilter {
+  local_user_id: LocalUserId,
+  person_id: PersonId,
+  admin: bool,
+  post_ids: HashSet<PostId>,
+  community_ids: HashSet<CommunityId>,
+}
+
+struct EventStream {
+  backlog: std::vec::IntoIter<Arc<LiveEvent>>,
+  receiver: broadcast::Receiver<Arc<LiveEvent>>,
+  keepalive: Interval,
+  filter: EventFilter,
+  context: Data<LemmyContext>,
+  _connection: LiveEventConnection,
+}
+
+/// Streams live events for the user as server-sent events. Clients can resume the stream after a
+/// disconnect by sending the id of the last received event in the `Last-Event-ID` header.
+async fn get_events(
+  req: HttpRequest,
+  query: Query<EventsQuery>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> Result<HttpResponse, LemmyError> {
+  let post_ids = parse_ids(&query.post_ids, PostId)?;
+  let community_ids = parse_ids(&query.community_ids, CommunityId)?;
+  if post_ids.len() + community_ids.len() > MAX_LIVE_EVENT_FILTERS {
+    Err(LemmyErrorType::TooManyLiveEventFilters)?
+  }
+  let filter = EventFilter {
+    local_user_id: local_user_view.local_user.id,
+    person_id: local_user_view.person.id,
+    admin: local_user_view.local_user.admin,
+    post_ids,
+    community_ids,
+  };
+
+  let connection = LiveEvents::connect(filter.local_user_id)?;
+  let last_event_id = req
+    .headers()
+    .get("Last-Event-ID")
+    .and_then(|h| h.to_str().ok())
+    .and_then(|h| h.trim().parse().ok());
+  let (backlog, receiver) = LiveEvents::subscribe(last_event_id);
+  let mut keepalive = interval(KEEPALIVE_INTERVAL);
+  keepalive.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+  let state = EventStream {
+    backlog: backlog.into_iter(),
+    receiver,
+    keepalive,
+    filter,
+    context,
+    _connection: connection,
+  };
+  let stream = stream::unfold(state, |mut state| async move {
+    let bytes = state.next_message().await?;
+    Some((Ok::<_, Infallible>(bytes), state))
+  });
+
+  Ok(
+    HttpResponse::Ok()
+      .content_type("text/event-stream")
+      .insert_header(CacheControl(vec![CacheDirective::NoCache]))
+      // Compressing would buffer the events
+      .insert_header(ContentEncoding::Identity)
+      .streaming(stream),
+  )
+}
+
+impl EventStream {
+  /// Returns the next message for the client, or None to close the stream. The stream is also
+  /// closed if it falls too far behind, so that the client reconnects and resumes from its last
+  /// event.
+  async fn next_message(&mut self) -> Option<Bytes> {
+    loop {
+      let event = match self.backlog.next() {
+        Some(event) => event,
+        None => tokio::select! {
+          event = self.receiver.recv() => event.ok()?,
+          _ = self.keepalive.tick() => return Some(Bytes::from_static(b": keepalive\n\n")),
+        },
+      };
+      match self.filter.apply(&event.kind, &self.context).await {
+        Ok(Some(data)) => return format_event(event.id, &data),
+        Ok(None) => {}
+        Err(e) => warn!("Failed to prepare live event: {e}"),
+      }
+    }
+  }
+}
+
+impl EventFilter {
+  async fn apply(
+    &self,
+    kind: &LiveEventKind,
+    context: &LemmyContext,
+  ) -> Result<Option<LiveEventData>, LemmyError> {
+    Ok(match kind {
+      LiveEventKind::Notification(local_user_id, notification)
+        if *local_user_id == self.local_user_id =>
+      {
+        Some(LiveEventData::Notification(notification.clone()))
+      }
+      LiveEventKind::NewComment(post_id, comment_id) if self.post_ids.contains(post_id) => {
+        Some(LiveEventData::NewComment {
+          post_id: *post_id,
+          comment_id: *comment_id,
+        })
+      }
+      LiveEventKind::NewPost(community_id, post_id)
+        if self.community_ids.contains(community_id) =>
+      {
+        Some(LiveEventData::NewPost {
+          community_id: *community_id,
+          post_id: *post_id,
+        })
+      }
+      LiveEventKind::NewReport(community_id)
+        if self.can_resolve_report(*community_id, context).await? =>
+      {
+        let count = get_report_count(self.person_id, self.admin, None, &mut context.pool()).await?;
+        Some(LiveEventData::ReportCount(count))
+      }
+      _ => None,
+    })
+  }
+
+  /// Moderators can be added or removed while the stream is open, so this is checked for each
+  /// report.
+  async fn can_resolve_report(
+    &self,
+    community_id: Option<CommunityId>,
+    context: &LemmyContext,
+  ) -> Result<bool, LemmyError> {
+    Ok(match community_id {
+      _ if self.admin => true,
+      Some(community_id) => {
+        CommunityView::is_mod_or_admin(&mut context.pool(), self.person_id, community_id).await?
+      }
+      None => false,
+    })
+  }
+}
+
+fn parse_ids<T: Eq + Hash>(
+  ids: &Option<String>,
+  new_id: fn(i32) -> T,
+) -> Result<HashSet<T>, LemmyError> {
+  let Some(ids) = ids else {
+    return Ok(HashSet::new());
+  };
+  ids
+    .split(',')
+    .filter(|id| !id.trim().is_empty())
+    .map(|id| {
+      id.trim()
+        .parse::<i32>()
+        .map(new_id)
+        .map_err(|_| LemmyErrorType::InvalidQuery.into())
+    })
+    .collect()
+}
+
+fn format_event(id: u64, data: &LiveEventData) -> Option<Bytes> {
+  let json = serde_json::to_string(data)
+    .map_err(|e| warn!("Failed to serialize live event: {e}"))
+    .ok()?;
+  Some(Bytes::from(format!("id: {id}\ndata: {json}\n\n")))
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+
+  use crate::events::{format_event, parse_ids};
+  use lemmy_api_common::live_events::LiveEventData;
+  use lemmy_db_schema::newtypes::{CommentId, PostId};
+  use std::collections::HashSet;
+
+  #[test]
+  fn test_parse_ids() {
+    let ids = parse_ids(&Some("1, 2,,3".to_string()), PostId).unwrap();
+    assert_eq!(HashSet::from([PostId(1), PostId(2), PostId(3)]), ids);
+    assert!(parse_ids(&None, PostId).unwrap().is_empty());
+    assert!(parse_ids(&Some("1,abc".to_string()), PostId).is_err());
+  }
+
+  #[test]
+  fn test_format_event() {
+    let data = LiveEventData::NewComment {
+      post_id: PostId(5),
+      comment_id: CommentId(7),
+    };
+    let bytes = format_event(42, &data).unwrap();
+    assert_eq!(
+      "id: 42\ndata: {\"type\":\"NewComment\",\"post_id\":5,\"comment_id\":7}\n\n",
+      std::str::from_utf8(&bytes).unwrap()
+    );
+  }
+}
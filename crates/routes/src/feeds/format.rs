@@ -0,0 +1,320 @@
+use actix_web::{
+  http::header::{Accept, Header, VARY},
+  HttpRequest,
+  HttpResponse,
+};
+use atom_syndication::{
+  ContentBuilder,
+  EntryBuilder,
+  FeedBuilder,
+  LinkBuilder,
+  PersonBuilder,
+  Text,
+};
+use chrono::{DateTime, Utc};
+use lemmy_utils::error::LemmyError;
+use once_cell::sync::Lazy;
+use rss::{
+  extension::dublincore::DublinCoreExtensionBuilder,
+  ChannelBuilder,
+  GuidBuilder,
+  ItemBuilder,
+};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+static RSS_NAMESPACE: Lazy<BTreeMap<String, String>> = Lazy::new(|| {
+  let mut h = BTreeMap::new();
+  h.insert(
+    "dc".to_string(),
+    rss::extension::dublincore::NAMESPACE.to_string(),
+  );
+  h
+});
+
+const JSON_FEED_VERSION: &str = "https://jsonfeed.org/version/1.1";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum FeedFormat {
+  Rss,
+  Atom,
+  Json,
+}
+
+impl FeedFormat {
+  pub(super) fn from_extension(extension: &str) -> Option<Self> {
+    match extension {
+      "xml" | "rss" => Some(FeedFormat::Rss),
+      "atom" => Some(FeedFormat::Atom),
+      "json" => Some(FeedFormat::Json),
+      _ => None,
+    }
+  }
+
+  /// Picks the format from the Accept header, for feed links without extension. Defaults to RSS
+  /// which all feed readers understand.
+  pub(super) fn negotiate(req: &HttpRequest) -> Self {
+    let Ok(accept) = Accept::parse(req) else {
+      return FeedFormat::Rss;
+    };
+    accept
+      .ranked()
+      .iter()
+      .find_map(|mime| Self::from_mime_type(mime.essence_str()))
+      .unwrap_or(FeedFormat::Rss)
+  }
+
+  fn from_mime_type(mime_type: &str) -> Option<Self> {
+    match mime_type {
+      "application/rss+xml" | "application/xml" | "text/xml" => Some(FeedFormat::Rss),
+      "application/atom+xml" => Some(FeedFormat::Atom),
+      "application/feed+json" | "application/json" => Some(FeedFormat::Json),
+      _ => None,
+    }
+  }
+
+  fn content_type(self) -> &'static str {
+    match self {
+      FeedFormat::Rss => "application/rss+xml",
+      FeedFormat::Atom => "application/atom+xml",
+      FeedFormat::Json => "application/feed+json",
+    }
+  }
+}
+
+/// A feed independent of the output format.
+pub(super) struct Feed {
+  pub title: String,
+  pub link: String,
+  pub description: Option<String>,
+  pub items: Vec<FeedItem>,
+}
+
+pub(super) struct FeedItem {
+  pub title: String,
+  /// Where the item points to, this is the link of url posts
+  pub link: String,
+  /// The page of the post or comment on this instance
+  pub guid: String,
+  pub author_name: String,
+  pub author_url: String,
+  pub published: DateTime<Utc>,
+  pub html: String,
+}
+
+impl Feed {
+  pub(super) fn into_response(self, format: FeedFormat) -> Result<HttpResponse, LemmyError> {
+    let body = match format {
+      FeedFormat::Rss => self.into_rss(),
+      FeedFormat::Atom => self.into_atom(),
+      FeedFormat::Json => self.into_json()?,
+    };
+    Ok(
+      HttpResponse::Ok()
+        .content_type(format.content_type())
+        .insert_header((VARY, "Accept"))
+        .body(body),
+    )
+  }
+
+  fn into_rss(self) -> String {
+    let items = self
+      .items
+      .into_iter()
+      .map(|item| {
+        let mut dc_extension = DublinCoreExtensionBuilder::default();
+        dc_extension.creators(vec![item.author_url]);
+        let guid = GuidBuilder::default()
+          .permalink(true)
+          .value(&item.guid)
+          .build();
+        ItemBuilder::default()
+          .title(item.title)
+          .pub_date(item.published.to_rfc2822())
+          .comments(item.guid)
+          .guid(guid)
+          .link(item.link)
+          .description(item.html)
+          .dublin_core_ext(dc_extension.build())
+          .build()
+      })
+      .collect::<Vec<_>>();
+
+    let mut channel_builder = ChannelBuilder::default();
+    channel_builder
+      .namespaces(RSS_NAMESPACE.clone())
+      .title(self.title)
+      .link(self.link)
+      .items(items);
+    if let Some(description) = self.description {
+      channel_builder.description(description);
+    }
+    channel_builder.build().to_string()
+  }
+
+  fn into_atom(self) -> String {
+    // Atom requires an update time, which is the newest item or the current time for empty feeds
+    let updated = self
+      .items
+      .iter()
+      .map(|item| item.published)
+      .max()
+      .unwrap_or_else(Utc::now);
+    let entries = self
+      .items
+      .into_iter()
+      .map(|item| {
+        let author = PersonBuilder::default()
+          .name(item.author_name)
+          .uri(Some(item.author_url))
+          .build();
+        let link = LinkBuilder::default().href(item.link).build();
+        let content = ContentBuilder::default()
+          .value(Some(item.html))
+          .content_type(Some("html".to_string()))
+          .build();
+        EntryBuilder::default()
+          .title(Text::plain(item.title))
+          .id(item.guid)
+          .updated(item.published)
+          .published(Some(item.published.into()))
+          .authors(vec![author])
+          .links(vec![link])
+          .content(Some(content))
+          .build()
+      })
+      .collect::<Vec<_>>();
+
+    let mut feed_builder = FeedBuilder::default();
+    feed_builder
+      .title(Text::plain(self.title))
+      .id(self.link.clone())
+      .updated(updated)
+      .links(vec![LinkBuilder::default().href(self.link).build()])
+      .entries(entries);
+    if let Some(description) = self.description {
+      feed_builder.subtitle(Some(Text::plain(description)));
+    }
+    feed_builder.build().to_string()
+  }
+
+  fn into_json(self) -> Result<String, LemmyError> {
+    let feed = JsonFeed {
+      version: JSON_FEED_VERSION,
+      title: self.title,
+      home_page_url: self.link,
+      description: self.description,
+      items: self
+        .items
+        .into_iter()
+        .map(|item| JsonFeedItem {
+          external_url: Some(item.link).filter(|link| link != &item.guid),
+          id: item.guid.clone(),
+          url: item.guid,
+          title: item.title,
+          content_html: item.html,
+          date_published: item.published,
+          authors: vec![JsonFeedAuthor {
+            name: item.author_name,
+            url: item.author_url,
+          }],
+        })
+        .collect(),
+    };
+    Ok(serde_json::to_string(&feed)?)
+  }
+}
+
+/// https://www.jsonfeed.org/version/1.1/
+#[derive(Serialize)]
+struct JsonFeed {
+  version: &'static str,
+  title: String,
+  home_page_url: String,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  description: Option<String>,
+  items: Vec<JsonFeedItem>,
+}
+
+#[derive(Serialize)]
+struct JsonFeedItem {
+  id: String,
+  url: String,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  external_url: Option<String>,
+  title: String,
+  content_html: String,
+  date_published: DateTime<Utc>,
+  authors: Vec<JsonFeedAuthor>,
+}
+
+#[derive(Serialize)]
+struct JsonFeedAuthor {
+  name: String,
+  url: String,
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use super::{Feed, FeedFormat, FeedItem};
+  use actix_web::test::TestRequest;
+  use chrono::{TimeZone, Utc};
+
+  fn test_feed() -> Feed {
+    Feed {
+      title: "Lemmy - All".to_string(),
+      link: "https://lemmy.tld".to_string(),
+      description: None,
+      items: vec![FeedItem {
+        title: "A post".to_string(),
+        link: "https://example.com/article".to_string(),
+        guid: "https://lemmy.tld/post/1".to_string(),
+        author_name: "alice".to_string(),
+        author_url: "https://lemmy.tld/u/alice".to_string(),
+        published: Utc.with_ymd_and_hms(2023, 9, 29, 12, 0, 0).unwrap(),
+        html: "<p>Hello</p>".to_string(),
+      }],
+    }
+  }
+
+  #[test]
+  fn test_negotiate_format() {
+    let req = TestRequest::default().to_http_request();
+    assert_eq!(FeedFormat::Rss, FeedFormat::negotiate(&req));
+    let req = TestRequest::default()
+      .insert_header(("Accept", "text/html, application/atom+xml;q=0.9"))
+      .to_http_request();
+    assert_eq!(FeedFormat::Atom, FeedFormat::negotiate(&req));
+    let req = TestRequest::default()
+      .insert_header(("Accept", "application/rss+xml;q=0.5, application/feed+json"))
+      .to_http_request();
+    assert_eq!(FeedFormat::Json, FeedFormat::negotiate(&req));
+  }
+
+  #[test]
+  fn test_atom_feed() {
+    let atom = test_feed().into_atom();
+    let parsed: atom_syndication::Feed = atom.parse().unwrap();
+    assert_eq!("Lemmy - All", parsed.title().as_str());
+    let entry = &parsed.entries()[0];
+    assert_eq!("https://lemmy.tld/post/1", entry.id());
+    assert_eq!("https://example.com/article", entry.links()[0].href());
+    assert_eq!("alice", entry.authors()[0].name());
+    assert_eq!(Some("<p>Hello</p>"), entry.content().unwrap().value());
+  }
+
+  #[test]
+  fn test_json_feed() {
+    let json: serde_json::Value = serde_json::from_str(&test_feed().into_json().unwrap()).unwrap();
+    assert_eq!("https://jsonfeed.org/version/1.1", json["version"]);
+    let item = &json["items"][0];
+    assert_eq!("https://lemmy.tld/post/1", item["id"]);
+    assert_eq!("https://example.com/article", item["external_url"]);
+    assert_eq!("2023-09-29T12:00:00Z", item["date_published"]);
+    assert_eq!("alice", item["authors"][0]["name"]);
+    assert!(json.get("description").is_none());
+  }
+}
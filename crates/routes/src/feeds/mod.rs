@@ -0,0 +1,652 @@
+use actix_web::{error::ErrorBadRequest, web, Error, HttpRequest, HttpResponse, Result};
+use anyhow::anyhow;
+use chrono::{DateTime, Utc};
+use format::{Feed, FeedFormat, FeedItem};
+use lemmy_api_common::{context::LemmyContext, utils::read_custom_feed};
+use lemmy_db_schema::{
+  newtypes::{CustomFeedId, LocalUserId, PostId},
+  source::{
+    community::Community,
+    feed_token::FeedToken,
+    local_user::LocalUser,
+    person::Person,
+    post::Post,
+  },
+  traits::{ApubActor, Crud},
+  utils::DbPool,
+  CommentSortType,
+  ListingType,
+  SortType,
+};
+use lemmy_db_views::{
+  comment_view::CommentQuery,
+  post_view::PostQuery,
+  structs::{CommentView, LocalUserView, PostView, SiteView},
+};
+use lemmy_db_views_actor::{
+  comment_reply_view::CommentReplyQuery,
+  person_mention_view::PersonMentionQuery,
+  structs::{CommentReplyView, PersonMentionView},
+};
+use lemmy_utils::{
+  cache_header::cache_1hour,
+  claims::Claims,
+  error::LemmyError,
+  utils::markdown::markdown_to_html,
+};
+use serde::Deserialize;
+use std::str::FromStr;
+
+mod format;
+
+const RSS_FETCH_LIMIT: i64 = 20;
+
+/// Extensions of the feed links. Links without extension use the Accept header instead.
+const FEED_EXTENSIONS: &str = "{format:xml|rss|atom|json}";
+
+#[derive(Deserialize)]
+struct Params {
+  sort: Option<String>,
+  limit: Option<i64>,
+  page: Option<i64>,
+}
+
+impl Params {
+  fn sort_type(&self) -> Result<SortType, Error> {
+    let sort_query = self
+      .sort
+      .clone()
+      .unwrap_or_else(|| SortType::Hot.to_string());
+    SortType::from_str(&sort_query).map_err(ErrorBadRequest)
+  }
+  fn get_limit(&self) -> i64 {
+    self.limit.unwrap_or(RSS_FETCH_LIMIT)
+  }
+  fn get_page(&self) -> i64 {
+    self.page.unwrap_or(1)
+  }
+}
+
+enum RequestType {
+  Community,
+  User,
+  Post,
+  CustomFeed,
+  Front,
+  Inbox,
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+  cfg.service(
+    web::scope("/feeds")
+      .route(
+        &format!("/u/{{name}}/comments.{FEED_EXTENSIONS}"),
+        web::get().to(get_user_comments_feed),
+      )
+      .route("/u/{name}/comments", web::get().to(get_user_comments_feed))
+      .route(
+        &format!("/{{type}}/{{name}}.{FEED_EXTENSIONS}"),
+        web::get().to(get_feed),
+      )
+      .route("/{type}/{name}", web::get().to(get_feed))
+      .route(
+        &format!("/all.{FEED_EXTENSIONS}"),
+        web::get().to(get_all_feed).wrap(cache_1hour()),
+      )
+      .route("/all", web::get().to(get_all_feed).wrap(cache_1hour()))
+      .route(
+        &format!("/local.{FEED_EXTENSIONS}"),
+        web::get().to(get_local_feed).wrap(cache_1hour()),
+      )
+      .route("/local", web::get().to(get_local_feed).wrap(cache_1hour())),
+  );
+}
+
+/// The format is given by the extension of the feed link, or negotiated with the Accept header.
+fn feed_format(req: &HttpRequest) -> Result<FeedFormat, Error> {
+  match req.match_info().get("format") {
+    Some(extension) => FeedFormat::from_extension(extension)
+      .ok_or_else(|| ErrorBadRequest(LemmyError::from(anyhow!("wrong_format")))),
+    None => Ok(FeedFormat::negotiate(req)),
+  }
+}
+
+#[tracing::instrument(skip_all)]
+async fn get_all_feed(
+  req: HttpRequest,
+  info: web::Query<Params>,
+  context: web::Data<LemmyContext>,
+) -> Result<HttpResponse, Error> {
+  Ok(
+    get_feed_data(
+      &context,
+      ListingType::All,
+      info.sort_type()?,
+      info.get_limit(),
+      info.get_page(),
+    )
+    .await?
+    .into_response(feed_format(&req)?)?,
+  )
+}
+
+#[tracing::instrument(skip_all)]
+async fn get_local_feed(
+  req: HttpRequest,
+  info: web::Query<Params>,
+  context: web::Data<LemmyContext>,
+) -> Result<HttpResponse, Error> {
+  Ok(
+    get_feed_data(
+      &context,
+      ListingType::Local,
+      info.sort_type()?,
+      info.get_limit(),
+      info.get_page(),
+    )
+    .await?
+    .into_response(feed_format(&req)?)?,
+  )
+}
+
+#[tracing::instrument(skip_all)]
+async fn get_feed_data(
+  context: &LemmyContext,
+  listing_type: ListingType,
+  sort_type: SortType,
+  limit: i64,
+  page: i64,
+) -> Result<Feed, LemmyError> {
+  let site_view = SiteView::read_local(&mut context.pool()).await?;
+
+  let posts = PostQuery {
+    listing_type: (Some(listing_type)),
+    sort: (Some(sort_type)),
+    limit: (Some(limit)),
+    page: (Some(page)),
+    ..Default::default()
+  }
+  .list(&mut context.pool())
+  .await?;
+
+  Ok(Feed {
+    title: format!("{} - {}", site_view.site.name, listing_type),
+    link: context.settings().get_protocol_and_hostname(),
+    description: site_view.site.description,
+    items: create_post_items(posts, &context.settings().get_protocol_and_hostname()),
+  })
+}
+
+#[tracing::instrument(skip_all)]
+async fn get_feed(
+  req: HttpRequest,
+  info: web::Query<Params>,
+  context: web::Data<LemmyContext>,
+) -> Result<HttpResponse, Error> {
+  let req_type: String = req.match_info().get("type").unwrap_or("none").parse()?;
+  let param: String = req.match_info().get("name").unwrap_or("none").parse()?;
+
+  let request_type = match req_type.as_str() {
+    "u" => RequestType::User,
+    "c" => RequestType::Community,
+    "post" => RequestType::Post,
+    "custom_feed" => RequestType::CustomFeed,
+    "front" => RequestType::Front,
+    "inbox" => RequestType::Inbox,
+    _ => return Err(ErrorBadRequest(LemmyError::from(anyhow!("wrong_type")))),
+  };
+
+  let jwt_secret = context.secret().jwt_secret.clone();
+  let protocol_and_hostname = context.settings().get_protocol_and_hostname();
+
+  let feed = match request_type {
+    RequestType::User => {
+      get_feed_user(
+        &mut context.pool(),
+        &info.sort_type()?,
+        &info.get_limit(),
+        &info.get_page(),
+        &param,
+        &protocol_and_hostname,
+      )
+      .await
+    }
+    RequestType::Community => {
+      get_feed_community(
+        &mut context.pool(),
+        &info.sort_type()?,
+        &info.get_limit(),
+        &info.get_page(),
+        &param,
+        &protocol_and_hostname,
+      )
+      .await
+    }
+    RequestType::Post => {
+      get_feed_post_comments(
+        &mut context.pool(),
+        &info.get_limit(),
+        &info.get_page(),
+        &param,
+        &protocol_and_hostname,
+      )
+      .await
+    }
+    RequestType::CustomFeed => {
+      get_feed_custom_feed(
+        &mut context.pool(),
+        &info.sort_type()?,
+        &info.get_limit(),
+        &info.get_page(),
+        &param,
+        &protocol_and_hostname,
+      )
+      .await
+    }
+    RequestType::Front => {
+      get_feed_front(
+        &mut context.pool(),
+        &jwt_secret,
+        &info.sort_type()?,
+        &info.get_limit(),
+        &info.get_page(),
+        &param,
+        &protocol_and_hostname,
+      )
+      .await
+    }
+    RequestType::Inbox => {
+      get_feed_inbox(
+        &mut context.pool(),
+        &jwt_secret,
+        &param,
+        &protocol_and_hostname,
+      )
+      .await
+    }
+  }
+  .map_err(ErrorBadRequest)?;
+
+  Ok(feed.into_response(feed_format(&req)?)?)
+}
+
+#[tracing::instrument(skip_all)]
+async fn get_user_comments_feed(
+  req: HttpRequest,
+  info: web::Query<Params>,
+  context: web::Data<LemmyContext>,
+) -> Result<HttpResponse, Error> {
+  let user_name = req.match_info().get("name").unwrap_or("none");
+  let feed = get_feed_user_comments(
+    &mut context.pool(),
+    &info.get_limit(),
+    &info.get_page(),
+    user_name,
+    &context.settings().get_protocol_and_hostname(),
+  )
+  .await
+  .map_err(ErrorBadRequest)?;
+
+  Ok(feed.into_response(feed_format(&req)?)?)
+}
+
+#[tracing::instrument(skip_all)]
+async fn get_feed_user(
+  pool: &mut DbPool<'_>,
+  sort_type: &SortType,
+  limit: &i64,
+  page: &i64,
+  user_name: &str,
+  protocol_and_hostname: &str,
+) -> Result<Feed, LemmyError> {
+  let site_view = SiteView::read_local(pool).await?;
+  let person = Person::read_from_name(pool, user_name, false).await?;
+
+  let posts = PostQuery {
+    listing_type: (Some(ListingType::All)),
+    sort: (Some(*sort_type)),
+    creator_id: (Some(person.id)),
+    limit: (Some(*limit)),
+    page: (Some(*page)),
+    ..Default::default()
+  }
+  .list(pool)
+  .await?;
+
+  Ok(Feed {
+    title: format!("{} - {}", site_view.site.name, person.name),
+    link: person.actor_id.to_string(),
+    description: None,
+    items: create_post_items(posts, protocol_and_hostname),
+  })
+}
+
+#[tracing::instrument(skip_all)]
+async fn get_feed_user_comments(
+  pool: &mut DbPool<'_>,
+  limit: &i64,
+  page: &i64,
+  user_name: &str,
+  protocol_and_hostname: &str,
+) -> Result<Feed, LemmyError> {
+  let site_view = SiteView::read_local(pool).await?;
+  let person = Person::read_from_name(pool, user_name, false).await?;
+
+  let comments = CommentQuery {
+    sort: (Some(CommentSortType::New)),
+    creator_id: (Some(person.id)),
+    limit: (Some(*limit)),
+    page: (Some(*page)),
+    ..Default::default()
+  }
+  .list(pool)
+  .await?;
+
+  Ok(Feed {
+    title: format!("{} - {} - Comments", site_view.site.name, person.name),
+    link: person.actor_id.to_string(),
+    description: None,
+    items: create_comment_items(comments, protocol_and_hostname),
+  })
+}
+
+#[tracing::instrument(skip_all)]
+async fn get_feed_community(
+  pool: &mut DbPool<'_>,
+  sort_type: &SortType,
+  limit: &i64,
+  page: &i64,
+  community_name: &str,
+  protocol_and_hostname: &str,
+) -> Result<Feed, LemmyError> {
+  let site_view = SiteView::read_local(pool).await?;
+  let community = Community::read_from_name(pool, community_name, false).await?;
+
+  let posts = PostQuery {
+    sort: (Some(*sort_type)),
+    community_id: (Some(community.id)),
+    limit: (Some(*limit)),
+    page: (Some(*page)),
+    ..Default::default()
+  }
+  .list(pool)
+  .await?;
+
+  Ok(Feed {
+    title: format!("{} - {}", site_view.site.name, community.name),
+    link: community.actor_id.to_string(),
+    description: community.description,
+    items: create_post_items(posts, protocol_and_hostname),
+  })
+}
+
+#[tracing::instrument(skip_all)]
+async fn get_feed_post_comments(
+  pool: &mut DbPool<'_>,
+  limit: &i64,
+  page: &i64,
+  post_id: &str,
+  protocol_and_hostname: &str,
+) -> Result<Feed, LemmyError> {
+  let site_view = SiteView::read_local(pool).await?;
+  let post_id = PostId(post_id.parse()?);
+  let post = Post::read(pool, post_id).await?;
+  if post.deleted || post.removed {
+    Err(anyhow!("post_not_found"))?
+  }
+
+  let comments = CommentQuery {
+    sort: (Some(CommentSortType::New)),
+    post_id: (Some(post.id)),
+    limit: (Some(*limit)),
+    page: (Some(*page)),
+    ..Default::default()
+  }
+  .list(pool)
+  .await?;
+
+  Ok(Feed {
+    title: format!("{} - {}", site_view.site.name, post.name),
+    link: format!("{}/post/{}", protocol_and_hostname, post.id),
+    description: None,
+    items: create_comment_items(comments, protocol_and_hostname),
+  })
+}
+
+/// Only public custom feeds are available, as the feed link doesn't identify the user.
+#[tracing::instrument(skip_all)]
+async fn get_feed_custom_feed(
+  pool: &mut DbPool<'_>,
+  sort_type: &SortType,
+  limit: &i64,
+  page: &i64,
+  feed_id: &str,
+  protocol_and_hostname: &str,
+) -> Result<Feed, LemmyError> {
+  let site_view = SiteView::read_local(pool).await?;
+  let custom_feed = read_custom_feed(pool, CustomFeedId(feed_id.parse()?), None).await?;
+
+  let posts = PostQuery {
+    sort: (Some(*sort_type)),
+    custom_feed_id: (Some(custom_feed.id)),
+    limit: (Some(*limit)),
+    page: (Some(*page)),
+    ..Default::default()
+  }
+  .list(pool)
+  .await?;
+
+  Ok(Feed {
+    title: format!("{} - {}", site_view.site.name, custom_feed.name),
+    link: format!("{}/custom_feed/{}", protocol_and_hostname, custom_feed.id.0),
+    description: custom_feed.description,
+    items: create_post_items(posts, protocol_and_hostname),
+  })
+}
+
+/// Private feeds are opened with the feed token of the user. Older feed links contain the login
+/// token instead, which still works.
+async fn local_user_id_from_token(
+  pool: &mut DbPool<'_>,
+  jwt_secret: &str,
+  token: &str,
+) -> Result<LocalUserId, LemmyError> {
+  match FeedToken::read_from_token(pool, token).await {
+    Ok(feed_token) => Ok(feed_token.local_user_id),
+    Err(_) => Ok(LocalUserId(Claims::decode(token, jwt_secret)?.claims.sub)),
+  }
+}
+
+#[tracing::instrument(skip_all)]
+async fn get_feed_front(
+  pool: &mut DbPool<'_>,
+  jwt_secret: &str,
+  sort_type: &SortType,
+  limit: &i64,
+  page: &i64,
+  token: &str,
+  protocol_and_hostname: &str,
+) -> Result<Feed, LemmyError> {
+  let site_view = SiteView::read_local(pool).await?;
+  let local_user_id = local_user_id_from_token(pool, jwt_secret, token).await?;
+  let local_user = LocalUserView::read(pool, local_user_id).await?;
+
+  let posts = PostQuery {
+    listing_type: (Some(ListingType::Subscribed)),
+    local_user: (Some(&local_user)),
+    sort: (Some(*sort_type)),
+    limit: (Some(*limit)),
+    page: (Some(*page)),
+    ..Default::default()
+  }
+  .list(pool)
+  .await?;
+
+  Ok(Feed {
+    title: format!("{} - Subscribed", site_view.site.name),
+    link: protocol_and_hostname.to_string(),
+    description: site_view.site.description,
+    items: create_post_items(posts, protocol_and_hostname),
+  })
+}
+
+#[tracing::instrument(skip_all)]
+async fn get_feed_inbox(
+  pool: &mut DbPool<'_>,
+  jwt_secret: &str,
+  token: &str,
+  protocol_and_hostname: &str,
+) -> Result<Feed, LemmyError> {
+  let site_view = SiteView::read_local(pool).await?;
+  let local_user_id = local_user_id_from_token(pool, jwt_secret, token).await?;
+  let local_user = LocalUser::read(pool, local_user_id).await?;
+  let person_id = local_user.person_id;
+  let show_bot_accounts = local_user.show_bot_accounts;
+
+  let sort = CommentSortType::New;
+
+  let replies = CommentReplyQuery {
+    recipient_id: (Some(person_id)),
+    my_person_id: (Some(person_id)),
+    show_bot_accounts: (show_bot_accounts),
+    sort: (Some(sort)),
+    limit: (Some(RSS_FETCH_LIMIT)),
+    ..Default::default()
+  }
+  .list(pool)
+  .await?;
+
+  let mentions = PersonMentionQuery {
+    recipient_id: (Some(person_id)),
+    my_person_id: (Some(person_id)),
+    show_bot_accounts: (show_bot_accounts),
+    sort: (Some(sort)),
+    limit: (Some(RSS_FETCH_LIMIT)),
+    ..Default::default()
+  }
+  .list(pool)
+  .await?;
+
+  Ok(Feed {
+    title: format!("{} - Inbox", site_view.site.name),
+    link: format!("{protocol_and_hostname}/inbox",),
+    description: site_view.site.description,
+    items: create_reply_and_mention_items(replies, mentions, protocol_and_hostname),
+  })
+}
+
+#[tracing::instrument(skip_all)]
+fn create_reply_and_mention_items(
+  replies: Vec<CommentReplyView>,
+  mentions: Vec<PersonMentionView>,
+  protocol_and_hostname: &str,
+) -> Vec<FeedItem> {
+  let mut reply_items: Vec<FeedItem> = replies
+    .iter()
+    .map(|r| {
+      let reply_url = format!("{}/comment/{}", protocol_and_hostname, r.comment.id);
+      build_item(
+        &r.creator,
+        &r.comment.published,
+        &reply_url,
+        &r.comment.content,
+      )
+    })
+    .collect();
+
+  let mut mention_items: Vec<FeedItem> = mentions
+    .iter()
+    .map(|m| {
+      let mention_url = format!("{}/comment/{}", protocol_and_hostname, m.comment.id);
+      build_item(
+        &m.creator,
+        &m.comment.published,
+        &mention_url,
+        &m.comment.content,
+      )
+    })
+    .collect();
+
+  reply_items.append(&mut mention_items);
+  reply_items
+}
+
+#[tracing::instrument(skip_all)]
+fn build_item(creator: &Person, published: &DateTime<Utc>, url: &str, content: &str) -> FeedItem {
+  FeedItem {
+    title: format!("Reply from {}", creator.name),
+    link: url.to_owned(),
+    guid: url.to_owned(),
+    author_name: creator.name.clone(),
+    author_url: creator.actor_id.to_string(),
+    published: *published,
+    // TODO add images
+    html: markdown_to_html(content),
+  }
+}
+
+#[tracing::instrument(skip_all)]
+fn create_comment_items(comments: Vec<CommentView>, protocol_and_hostname: &str) -> Vec<FeedItem> {
+  comments
+    .into_iter()
+    .filter(|c| !c.comment.deleted && !c.comment.removed)
+    .map(|c| {
+      let comment_url = format!("{}/comment/{}", protocol_and_hostname, c.comment.id);
+      FeedItem {
+        title: format!("{} on {}", c.creator.name, c.post.name),
+        link: comment_url.clone(),
+        guid: comment_url,
+        author_name: c.creator.name,
+        author_url: c.creator.actor_id.to_string(),
+        published: c.comment.published,
+        html: markdown_to_html(&c.comment.content),
+      }
+    })
+    .collect()
+}
+
+#[tracing::instrument(skip_all)]
+fn create_post_items(posts: Vec<PostView>, protocol_and_hostname: &str) -> Vec<FeedItem> {
+  let mut items: Vec<FeedItem> = Vec::new();
+
+  for p in posts {
+    let post_url = format!("{}/post/{}", protocol_and_hostname, p.post.id);
+    let community_url = format!("{}/c/{}", protocol_and_hostname, p.community.name);
+
+    // TODO add images
+    let mut description = format!("submitted by <a href=\"{}\">{}</a> to <a href=\"{}\">{}</a><br>{} points | <a href=\"{}\">{} comments</a>",
+    p.creator.actor_id,
+    p.creator.name,
+    community_url,
+    p.community.name,
+    p.counts.score,
+    post_url,
+    p.counts.comments);
+
+    // If its a url post, add it to the description
+    let link = if let Some(url) = p.post.url {
+      let link_html = format!("<br><a href=\"{url}\">{url}</a>");
+      description.push_str(&link_html);
+      url.to_string()
+    } else {
+      post_url.clone()
+    };
+
+    if let Some(body) = p.post.body {
+      let html = markdown_to_html(&body);
+      description.push_str(&html);
+    }
+
+    items.push(FeedItem {
+      title: p.post.name,
+      link,
+      guid: post_url,
+      author_name: p.creator.name,
+      author_url: p.creator.actor_id.to_string(),
+      published: p.post.published,
+      html: description,
+    });
+  }
+
+  items
+}
@@ -1,26 +1,53 @@
+use actix_multipart::{Field, Multipart};
 use actix_web::{
   body::BodyStream,
   error,
-  http::{
-    header::{HeaderName, ACCEPT_ENCODING, HOST},
-    StatusCode,
-  },
+  http::{header::CONTENT_LENGTH, StatusCode},
   web,
   Error,
   HttpRequest,
   HttpResponse,
 };
-use futures::stream::{Stream, StreamExt};
-use lemmy_api_common::context::LemmyContext;
-use lemmy_db_schema::source::{
-  image_upload::{ImageUpload, ImageUploadForm},
-  local_site::LocalSite,
+use anyhow::anyhow;
+use bytes::{Bytes, BytesMut};
+use futures::{future, stream, StreamExt, TryStreamExt};
+use lemmy_api_common::{
+  context::LemmyContext,
+  image_proxy::{check_image_proxy_domain, check_image_proxy_signature},
+  media::{
+    cache_remote_image,
+    media_backend,
+    scanner::scan_stored_media,
+    MediaBackend,
+    MediaOptions,
+    StoredMedia,
+    UploadStream,
+  },
+  person::UploadQuotaResponse,
+  utils::read_upload_quota,
+};
+use lemmy_db_schema::{
+  source::{
+    image_upload::{ImageUpload, ImageUploadForm},
+    local_site::LocalSite,
+    media_scan::MediaScan,
+    remote_image::{RemoteImage, RemoteImageForm},
+  },
+  MediaScanResult,
 };
 use lemmy_db_views::structs::LocalUserView;
-use lemmy_utils::{rate_limit::RateLimitCell, REQWEST_TIMEOUT};
-use reqwest::Body;
-use reqwest_middleware::{ClientWithMiddleware, RequestBuilder};
+use lemmy_utils::{
+  error::{LemmyError, LemmyErrorType, LemmyResult},
+  rate_limit::RateLimitCell,
+};
+use reqwest_middleware::ClientWithMiddleware;
 use serde::{Deserialize, Serialize};
+use std::{cell::Cell, rc::Rc};
+use tokio::sync::mpsc;
+use url::Url;
+
+/// Number of bytes which are needed to detect the type of an upload
+const MIME_TYPE_HEAD_LENGTH: usize = 12;
 
 pub fn config(
   cfg: &mut web::ServiceConfig,
@@ -39,6 +66,7 @@ pub fn config(
     .service(web::resource("/pictrs/image/delete/{token}/{filename}").route(web::get().to(delete)));
 }
 
+/// Response in the same format as the pictrs upload, which clients already understand.
 #[derive(Debug, Serialize, Deserialize)]
 struct Image {
   file: String,
@@ -52,9 +80,9 @@ struct Images {
 }
 
 #[derive(Deserialize)]
-struct PictrsParams {
-  format: Option<String>,
-  thumbnail: Option<i32>,
+pub struct ImageProxyParams {
+  url: String,
+  sig: String,
 }
 
 #[derive(Deserialize)]
@@ -65,76 +93,223 @@ enum PictrsPurgeParams {
   Alias(String),
 }
 
-fn adapt_request(
-  request: &HttpRequest,
-  client: &ClientWithMiddleware,
-  url: String,
-) -> RequestBuilder {
-  // remove accept-encoding header so that pictrs doesnt compress the response
-  const INVALID_HEADERS: &[HeaderName] = &[ACCEPT_ENCODING, HOST];
-
-  let client_request = client
-    .request(request.method().clone(), url)
-    .timeout(REQWEST_TIMEOUT);
-
-  request
-    .headers()
-    .iter()
-    .fold(client_request, |client_req, (key, value)| {
-      if INVALID_HEADERS.contains(key) {
-        client_req
-      } else {
-        client_req.header(key, value)
-      }
-    })
-}
-
 async fn upload(
   req: HttpRequest,
-  body: web::Payload,
+  mut body: Multipart,
   client: web::Data<ClientWithMiddleware>,
   context: web::Data<LemmyContext>,
   // require login
   local_user_view: LocalUserView,
 ) -> Result<HttpResponse, Error> {
-  // TODO: check rate limit here
+  let quota = read_upload_quota(&local_user_view.local_user, &context).await?;
+  // The size of the whole request is slightly larger than the files, but allows to reject large
+  // uploads before reading them
+  let content_length: i64 = req
+    .headers()
+    .get(CONTENT_LENGTH)
+    .and_then(|v| v.to_str().ok())
+    .and_then(|v| v.parse().ok())
+    .unwrap_or(0);
+  check_upload_quota(&quota, 1, content_length)?;
+  let backend = media_backend(&client, context.settings())?;
+  let client_address = req.head().peer_addr.map(|addr| addr.to_string());
+
+  let mut stored: Vec<StoredMedia> = vec![];
+  let mut uploaded_size = 0;
+  while let Some(field) = body.try_next().await? {
+    // A request can contain several files, which all count towards the quota
+    let upload = match check_upload_quota(&quota, stored.len() + 1, uploaded_size) {
+      Ok(()) => upload_field(field, &quota, backend.as_ref(), client_address.as_deref()).await,
+      Err(e) => Err(e),
+    };
+    match upload {
+      Ok((media, size)) => {
+        stored.push(media);
+        uploaded_size += size;
+      }
+      Err(e) => {
+        delete_stored(&stored, backend.as_ref()).await;
+        Err(e)?
+      }
+    }
+  }
+  if let Err(e) = check_upload_quota(&quota, stored.len(), uploaded_size) {
+    delete_stored(&stored, backend.as_ref()).await;
+    Err(e)?
+  }
 
-  let pictrs_config = context.settings().pictrs_config()?;
-  let image_url = format!("{}image", pictrs_config.url);
+  // The backend may have converted a file to a type which is not allowed
+  let allowed = |media: &StoredMedia| match &media.content_type {
+    Some(content_type) => quota.allowed_mime_types.contains(content_type),
+    None => true,
+  };
+  if !stored.iter().all(allowed) {
+    delete_stored(&stored, backend.as_ref()).await;
+    Err(LemmyError::from(LemmyErrorType::UploadTypeNotAllowed))?
+  }
 
-  let mut client_req = adapt_request(&req, &client, image_url);
+  for media in &stored {
+    let scan = scan_stored_media(
+      &context,
+      backend.as_ref(),
+      media,
+      None,
+      Some(local_user_view.person.id),
+      None,
+    )
+    .await;
+    if let Err(e) = scan {
+      delete_stored(&stored, backend.as_ref()).await;
+      Err(e)?
+    }
+  }
+
+  for media in &stored {
+    let form = ImageUploadForm {
+      local_user_id: local_user_view.local_user.id,
+      pictrs_alias: media.alias.clone(),
+      pictrs_delete_token: media.delete_token.clone(),
+      size: media.size,
+      alt_text: None,
+    };
+    ImageUpload::create(&mut context.pool(), &form)
+      .await
+      .map_err(error::ErrorBadRequest)?;
+  }
 
-  if let Some(addr) = req.head().peer_addr {
-    client_req = client_req.header("X-Forwarded-For", addr.to_string())
+  let images = Images {
+    msg: "ok".to_string(),
+    files: Some(
+      stored
+        .into_iter()
+        .map(|media| Image {
+          file: media.alias,
+          delete_token: media.delete_token,
+        })
+        .collect(),
+    ),
   };
-  let res = client_req
-    .body(Body::wrap_stream(make_send(body)))
-    .send()
+  Ok(HttpResponse::Created().json(images))
+}
+
+/// Checks the type of an uploaded file, and passes it on to the media backend. Returns the stored
+/// file and its size.
+async fn upload_field(
+  mut field: Field,
+  quota: &UploadQuotaResponse,
+  backend: &dyn MediaBackend,
+  client_address: Option<&str>,
+) -> LemmyResult<(StoredMedia, i64)> {
+  let mut head = BytesMut::new();
+  while head.len() < MIME_TYPE_HEAD_LENGTH {
+    let Some(chunk) = field.try_next().await.map_err(|e| anyhow!("{e}"))? else {
+      break;
+    };
+    head.extend_from_slice(&chunk);
+  }
+  // The declared content type can't be trusted, and the type needs to be checked before the
+  // backend stores the file
+  let content_type = detect_mime_type(&head)
+    .filter(|c| quota.allowed_mime_types.iter().any(|a| a == c))
+    .ok_or(LemmyErrorType::UploadTypeNotAllowed)?;
+
+  let size = Rc::new(Cell::new(0));
+  let data = forward_field(head.freeze(), field, quota.max_file_size, size.clone());
+  let media = backend
+    .upload(data, Some(content_type), client_address)
     .await
-    .map_err(error::ErrorBadRequest)?;
+    .map_err(|e| {
+      // The backend may only see that the upload was aborted
+      if size.get() > quota.max_file_size {
+        LemmyErrorType::UploadTooLarge.into()
+      } else {
+        e
+      }
+    })?;
+  Ok((media, size.get()))
+}
 
-  let status = res.status();
-  let images = res.json::<Images>().await.map_err(error::ErrorBadRequest)?;
-  if let Some(images) = &images.files {
-    for uploaded_image in images {
-      let form = ImageUploadForm {
-        local_user_id: local_user_view.local_user.id,
-        pictrs_alias: uploaded_image.file.to_string(),
-        pictrs_delete_token: uploaded_image.delete_token.to_string(),
-      };
-      ImageUpload::create(&mut context.pool(), &form)
-        .await
-        .map_err(error::ErrorBadRequest)?;
+/// Passes an uploaded file on to the media backend while it is received, and stops once it gets
+/// larger than `max_size`. Multipart fields can only be read on the current thread, so the data is
+/// sent through a channel.
+fn forward_field(head: Bytes, field: Field, max_size: i64, size: Rc<Cell<i64>>) -> UploadStream {
+  let (tx, rx) = mpsc::channel(8);
+  let mut data = stream::once(future::ok(head)).chain(field.map_err(|e| anyhow!("{e}").into()));
+  actix_web::rt::spawn(async move {
+    while let Some(chunk) = data.next().await {
+      let chunk = chunk.and_then(|chunk| {
+        // The content length can be missing or wrong, so the size is counted while reading
+        size.set(size.get() + chunk.len() as i64);
+        if size.get() > max_size {
+          Err(LemmyErrorType::UploadTooLarge)?
+        }
+        Ok(chunk)
+      });
+      let failed = chunk.is_err();
+      // Stops when the backend doesn't read any further
+      if tx.send(chunk).await.is_err() || failed {
+        break;
+      }
+    }
+  });
+  Box::pin(stream::unfold(rx, |mut rx| async move {
+    rx.recv().await.map(|chunk| (chunk, rx))
+  }))
+}
+
+/// Detects the type of a file from its first bytes. Unknown types are not accepted.
+fn detect_mime_type(head: &[u8]) -> Option<&'static str> {
+  let iso_media_brand = match head.get(4..8) {
+    Some(b"ftyp") => head.get(8..12),
+    _ => None,
+  };
+  if head.starts_with(b"\x89PNG\r\n\x1a\n") {
+    Some("image/png")
+  } else if head.starts_with(&[0xff, 0xd8, 0xff]) {
+    Some("image/jpeg")
+  } else if head.starts_with(b"GIF87a") || head.starts_with(b"GIF89a") {
+    Some("image/gif")
+  } else if head.starts_with(b"RIFF") && head.get(8..12) == Some(b"WEBP") {
+    Some("image/webp")
+  } else if head.starts_with(&[0x1a, 0x45, 0xdf, 0xa3]) {
+    Some("video/webm")
+  } else {
+    match iso_media_brand? {
+      b"avif" | b"avis" => Some("image/avif"),
+      b"heic" | b"heix" | b"mif1" | b"msf1" => Some("image/heic"),
+      b"qt  " => Some("video/quicktime"),
+      _ => Some("video/mp4"),
     }
   }
+}
 
-  Ok(HttpResponse::build(status).json(images))
+async fn delete_stored(stored: &[StoredMedia], backend: &dyn MediaBackend) {
+  for media in stored {
+    backend.delete(&media.alias, &media.delete_token).await.ok();
+  }
+}
+
+/// Rejects uploads which would go over the limits of the user, with the number and total size of
+/// the files in this request.
+fn check_upload_quota(quota: &UploadQuotaResponse, files: usize, size: i64) -> LemmyResult<()> {
+  if quota
+    .max_files
+    .is_some_and(|max| quota.upload_count + files as i64 > i64::from(max))
+  {
+    Err(LemmyErrorType::UploadQuotaExceeded)?
+  }
+  if quota
+    .max_total_size
+    .is_some_and(|max| quota.upload_size + size > max)
+  {
+    Err(LemmyErrorType::UploadQuotaExceeded)?
+  }
+  Ok(())
 }
 
 async fn full_res(
   filename: web::Path<String>,
-  web::Query(params): web::Query<PictrsParams>,
-  req: HttpRequest,
+  web::Query(options): web::Query<MediaOptions>,
   client: web::Data<ClientWithMiddleware>,
   context: web::Data<LemmyContext>,
   local_user_view: Option<LocalUserView>,
@@ -147,61 +322,109 @@ async fn full_res(
     return Ok(HttpResponse::Unauthorized().finish());
   }
   let name = &filename.into_inner();
+  if is_quarantined(&context, name).await? {
+    return Ok(HttpResponse::NotFound().finish());
+  }
 
-  // If there are no query params, the URL is original
-  let pictrs_config = context.settings().pictrs_config()?;
-  let url = if params.format.is_none() && params.thumbnail.is_none() {
-    format!("{}image/original/{}", pictrs_config.url, name,)
-  } else {
-    // Take file type from name, or jpg if nothing is given
-    let format = params
-      .format
-      .unwrap_or_else(|| name.split('.').last().unwrap_or("jpg").to_string());
-
-    let mut url = format!("{}image/process.{}?src={}", pictrs_config.url, format, name,);
-
-    if let Some(size) = params.thumbnail {
-      url = format!("{url}&thumbnail={size}",);
+  let backend = media_backend(&client, context.settings())?;
+  let res = match backend.read(name, &options).await {
+    Ok(res) => res,
+    Err(e) if e.error_type == LemmyErrorType::CouldntFindImage => {
+      return Ok(HttpResponse::NotFound().finish())
     }
-    url
+    Err(e) => Err(e)?,
   };
-
-  image(url, req, client).await
-}
-
-async fn image(
-  url: String,
-  req: HttpRequest,
-  client: web::Data<ClientWithMiddleware>,
-) -> Result<HttpResponse, Error> {
-  let mut client_req = adapt_request(&req, &client, url);
-
-  if let Some(addr) = req.head().peer_addr {
-    client_req = client_req.header("X-Forwarded-For", addr.to_string());
+  if res.status == StatusCode::NOT_FOUND {
+    return Ok(HttpResponse::NotFound().finish());
   }
 
-  if let Some(addr) = req.head().peer_addr {
-    client_req = client_req.header("X-Forwarded-For", addr.to_string());
+  let mut client_res = HttpResponse::build(res.status);
+  for (name, value) in res.headers.iter() {
+    client_res.insert_header((name.clone(), value.clone()));
   }
+  Ok(client_res.body(BodyStream::new(res.body)))
+}
 
-  let res = client_req.send().await.map_err(error::ErrorBadRequest)?;
-
-  if res.status() == StatusCode::NOT_FOUND {
+/// Serves a remote image from the cache of the media backend, and fetches it on first use. Links
+/// to this endpoint are generated by `proxy_image_link`.
+pub async fn image_proxy(
+  web::Query(params): web::Query<ImageProxyParams>,
+  context: web::Data<LemmyContext>,
+  local_user_view: Option<LocalUserView>,
+) -> Result<HttpResponse, Error> {
+  let config = &context.settings().media.image_proxy;
+  if !config.enabled {
     return Ok(HttpResponse::NotFound().finish());
   }
+  let local_site = LocalSite::read(&mut context.pool())
+    .await
+    .map_err(error::ErrorBadRequest)?;
+  if local_site.private_instance && local_user_view.is_none() {
+    return Ok(HttpResponse::Unauthorized().finish());
+  }
+  check_image_proxy_signature(&params.url, &params.sig, &context)?;
+  let url = Url::parse(&params.url).map_err(error::ErrorBadRequest)?;
+  check_image_proxy_domain(&url, config)?;
+
+  let backend = media_backend(context.client(), context.settings())?;
+  let cached = match RemoteImage::read(&mut context.pool(), url.as_str()).await {
+    Ok(image) => {
+      if is_quarantined(&context, &image.pictrs_alias).await? {
+        return Ok(HttpResponse::NotFound().finish());
+      }
+      backend
+        .read(&image.pictrs_alias, &MediaOptions::default())
+        .await
+        .ok()
+        .filter(|res| res.status.is_success())
+    }
+    Err(_) => None,
+  };
+  let res = match cached {
+    Some(res) => res,
+    // Not fetched yet, or the cached copy was removed
+    None => {
+      let stored = cache_remote_image(backend.as_ref(), &url, &context).await?;
+      // Denied files are already purged here, so they are never linked
+      let scan =
+        scan_stored_media(&context, backend.as_ref(), &stored, None, None, Some(&url)).await?;
+      // Quarantined files are linked too, so that they aren't fetched again until an admin
+      // reviewed them
+      let form = RemoteImageForm {
+        link: url.to_string(),
+        pictrs_alias: stored.alias.clone(),
+      };
+      RemoteImage::upsert(&mut context.pool(), &form)
+        .await
+        .map_err(error::ErrorBadRequest)?;
+      if scan == MediaScanResult::Quarantine {
+        return Ok(HttpResponse::NotFound().finish());
+      }
+      backend
+        .read(&stored.alias, &MediaOptions::default())
+        .await?
+    }
+  };
 
-  let mut client_res = HttpResponse::build(res.status());
-
-  for (name, value) in res.headers().iter().filter(|(h, _)| *h != "connection") {
+  let mut client_res = HttpResponse::build(res.status);
+  for (name, value) in res.headers.iter() {
     client_res.insert_header((name.clone(), value.clone()));
   }
+  Ok(client_res.body(BodyStream::new(res.body)))
+}
 
-  Ok(client_res.body(BodyStream::new(res.bytes_stream())))
+/// Quarantined files are kept until an admin reviewed them, but not served in the meantime.
+async fn is_quarantined(context: &LemmyContext, alias: &str) -> Result<bool, Error> {
+  if context.settings().media.scanner.is_none() {
+    return Ok(false);
+  }
+  MediaScan::is_quarantined(&mut context.pool(), alias)
+    .await
+    .map_err(error::ErrorBadRequest)
 }
 
 async fn delete(
   components: web::Path<(String, String)>,
-  req: HttpRequest,
   client: web::Data<ClientWithMiddleware>,
   context: web::Data<LemmyContext>,
   // require login
@@ -209,62 +432,74 @@ async fn delete(
 ) -> Result<HttpResponse, Error> {
   let (token, file) = components.into_inner();
 
-  let pictrs_config = context.settings().pictrs_config()?;
-  let url = format!("{}image/delete/{}/{}", pictrs_config.url, &token, &file);
-
-  let mut client_req = adapt_request(&req, &client, url);
-
-  if let Some(addr) = req.head().peer_addr {
-    client_req = client_req.header("X-Forwarded-For", addr.to_string());
-  }
-
-  let res = client_req.send().await.map_err(error::ErrorBadRequest)?;
+  media_backend(&client, context.settings())?
+    .delete(&file, &token)
+    .await?;
 
   ImageUpload::delete_by_alias(&mut context.pool(), &file)
     .await
     .map_err(error::ErrorBadRequest)?;
 
-  Ok(HttpResponse::build(res.status()).body(BodyStream::new(res.bytes_stream())))
+  Ok(HttpResponse::NoContent().finish())
 }
 
-fn make_send<S>(mut stream: S) -> impl Stream<Item = S::Item> + Send + Unpin + 'static
-where
-  S: Stream + Unpin + 'static,
-  S::Item: Send,
-{
-  // NOTE: the 8 here is arbitrary
-  let (tx, rx) = tokio::sync::mpsc::channel(8);
-
-  // NOTE: spawning stream into a new task can potentially hit this bug:
-  // - https://github.com/actix/actix-web/issues/1679
-  //
-  // Since 4.0.0-beta.2 this issue is incredibly less frequent. I have not personally reproduced it.
-  // That said, it is still technically possible to encounter.
-  actix_web::rt::spawn(async move {
-    while let Some(res) = stream.next().await {
-      if tx.send(res).await.is_err() {
-        break;
-      }
-    }
-  });
-
-  SendStream { rx }
-}
-
-struct SendStream<T> {
-  rx: tokio::sync::mpsc::Receiver<T>,
-}
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use super::{check_upload_quota, detect_mime_type};
+  use lemmy_api_common::person::UploadQuotaResponse;
+  use lemmy_utils::error::LemmyErrorType;
+
+  #[test]
+  fn test_check_upload_quota() {
+    let quota = UploadQuotaResponse {
+      upload_count: 8,
+      upload_size: 900,
+      max_files: Some(10),
+      max_total_size: Some(1000),
+      max_file_size: 100,
+      allowed_mime_types: vec![],
+    };
+    assert!(check_upload_quota(&quota, 2, 100).is_ok());
+    // Each file of a request counts
+    let err = check_upload_quota(&quota, 3, 10).unwrap_err();
+    assert_eq!(LemmyErrorType::UploadQuotaExceeded, err.error_type);
+    let err = check_upload_quota(&quota, 1, 101).unwrap_err();
+    assert_eq!(LemmyErrorType::UploadQuotaExceeded, err.error_type);
+
+    let unlimited = UploadQuotaResponse {
+      max_files: None,
+      max_total_size: None,
+      ..quota
+    };
+    assert!(check_upload_quota(&unlimited, 100, i64::from(i32::MAX)).is_ok());
+  }
 
-impl<T> Stream for SendStream<T>
-where
-  T: Send,
-{
-  type Item = T;
-
-  fn poll_next(
-    mut self: std::pin::Pin<&mut Self>,
-    cx: &mut std::task::Context<'_>,
-  ) -> std::task::Poll<Option<Self::Item>> {
-    std::pin::Pin::new(&mut self.rx).poll_recv(cx)
+  #[test]
+  fn test_detect_mime_type() {
+    assert_eq!(
+      Some("image/png"),
+      detect_mime_type(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR")
+    );
+    assert_eq!(
+      Some("image/jpeg"),
+      detect_mime_type(b"\xff\xd8\xff\xe0\0\x10JFIF")
+    );
+    assert_eq!(Some("image/gif"), detect_mime_type(b"GIF89a\x01\0\x01\0"));
+    assert_eq!(
+      Some("image/webp"),
+      detect_mime_type(b"RIFF\x24\0\0\0WEBPVP8 ")
+    );
+    assert_eq!(Some("image/avif"), detect_mime_type(b"\0\0\0\x1cftypavif"));
+    assert_eq!(Some("video/mp4"), detect_mime_type(b"\0\0\0\x20ftypisom"));
+    assert_eq!(
+      Some("video/webm"),
+      detect_mime_type(b"\x1a\x45\xdf\xa3\x9f")
+    );
+    assert_eq!(None, detect_mime_type(b"<svg xmlns="));
+    assert_eq!(None, detect_mime_type(b"RIFF\x24\0\0\0WAVEfmt "));
+    assert_eq!(None, detect_mime_type(b""));
   }
 }
@@ -1,11 +1,45 @@
+use std::{env, fs, path::PathBuf};
+
+const LANGUAGES: [&str; 4] = ["en", "fi", "ko", "pt"];
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-  rosetta_build::config()
-    .source("en", "translations/email/en.json")
-    .source("fi", "translations/email/fi.json")
-    .source("ko", "translations/email/ko.json")
-    .source("pt", "translations/email/pt.json")
-    .fallback("en")
-    .generate()?;
+  let out_dir = PathBuf::from(env::var("OUT_DIR")?).join("email");
+  fs::create_dir_all(&out_dir)?;
+
+  let mut config = rosetta_build::config();
+  for lang in LANGUAGES {
+    let merged = merge_translations(lang)?;
+    let path = out_dir.join(format!("{lang}.json"));
+    let merged = serde_json::to_string(&merged)?;
+    // rosetta reruns the build script when its sources change, so leave them untouched if the
+    // content is the same
+    if fs::read_to_string(&path).ok().as_ref() != Some(&merged) {
+      fs::write(&path, merged)?;
+    }
+    config = config.source(lang, path.to_string_lossy());
+  }
+  config.fallback("en").generate()?;
 
   Ok(())
 }
+
+/// Adds the strings from `email_translations/` which are not yet part of lemmy-translations to
+/// the upstream translation file for the given language.
+fn merge_translations(
+  lang: &str,
+) -> Result<serde_json::Map<String, serde_json::Value>, Box<dyn std::error::Error>> {
+  let upstream = format!("translations/email/{lang}.json");
+  let extra = format!("email_translations/{lang}.json");
+  println!("cargo:rerun-if-changed={upstream}");
+  println!("cargo:rerun-if-changed={extra}");
+
+  let mut merged: serde_json::Map<String, serde_json::Value> =
+    serde_json::from_str(&fs::read_to_string(upstream)?)?;
+  if let Ok(extra) = fs::read_to_string(extra) {
+    let extra: serde_json::Map<String, serde_json::Value> = serde_json::from_str(&extra)?;
+    for (key, value) in extra {
+      merged.entry(key).or_insert(value);
+    }
+  }
+  Ok(merged)
+}
@@ -1,15 +1,17 @@
 use crate::{
   error::{LemmyError, LemmyErrorExt, LemmyErrorType},
-  settings::structs::Settings,
+  settings::structs::{EmailConfig, Settings},
 };
 use html2text;
 use lettre::{
   message::{Mailbox, MultiPart},
-  transport::smtp::{authentication::Credentials, extension::ClientId},
+  transport::smtp::{authentication::Credentials, extension::ClientId, PoolConfig},
   Address,
   AsyncTransport,
   Message,
 };
+use once_cell::sync::{Lazy, OnceCell};
+use regex::{Captures, Regex};
 use std::str::FromStr;
 use uuid::Uuid;
 
@@ -17,35 +19,40 @@ pub mod translations {
   rosetta_i18n::include_translations!();
 }
 
+static TEMPLATE_PLACEHOLDER_REGEX: Lazy<Regex> =
+  Lazy::new(|| Regex::new(r"\{\{\s*([a-z_]+)\s*\}\}").expect("compile regex"));
+
 type AsyncSmtpTransport = lettre::AsyncSmtpTransport<lettre::Tokio1Executor>;
 
+/// The smtp transport is built once and shared, so that its connection pool is reused between
+/// emails instead of opening a new connection for each of them.
+static MAILER: OnceCell<AsyncSmtpTransport> = OnceCell::new();
+
 pub async fn send_email(
   subject: &str,
   to_email: &str,
   to_username: &str,
   html: &str,
+  reply_to: Option<&str>,
   settings: &Settings,
 ) -> Result<(), LemmyError> {
-  let email_config = settings.email.clone().ok_or(LemmyErrorType::NoEmailSetup)?;
-  let domain = settings.hostname.clone();
-
-  let (smtp_server, smtp_port) = {
-    let email_and_port = email_config.smtp_server.split(':').collect::<Vec<&str>>();
-    let email = *email_and_port
-      .first()
-      .ok_or(LemmyErrorType::MissingAnEmail)?;
-    let port = email_and_port
-      .get(1)
-      .ok_or(LemmyErrorType::EmailSmtpServerNeedsAPort)?
-      .parse::<u16>()?;
-
-    (email, port)
-  };
+  let email_config = settings
+    .email
+    .as_ref()
+    .ok_or(LemmyErrorType::NoEmailSetup)?;
 
   // use usize::MAX as the line wrap length, since lettre handles the wrapping for us
   let plain_text = html2text::from_read(html.as_bytes(), usize::MAX);
 
-  let email = Message::builder()
+  let mut builder = Message::builder();
+  if let Some(reply_to) = reply_to {
+    builder = builder.reply_to(
+      reply_to
+        .parse()
+        .with_lemmy_type(LemmyErrorType::InvalidEmailAddress)?,
+    );
+  }
+  let email = builder
     .from(
       email_config
         .smtp_from_address
@@ -54,7 +61,7 @@ pub async fn send_email(
     )
     .to(Mailbox::new(
       Some(to_username.to_string()),
-      Address::from_str(to_email).expect("email to address isn't valid"),
+      Address::from_str(to_email).with_lemmy_type(LemmyErrorType::InvalidEmailAddress)?,
     ))
     .message_id(Some(format!("<{}@{}>", Uuid::new_v4(), settings.hostname)))
     .subject(subject)
@@ -64,6 +71,37 @@ pub async fn send_email(
     ))
     .expect("email built incorrectly");
 
+  let mailer = MAILER.get_or_try_init(|| build_mailer(email_config, &settings.hostname))?;
+  let res = mailer.send(email).await;
+
+  // Permanent errors (smtp status 5xx) mean that the server will never accept this email, for
+  // example because the address doesn't exist. It must not be retried.
+  let error_type = match &res {
+    Err(e) if e.is_permanent() => LemmyErrorType::EmailRejected,
+    _ => LemmyErrorType::EmailSendFailed,
+  };
+  res.with_lemmy_type(error_type)?;
+
+  Ok(())
+}
+
+fn build_mailer(
+  email_config: &EmailConfig,
+  domain: &str,
+) -> Result<AsyncSmtpTransport, LemmyError> {
+  let (smtp_server, smtp_port) = {
+    let email_and_port = email_config.smtp_server.split(':').collect::<Vec<&str>>();
+    let email = *email_and_port
+      .first()
+      .ok_or(LemmyErrorType::MissingAnEmail)?;
+    let port = email_and_port
+      .get(1)
+      .ok_or(LemmyErrorType::EmailSmtpServerNeedsAPort)?
+      .parse::<u16>()?;
+
+    (email, port)
+  };
+
   // don't worry about 'dangeous'. it's just that leaving it at the default configuration
   // is bad.
 
@@ -77,18 +115,72 @@ pub async fn send_email(
   // Set the creds if they exist
   let smtp_password = std::env::var("LEMMY_SMTP_PASSWORD")
     .ok()
-    .or(email_config.smtp_password);
+    .or(email_config.smtp_password.clone());
 
-  if let (Some(username), Some(password)) = (email_config.smtp_login, smtp_password) {
+  if let (Some(username), Some(password)) = (email_config.smtp_login.clone(), smtp_password) {
     builder = builder.credentials(Credentials::new(username, password));
   }
 
-  let mailer = builder.hello_name(ClientId::Domain(domain)).build();
+  let pool_config = PoolConfig::new().max_size(email_config.outbox.max_connections);
+  Ok(
+    builder
+      .hello_name(ClientId::Domain(domain.to_string()))
+      .pool_config(pool_config)
+      .build(),
+  )
+}
+
+/// Replaces the `{{ variable }}` placeholders of an email template with their values. Unknown
+/// placeholders are kept as they are.
+pub fn render_email_template(template: &str, variables: &[(&str, &str)]) -> String {
+  TEMPLATE_PLACEHOLDER_REGEX
+    .replace_all(template, |caps: &Captures| {
+      let name = caps.get(1).map(|m| m.as_str()).unwrap_or_default();
+      variables
+        .iter()
+        .find(|(n, _)| n == &name)
+        .map(|(_, value)| value.to_string())
+        .unwrap_or_else(|| {
+          caps
+            .get(0)
+            .map(|m| m.as_str())
+            .unwrap_or_default()
+            .to_string()
+        })
+    })
+    .into_owned()
+}
 
-  mailer
-    .send(email)
-    .await
-    .with_lemmy_type(LemmyErrorType::EmailSendFailed)?;
+/// Returns the names of all placeholders used in an email template.
+pub fn email_template_placeholders(template: &str) -> Vec<&str> {
+  TEMPLATE_PLACEHOLDER_REGEX
+    .captures_iter(template)
+    .filter_map(|caps| caps.get(1).map(|m| m.as_str()))
+    .collect()
+}
 
-  Ok(())
+#[cfg(test)]
+mod tests {
+  use crate::email::{email_template_placeholders, render_email_template};
+
+  #[test]
+  fn test_render_email_template() {
+    let template = "<p>Hello {{recipient}}, {{ sender }} replied: {{ content }}</p>{{ unknown }}";
+    let rendered = render_email_template(
+      template,
+      &[
+        ("recipient", "alice"),
+        ("sender", "bob"),
+        ("content", "Hi!"),
+      ],
+    );
+    assert_eq!(
+      "<p>Hello alice, bob replied: Hi!</p>{{ unknown }}",
+      rendered
+    );
+    assert_eq!(
+      vec!["recipient", "sender", "content", "unknown"],
+      email_template_placeholders(template)
+    );
+  }
 }
@@ -213,6 +213,49 @@ pub enum LemmyErrorType {
   ContradictingFilters,
   InstanceBlockAlreadyExists,
   AuthCookieInsecure,
+  CommunityWasMoved,
+  CantMoveCommunityToSameInstance,
+  InstanceIsBlocked,
+  InvalidFederationBlocklist,
+  InstanceIsLimited,
+  CouldntFindImage,
+  UploadTooLarge,
+  UploadQuotaExceeded,
+  UploadTypeNotAllowed,
+  MediaBackendNotConfigured,
+  InvalidDeleteToken,
+  InvalidImageProxySignature,
+  AltTextTooLong,
+  AltTextRequired,
+  GalleryTooLarge,
+  MediaDenied,
+  MediaScanFailed,
+  CustomFeedNotFound,
+  CustomFeedAlreadyExists,
+  InvalidCustomFeedName,
+  CustomFeedTooLarge,
+  InvalidEmailAddress,
+  EmailRejected,
+  InvalidPushSubscription,
+  PushSendFailed,
+  TooManyLiveEventConnections,
+  TooManyLiveEventFilters,
+  CouldntUpdateNotificationSettings,
+  ConversationTooLarge,
+  CouldntUpdateConversation,
+  InvalidDeviceKey,
+  TooManyDeviceKeys,
+  InvalidEncryptedContent,
+  RecipientDoesNotSupportEncryption,
+  RecipientDoesNotAcceptPrivateMessages,
+  ReplyByEmailDisabled,
+  InvalidReplyAddress,
+  InvalidInboundEmail,
+  InvalidEmailTemplate,
+  UrlNotPublic,
+  CantMoveCommunityToItself,
+  CommunityMoveNotConfirmed,
+  TooManyRedirects,
   Unknown(String),
 }
 
@@ -49,6 +49,12 @@ pub struct Settings {
   #[default(None)]
   #[doku(example = "Some(Default::default())")]
   pub prometheus: Option<PrometheusConfig>,
+  /// Fetching of older posts and comments when a remote community gets its first local follower
+  #[default(Default::default())]
+  pub backfill: BackfillConfig,
+  /// Storage of uploaded images and cached thumbnails
+  #[default(Default::default())]
+  pub media: MediaConfig,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, SmartDefault, Document)]
@@ -68,6 +74,131 @@ pub struct PictrsConfig {
   pub cache_remote_images: bool,
 }
 
+#[derive(Debug, Deserialize, Serialize, Clone, SmartDefault, Document)]
+#[serde(default, deny_unknown_fields)]
+pub struct MediaConfig {
+  /// Where images are stored. `pictrs` uses the pictrs section above, `filesystem` and `s3` handle
+  /// images without pictrs.
+  #[default(MediaBackendType::Pictrs)]
+  pub backend: MediaBackendType,
+  /// Directory for the `filesystem` backend
+  #[default("media".to_string())]
+  pub path: String,
+  /// Bucket for the `s3` backend
+  #[default(None)]
+  #[doku(example = "Some(Default::default())")]
+  pub s3: Option<S3Config>,
+  /// Limits for images uploaded by local users
+  #[default(Default::default())]
+  pub upload_quota: UploadQuotaConfig,
+  /// Serve remote images through this instance, so that other servers don't see the IP addresses
+  /// of users
+  #[default(Default::default())]
+  pub image_proxy: ImageProxyConfig,
+  /// Scan uploads and cached remote images before they are published, for example to detect
+  /// known illegal imagery
+  #[default(None)]
+  #[doku(example = "Some(Default::default())")]
+  pub scanner: Option<MediaScannerConfig>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq, Document)]
+#[serde(rename_all = "lowercase")]
+pub enum MediaBackendType {
+  #[default]
+  Pictrs,
+  Filesystem,
+  S3,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, SmartDefault, Document)]
+#[serde(default, deny_unknown_fields)]
+pub struct S3Config {
+  /// Address of the S3-compatible service. Buckets are addressed in the path, so this also works
+  /// with Minio or Garage.
+  #[default(Url::parse("http://localhost:9000").expect("parse s3 url"))]
+  #[doku(example = "http://localhost:9000")]
+  pub endpoint: Url,
+  /// Name of the bucket, which needs to exist already
+  #[default("lemmy-media".to_string())]
+  #[doku(example = "lemmy-media")]
+  pub bucket: String,
+  #[default("us-east-1".to_string())]
+  #[doku(example = "us-east-1")]
+  pub region: String,
+  pub access_key: String,
+  pub secret_key: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, SmartDefault, Document)]
+#[serde(default, deny_unknown_fields)]
+pub struct UploadQuotaConfig {
+  /// Maximum size of a single upload in bytes. This also limits the size of remote images which
+  /// are cached.
+  #[default(10_485_760)]
+  pub max_file_size: i64,
+  /// Maximum total size of all uploads of a user in bytes. Admins can change it for single users.
+  #[default(None)]
+  #[doku(example = "104857600")]
+  pub max_total_size: Option<i64>,
+  /// Maximum number of uploads of a user. Admins can change it for single users.
+  #[default(None)]
+  #[doku(example = "100")]
+  pub max_files: Option<i32>,
+  /// Types of files which can be uploaded
+  #[default(vec![
+    "image/png".to_string(),
+    "image/jpeg".to_string(),
+    "image/gif".to_string(),
+    "image/webp".to_string(),
+    "image/avif".to_string(),
+    "video/mp4".to_string(),
+    "video/webm".to_string(),
+  ])]
+  #[doku(example = "image/png")]
+  pub allowed_mime_types: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, SmartDefault, Document)]
+#[serde(default, deny_unknown_fields)]
+pub struct ImageProxyConfig {
+  /// Rewrite links to remote images in API responses, so that they are loaded from
+  /// `/api/v3/image_proxy` and cached with the media backend
+  #[default(false)]
+  pub enabled: bool,
+  /// If not empty, only images from these domains and their subdomains are proxied. Images from
+  /// other domains are not loaded at all.
+  #[default(Vec::new())]
+  #[doku(example = "example.com")]
+  pub allowed_domains: Vec<String>,
+  /// Images from these domains and their subdomains are never loaded
+  #[default(Vec::new())]
+  #[doku(example = "tracker.example.com")]
+  pub blocked_domains: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, SmartDefault, Document)]
+#[serde(default, deny_unknown_fields)]
+pub struct MediaScannerConfig {
+  /// Scanner service which receives each file as POST request body, and responds with
+  /// `{"result": "allow", "reason": null}`. The result can also be `deny` to delete the file, or
+  /// `quarantine` to hide it until an admin reviewed it.
+  #[default(None)]
+  #[doku(example = "http://localhost:8090/scan")]
+  pub url: Option<Url>,
+  /// Local command which is used if no url is set. It receives the file on stdin and prints
+  /// `allow`, `deny` or `quarantine`, optionally followed by a reason.
+  #[default(None)]
+  #[doku(example = "/usr/local/bin/scan-media")]
+  pub command: Option<String>,
+  /// Publish media if the scanner fails or is unreachable. By default such media is rejected.
+  #[default(false)]
+  pub allow_on_error: bool,
+  /// Maximum time in seconds which the scanner may take for a file, after which the scan fails.
+  #[default(60)]
+  pub timeout_seconds: u64,
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone, SmartDefault, Document)]
 #[serde(default)]
 pub struct DatabaseConfig {
@@ -146,6 +277,39 @@ pub struct EmailConfig {
   #[default("none")]
   #[doku(example = "none")]
   pub tls_type: String,
+  /// Delivery of queued notification emails
+  #[serde(default)]
+  pub outbox: EmailOutboxConfig,
+  /// Answering notification emails to reply to comments and private messages
+  #[serde(default)]
+  pub reply_by_email: ReplyByEmailConfig,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, SmartDefault, Document)]
+#[serde(default, deny_unknown_fields)]
+pub struct EmailOutboxConfig {
+  /// Maximum number of delivery attempts for a queued email. Afterwards it is kept as dead letter.
+  #[default(8)]
+  #[doku(example = "8")]
+  pub max_attempts: i32,
+  /// Maximum number of smtp connections which are kept open and reused
+  #[default(4)]
+  #[doku(example = "4")]
+  pub max_connections: u32,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, SmartDefault, Document)]
+#[serde(default, deny_unknown_fields)]
+pub struct ReplyByEmailConfig {
+  /// Domain of the reply addresses. The mail server must pass all mails for this domain to
+  /// `/api/v3/email/inbound`. Reply by email is disabled if this is not set.
+  #[default(None)]
+  #[doku(example = "reply.example.com")]
+  pub domain: Option<String>,
+  /// Secret which the mail server sends as bearer token in the `Authorization` header
+  #[default(None)]
+  #[doku(example = "my-inbound-secret")]
+  pub inbound_secret: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, SmartDefault, Document)]
@@ -178,3 +342,17 @@ pub struct PrometheusConfig {
   #[doku(example = "10002")]
   pub port: Option<i32>,
 }
+
+#[derive(Debug, Deserialize, Serialize, Clone, SmartDefault, Document)]
+#[serde(default, deny_unknown_fields)]
+pub struct BackfillConfig {
+  /// Maximum number of posts to fetch from the community outbox. Set to 0 to disable backfill.
+  #[default(50)]
+  pub max_posts: usize,
+  /// Maximum number of comments to fetch for each backfilled post
+  #[default(50)]
+  pub max_comments_per_post: usize,
+  /// Delay between two requests to the remote instance, in milliseconds
+  #[default(500)]
+  pub request_delay_ms: u64,
+}
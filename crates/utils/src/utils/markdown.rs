@@ -1,4 +1,4 @@
-use markdown_it::MarkdownIt;
+use markdown_it::{plugins::cmark::inline::image::Image, MarkdownIt};
 use once_cell::sync::Lazy;
 
 mod spoiler_rule;
@@ -16,12 +16,85 @@ pub fn markdown_to_html(text: &str) -> String {
   MARKDOWN_PARSER.parse(text).xrender()
 }
 
+/// Replaces the links of all images in the markdown text. The closure gets the link of each image
+/// and returns a replacement, or `None` to keep it. The rest of the text is unchanged.
+pub fn markdown_rewrite_image_links(
+  text: &str,
+  rewrite: impl Fn(&str) -> Option<String>,
+) -> String {
+  let mut replacements = vec![];
+  MARKDOWN_PARSER.parse(text).walk(|node, _| {
+    let (Some(image), Some(srcmap)) = (node.cast::<Image>(), node.srcmap) else {
+      return;
+    };
+    let (start, end) = srcmap.get_byte_offsets();
+    let Some((link_start, link_end)) = text
+      .get(start..end)
+      .and_then(|source| image_link_position(source, &image.url))
+    else {
+      return;
+    };
+    if let Some(new_link) = rewrite(&image.url) {
+      replacements.push((start + link_start, start + link_end, new_link));
+    }
+  });
+
+  let mut result = text.to_string();
+  // Nested images come after their parent, so replacing from the end keeps all offsets valid
+  replacements.sort_by_key(|(start, _, _)| *start);
+  for (start, end, new_link) in replacements.into_iter().rev() {
+    result.replace_range(start..end, &new_link);
+  }
+  result
+}
+
+/// Finds the link destination in the source of an image like `![alt](<link> "title")`, and returns
+/// its byte range including angle brackets. The alt text may contain `](` as well, so the
+/// destination which matches the parsed link is preferred.
+fn image_link_position(source: &str, url: &str) -> Option<(usize, usize)> {
+  let candidates: Vec<(usize, usize)> = source
+    .match_indices("](")
+    .filter_map(|(i, _)| {
+      let start = i + 2;
+      let rest = source.get(start..)?;
+      let start = start + (rest.len() - rest.trim_start().len());
+      let rest = source.get(start..)?;
+      let len = if rest.starts_with('<') {
+        rest.find('>')? + 1
+      } else {
+        let mut depth = 0;
+        rest
+          .char_indices()
+          .find(|(_, c)| match c {
+            '(' => {
+              depth += 1;
+              false
+            }
+            ')' if depth == 0 => true,
+            ')' => {
+              depth -= 1;
+              false
+            }
+            c => c.is_whitespace(),
+          })
+          .map(|(i, _)| i)?
+      };
+      Some((start, start + len))
+    })
+    .collect();
+  candidates
+    .iter()
+    .find(|(start, end)| source.get(*start..*end) == Some(url))
+    .or(candidates.first())
+    .copied()
+}
+
 #[cfg(test)]
 mod tests {
   #![allow(clippy::unwrap_used)]
   #![allow(clippy::indexing_slicing)]
 
-  use crate::utils::markdown::markdown_to_html;
+  use crate::utils::markdown::{markdown_rewrite_image_links, markdown_to_html};
 
   #[test]
   fn test_basic_markdown() {
@@ -83,4 +156,48 @@ mod tests {
       );
     });
   }
+
+  #[test]
+  fn test_markdown_rewrite_image_links() {
+    let proxy = |link: &str| Some(format!("https://lemmy.ml/proxy/{}", link.len()));
+    let tests = [
+      (
+        "plain image",
+        "Look ![cat](https://example.com/cat.png) here",
+        "Look ![cat](https://lemmy.ml/proxy/27) here",
+      ),
+      (
+        "with title and angle brackets",
+        "![cat](<https://example.com/cat.png> \"Cat\")",
+        "![cat](https://lemmy.ml/proxy/27 \"Cat\")",
+      ),
+      (
+        "parentheses in link",
+        "![x](https://example.com/a_(b).png)",
+        "![x](https://lemmy.ml/proxy/29)",
+      ),
+      (
+        "normal links are unchanged",
+        "[cat](https://example.com/cat.png) `![x](y)`",
+        "[cat](https://example.com/cat.png) `![x](y)`",
+      ),
+      (
+        "multiple images",
+        "![a](https://a.com/1.png)\n\n* ![b](https://b.com/2.png)",
+        "![a](https://lemmy.ml/proxy/19)\n\n* ![b](https://lemmy.ml/proxy/19)",
+      ),
+    ];
+    tests.iter().for_each(|&(msg, input, expected)| {
+      let result = markdown_rewrite_image_links(input, proxy);
+      assert_eq!(
+        result, expected,
+        "Testing {}, with original input '{}'",
+        msg, input
+      );
+    });
+
+    // Returning none keeps the link
+    let text = "![cat](https://example.com/cat.png)";
+    assert_eq!(text, markdown_rewrite_image_links(text, |_| None));
+  }
 }
@@ -1,4 +1,7 @@
-use crate::error::{LemmyErrorExt, LemmyErrorType, LemmyResult};
+use crate::{
+  email::email_template_placeholders,
+  error::{LemmyErrorExt, LemmyErrorType, LemmyResult},
+};
 use itertools::Itertools;
 use once_cell::sync::Lazy;
 use regex::{Regex, RegexBuilder};
@@ -11,6 +14,8 @@ static VALID_POST_TITLE_REGEX: Lazy<Regex> =
 static VALID_MATRIX_ID_REGEX: Lazy<Regex> = Lazy::new(|| {
   Regex::new(r"^@[A-Za-z0-9._=-]+:[A-Za-z0-9.-]+\.[A-Za-z]{2,}$").expect("compile regex")
 });
+static VALID_DEVICE_ID_REGEX: Lazy<Regex> =
+  Lazy::new(|| Regex::new(r"^[a-zA-Z0-9_-]{1,64}$").expect("compile regex"));
 // taken from https://en.wikipedia.org/wiki/UTM_parameters
 static CLEAN_URL_PARAMS_REGEX: Lazy<Regex> = Lazy::new(|| {
   Regex::new(r"^utm_source|utm_medium|utm_campaign|utm_term|utm_content|gclid|gclsrc|dclid|fbclid$")
@@ -20,6 +25,16 @@ static CLEAN_URL_PARAMS_REGEX: Lazy<Regex> = Lazy::new(|| {
 const BODY_MAX_LENGTH: usize = 10000;
 const POST_BODY_MAX_LENGTH: usize = 50000;
 const BIO_MAX_LENGTH: usize = 300;
+pub const ALT_TEXT_MAX_LENGTH: usize = 1500;
+const GALLERY_MAX_IMAGES: usize = 20;
+const CUSTOM_FEED_NAME_MAX_LENGTH: usize = 100;
+const CUSTOM_FEED_MAX_COMMUNITIES: usize = 100;
+const CONVERSATION_MAX_RECIPIENTS: usize = 10;
+const DEVICE_KEYS_MAX: usize = 10;
+const DEVICE_PUBLIC_KEY_MAX_LENGTH: usize = 2000;
+const ENCRYPTED_CONTENT_MAX_LENGTH: usize = 100_000;
+const EMAIL_TEMPLATE_SUBJECT_MAX_LENGTH: usize = 200;
+const EMAIL_TEMPLATE_BODY_MAX_LENGTH: usize = 100_000;
 const SITE_NAME_MAX_LENGTH: usize = 20;
 const SITE_NAME_MIN_LENGTH: usize = 1;
 const SITE_DESCRIPTION_MAX_LENGTH: usize = 150;
@@ -127,6 +142,101 @@ pub fn is_valid_post_title(title: &str) -> LemmyResult<()> {
   }
 }
 
+/// Checks the length of an image description. The limit is the same as on Mastodon.
+pub fn is_valid_alt_text_field(alt_text: &Option<String>) -> LemmyResult<()> {
+  match alt_text {
+    Some(alt_text) if alt_text.chars().count() > ALT_TEXT_MAX_LENGTH => {
+      Err(LemmyErrorType::AltTextTooLong)?
+    }
+    _ => Ok(()),
+  }
+}
+
+pub fn check_gallery_size(images: usize) -> LemmyResult<()> {
+  if images > GALLERY_MAX_IMAGES {
+    Err(LemmyErrorType::GalleryTooLarge)?
+  }
+  Ok(())
+}
+
+pub fn is_valid_custom_feed_name(name: &str) -> LemmyResult<()> {
+  let check = !name.trim().is_empty()
+    && name.chars().count() <= CUSTOM_FEED_NAME_MAX_LENGTH
+    && !has_newline(name);
+  if !check {
+    Err(LemmyErrorType::InvalidCustomFeedName)?
+  }
+  Ok(())
+}
+
+pub fn check_custom_feed_size(communities: usize) -> LemmyResult<()> {
+  if communities > CUSTOM_FEED_MAX_COMMUNITIES {
+    Err(LemmyErrorType::CustomFeedTooLarge)?
+  }
+  Ok(())
+}
+
+pub fn check_conversation_size(recipients: usize) -> LemmyResult<()> {
+  if recipients > CONVERSATION_MAX_RECIPIENTS {
+    Err(LemmyErrorType::ConversationTooLarge)?
+  }
+  Ok(())
+}
+
+pub fn is_valid_device_key(device_id: &str, public_key: &str) -> LemmyResult<()> {
+  let check = VALID_DEVICE_ID_REGEX.is_match(device_id)
+    && !public_key.trim().is_empty()
+    && public_key.len() <= DEVICE_PUBLIC_KEY_MAX_LENGTH
+    && public_key
+      .chars()
+      .all(|c| c.is_ascii_graphic() || c.is_ascii_whitespace());
+  if !check {
+    Err(LemmyErrorType::InvalidDeviceKey)?
+  }
+  Ok(())
+}
+
+pub fn check_device_key_count(keys: usize) -> LemmyResult<()> {
+  if keys > DEVICE_KEYS_MAX {
+    Err(LemmyErrorType::TooManyDeviceKeys)?
+  }
+  Ok(())
+}
+
+/// The server can't sanitize encrypted messages, so only allow printable characters which
+/// have no special meaning in HTML. This way clients without encryption support can show the
+/// ciphertext without harm.
+pub fn is_valid_encrypted_content(content: &str) -> LemmyResult<()> {
+  let check = !content.trim().is_empty()
+    && content.len() <= ENCRYPTED_CONTENT_MAX_LENGTH
+    && content.chars().all(|c| {
+      (c.is_ascii_graphic() || c.is_ascii_whitespace())
+        && !matches!(c, '<' | '>' | '&' | '"' | '\'')
+    });
+  if !check {
+    Err(LemmyErrorType::InvalidEncryptedContent)?
+  }
+  Ok(())
+}
+
+/// Checks the length of an email template, and that it only uses the variables which are
+/// available for its type.
+pub fn is_valid_email_template(subject: &str, body: &str, variables: &[&str]) -> LemmyResult<()> {
+  let check = !subject.trim().is_empty()
+    && !subject.contains('\n')
+    && subject.chars().count() <= EMAIL_TEMPLATE_SUBJECT_MAX_LENGTH
+    && !body.trim().is_empty()
+    && body.len() <= EMAIL_TEMPLATE_BODY_MAX_LENGTH
+    && email_template_placeholders(subject)
+      .into_iter()
+      .chain(email_template_placeholders(body))
+      .all(|p| variables.contains(&p));
+  if !check {
+    Err(LemmyErrorType::InvalidEmailTemplate)?
+  }
+  Ok(())
+}
+
 /// This could be post bodies, comments, or any description field
 pub fn is_valid_body_field(body: &Option<String>, post: bool) -> LemmyResult<()> {
   if let Some(body) = body {
@@ -274,17 +384,34 @@ mod tests {
     error::LemmyErrorType,
     utils::validation::{
       build_and_check_regex,
+      check_conversation_size,
+      check_custom_feed_size,
+      check_device_key_count,
+      check_gallery_size,
       check_site_visibility_valid,
       check_url_scheme,
       clean_url_params,
       is_valid_actor_name,
+      is_valid_alt_text_field,
       is_valid_bio_field,
+      is_valid_custom_feed_name,
+      is_valid_device_key,
       is_valid_display_name,
+      is_valid_email_template,
+      is_valid_encrypted_content,
       is_valid_matrix_id,
       is_valid_post_title,
       site_description_length_check,
       site_name_length_check,
+      ALT_TEXT_MAX_LENGTH,
       BIO_MAX_LENGTH,
+      CONVERSATION_MAX_RECIPIENTS,
+      CUSTOM_FEED_MAX_COMMUNITIES,
+      CUSTOM_FEED_NAME_MAX_LENGTH,
+      DEVICE_KEYS_MAX,
+      DEVICE_PUBLIC_KEY_MAX_LENGTH,
+      EMAIL_TEMPLATE_SUBJECT_MAX_LENGTH,
+      GALLERY_MAX_IMAGES,
       SITE_DESCRIPTION_MAX_LENGTH,
       SITE_NAME_MAX_LENGTH,
     },
@@ -405,6 +532,75 @@ mod tests {
     );
   }
 
+  #[test]
+  fn test_valid_alt_text() {
+    assert!(is_valid_alt_text_field(&None).is_ok());
+    let alt_text = (0..ALT_TEXT_MAX_LENGTH).map(|_| 'A').collect::<String>();
+    assert!(is_valid_alt_text_field(&Some(alt_text.clone())).is_ok());
+
+    let invalid_result = is_valid_alt_text_field(&Some(format!("{alt_text}A")));
+    assert_eq!(
+      Some(LemmyErrorType::AltTextTooLong),
+      invalid_result.err().map(|e| e.error_type)
+    );
+  }
+
+  #[test]
+  fn test_check_gallery_size() {
+    assert!(check_gallery_size(GALLERY_MAX_IMAGES).is_ok());
+    assert!(check_gallery_size(GALLERY_MAX_IMAGES + 1).is_err());
+  }
+
+  #[test]
+  fn test_check_conversation_size() {
+    assert!(check_conversation_size(CONVERSATION_MAX_RECIPIENTS).is_ok());
+    assert!(check_conversation_size(CONVERSATION_MAX_RECIPIENTS + 1).is_err());
+  }
+
+  #[test]
+  fn test_valid_device_key() {
+    assert!(is_valid_device_key(
+      "phone-1",
+      "MCowBQYDK2VuAyEA3p7bfXt9wbTTW2HC7OQ1Nz+DQ8hbeGdNrfx+FG+IK08="
+    )
+    .is_ok());
+    assert!(is_valid_device_key("my phone", "key").is_err());
+    assert!(is_valid_device_key("phone", " ").is_err());
+    assert!(is_valid_device_key("phone", &"A".repeat(DEVICE_PUBLIC_KEY_MAX_LENGTH + 1)).is_err());
+    assert!(check_device_key_count(DEVICE_KEYS_MAX).is_ok());
+    assert!(check_device_key_count(DEVICE_KEYS_MAX + 1).is_err());
+  }
+
+  #[test]
+  fn test_valid_encrypted_content() {
+    assert!(is_valid_encrypted_content("aGVsbG8=.c2VjcmV0").is_ok());
+    assert!(is_valid_encrypted_content("").is_err());
+    assert!(is_valid_encrypted_content("<script>").is_err());
+    assert!(is_valid_encrypted_content("héllo").is_err());
+  }
+
+  #[test]
+  fn test_valid_email_template() {
+    let variables = ["link", "recipient"];
+    assert!(is_valid_email_template("Hi {{ recipient }}", "<a>{{link}}</a>", &variables).is_ok());
+    assert!(is_valid_email_template("Hi", "{{ content }}", &variables).is_err());
+    assert!(is_valid_email_template("", "body", &variables).is_err());
+    assert!(is_valid_email_template("Two\nlines", "body", &variables).is_err());
+    assert!(is_valid_email_template("Hi", " ", &variables).is_err());
+    let long_subject = "A".repeat(EMAIL_TEMPLATE_SUBJECT_MAX_LENGTH + 1);
+    assert!(is_valid_email_template(&long_subject, "body", &variables).is_err());
+  }
+
+  #[test]
+  fn test_valid_custom_feed_name() {
+    assert!(is_valid_custom_feed_name("Linux").is_ok());
+    assert!(is_valid_custom_feed_name(" ").is_err());
+    assert!(is_valid_custom_feed_name("Linux\nGaming").is_err());
+    assert!(is_valid_custom_feed_name(&"A".repeat(CUSTOM_FEED_NAME_MAX_LENGTH + 1)).is_err());
+    assert!(check_custom_feed_size(CUSTOM_FEED_MAX_COMMUNITIES).is_ok());
+    assert!(check_custom_feed_size(CUSTOM_FEED_MAX_COMMUNITIES + 1).is_err());
+  }
+
   #[test]
   fn test_valid_site_description() {
     assert!(site_description_length_check(
@@ -0,0 +1,4 @@
+ALTER TABLE community
+    DROP COLUMN moved_to,
+    DROP COLUMN moved_from;
+
@@ -0,0 +1,4 @@
+ALTER TABLE community
+    ADD COLUMN moved_to varchar(255),
+    ADD COLUMN moved_from varchar(255);
+
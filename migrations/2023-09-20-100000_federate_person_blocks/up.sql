@@ -0,0 +1,4 @@
+-- Send a Block activity to remote instances when a local user blocks one of their users
+ALTER TABLE local_site
+    ADD COLUMN federate_person_blocks boolean NOT NULL DEFAULT FALSE;
+
@@ -0,0 +1,12 @@
+DROP TABLE admin_block_instance;
+
+ALTER TABLE federation_blocklist
+    DROP COLUMN severity,
+    DROP COLUMN public_comment,
+    DROP COLUMN private_comment,
+    DROP COLUMN subscription_id;
+
+DROP TYPE federation_block_severity_enum;
+
+DROP TABLE federation_blocklist_subscription;
+
@@ -0,0 +1,30 @@
+-- Shared denylists which are fetched again every day
+CREATE TABLE federation_blocklist_subscription (
+    id serial PRIMARY KEY,
+    url text NOT NULL UNIQUE,
+    admin_person_id int REFERENCES person ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    published timestamptz NOT NULL DEFAULT now(),
+    updated timestamptz
+);
+
+CREATE TYPE federation_block_severity_enum AS enum (
+    'Silence',
+    'Suspend'
+);
+
+ALTER TABLE federation_blocklist
+    ADD COLUMN severity federation_block_severity_enum NOT NULL DEFAULT 'Suspend',
+    ADD COLUMN public_comment text,
+    ADD COLUMN private_comment text,
+    ADD COLUMN subscription_id int REFERENCES federation_blocklist_subscription ON UPDATE CASCADE ON DELETE CASCADE;
+
+CREATE TABLE admin_block_instance (
+    id serial PRIMARY KEY,
+    admin_person_id int REFERENCES person ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    instance_id int REFERENCES instance ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    severity federation_block_severity_enum NOT NULL,
+    blocked boolean NOT NULL,
+    reason text,
+    when_ timestamptz NOT NULL DEFAULT now()
+);
+
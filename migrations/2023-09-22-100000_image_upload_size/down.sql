@@ -0,0 +1,3 @@
+ALTER TABLE image_upload
+    DROP COLUMN size;
+
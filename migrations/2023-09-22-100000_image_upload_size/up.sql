@@ -0,0 +1,4 @@
+-- Unknown for images which were uploaded before
+ALTER TABLE image_upload
+    ADD COLUMN size bigint;
+
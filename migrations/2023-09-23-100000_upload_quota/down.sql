@@ -0,0 +1,4 @@
+ALTER TABLE local_user
+    DROP COLUMN upload_quota_max_total_size,
+    DROP COLUMN upload_quota_max_files;
+
@@ -0,0 +1,5 @@
+-- Overrides of the upload quota from the config, set by admins
+ALTER TABLE local_user
+    ADD COLUMN upload_quota_max_total_size bigint,
+    ADD COLUMN upload_quota_max_files int;
+
@@ -0,0 +1,7 @@
+-- Remote images which were fetched by the image proxy, with the alias of the cached copy
+CREATE TABLE remote_image (
+    link text PRIMARY KEY,
+    pictrs_alias text NOT NULL,
+    published timestamptz NOT NULL DEFAULT now()
+);
+
@@ -0,0 +1,9 @@
+ALTER TABLE post
+    DROP COLUMN alt_text;
+
+ALTER TABLE image_upload
+    DROP COLUMN alt_text;
+
+ALTER TABLE community
+    DROP COLUMN require_alt_text;
+
@@ -0,0 +1,9 @@
+ALTER TABLE post
+    ADD COLUMN alt_text text;
+
+ALTER TABLE image_upload
+    ADD COLUMN alt_text text;
+
+ALTER TABLE community
+    ADD COLUMN require_alt_text boolean NOT NULL DEFAULT FALSE;
+
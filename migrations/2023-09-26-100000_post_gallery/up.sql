@@ -0,0 +1,12 @@
+-- Ordered images of a gallery post. The first image is also stored as post.url, so that clients
+-- without gallery support still show it.
+CREATE TABLE post_gallery_image (
+    id serial PRIMARY KEY,
+    post_id int REFERENCES post ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    url text NOT NULL,
+    alt_text text,
+    position int NOT NULL,
+    published timestamptz NOT NULL DEFAULT now(),
+    UNIQUE (post_id, position)
+);
+
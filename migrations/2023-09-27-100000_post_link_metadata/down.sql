@@ -0,0 +1,6 @@
+ALTER TABLE post
+    DROP COLUMN url_content_type,
+    DROP COLUMN embed_width,
+    DROP COLUMN embed_height,
+    DROP COLUMN embed_duration;
+
@@ -0,0 +1,7 @@
+-- Additional link metadata, so that clients can render players for video and audio links
+ALTER TABLE post
+    ADD COLUMN url_content_type text,
+    ADD COLUMN embed_width int,
+    ADD COLUMN embed_height int,
+    ADD COLUMN embed_duration int;
+
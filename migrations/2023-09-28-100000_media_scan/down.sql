@@ -0,0 +1,4 @@
+DROP TABLE media_scan;
+
+DROP TYPE media_scan_result_enum;
+
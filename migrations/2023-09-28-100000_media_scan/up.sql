@@ -0,0 +1,24 @@
+CREATE TYPE media_scan_result_enum AS enum (
+    'Allow',
+    'Deny',
+    'Quarantine'
+);
+
+-- Results of the media scanner for uploads and cached remote images. Denied and quarantined media
+-- stays unresolved until an admin reviewed it.
+CREATE TABLE media_scan (
+    id serial PRIMARY KEY,
+    pictrs_alias text NOT NULL,
+    -- The uploader, empty for remote images
+    person_id int REFERENCES person ON UPDATE CASCADE ON DELETE SET NULL,
+    -- The original link of a remote image
+    remote_url text,
+    result media_scan_result_enum NOT NULL,
+    reason text,
+    resolved boolean NOT NULL DEFAULT FALSE,
+    resolver_id int REFERENCES person ON UPDATE CASCADE ON DELETE SET NULL,
+    published timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_media_scan_pictrs_alias ON media_scan (pictrs_alias);
+
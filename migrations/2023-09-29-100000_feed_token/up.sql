@@ -0,0 +1,8 @@
+-- Tokens for private feeds like the front page and inbox, so that feed readers don't need the
+-- login token.
+CREATE TABLE feed_token (
+    local_user_id int PRIMARY KEY REFERENCES local_user ON UPDATE CASCADE ON DELETE CASCADE,
+    token text NOT NULL UNIQUE,
+    published timestamptz NOT NULL DEFAULT now()
+);
+
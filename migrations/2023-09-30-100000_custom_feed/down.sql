@@ -0,0 +1,4 @@
+DROP TABLE custom_feed_community;
+
+DROP TABLE custom_feed;
+
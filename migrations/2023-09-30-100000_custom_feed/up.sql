@@ -0,0 +1,19 @@
+-- Named groups of communities, which users browse like a single community
+CREATE TABLE custom_feed (
+    id serial PRIMARY KEY,
+    creator_id int REFERENCES person ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    name varchar(255) NOT NULL,
+    description text,
+    -- Public feeds can be viewed by anyone who has the link
+    public boolean NOT NULL DEFAULT FALSE,
+    published timestamptz NOT NULL DEFAULT now(),
+    updated timestamptz,
+    UNIQUE (creator_id, name)
+);
+
+CREATE TABLE custom_feed_community (
+    custom_feed_id int REFERENCES custom_feed ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    community_id int REFERENCES community ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    PRIMARY KEY (custom_feed_id, community_id)
+);
+
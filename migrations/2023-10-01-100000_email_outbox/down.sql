@@ -0,0 +1,9 @@
+DROP TABLE email_digest_item;
+
+DROP TABLE email_outbox;
+
+ALTER TABLE local_user
+    DROP COLUMN email_digest;
+
+DROP TYPE email_digest_mode_enum;
+
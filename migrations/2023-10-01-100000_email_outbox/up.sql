@@ -0,0 +1,40 @@
+CREATE TYPE email_digest_mode_enum AS enum (
+    'Off',
+    'Daily',
+    'Weekly'
+);
+
+ALTER TABLE local_user
+    ADD COLUMN email_digest email_digest_mode_enum NOT NULL DEFAULT 'Off';
+
+-- Emails waiting to be sent by the background worker. Sent emails are deleted, emails which failed
+-- permanently or too often are kept as dead letters.
+CREATE TABLE email_outbox (
+    id serial PRIMARY KEY,
+    local_user_id int REFERENCES local_user ON UPDATE CASCADE ON DELETE CASCADE,
+    recipient_email text NOT NULL,
+    recipient_name text NOT NULL,
+    subject text NOT NULL,
+    html text NOT NULL,
+    attempts int NOT NULL DEFAULT 0,
+    next_attempt timestamptz NOT NULL DEFAULT now(),
+    last_error text,
+    dead boolean NOT NULL DEFAULT FALSE,
+    published timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_email_outbox_next_attempt ON email_outbox (next_attempt)
+WHERE
+    NOT dead;
+
+-- Notifications for users in digest mode, which are bundled into a single email
+CREATE TABLE email_digest_item (
+    id serial PRIMARY KEY,
+    local_user_id int NOT NULL REFERENCES local_user ON UPDATE CASCADE ON DELETE CASCADE,
+    subject text NOT NULL,
+    html text NOT NULL,
+    published timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_email_digest_item_local_user ON email_digest_item (local_user_id);
+
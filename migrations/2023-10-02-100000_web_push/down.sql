@@ -0,0 +1,10 @@
+DROP TABLE notification_preference;
+
+DROP TYPE notification_type_enum;
+
+DROP TABLE push_subscription;
+
+ALTER TABLE secret
+    DROP COLUMN vapid_private_key,
+    DROP COLUMN vapid_public_key;
+
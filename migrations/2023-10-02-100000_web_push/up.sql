@@ -0,0 +1,32 @@
+-- Keys to sign push messages (VAPID), generated on startup
+ALTER TABLE secret
+    ADD COLUMN vapid_private_key text,
+    ADD COLUMN vapid_public_key text;
+
+-- Web Push subscriptions, one for each device or browser of a user
+CREATE TABLE push_subscription (
+    id serial PRIMARY KEY,
+    local_user_id int NOT NULL REFERENCES local_user ON UPDATE CASCADE ON DELETE CASCADE,
+    endpoint text NOT NULL UNIQUE,
+    p256dh text NOT NULL,
+    auth text NOT NULL,
+    published timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_push_subscription_local_user ON push_subscription (local_user_id);
+
+CREATE TYPE notification_type_enum AS enum (
+    'Reply',
+    'Mention',
+    'PrivateMessage',
+    'ModeratorEvent'
+);
+
+-- How a user wants to be notified for each type of notification. Without a row the defaults apply.
+CREATE TABLE notification_preference (
+    local_user_id int REFERENCES local_user ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    notification_type notification_type_enum NOT NULL,
+    push boolean NOT NULL DEFAULT TRUE,
+    PRIMARY KEY (local_user_id, notification_type)
+);
+
@@ -0,0 +1,30 @@
+DROP TABLE comment_mute;
+
+DROP TABLE post_mute;
+
+DROP TABLE post_subscription;
+
+ALTER TABLE notification_preference
+    DROP COLUMN in_app,
+    DROP COLUMN email,
+    DROP COLUMN only_from_followed;
+
+-- Values can't be removed from an enum, so it needs to be recreated
+DELETE FROM notification_preference
+WHERE notification_type IN ('ModeratorAction', 'PostSubscription');
+
+ALTER TYPE notification_type_enum RENAME TO notification_type_enum__;
+
+CREATE TYPE notification_type_enum AS enum (
+    'Reply',
+    'Mention',
+    'PrivateMessage',
+    'ModeratorEvent'
+);
+
+ALTER TABLE notification_preference
+    ALTER COLUMN notification_type TYPE notification_type_enum
+    USING notification_type::text::notification_type_enum;
+
+DROP TYPE notification_type_enum__;
+
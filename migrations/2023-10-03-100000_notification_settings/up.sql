@@ -0,0 +1,36 @@
+ALTER TYPE notification_type_enum
+    ADD VALUE 'ModeratorAction';
+
+ALTER TYPE notification_type_enum
+    ADD VALUE 'PostSubscription';
+
+ALTER TABLE notification_preference
+    ADD COLUMN in_app boolean NOT NULL DEFAULT TRUE,
+    ADD COLUMN email boolean NOT NULL DEFAULT TRUE,
+    ADD COLUMN only_from_followed boolean NOT NULL DEFAULT FALSE;
+
+-- Posts for which a user is notified about all new comments
+CREATE TABLE post_subscription (
+    person_id int REFERENCES person ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    post_id int REFERENCES post ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    published timestamptz NOT NULL DEFAULT now(),
+    PRIMARY KEY (person_id, post_id)
+);
+
+CREATE INDEX idx_post_subscription_post ON post_subscription (post_id);
+
+-- Posts and comment chains for which a user doesn't get notified about replies
+CREATE TABLE post_mute (
+    person_id int REFERENCES person ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    post_id int REFERENCES post ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    published timestamptz NOT NULL DEFAULT now(),
+    PRIMARY KEY (person_id, post_id)
+);
+
+CREATE TABLE comment_mute (
+    person_id int REFERENCES person ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    comment_id int REFERENCES comment ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    published timestamptz NOT NULL DEFAULT now(),
+    PRIMARY KEY (person_id, comment_id)
+);
+
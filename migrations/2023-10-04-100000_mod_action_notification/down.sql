@@ -0,0 +1,4 @@
+DROP TABLE mod_action_notification;
+
+DROP TYPE mod_action_type_enum;
+
@@ -0,0 +1,27 @@
+CREATE TYPE mod_action_type_enum AS enum (
+    'RemovePost',
+    'RestorePost',
+    'RemoveComment',
+    'RestoreComment',
+    'LockPost',
+    'UnlockPost',
+    'BanFromCommunity',
+    'UnbanFromCommunity'
+);
+
+-- Tells a user that a moderator took action against their content or account
+CREATE TABLE mod_action_notification (
+    id serial PRIMARY KEY,
+    recipient_id int REFERENCES person ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    mod_person_id int REFERENCES person ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    action mod_action_type_enum NOT NULL,
+    community_id int REFERENCES community ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    post_id int REFERENCES post ON UPDATE CASCADE ON DELETE CASCADE,
+    comment_id int REFERENCES comment ON UPDATE CASCADE ON DELETE CASCADE,
+    reason text,
+    read boolean NOT NULL DEFAULT FALSE,
+    published timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_mod_action_notification_recipient ON mod_action_notification (recipient_id, published DESC);
+
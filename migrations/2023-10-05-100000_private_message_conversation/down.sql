@@ -0,0 +1,11 @@
+DROP TRIGGER conversation_new_message ON private_message;
+
+DROP FUNCTION conversation_new_message;
+
+ALTER TABLE private_message
+    DROP COLUMN conversation_id;
+
+DROP TABLE conversation_participant;
+
+DROP TABLE conversation;
+
@@ -0,0 +1,111 @@
+-- Private messages are grouped into conversations between a fixed set of participants
+CREATE TABLE conversation (
+    id serial PRIMARY KEY,
+    published timestamptz NOT NULL DEFAULT now(),
+    last_message_published timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE TABLE conversation_participant (
+    conversation_id int REFERENCES conversation ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    person_id int REFERENCES person ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    muted boolean NOT NULL DEFAULT FALSE,
+    archived boolean NOT NULL DEFAULT FALSE,
+    last_read timestamptz,
+    published timestamptz NOT NULL DEFAULT now(),
+    PRIMARY KEY (conversation_id, person_id)
+);
+
+CREATE INDEX idx_conversation_participant_person ON conversation_participant (person_id);
+
+ALTER TABLE private_message
+    ADD COLUMN conversation_id int REFERENCES conversation ON UPDATE CASCADE ON DELETE CASCADE;
+
+-- Create a conversation for each pair of persons which exchanged messages
+CREATE TEMPORARY TABLE conversation_pair AS
+SELECT
+    nextval(pg_get_serial_sequence('conversation', 'id'))::int AS id,
+    person_a,
+    person_b,
+    published,
+    last_message_published
+FROM (
+    SELECT
+        least (creator_id, recipient_id) AS person_a,
+        greatest (creator_id, recipient_id) AS person_b,
+        min(published) AS published,
+        max(published) AS last_message_published
+    FROM
+        private_message
+    GROUP BY
+        1,
+        2) AS pairs;
+
+INSERT INTO conversation (id, published, last_message_published)
+SELECT
+    id,
+    published,
+    last_message_published
+FROM
+    conversation_pair;
+
+INSERT INTO conversation_participant (conversation_id, person_id, published)
+SELECT
+    id,
+    person_a,
+    published
+FROM
+    conversation_pair
+UNION
+SELECT
+    id,
+    person_b,
+    published
+FROM
+    conversation_pair;
+
+UPDATE
+    private_message
+SET
+    conversation_id = conversation_pair.id
+FROM
+    conversation_pair
+WHERE
+    least (private_message.creator_id, private_message.recipient_id) = conversation_pair.person_a
+    AND greatest (private_message.creator_id, private_message.recipient_id) = conversation_pair.person_b;
+
+DROP TABLE conversation_pair;
+
+ALTER TABLE private_message
+    ALTER COLUMN conversation_id SET NOT NULL;
+
+CREATE INDEX idx_private_message_conversation ON private_message (conversation_id, published DESC);
+
+-- Move conversations to the top of the list and out of the archive on new messages
+CREATE OR REPLACE FUNCTION conversation_new_message ()
+    RETURNS TRIGGER
+    LANGUAGE plpgsql
+    AS $$
+BEGIN
+    UPDATE
+        conversation
+    SET
+        last_message_published = NEW.published
+    WHERE
+        id = NEW.conversation_id
+        AND last_message_published < NEW.published;
+    UPDATE
+        conversation_participant
+    SET
+        archived = FALSE
+    WHERE
+        conversation_id = NEW.conversation_id
+        AND archived;
+    RETURN NULL;
+END
+$$;
+
+CREATE OR REPLACE TRIGGER conversation_new_message
+    AFTER INSERT ON private_message
+    FOR EACH ROW
+    EXECUTE PROCEDURE conversation_new_message ();
+
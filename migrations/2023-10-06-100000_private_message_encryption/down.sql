@@ -0,0 +1,5 @@
+ALTER TABLE private_message
+    DROP COLUMN encrypted;
+
+DROP TABLE person_device_key;
+
@@ -0,0 +1,12 @@
+-- Public keys of the devices which a person uses to read end-to-end encrypted private messages
+CREATE TABLE person_device_key (
+    person_id int REFERENCES person ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    device_id varchar(255) NOT NULL,
+    public_key text NOT NULL,
+    published timestamptz NOT NULL DEFAULT now(),
+    PRIMARY KEY (person_id, device_id)
+);
+
+ALTER TABLE private_message
+    ADD COLUMN encrypted boolean NOT NULL DEFAULT FALSE;
+
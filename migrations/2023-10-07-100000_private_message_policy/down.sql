@@ -0,0 +1,8 @@
+ALTER TABLE conversation_participant
+    DROP COLUMN message_request;
+
+ALTER TABLE local_user
+    DROP COLUMN private_message_policy;
+
+DROP TYPE private_message_policy_enum;
+
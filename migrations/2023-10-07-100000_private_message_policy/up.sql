@@ -0,0 +1,14 @@
+CREATE TYPE private_message_policy_enum AS enum (
+    'Everyone',
+    'Following',
+    'Local',
+    'Nobody'
+);
+
+ALTER TABLE local_user
+    ADD COLUMN private_message_policy private_message_policy_enum NOT NULL DEFAULT 'Everyone';
+
+-- Conversations started by someone the participant doesn't follow, until they accept it
+ALTER TABLE conversation_participant
+    ADD COLUMN message_request boolean NOT NULL DEFAULT FALSE;
+
@@ -0,0 +1,4 @@
+-- Address for answering a notification by email
+ALTER TABLE email_outbox
+    ADD COLUMN reply_to text;
+
@@ -0,0 +1,24 @@
+CREATE TYPE email_template_type_enum AS enum (
+    'VerifyEmail',
+    'PasswordReset',
+    'ApplicationApproved',
+    'ApplicationDenied',
+    'CommentReply',
+    'PostReply',
+    'Mention',
+    'PrivateMessage'
+);
+
+-- Email texts set by the admins, which replace the built-in translations. Templates with the
+-- undetermined language are used for all languages which have no template of their own.
+CREATE TABLE email_template (
+    template_type email_template_type_enum NOT NULL,
+    language_id int REFERENCES
+    LANGUAGE ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    subject text NOT NULL,
+    body text NOT NULL,
+    published timestamptz NOT NULL DEFAULT now(),
+    updated timestamptz,
+    PRIMARY KEY (template_type, language_id)
+);
+
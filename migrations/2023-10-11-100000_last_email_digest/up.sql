@@ -0,0 +1,3 @@
+ALTER TABLE local_user
+    ADD COLUMN last_email_digest timestamptz;
+
@@ -0,0 +1,3 @@
+ALTER TYPE email_template_type_enum
+    ADD VALUE 'ModAction';
+
@@ -0,0 +1,27 @@
+DELETE FROM email_template
+WHERE template_type = 'EmailDigest';
+
+-- rename the old enum
+ALTER TYPE email_template_type_enum RENAME TO email_template_type_enum__;
+
+-- create the new enum
+CREATE TYPE email_template_type_enum AS ENUM (
+    'VerifyEmail',
+    'PasswordReset',
+    'ApplicationApproved',
+    'ApplicationDenied',
+    'CommentReply',
+    'PostReply',
+    'Mention',
+    'PrivateMessage',
+    'ModAction'
+);
+
+-- alter all your enum columns
+ALTER TABLE email_template
+    ALTER COLUMN template_type TYPE email_template_type_enum
+    USING template_type::text::email_template_type_enum;
+
+-- drop the old enum
+DROP TYPE email_template_type_enum__;
+
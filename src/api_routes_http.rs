@@ -1,6 +1,11 @@
 use actix_web::{guard, web};
 use lemmy_api::{
-  comment::{distinguish::distinguish_comment, like::like_comment, save::save_comment},
+  comment::{
+    distinguish::distinguish_comment,
+    like::like_comment,
+    mute::mute_comment,
+    save::save_comment,
+  },
   comment_report::{
     create::create_comment_report,
     list::list_comment_reports,
@@ -10,6 +15,7 @@ use lemmy_api::{
     add_mod::add_mod_to_community,
     ban::ban_from_community,
     block::block_community,
+    export::export_community,
     follow::follow_community,
     hide::hide_community,
     transfer::transfer_community,
@@ -20,18 +26,39 @@ use lemmy_api::{
     block::block_person,
     change_password::change_password,
     change_password_after_reset::change_password_after_reset,
+    device_key::{add::add_device_key, delete::delete_device_key, list::list_device_keys},
+    feed_token::{delete::delete_feed_token, generate::generate_feed_token, get::get_feed_token},
     generate_totp_secret::generate_totp_secret,
     get_captcha::get_captcha,
     list_banned::list_banned_users,
     login::login,
+    media::{
+      delete::delete_media,
+      edit::edit_media,
+      list::list_media,
+      list_all::list_all_media,
+      quota::get_upload_quota,
+      set_quota::set_upload_quota,
+    },
+    notification_preference::{
+      edit::edit_notification_preference,
+      list::list_notification_preferences,
+    },
     notifications::{
       list_mentions::list_mentions,
+      list_mod_actions::list_mod_action_notifications,
       list_replies::list_replies,
       mark_all_read::mark_all_notifications_read,
       mark_mention_read::mark_person_mention_as_read,
+      mark_mod_action_read::mark_mod_action_notification_as_read,
       mark_reply_read::mark_reply_as_read,
       unread_count::unread_count,
     },
+    push_subscription::{
+      create::create_push_subscription,
+      delete::delete_push_subscription,
+      list::list_push_subscriptions,
+    },
     report_count::report_count,
     reset_password::reset_password,
     save_settings::save_user_settings,
@@ -44,14 +71,28 @@ use lemmy_api::{
     like::like_post,
     lock::lock_post,
     mark_read::mark_post_as_read,
+    mute::mute_post,
+    notification_settings::get_post_notification_settings,
+    refresh_metadata::refresh_post_metadata,
     save::save_post,
+    subscribe::subscribe_to_post,
   },
   post_report::{
     create::create_post_report,
     list::list_post_reports,
     resolve::resolve_post_report,
   },
-  private_message::mark_read::mark_pm_as_read,
+  private_message::{
+    conversation::{
+      accept::accept_message_request,
+      archive::archive_conversation,
+      list::list_conversations,
+      mark_read::mark_conversation_as_read,
+      mute::mute_conversation,
+      read::get_conversation,
+    },
+    mark_read::mark_pm_as_read,
+  },
   private_message_report::{
     create::create_pm_report,
     list::list_pm_reports,
@@ -59,8 +100,21 @@ use lemmy_api::{
   },
   site::{
     block::block_instance,
+    email_template::{
+      delete::delete_email_template,
+      list::list_email_templates,
+      preview::preview_email_template,
+      save::save_email_template,
+    },
     federated_instances::get_federated_instances,
+    federation_blocklist::{
+      delete_subscription::delete_federation_blocklist_subscription,
+      edit::edit_federation_block,
+      import::import_federation_blocklist,
+      list::list_federation_blocklist,
+    },
     leave_admin::leave_admin,
+    media_scan::{list::list_media_scans, resolve::resolve_media_scan},
     mod_log::get_mod_log,
     purge::{
       comment::purge_comment,
@@ -96,6 +150,13 @@ use lemmy_api_crud::{
     delete::delete_custom_emoji,
     update::update_custom_emoji,
   },
+  custom_feed::{
+    create::create_custom_feed,
+    delete::delete_custom_feed,
+    list::list_custom_feeds,
+    read::get_custom_feed,
+    update::update_custom_feed,
+  },
   post::{
     create::create_post,
     delete::delete_post,
@@ -109,17 +170,21 @@ use lemmy_api_crud::{
     read::get_private_message,
     update::update_private_message,
   },
+  reply_by_email::receive_reply_email,
   site::{create::create_site, read::get_site, update::update_site},
   user::{create::register, delete::delete_account},
 };
 use lemmy_apub::api::{
+  import_community::import_community,
   list_comments::list_comments,
   list_posts::list_posts,
+  move_community::move_community,
   read_community::get_community,
   read_person::read_person,
   resolve_object::resolve_object,
   search::search,
 };
+use lemmy_routes::images::image_proxy;
 use lemmy_utils::rate_limit::RateLimitCell;
 
 pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
@@ -150,6 +215,17 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
           .wrap(rate_limit.message())
           .route(web::get().to(resolve_object)),
       )
+      .service(
+        web::resource("/image_proxy")
+          .wrap(rate_limit.image())
+          .route(web::get().to(image_proxy)),
+      )
+      // Replies to notification emails, forwarded by the local mail server
+      .service(
+        web::resource("/email/inbound")
+          .app_data(web::PayloadConfig::new(10 * 1024 * 1024))
+          .route(web::post().to(receive_reply_email)),
+      )
       // Community
       .service(
         web::resource("/community")
@@ -170,13 +246,17 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
           // Mod Actions
           .route("/remove", web::post().to(remove_community))
           .route("/transfer", web::post().to(transfer_community))
+          .route("/export", web::get().to(export_community))
+          .route("/import", web::post().to(import_community))
+          .route("/move", web::post().to(move_community))
           .route("/ban_user", web::post().to(ban_from_community))
           .route("/mod", web::post().to(add_mod_to_community)),
       )
       .service(
         web::scope("/federated_instances")
           .wrap(rate_limit.message())
-          .route("", web::get().to(get_federated_instances)),
+          .route("", web::get().to(get_federated_instances))
+          .route("/blocklist", web::get().to(list_federation_blocklist)),
       )
       // Post
       .service(
@@ -199,10 +279,17 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
           .route("/list", web::get().to(list_posts))
           .route("/like", web::post().to(like_post))
           .route("/save", web::put().to(save_post))
+          .route("/subscribe", web::put().to(subscribe_to_post))
+          .route("/mute", web::put().to(mute_post))
+          .route(
+            "/notification_settings",
+            web::get().to(get_post_notification_settings),
+          )
           .route("/report", web::post().to(create_post_report))
           .route("/report/resolve", web::put().to(resolve_post_report))
           .route("/report/list", web::get().to(list_post_reports))
-          .route("/site_metadata", web::get().to(get_link_metadata)),
+          .route("/site_metadata", web::get().to(get_link_metadata))
+          .route("/refresh_metadata", web::post().to(refresh_post_metadata)),
       )
       // Comment
       .service(
@@ -223,6 +310,7 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
           .route("/distinguish", web::post().to(distinguish_comment))
           .route("/like", web::post().to(like_comment))
           .route("/save", web::put().to(save_comment))
+          .route("/mute", web::put().to(mute_comment))
           .route("/list", web::get().to(list_comments))
           .route("/report", web::post().to(create_comment_report))
           .route("/report/resolve", web::put().to(resolve_comment_report))
@@ -237,6 +325,18 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
           .route("", web::put().to(update_private_message))
           .route("/delete", web::post().to(delete_private_message))
           .route("/mark_as_read", web::post().to(mark_pm_as_read))
+          .route("/conversation", web::get().to(get_conversation))
+          .route("/conversation/list", web::get().to(list_conversations))
+          .route("/conversation/mute", web::put().to(mute_conversation))
+          .route("/conversation/archive", web::put().to(archive_conversation))
+          .route(
+            "/conversation/mark_as_read",
+            web::post().to(mark_conversation_as_read),
+          )
+          .route(
+            "/conversation/accept",
+            web::post().to(accept_message_request),
+          )
           .route("/report", web::post().to(create_pm_report))
           .route("/report/resolve", web::put().to(resolve_pm_report))
           .route("/report/list", web::get().to(list_pm_reports)),
@@ -267,6 +367,11 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
             web::post().to(mark_person_mention_as_read),
           )
           .route("/replies", web::get().to(list_replies))
+          .route("/mod_action", web::get().to(list_mod_action_notifications))
+          .route(
+            "/mod_action/mark_as_read",
+            web::post().to(mark_mod_action_notification_as_read),
+          )
           // Admin action. I don't like that it's in /user
           .route("/ban", web::post().to(ban_from_site))
           .route("/banned", web::get().to(list_banned_users))
@@ -291,13 +396,49 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
           .route("/verify_email", web::post().to(verify_email))
           .route("/leave_admin", web::post().to(leave_admin))
           .route("/totp/generate", web::post().to(generate_totp_secret))
-          .route("/totp/update", web::post().to(update_totp)),
+          .route("/totp/update", web::post().to(update_totp))
+          .route("/feed_token", web::get().to(get_feed_token))
+          .route("/feed_token/generate", web::post().to(generate_feed_token))
+          .route("/feed_token/delete", web::post().to(delete_feed_token))
+          .route(
+            "/push_subscription",
+            web::post().to(create_push_subscription),
+          )
+          .route(
+            "/push_subscription/list",
+            web::get().to(list_push_subscriptions),
+          )
+          .route(
+            "/push_subscription/delete",
+            web::post().to(delete_push_subscription),
+          )
+          .route("/device_key", web::post().to(add_device_key))
+          .route("/device_key/list", web::get().to(list_device_keys))
+          .route("/device_key/delete", web::post().to(delete_device_key))
+          .route(
+            "/notification_preference",
+            web::put().to(edit_notification_preference),
+          )
+          .route(
+            "/notification_preference/list",
+            web::get().to(list_notification_preferences),
+          ),
+      )
+      .service(
+        web::scope("/account")
+          .wrap(rate_limit.message())
+          .route("/list_media", web::get().to(list_media))
+          .route("/delete_media", web::post().to(delete_media))
+          .route("/edit_media", web::put().to(edit_media))
+          .route("/upload_quota", web::get().to(get_upload_quota)),
       )
       // Admin Actions
       .service(
         web::scope("/admin")
           .wrap(rate_limit.message())
           .route("/add", web::post().to(add_admin))
+          .route("/list_all_media", web::get().to(list_all_media))
+          .route("/upload_quota", web::put().to(set_upload_quota))
           .route(
             "/registration_application/count",
             web::get().to(get_unread_registration_application_count),
@@ -310,12 +451,33 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
             "/registration_application/approve",
             web::put().to(approve_registration_application),
           )
+          .route("/media_scan/list", web::get().to(list_media_scans))
+          .route("/media_scan/resolve", web::put().to(resolve_media_scan))
+          .route("/email_template", web::put().to(save_email_template))
+          .route("/email_template/list", web::get().to(list_email_templates))
+          .route(
+            "/email_template/delete",
+            web::post().to(delete_email_template),
+          )
+          .route(
+            "/email_template/preview",
+            web::post().to(preview_email_template),
+          )
           .service(
             web::scope("/purge")
               .route("/person", web::post().to(purge_person))
               .route("/community", web::post().to(purge_community))
               .route("/post", web::post().to(purge_post))
               .route("/comment", web::post().to(purge_comment)),
+          )
+          .service(
+            web::scope("/federation_blocklist")
+              .route("", web::put().to(edit_federation_block))
+              .route("/import", web::post().to(import_federation_blocklist))
+              .route(
+                "/subscription/delete",
+                web::post().to(delete_federation_blocklist_subscription),
+              ),
           ),
       )
       .service(
@@ -324,6 +486,15 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
           .route("", web::post().to(create_custom_emoji))
           .route("", web::put().to(update_custom_emoji))
           .route("/delete", web::post().to(delete_custom_emoji)),
+      )
+      .service(
+        web::scope("/custom_feed")
+          .wrap(rate_limit.message())
+          .route("", web::get().to(get_custom_feed))
+          .route("", web::post().to(create_custom_feed))
+          .route("", web::put().to(update_custom_feed))
+          .route("/delete", web::post().to(delete_custom_feed))
+          .route("/list", web::get().to(list_custom_feeds)),
       ),
   );
   cfg.service(
@@ -33,6 +33,7 @@ use lemmy_api_common::{
     check_private_instance_and_federation_enabled,
     local_site_rate_limit_to_rate_limit_config,
   },
+  web_push::init_vapid_keys,
 };
 use lemmy_apub::{
   activities::{handle_outgoing_activities, match_outgoing_activities},
@@ -44,7 +45,7 @@ use lemmy_db_schema::{
   utils::{build_db_pool, get_database_url, run_migrations},
 };
 use lemmy_federate::{start_stop_federation_workers_cancellable, Opts};
-use lemmy_routes::{feeds, images, nodeinfo, webfinger};
+use lemmy_routes::{events, feeds, images, nodeinfo, webfinger};
 use lemmy_utils::{
   error::LemmyError,
   rate_limit::RateLimitCell,
@@ -132,6 +133,9 @@ pub async fn start_lemmy_server(args: CmdArgs) -> Result<(), LemmyError> {
   let secret = Secret::init(&mut (&pool).into())
     .await
     .expect("Couldn't initialize secrets.");
+  let secret = init_vapid_keys(secret, &mut (&pool).into())
+    .await
+    .expect("Couldn't generate keys for push notifications.");
 
   // Make sure the local site is set up.
   let site_view = SiteView::read_local(&mut (&pool).into())
@@ -311,6 +315,7 @@ fn create_http_server(
           webfinger::config(cfg);
         }
       })
+      .configure(events::config)
       .configure(feeds::config)
       .configure(|cfg| images::config(cfg, pictrs_client.clone(), &rate_limit_cell))
       .configure(nodeinfo::config)
@@ -10,8 +10,13 @@ use diesel::{
   QueryableByName,
 };
 use diesel_async::{AsyncPgConnection, RunQueryDsl};
-use lemmy_api_common::context::LemmyContext;
+use lemmy_api::site::federation_blocklist::refresh_blocklist_subscriptions;
+use lemmy_api_common::{
+  context::LemmyContext,
+  utils::{build_email, queue_email, sanitize_html_federation},
+};
 use lemmy_db_schema::{
+  newtypes::LocalUserId,
   schema::{
     captcha_answer,
     comment,
@@ -22,11 +27,22 @@ use lemmy_db_schema::{
     received_activity,
     sent_activity,
   },
-  source::instance::{Instance, InstanceForm},
+  source::{
+    email_digest_item::EmailDigestItem,
+    email_outbox::{EmailOutbox, EmailOutboxFailureForm},
+    instance::{Instance, InstanceForm},
+    local_user::{LocalUser, LocalUserUpdateForm},
+  },
+  traits::Crud,
   utils::{get_conn, naive_now, now, DbPool, DELETED_REPLACEMENT_TEXT},
+  EmailTemplateType,
 };
+use lemmy_db_views::structs::LocalUserView;
 use lemmy_routes::nodeinfo::NodeInfo;
-use lemmy_utils::error::{LemmyError, LemmyResult};
+use lemmy_utils::{
+  email::send_email,
+  error::{LemmyError, LemmyErrorType, LemmyResult},
+};
 use reqwest_middleware::ClientWithMiddleware;
 use std::time::Duration;
 use tracing::{error, info, warn};
@@ -112,6 +128,35 @@ pub async fn setup(context: LemmyContext) -> Result<(), LemmyError> {
     }
   });
 
+  let context_1 = context.clone();
+  // Refresh the shared federation blocklists which admins subscribed to
+  scheduler.every(CTimeUnits::days(1)).run(move || {
+    let context = context_1.clone();
+
+    async move {
+      refresh_blocklist_subscriptions(&context)
+        .await
+        .map_err(|e| warn!("Failed to refresh federation blocklists: {e}"))
+        .ok();
+    }
+  });
+
+  if context.settings().email.is_some() {
+    // Deliver queued emails separately, so that slow smtp servers don't hold up the other tasks
+    let _email_outbox = tokio::task::spawn(send_queued_emails(context.clone()));
+  }
+
+  let context_1 = context.clone();
+  // Send the email digests which are due. Each user's period is tracked separately, so that
+  // digests don't depend on when the server was started.
+  scheduler.every(CTimeUnits::hours(1)).run(move || {
+    let context = context_1.clone();
+
+    async move {
+      send_email_digests(&context).await;
+    }
+  });
+
   // Manually run the scheduler in an event loop
   loop {
     scheduler.run_pending().await;
@@ -299,7 +344,7 @@ async fn delete_expired_captcha_answers(pool: &mut DbPool<'_>) {
   }
 }
 
-/// Clear old activities (this table gets very large)
+/// Clear old activities (this table gets very large), and old email dead letters
 async fn clear_old_activities(pool: &mut DbPool<'_>) {
   info!("Clearing old activities...");
   let conn = get_conn(pool).await;
@@ -325,6 +370,180 @@ async fn clear_old_activities(pool: &mut DbPool<'_>) {
       error!("Failed to get connection from pool: {e}");
     }
   }
+
+  EmailOutbox::delete_old_dead_letters(pool)
+    .await
+    .map_err(|e| error!("Failed to clear old email dead letters: {e}"))
+    .ok();
+}
+
+/// Number of emails which are taken from the outbox at once
+const EMAIL_OUTBOX_BATCH_SIZE: i64 = 50;
+
+/// Sends the emails from the outbox. Failed emails are retried with exponential backoff, until
+/// they were rejected by the smtp server or reached the maximum number of attempts.
+async fn send_queued_emails(context: LemmyContext) {
+  loop {
+    let emails = EmailOutbox::claim_due(&mut context.pool(), EMAIL_OUTBOX_BATCH_SIZE)
+      .await
+      .map_err(|e| error!("Failed to read email outbox: {e}"))
+      .unwrap_or_default();
+    if emails.is_empty() {
+      tokio::time::sleep(Duration::from_secs(5)).await;
+    }
+    for email in emails {
+      send_queued_email(&context, email).await;
+    }
+  }
+}
+
+async fn send_queued_email(context: &LemmyContext, email: EmailOutbox) {
+  let res = send_email(
+    &email.subject,
+    &email.recipient_email,
+    &email.recipient_name,
+    &email.html,
+    email.reply_to.as_deref(),
+    context.settings(),
+  )
+  .await;
+  let pool = &mut context.pool();
+  let res = match res {
+    Ok(()) => EmailOutbox::mark_sent(pool, email.id).await.map(|_| ()),
+    Err(e) => {
+      let attempts = email.attempts + 1;
+      let max_attempts = context
+        .settings()
+        .email
+        .as_ref()
+        .map(|c| c.outbox.max_attempts)
+        .unwrap_or_default();
+      let rejected = matches!(
+        e.error_type,
+        LemmyErrorType::EmailRejected | LemmyErrorType::InvalidEmailAddress
+      );
+      let dead = rejected || attempts >= max_attempts;
+      if dead {
+        warn!("Giving up on email to {}: {e}", email.recipient_email);
+      }
+      // Keep sending to addresses which bounce hurts the reputation of the instance, so notifications
+      // for the user are turned off until they fix their email.
+      if let (true, Some(local_user_id)) = (rejected, email.local_user_id) {
+        let form = LocalUserUpdateForm {
+          send_notifications_to_email: Some(false),
+          ..Default::default()
+        };
+        LocalUser::update(pool, local_user_id, &form)
+          .await
+          .map_err(|e| error!("Failed to disable email notifications: {e}"))
+          .ok();
+      }
+      let form = EmailOutboxFailureForm {
+        attempts,
+        next_attempt: naive_now() + email_retry_backoff(attempts),
+        last_error: Some(e.to_string()),
+        dead,
+      };
+      EmailOutbox::mark_failed(pool, email.id, &form)
+        .await
+        .map(|_| ())
+    }
+  };
+  res
+    .map_err(|e| error!("Failed to update email outbox: {e}"))
+    .ok();
+}
+
+/// Waits one minute after the first failed attempt, and doubles that for each further one, up to
+/// a day.
+fn email_retry_backoff(attempts: i32) -> chrono::Duration {
+  let exponent = u32::try_from(attempts.saturating_sub(1)).unwrap_or_default();
+  let minutes = 2_i64.saturating_pow(exponent.min(20));
+  chrono::Duration::minutes(minutes.min(24 * 60))
+}
+
+/// Bundles the pending notifications of each user whose digest is due into a single email.
+async fn send_email_digests(context: &LemmyContext) {
+  info!("Sending email digests...");
+  let local_user_ids = match EmailDigestItem::list_due_local_users(&mut context.pool()).await {
+    Ok(ids) => ids,
+    Err(e) => {
+      error!("Failed to read due email digests: {e}");
+      return;
+    }
+  };
+
+  for local_user_id in local_user_ids {
+    send_email_digest(context, local_user_id)
+      .await
+      .map_err(|e| error!("Failed to send email digest: {e}"))
+      .ok();
+  }
+  info!("Done.");
+}
+
+/// Queues the digest of a single user. The items are only removed if the email was queued, and
+/// both happen in one transaction so that nothing gets lost or sent twice.
+async fn send_email_digest(context: &LemmyContext, local_user_id: LocalUserId) -> LemmyResult<()> {
+  let pool = &mut context.pool();
+  let local_user_view = LocalUserView::read(pool, local_user_id).await?;
+  let settings = context.settings();
+  let conn = &mut get_conn(pool).await?;
+  conn
+    .build_transaction()
+    .run(|conn| {
+      Box::pin(async move {
+        let items = EmailDigestItem::take_for_user(&mut conn.into(), local_user_id).await?;
+        if items.is_empty() {
+          return Ok(());
+        }
+        // Without an email address the items can't be delivered anyway
+        if local_user_view.local_user.email.is_some() {
+          let (subject, html) = build_email(
+            EmailTemplateType::EmailDigest,
+            &local_user_view,
+            &[
+              ("count", &items.len().to_string()),
+              ("content", &email_digest_content(&items)),
+            ],
+            &mut conn.into(),
+            settings,
+          )
+          .await;
+          queue_email(
+            &local_user_view,
+            &subject,
+            &html,
+            None,
+            &mut conn.into(),
+            settings,
+          )
+          .await?;
+        }
+        let form = LocalUserUpdateForm {
+          last_email_digest: Some(Some(naive_now())),
+          ..Default::default()
+        };
+        LocalUser::update(&mut conn.into(), local_user_id, &form).await?;
+        Ok(())
+      }) as _
+    })
+    .await
+}
+
+/// Joins the collected emails, each one below its subject.
+fn email_digest_content(items: &[EmailDigestItem]) -> String {
+  items
+    .iter()
+    .map(|i| {
+      format!(
+        "<h3>{}</h3>\n{}",
+        sanitize_html_federation(&i.subject),
+        i.html
+      )
+    })
+    .collect::<Vec<_>>()
+    .join("\n<hr>\n")
 }
 
 /// overwrite posts and comments 30d after deletion
@@ -522,9 +741,40 @@ mod tests {
   #![allow(clippy::unwrap_used)]
   #![allow(clippy::indexing_slicing)]
 
+  use super::{email_digest_content, email_retry_backoff};
+  use lemmy_db_schema::{
+    newtypes::LocalUserId,
+    source::email_digest_item::EmailDigestItem,
+    utils::naive_now,
+  };
   use lemmy_routes::nodeinfo::NodeInfo;
   use reqwest::Client;
 
+  #[test]
+  fn test_email_retry_backoff() {
+    assert_eq!(1, email_retry_backoff(1).num_minutes());
+    assert_eq!(2, email_retry_backoff(2).num_minutes());
+    assert_eq!(64, email_retry_backoff(7).num_minutes());
+    assert_eq!(24 * 60, email_retry_backoff(12).num_minutes());
+    assert_eq!(24 * 60, email_retry_backoff(i32::MAX).num_minutes());
+  }
+
+  #[test]
+  fn test_email_digest_content() {
+    let item = |subject: &str| EmailDigestItem {
+      id: 0,
+      local_user_id: LocalUserId(1),
+      subject: subject.to_string(),
+      html: "<p>hello</p>".to_string(),
+      published: naive_now(),
+    };
+    let items = vec![item("Reply from alice"), item("Mentioned by <b>bob</b>")];
+    assert_eq!(
+      "<h3>Reply from alice</h3>\n<p>hello</p>\n<hr>\n<h3>Mentioned by &lt;b>bob&lt;/b></h3>\n<p>hello</p>",
+      email_digest_content(&items)
+    );
+  }
+
   #[tokio::test]
   #[ignore]
   async fn test_nodeinfo() {